@@ -1,6 +1,7 @@
 use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
 use picoflow::dag::DagEngine;
-use picoflow::models::{ShellConfig, TaskConfig, TaskExecutorConfig, TaskType};
+use picoflow::models::{Dependency, ShellConfig, TaskConfig, TaskExecutorConfig, TaskType};
+use std::collections::HashMap;
 
 /// Create a linear chain of tasks: task0 -> task1 -> task2 -> ...
 /// This represents the worst case for topological sort (maximum depth, no parallelism)
@@ -11,7 +12,7 @@ fn create_linear_chain(count: usize) -> Vec<TaskConfig> {
         let depends_on = if i == 0 {
             vec![]
         } else {
-            vec![format!("task{}", i - 1)]
+            vec![format!("task{}", i - 1).into()]
         };
 
         tasks.push(TaskConfig {
@@ -22,11 +23,25 @@ fn create_linear_chain(count: usize) -> Vec<TaskConfig> {
                 command: "/bin/true".to_string(),
                 args: vec![],
                 workdir: None,
+                create_workdir: false,
                 env: None,
+                env_file: None,
+                fail_on_stderr_regex: None,
+                shell: None,
             }),
             retry: Some(3),
             timeout: Some(300),
-            continue_on_failure: false,
+            delay_before: None,
+            continue_on_failure: None,
+            outputs: false,
+            run_condition: Default::default(),
+            enabled: None,
+            labels: HashMap::new(),
+            requires_files: vec![],
+            missing_files_action: Default::default(),
+            cache_key: None,
+            cache_ttl_secs: None,
+            priority: None,
         });
     }
 
@@ -48,11 +63,25 @@ fn create_diamond_dag(layers: usize) -> Vec<TaskConfig> {
             command: "/bin/true".to_string(),
             args: vec![],
             workdir: None,
+            create_workdir: false,
             env: None,
+            env_file: None,
+            fail_on_stderr_regex: None,
+            shell: None,
         }),
         retry: Some(3),
         timeout: Some(300),
-        continue_on_failure: false,
+        delay_before: None,
+        continue_on_failure: None,
+        outputs: false,
+        run_condition: Default::default(),
+        enabled: None,
+        labels: HashMap::new(),
+        requires_files: vec![],
+        missing_files_action: Default::default(),
+        cache_key: None,
+        cache_ttl_secs: None,
+        priority: None,
     });
     task_counter += 1;
 
@@ -70,23 +99,37 @@ fn create_diamond_dag(layers: usize) -> Vec<TaskConfig> {
             tasks.push(TaskConfig {
                 name: format!("task{}", task_counter),
                 task_type: TaskType::Shell,
-                depends_on: vec![prev_root.clone()],
+                depends_on: vec![prev_root.as_str().into()],
                 config: TaskExecutorConfig::Shell(ShellConfig {
                     command: "/bin/true".to_string(),
                     args: vec![],
                     workdir: None,
+                    create_workdir: false,
                     env: None,
+                    env_file: None,
+                    fail_on_stderr_regex: None,
+                    shell: None,
                 }),
                 retry: Some(3),
                 timeout: Some(300),
-                continue_on_failure: false,
+                delay_before: None,
+                continue_on_failure: None,
+                outputs: false,
+                run_condition: Default::default(),
+                enabled: None,
+                labels: HashMap::new(),
+                requires_files: vec![],
+                missing_files_action: Default::default(),
+                cache_key: None,
+                cache_ttl_secs: None,
+                priority: None,
             });
             task_counter += 1;
         }
 
         // Converge to single task
-        let deps: Vec<String> = (branch_start..task_counter)
-            .map(|i| format!("task{}", i))
+        let deps: Vec<Dependency> = (branch_start..task_counter)
+            .map(|i| format!("task{}", i).into())
             .collect();
         tasks.push(TaskConfig {
             name: format!("task{}", task_counter),
@@ -96,11 +139,25 @@ fn create_diamond_dag(layers: usize) -> Vec<TaskConfig> {
                 command: "/bin/true".to_string(),
                 args: vec![],
                 workdir: None,
+                create_workdir: false,
                 env: None,
+                env_file: None,
+                fail_on_stderr_regex: None,
+                shell: None,
             }),
             retry: Some(3),
             timeout: Some(300),
-            continue_on_failure: false,
+            delay_before: None,
+            continue_on_failure: None,
+            outputs: false,
+            run_condition: Default::default(),
+            enabled: None,
+            labels: HashMap::new(),
+            requires_files: vec![],
+            missing_files_action: Default::default(),
+            cache_key: None,
+            cache_ttl_secs: None,
+            priority: None,
         });
         task_counter += 1;
     }
@@ -122,11 +179,25 @@ fn create_wide_parallel(width: usize) -> Vec<TaskConfig> {
             command: "/bin/true".to_string(),
             args: vec![],
             workdir: None,
+            create_workdir: false,
             env: None,
+            env_file: None,
+            fail_on_stderr_regex: None,
+            shell: None,
         }),
         retry: Some(3),
         timeout: Some(300),
-        continue_on_failure: false,
+        delay_before: None,
+        continue_on_failure: None,
+        outputs: false,
+        run_condition: Default::default(),
+        enabled: None,
+        labels: HashMap::new(),
+        requires_files: vec![],
+        missing_files_action: Default::default(),
+        cache_key: None,
+        cache_ttl_secs: None,
+        priority: None,
     });
 
     // Parallel tasks
@@ -134,21 +205,37 @@ fn create_wide_parallel(width: usize) -> Vec<TaskConfig> {
         tasks.push(TaskConfig {
             name: format!("parallel{}", i),
             task_type: TaskType::Shell,
-            depends_on: vec!["root".to_string()],
+            depends_on: vec!["root".into()],
             config: TaskExecutorConfig::Shell(ShellConfig {
                 command: "/bin/true".to_string(),
                 args: vec![],
                 workdir: None,
+                create_workdir: false,
                 env: None,
+                env_file: None,
+                fail_on_stderr_regex: None,
+                shell: None,
             }),
             retry: Some(3),
             timeout: Some(300),
-            continue_on_failure: false,
+            delay_before: None,
+            continue_on_failure: None,
+            outputs: false,
+            run_condition: Default::default(),
+            enabled: None,
+            labels: HashMap::new(),
+            requires_files: vec![],
+            missing_files_action: Default::default(),
+            cache_key: None,
+            cache_ttl_secs: None,
+            priority: None,
         });
     }
 
     // Final task that depends on all parallel tasks
-    let parallel_deps: Vec<String> = (0..width).map(|i| format!("parallel{}", i)).collect();
+    let parallel_deps: Vec<Dependency> = (0..width)
+        .map(|i| format!("parallel{}", i).into())
+        .collect();
     tasks.push(TaskConfig {
         name: "final".to_string(),
         task_type: TaskType::Shell,
@@ -157,11 +244,25 @@ fn create_wide_parallel(width: usize) -> Vec<TaskConfig> {
             command: "/bin/true".to_string(),
             args: vec![],
             workdir: None,
+            create_workdir: false,
             env: None,
+            env_file: None,
+            fail_on_stderr_regex: None,
+            shell: None,
         }),
         retry: Some(3),
         timeout: Some(300),
-        continue_on_failure: false,
+        delay_before: None,
+        continue_on_failure: None,
+        outputs: false,
+        run_condition: Default::default(),
+        enabled: None,
+        labels: HashMap::new(),
+        requires_files: vec![],
+        missing_files_action: Default::default(),
+        cache_key: None,
+        cache_ttl_secs: None,
+        priority: None,
     });
 
     tasks
@@ -1,6 +1,7 @@
 use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
 use picoflow::dag::DagEngine;
 use picoflow::models::{ShellConfig, TaskConfig, TaskExecutorConfig, TaskType};
+use std::collections::HashMap;
 
 /// Helper to create test tasks for memory benchmarking
 fn create_test_tasks(count: usize) -> Vec<TaskConfig> {
@@ -9,7 +10,7 @@ fn create_test_tasks(count: usize) -> Vec<TaskConfig> {
         let depends_on = if i == 0 {
             vec![]
         } else {
-            vec![format!("task{}", i - 1)]
+            vec![format!("task{}", i - 1).into()]
         };
 
         tasks.push(TaskConfig {
@@ -20,11 +21,25 @@ fn create_test_tasks(count: usize) -> Vec<TaskConfig> {
                 command: "/bin/true".to_string(),
                 args: vec![],
                 workdir: None,
+                create_workdir: false,
                 env: None,
+                env_file: None,
+                fail_on_stderr_regex: None,
+                shell: None,
             }),
             retry: Some(0),
             timeout: Some(30),
-            continue_on_failure: false,
+            delay_before: None,
+            continue_on_failure: None,
+            outputs: false,
+            run_condition: Default::default(),
+            enabled: None,
+            labels: HashMap::new(),
+            requires_files: vec![],
+            missing_files_action: Default::default(),
+            cache_key: None,
+            cache_ttl_secs: None,
+            priority: None,
         });
     }
     tasks
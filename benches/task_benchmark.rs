@@ -11,7 +11,11 @@ fn create_shell_config(command: &str, args: Vec<String>) -> (ShellExecutor, Task
         command: command.to_string(),
         args,
         workdir: None,
+        create_workdir: false,
         env: None,
+        env_file: None,
+        fail_on_stderr_regex: None,
+        shell: None,
     });
     (executor, config)
 }
@@ -123,7 +127,11 @@ fn bench_timeout_enforcement(c: &mut Criterion) {
             command: "/bin/sleep".to_string(),
             args: vec!["0.1".to_string()],
             workdir: None,
+            create_workdir: false,
             env: None,
+            env_file: None,
+            fail_on_stderr_regex: None,
+            shell: None,
         });
         b.iter(|| {
             rt.block_on(async {
@@ -154,7 +162,11 @@ fn bench_sequential_execution(c: &mut Criterion) {
                     command: "/bin/true".to_string(),
                     args: vec![],
                     workdir: None,
+                    create_workdir: false,
                     env: None,
+                    env_file: None,
+                    fail_on_stderr_regex: None,
+                    shell: None,
                 });
 
                 b.iter(|| {
@@ -187,7 +199,11 @@ fn bench_parallel_execution(c: &mut Criterion) {
                     command: "/bin/true".to_string(),
                     args: vec![],
                     workdir: None,
+                    create_workdir: false,
                     env: None,
+                    env_file: None,
+                    fail_on_stderr_regex: None,
+                    shell: None,
                 });
 
                 b.iter(|| {
@@ -238,7 +254,11 @@ fn bench_task_with_env_vars(c: &mut Criterion) {
             command: "/bin/echo".to_string(),
             args: vec!["hello".to_string()],
             workdir: None,
+            create_workdir: false,
             env: Some(env),
+            env_file: None,
+            fail_on_stderr_regex: None,
+            shell: None,
         });
         b.iter(|| {
             rt.block_on(async {
@@ -259,7 +279,11 @@ fn bench_task_with_env_vars(c: &mut Criterion) {
             command: "/bin/echo".to_string(),
             args: vec!["hello".to_string()],
             workdir: None,
+            create_workdir: false,
             env: Some(env),
+            env_file: None,
+            fail_on_stderr_regex: None,
+            shell: None,
         });
         b.iter(|| {
             rt.block_on(async {
@@ -1,9 +1,11 @@
 use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
 use picoflow::models::{
-    ShellConfig, TaskConfig, TaskExecutorConfig, TaskType, WorkflowConfig, WorkflowGlobalConfig,
+    Dependency, OutputPolicy, ShellConfig, TaskConfig, TaskExecutorConfig, TaskType,
+    WorkflowConfig, WorkflowGlobalConfig,
 };
 use picoflow::scheduler::TaskScheduler;
 use picoflow::state::StateManager;
+use std::collections::HashMap;
 use std::sync::Arc;
 use tempfile::TempDir;
 use tokio::runtime::Runtime;
@@ -14,10 +16,24 @@ fn create_simple_sequential_workflow() -> WorkflowConfig {
         name: "simple_sequential".to_string(),
         description: Some("Simple 3-task sequential workflow".to_string()),
         schedule: None,
+        timezone: None,
+        labels: HashMap::new(),
+        allow_overlap: false,
+        on_complete: None,
+        params: HashMap::new(),
         config: WorkflowGlobalConfig {
             max_parallel: 1,
             retry_default: 0,
             timeout_default: 30,
+            retry_jitter: false,
+            max_retries_cap: 10,
+            continue_on_failure_default: false,
+            memory_limit_mb: None,
+            max_output_bytes: None,
+            store_output: OutputPolicy::Always,
+            backoff_strategy: Default::default(),
+            ssh_defaults: None,
+            max_depth: None,
         },
         tasks: vec![
             TaskConfig {
@@ -28,39 +44,81 @@ fn create_simple_sequential_workflow() -> WorkflowConfig {
                     command: "/bin/echo".to_string(),
                     args: vec!["Task 1".to_string()],
                     workdir: None,
+                    create_workdir: false,
                     env: None,
+                    env_file: None,
+                    fail_on_stderr_regex: None,
+                    shell: None,
                 }),
                 retry: Some(0),
                 timeout: Some(30),
-                continue_on_failure: false,
+                delay_before: None,
+                continue_on_failure: None,
+                outputs: false,
+                run_condition: Default::default(),
+                enabled: None,
+                labels: HashMap::new(),
+                requires_files: vec![],
+                missing_files_action: Default::default(),
+                cache_key: None,
+                cache_ttl_secs: None,
+                priority: None,
             },
             TaskConfig {
                 name: "task2".to_string(),
                 task_type: TaskType::Shell,
-                depends_on: vec!["task1".to_string()],
+                depends_on: vec!["task1".into()],
                 config: TaskExecutorConfig::Shell(ShellConfig {
                     command: "/bin/echo".to_string(),
                     args: vec!["Task 2".to_string()],
                     workdir: None,
+                    create_workdir: false,
                     env: None,
+                    env_file: None,
+                    fail_on_stderr_regex: None,
+                    shell: None,
                 }),
                 retry: Some(0),
                 timeout: Some(30),
-                continue_on_failure: false,
+                delay_before: None,
+                continue_on_failure: None,
+                outputs: false,
+                run_condition: Default::default(),
+                enabled: None,
+                labels: HashMap::new(),
+                requires_files: vec![],
+                missing_files_action: Default::default(),
+                cache_key: None,
+                cache_ttl_secs: None,
+                priority: None,
             },
             TaskConfig {
                 name: "task3".to_string(),
                 task_type: TaskType::Shell,
-                depends_on: vec!["task2".to_string()],
+                depends_on: vec!["task2".into()],
                 config: TaskExecutorConfig::Shell(ShellConfig {
                     command: "/bin/echo".to_string(),
                     args: vec!["Task 3".to_string()],
                     workdir: None,
+                    create_workdir: false,
                     env: None,
+                    env_file: None,
+                    fail_on_stderr_regex: None,
+                    shell: None,
                 }),
                 retry: Some(0),
                 timeout: Some(30),
-                continue_on_failure: false,
+                delay_before: None,
+                continue_on_failure: None,
+                outputs: false,
+                run_condition: Default::default(),
+                enabled: None,
+                labels: HashMap::new(),
+                requires_files: vec![],
+                missing_files_action: Default::default(),
+                cache_key: None,
+                cache_ttl_secs: None,
+                priority: None,
             },
         ],
     }
@@ -72,10 +130,24 @@ fn create_complex_dag_workflow() -> WorkflowConfig {
         name: "complex_dag".to_string(),
         description: Some("Complex 10-task DAG workflow".to_string()),
         schedule: None,
+        timezone: None,
+        labels: HashMap::new(),
+        allow_overlap: false,
+        on_complete: None,
+        params: HashMap::new(),
         config: WorkflowGlobalConfig {
             max_parallel: 4,
             retry_default: 0,
             timeout_default: 30,
+            retry_jitter: false,
+            max_retries_cap: 10,
+            continue_on_failure_default: false,
+            memory_limit_mb: None,
+            max_output_bytes: None,
+            store_output: OutputPolicy::Always,
+            backoff_strategy: Default::default(),
+            ssh_defaults: None,
+            max_depth: None,
         },
         tasks: vec![
             // Root task
@@ -87,146 +159,286 @@ fn create_complex_dag_workflow() -> WorkflowConfig {
                     command: "/bin/echo".to_string(),
                     args: vec!["Initialize".to_string()],
                     workdir: None,
+                    create_workdir: false,
                     env: None,
+                    env_file: None,
+                    fail_on_stderr_regex: None,
+                    shell: None,
                 }),
                 retry: Some(0),
                 timeout: Some(30),
-                continue_on_failure: false,
+                delay_before: None,
+                continue_on_failure: None,
+                outputs: false,
+                run_condition: Default::default(),
+                enabled: None,
+                labels: HashMap::new(),
+                requires_files: vec![],
+                missing_files_action: Default::default(),
+                cache_key: None,
+                cache_ttl_secs: None,
+                priority: None,
             },
             // Parallel branch 1
             TaskConfig {
                 name: "process_a1".to_string(),
                 task_type: TaskType::Shell,
-                depends_on: vec!["init".to_string()],
+                depends_on: vec!["init".into()],
                 config: TaskExecutorConfig::Shell(ShellConfig {
                     command: "/bin/echo".to_string(),
                     args: vec!["Process A1".to_string()],
                     workdir: None,
+                    create_workdir: false,
                     env: None,
+                    env_file: None,
+                    fail_on_stderr_regex: None,
+                    shell: None,
                 }),
                 retry: Some(0),
                 timeout: Some(30),
-                continue_on_failure: false,
+                delay_before: None,
+                continue_on_failure: None,
+                outputs: false,
+                run_condition: Default::default(),
+                enabled: None,
+                labels: HashMap::new(),
+                requires_files: vec![],
+                missing_files_action: Default::default(),
+                cache_key: None,
+                cache_ttl_secs: None,
+                priority: None,
             },
             TaskConfig {
                 name: "process_a2".to_string(),
                 task_type: TaskType::Shell,
-                depends_on: vec!["process_a1".to_string()],
+                depends_on: vec!["process_a1".into()],
                 config: TaskExecutorConfig::Shell(ShellConfig {
                     command: "/bin/echo".to_string(),
                     args: vec!["Process A2".to_string()],
                     workdir: None,
+                    create_workdir: false,
                     env: None,
+                    env_file: None,
+                    fail_on_stderr_regex: None,
+                    shell: None,
                 }),
                 retry: Some(0),
                 timeout: Some(30),
-                continue_on_failure: false,
+                delay_before: None,
+                continue_on_failure: None,
+                outputs: false,
+                run_condition: Default::default(),
+                enabled: None,
+                labels: HashMap::new(),
+                requires_files: vec![],
+                missing_files_action: Default::default(),
+                cache_key: None,
+                cache_ttl_secs: None,
+                priority: None,
             },
             // Parallel branch 2
             TaskConfig {
                 name: "process_b1".to_string(),
                 task_type: TaskType::Shell,
-                depends_on: vec!["init".to_string()],
+                depends_on: vec!["init".into()],
                 config: TaskExecutorConfig::Shell(ShellConfig {
                     command: "/bin/echo".to_string(),
                     args: vec!["Process B1".to_string()],
                     workdir: None,
+                    create_workdir: false,
                     env: None,
+                    env_file: None,
+                    fail_on_stderr_regex: None,
+                    shell: None,
                 }),
                 retry: Some(0),
                 timeout: Some(30),
-                continue_on_failure: false,
+                delay_before: None,
+                continue_on_failure: None,
+                outputs: false,
+                run_condition: Default::default(),
+                enabled: None,
+                labels: HashMap::new(),
+                requires_files: vec![],
+                missing_files_action: Default::default(),
+                cache_key: None,
+                cache_ttl_secs: None,
+                priority: None,
             },
             TaskConfig {
                 name: "process_b2".to_string(),
                 task_type: TaskType::Shell,
-                depends_on: vec!["process_b1".to_string()],
+                depends_on: vec!["process_b1".into()],
                 config: TaskExecutorConfig::Shell(ShellConfig {
                     command: "/bin/echo".to_string(),
                     args: vec!["Process B2".to_string()],
                     workdir: None,
+                    create_workdir: false,
                     env: None,
+                    env_file: None,
+                    fail_on_stderr_regex: None,
+                    shell: None,
                 }),
                 retry: Some(0),
                 timeout: Some(30),
-                continue_on_failure: false,
+                delay_before: None,
+                continue_on_failure: None,
+                outputs: false,
+                run_condition: Default::default(),
+                enabled: None,
+                labels: HashMap::new(),
+                requires_files: vec![],
+                missing_files_action: Default::default(),
+                cache_key: None,
+                cache_ttl_secs: None,
+                priority: None,
             },
             // Parallel branch 3
             TaskConfig {
                 name: "process_c1".to_string(),
                 task_type: TaskType::Shell,
-                depends_on: vec!["init".to_string()],
+                depends_on: vec!["init".into()],
                 config: TaskExecutorConfig::Shell(ShellConfig {
                     command: "/bin/echo".to_string(),
                     args: vec!["Process C1".to_string()],
                     workdir: None,
+                    create_workdir: false,
                     env: None,
+                    env_file: None,
+                    fail_on_stderr_regex: None,
+                    shell: None,
                 }),
                 retry: Some(0),
                 timeout: Some(30),
-                continue_on_failure: false,
+                delay_before: None,
+                continue_on_failure: None,
+                outputs: false,
+                run_condition: Default::default(),
+                enabled: None,
+                labels: HashMap::new(),
+                requires_files: vec![],
+                missing_files_action: Default::default(),
+                cache_key: None,
+                cache_ttl_secs: None,
+                priority: None,
             },
             TaskConfig {
                 name: "process_c2".to_string(),
                 task_type: TaskType::Shell,
-                depends_on: vec!["process_c1".to_string()],
+                depends_on: vec!["process_c1".into()],
                 config: TaskExecutorConfig::Shell(ShellConfig {
                     command: "/bin/echo".to_string(),
                     args: vec!["Process C2".to_string()],
                     workdir: None,
+                    create_workdir: false,
                     env: None,
+                    env_file: None,
+                    fail_on_stderr_regex: None,
+                    shell: None,
                 }),
                 retry: Some(0),
                 timeout: Some(30),
-                continue_on_failure: false,
+                delay_before: None,
+                continue_on_failure: None,
+                outputs: false,
+                run_condition: Default::default(),
+                enabled: None,
+                labels: HashMap::new(),
+                requires_files: vec![],
+                missing_files_action: Default::default(),
+                cache_key: None,
+                cache_ttl_secs: None,
+                priority: None,
             },
             // Convergence task
             TaskConfig {
                 name: "aggregate".to_string(),
                 task_type: TaskType::Shell,
                 depends_on: vec![
-                    "process_a2".to_string(),
-                    "process_b2".to_string(),
-                    "process_c2".to_string(),
+                    "process_a2".into(),
+                    "process_b2".into(),
+                    "process_c2".into(),
                 ],
                 config: TaskExecutorConfig::Shell(ShellConfig {
                     command: "/bin/echo".to_string(),
                     args: vec!["Aggregate".to_string()],
                     workdir: None,
+                    create_workdir: false,
                     env: None,
+                    env_file: None,
+                    fail_on_stderr_regex: None,
+                    shell: None,
                 }),
                 retry: Some(0),
                 timeout: Some(30),
-                continue_on_failure: false,
+                delay_before: None,
+                continue_on_failure: None,
+                outputs: false,
+                run_condition: Default::default(),
+                enabled: None,
+                labels: HashMap::new(),
+                requires_files: vec![],
+                missing_files_action: Default::default(),
+                cache_key: None,
+                cache_ttl_secs: None,
+                priority: None,
             },
             // Final tasks
             TaskConfig {
                 name: "validate".to_string(),
                 task_type: TaskType::Shell,
-                depends_on: vec!["aggregate".to_string()],
+                depends_on: vec!["aggregate".into()],
                 config: TaskExecutorConfig::Shell(ShellConfig {
                     command: "/bin/echo".to_string(),
                     args: vec!["Validate".to_string()],
                     workdir: None,
+                    create_workdir: false,
                     env: None,
+                    env_file: None,
+                    fail_on_stderr_regex: None,
+                    shell: None,
                 }),
                 retry: Some(0),
                 timeout: Some(30),
-                continue_on_failure: false,
+                delay_before: None,
+                continue_on_failure: None,
+                outputs: false,
+                run_condition: Default::default(),
+                enabled: None,
+                labels: HashMap::new(),
+                requires_files: vec![],
+                missing_files_action: Default::default(),
+                cache_key: None,
+                cache_ttl_secs: None,
+                priority: None,
             },
             TaskConfig {
                 name: "finalize".to_string(),
                 task_type: TaskType::Shell,
-                depends_on: vec!["validate".to_string()],
+                depends_on: vec!["validate".into()],
                 config: TaskExecutorConfig::Shell(ShellConfig {
                     command: "/bin/echo".to_string(),
                     args: vec!["Finalize".to_string()],
                     workdir: None,
+                    create_workdir: false,
                     env: None,
+                    env_file: None,
+                    fail_on_stderr_regex: None,
+                    shell: None,
                 }),
                 retry: Some(0),
                 timeout: Some(30),
-                continue_on_failure: false,
+                delay_before: None,
+                continue_on_failure: None,
+                outputs: false,
+                run_condition: Default::default(),
+                enabled: None,
+                labels: HashMap::new(),
+                requires_files: vec![],
+                missing_files_action: Default::default(),
+                cache_key: None,
+                cache_ttl_secs: None,
+                priority: None,
             },
         ],
     }
@@ -242,31 +454,61 @@ fn create_parallel_workflow(task_count: usize, max_parallel: usize) -> WorkflowC
             command: "/bin/echo".to_string(),
             args: vec!["Start".to_string()],
             workdir: None,
+            create_workdir: false,
             env: None,
+            env_file: None,
+            fail_on_stderr_regex: None,
+            shell: None,
         }),
         retry: Some(0),
         timeout: Some(30),
-        continue_on_failure: false,
+        delay_before: None,
+        continue_on_failure: None,
+        outputs: false,
+        run_condition: Default::default(),
+        enabled: None,
+        labels: HashMap::new(),
+        requires_files: vec![],
+        missing_files_action: Default::default(),
+        cache_key: None,
+        cache_ttl_secs: None,
+        priority: None,
     }];
 
     for i in 0..task_count {
         tasks.push(TaskConfig {
             name: format!("parallel{}", i),
             task_type: TaskType::Shell,
-            depends_on: vec!["start".to_string()],
+            depends_on: vec!["start".into()],
             config: TaskExecutorConfig::Shell(ShellConfig {
                 command: "/bin/echo".to_string(),
                 args: vec![format!("Parallel task {}", i)],
                 workdir: None,
+                create_workdir: false,
                 env: None,
+                env_file: None,
+                fail_on_stderr_regex: None,
+                shell: None,
             }),
             retry: Some(0),
             timeout: Some(30),
-            continue_on_failure: false,
+            delay_before: None,
+            continue_on_failure: None,
+            outputs: false,
+            run_condition: Default::default(),
+            enabled: None,
+            labels: HashMap::new(),
+            requires_files: vec![],
+            missing_files_action: Default::default(),
+            cache_key: None,
+            cache_ttl_secs: None,
+            priority: None,
         });
     }
 
-    let parallel_deps: Vec<String> = (0..task_count).map(|i| format!("parallel{}", i)).collect();
+    let parallel_deps: Vec<Dependency> = (0..task_count)
+        .map(|i| format!("parallel{}", i).into())
+        .collect();
     tasks.push(TaskConfig {
         name: "finish".to_string(),
         task_type: TaskType::Shell,
@@ -275,21 +517,49 @@ fn create_parallel_workflow(task_count: usize, max_parallel: usize) -> WorkflowC
             command: "/bin/echo".to_string(),
             args: vec!["Finish".to_string()],
             workdir: None,
+            create_workdir: false,
             env: None,
+            env_file: None,
+            fail_on_stderr_regex: None,
+            shell: None,
         }),
         retry: Some(0),
         timeout: Some(30),
-        continue_on_failure: false,
+        delay_before: None,
+        continue_on_failure: None,
+        outputs: false,
+        run_condition: Default::default(),
+        enabled: None,
+        labels: HashMap::new(),
+        requires_files: vec![],
+        missing_files_action: Default::default(),
+        cache_key: None,
+        cache_ttl_secs: None,
+        priority: None,
     });
 
     WorkflowConfig {
         name: format!("parallel_{}_tasks", task_count),
         description: Some(format!("{} parallel tasks", task_count)),
         schedule: None,
+        timezone: None,
+        labels: HashMap::new(),
+        allow_overlap: false,
+        on_complete: None,
+        params: HashMap::new(),
         config: WorkflowGlobalConfig {
             max_parallel,
             retry_default: 0,
             timeout_default: 30,
+            retry_jitter: false,
+            max_retries_cap: 10,
+            continue_on_failure_default: false,
+            memory_limit_mb: None,
+            max_output_bytes: None,
+            store_output: OutputPolicy::Always,
+            backoff_strategy: Default::default(),
+            ssh_defaults: None,
+            max_depth: None,
         },
         tasks,
     }
@@ -303,7 +573,7 @@ fn create_large_sequential_workflow(task_count: usize) -> WorkflowConfig {
         let depends_on = if i == 0 {
             vec![]
         } else {
-            vec![format!("task{}", i - 1)]
+            vec![format!("task{}", i - 1).into()]
         };
 
         tasks.push(TaskConfig {
@@ -314,11 +584,25 @@ fn create_large_sequential_workflow(task_count: usize) -> WorkflowConfig {
                 command: "/bin/true".to_string(),
                 args: vec![],
                 workdir: None,
+                create_workdir: false,
                 env: None,
+                env_file: None,
+                fail_on_stderr_regex: None,
+                shell: None,
             }),
             retry: Some(0),
             timeout: Some(30),
-            continue_on_failure: false,
+            delay_before: None,
+            continue_on_failure: None,
+            outputs: false,
+            run_condition: Default::default(),
+            enabled: None,
+            labels: HashMap::new(),
+            requires_files: vec![],
+            missing_files_action: Default::default(),
+            cache_key: None,
+            cache_ttl_secs: None,
+            priority: None,
         });
     }
 
@@ -326,10 +610,24 @@ fn create_large_sequential_workflow(task_count: usize) -> WorkflowConfig {
         name: format!("sequential_{}_tasks", task_count),
         description: Some(format!("{} sequential tasks", task_count)),
         schedule: None,
+        timezone: None,
+        labels: HashMap::new(),
+        allow_overlap: false,
+        on_complete: None,
+        params: HashMap::new(),
         config: WorkflowGlobalConfig {
             max_parallel: 1,
             retry_default: 0,
             timeout_default: 30,
+            retry_jitter: false,
+            max_retries_cap: 10,
+            continue_on_failure_default: false,
+            memory_limit_mb: None,
+            max_output_bytes: None,
+            store_output: OutputPolicy::Always,
+            backoff_strategy: Default::default(),
+            ssh_defaults: None,
+            max_depth: None,
         },
         tasks,
     }
@@ -355,7 +653,7 @@ fn bench_simple_sequential_workflow(c: &mut Criterion) {
             |(scheduler, workflow, _temp_dir)| {
                 rt.block_on(async move {
                     let success = scheduler
-                        .execute_workflow(black_box(&workflow))
+                        .execute_workflow_ok(black_box(&workflow))
                         .await
                         .unwrap();
                     assert!(success);
@@ -388,7 +686,7 @@ fn bench_complex_dag_workflow(c: &mut Criterion) {
             |(scheduler, workflow, _temp_dir)| {
                 rt.block_on(async move {
                     let success = scheduler
-                        .execute_workflow(black_box(&workflow))
+                        .execute_workflow_ok(black_box(&workflow))
                         .await
                         .unwrap();
                     assert!(success);
@@ -429,7 +727,7 @@ fn bench_parallel_workflow_execution(c: &mut Criterion) {
                     |(scheduler, workflow, _temp_dir)| {
                         rt.block_on(async move {
                             let success = scheduler
-                                .execute_workflow(black_box(&workflow))
+                                .execute_workflow_ok(black_box(&workflow))
                                 .await
                                 .unwrap();
                             assert!(success);
@@ -471,7 +769,7 @@ fn bench_large_sequential_workflow(c: &mut Criterion) {
                     |(scheduler, workflow, _temp_dir)| {
                         rt.block_on(async move {
                             let success = scheduler
-                                .execute_workflow(black_box(&workflow))
+                                .execute_workflow_ok(black_box(&workflow))
                                 .await
                                 .unwrap();
                             assert!(success);
@@ -643,7 +941,7 @@ tasks:
                 let workflow: WorkflowConfig =
                     serde_yaml::from_str(black_box(workflow_yaml)).unwrap();
                 // Execute workflow
-                let success = scheduler.execute_workflow(&workflow).await.unwrap();
+                let success = scheduler.execute_workflow_ok(&workflow).await.unwrap();
                 assert!(success);
             },
             criterion::BatchSize::SmallInput,
@@ -1,15 +1,29 @@
 //! CLI interface for PicoFlow
 
+use crate::cron_scheduler::{
+    describe_schedule_in, parse_timezone, validate_cron_expression, SchedulerStatus,
+};
 use crate::dag::DagEngine;
-use crate::logging::{init_logging, LogConfig, LogFormat, LogLevel};
-use crate::parser::parse_workflow_file;
+use crate::error::{PicoFlowError, ValidationError};
+use crate::logging::{init_logging, LogConfig, LogFormat, LogLevel, LogLevelHandle};
+use crate::models::{
+    ExportFormat, TaskExecution, TaskStatus, WorkflowConfig, WorkflowExecution, MAX_YAML_SIZE,
+};
+use crate::parser::{
+    load_workflow_file, parse_workflow_file, parse_workflow_file_with_params,
+    parse_workflow_source_with_params, parse_workflow_yaml_with_params, validate_workflow,
+};
 use crate::scheduler::TaskScheduler;
 use crate::state::StateManager;
 use crate::templates;
-use clap::{Parser, Subcommand, ValueEnum};
-use std::path::PathBuf;
+use clap::{ArgAction, Parser, Subcommand, ValueEnum};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::ffi::OsString;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use tracing::{error, info};
+use tracing::{error, info, warn};
 
 /// PicoFlow - Lightweight DAG workflow orchestrator for edge devices
 #[derive(Parser, Debug)]
@@ -21,24 +35,71 @@ pub struct Cli {
     #[arg(short, long, default_value = "info", global = true)]
     pub log_level: String,
 
-    /// Log format (json or pretty)
-    #[arg(long, default_value = "json", global = true)]
+    /// More verbose logging: -v debug, -vv trace (overrides --log-level)
+    #[arg(short, long, action = ArgAction::Count, global = true, conflicts_with = "quiet")]
+    pub verbose: u8,
+
+    /// Only log errors (overrides --log-level)
+    #[arg(short, long, global = true)]
+    pub quiet: bool,
+
+    /// Log format (text or json)
+    #[arg(long, default_value = "text", global = true)]
     pub log_format: String,
 
     /// Database path for state persistence
     #[arg(long, default_value = "picoflow.db", global = true)]
     pub db_path: PathBuf,
 
+    /// Output format for `workflow list`, `history` and `status`
+    #[arg(long, value_enum, default_value = "table", global = true)]
+    pub output: OutputFormat,
+
     #[command(subcommand)]
     pub command: Commands,
+
+    /// Changes the log level at runtime; set by [`Cli::init_logging`]
+    #[arg(skip)]
+    pub log_level_handle: Option<LogLevelHandle>,
 }
 
 #[derive(Subcommand, Debug)]
 pub enum Commands {
     /// Execute a workflow once
     Run {
-        /// Path to workflow YAML file
+        /// Path to workflow YAML file, or `-` to read it from stdin
         workflow: PathBuf,
+
+        /// Detached Ed25519 signature of the workflow file; refuse to run on mismatch
+        #[arg(long, value_name = "SIG_FILE", requires = "pubkey")]
+        verify_sig: Option<PathBuf>,
+
+        /// Ed25519 public key used with --verify-sig (PEM, DER, or raw 32 bytes)
+        #[arg(long, value_name = "KEY_FILE", requires = "verify_sig")]
+        pubkey: Option<PathBuf>,
+
+        /// Stream shell task output to DIR/<execution_id>/<task>.log while tasks run
+        #[arg(long, value_name = "DIR")]
+        task_log_dir: Option<PathBuf>,
+
+        /// Set a variable for `${VAR}` expansion in the workflow, overriding the process
+        /// environment (repeatable)
+        #[arg(long = "env", value_name = "KEY=VALUE", value_parser = parse_env_assignment)]
+        env: Vec<(String, String)>,
+
+        /// Supply a value for a param declared under the workflow's `params`, referenced
+        /// as `${params.NAME}` (repeatable)
+        #[arg(short = 'p', long = "param", value_name = "NAME=VALUE", value_parser = parse_env_assignment)]
+        params: Vec<(String, String)>,
+
+        /// Run at most N tasks at once, overriding the workflow's `config.max_parallel`
+        #[arg(long, value_name = "N", value_parser = parse_max_parallel)]
+        max_parallel: Option<usize>,
+
+        /// Re-run the workflow whenever the file changes (until Ctrl-C)
+        #[cfg(feature = "watch")]
+        #[arg(long)]
+        watch: bool,
     },
 
     /// Validate workflow YAML and DAG
@@ -47,6 +108,25 @@ pub enum Commands {
         workflow: PathBuf,
     },
 
+    /// Print the workflow DAG as a graph (graphviz DOT or Mermaid)
+    Graph {
+        /// Path to workflow YAML file
+        workflow: PathBuf,
+
+        /// Output format
+        #[arg(long, value_enum, default_value = "dot")]
+        format: GraphFormat,
+    },
+
+    /// Explain how a task fits into its workflow: dependencies, level and limits
+    Explain {
+        /// Path to workflow YAML file
+        workflow: PathBuf,
+
+        /// Task name
+        task: String,
+    },
+
     /// Show workflow execution status
     Status {
         /// Workflow name (optional, shows all if not specified)
@@ -69,6 +149,16 @@ pub enum Commands {
         command: DaemonCommands,
     },
 
+    /// Run a daemon-scheduled workflow now, outside its schedule
+    Trigger {
+        /// Workflow name
+        workflow: String,
+
+        /// Path of the daemon's control socket
+        #[arg(long, default_value = "/tmp/picoflow.sock")]
+        socket: PathBuf,
+    },
+
     /// Show workflow execution history
     History {
         /// Workflow name
@@ -91,12 +181,12 @@ pub enum Commands {
         workflow: String,
     },
 
-    /// Show task execution logs
+    /// Show the stored stdout/stderr of an execution's tasks
     Logs {
-        /// Workflow name
-        workflow: String,
+        /// Execution ID, or a workflow name to show its latest execution
+        target: String,
 
-        /// Execution ID (optional, shows latest if not specified)
+        /// Execution ID of the named workflow (instead of its latest execution)
         #[arg(short, long)]
         execution_id: Option<i64>,
 
@@ -105,14 +195,42 @@ pub enum Commands {
         task: Option<String>,
     },
 
+    /// Export stored execution history for offline analysis
+    Export {
+        /// File format
+        #[arg(long, value_enum, default_value = "ndjson")]
+        format: ExportFormatArg,
+
+        /// Write to this file instead of stdout
+        #[arg(long, value_name = "FILE")]
+        out: Option<PathBuf>,
+
+        /// Include each execution's task attempts (and their output)
+        #[arg(long)]
+        tasks: bool,
+    },
+
+    /// State database maintenance
+    Db {
+        #[command(subcommand)]
+        command: DbCommands,
+    },
+
+    /// Create a project layout (workflows/, state/) with an example workflow
+    Init {
+        /// Project directory (created if missing)
+        #[arg(default_value = ".")]
+        dir: PathBuf,
+    },
+
     /// Generate example workflow YAML templates
     Template {
         /// Template type (omit to list available templates)
         #[arg(short = 't', long = "type")]
         template_type: Option<TemplateType>,
 
-        /// Write output to a file instead of stdout
-        #[arg(short, long)]
+        /// Write output to a file instead of stdout (also accepted as `--output`)
+        #[arg(short = 'o', long = "output-file", id = "output_file")]
         output: Option<PathBuf>,
     },
 }
@@ -132,22 +250,128 @@ pub enum TemplateType {
     Full,
 }
 
+/// Output formats for the `graph` subcommand.
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum GraphFormat {
+    /// Graphviz DOT (render with `dot -Tsvg`)
+    Dot,
+    /// Mermaid flowchart (renders in GitHub/GitLab markdown)
+    Mermaid,
+}
+
+/// `--format` values for the `export` subcommand, mirroring [`ExportFormat`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ExportFormatArg {
+    /// Comma-separated values with a header row
+    Csv,
+    /// One JSON object per line
+    Ndjson,
+}
+
+impl From<ExportFormatArg> for ExportFormat {
+    fn from(format: ExportFormatArg) -> Self {
+        match format {
+            ExportFormatArg::Csv => ExportFormat::Csv,
+            ExportFormatArg::Ndjson => ExportFormat::Ndjson,
+        }
+    }
+}
+
+/// Output formats for commands that report stored state.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// Human-readable tables
+    Table,
+    /// JSON for scripting (serialized model structs)
+    Json,
+}
+
+/// A workflow execution together with its task records, as emitted by
+/// `picoflow status <workflow> --output json`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExecutionStatus {
+    #[serde(flatten)]
+    pub execution: WorkflowExecution,
+    pub tasks: Vec<TaskExecution>,
+}
+
+/// The daemon's state as emitted by `picoflow daemon status --output json`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DaemonStatus {
+    /// Whether the PID file names a running process
+    pub running: bool,
+    /// PID of the running daemon
+    pub pid: Option<u32>,
+    /// Live scheduler state, absent if the daemon is not running or did not answer
+    pub scheduler: Option<SchedulerStatus>,
+}
+
 #[derive(Subcommand, Debug)]
 pub enum WorkflowCommands {
     /// List all workflows with execution statistics
     List,
 }
 
+#[derive(Subcommand, Debug)]
+pub enum DbCommands {
+    /// Reclaim space freed by pruned executions (briefly blocks writes)
+    Compact,
+}
+
 #[derive(Subcommand, Debug)]
 pub enum DaemonCommands {
     /// Start daemon in background with scheduled workflows
     Start {
         /// Path to workflow YAML file (must have schedule defined)
-        workflow: PathBuf,
+        #[arg(required_unless_present = "workflows_dir")]
+        workflow: Option<PathBuf>,
+
+        /// Also load every scheduled *.yaml/*.yml workflow in this directory
+        #[arg(long)]
+        workflows_dir: Option<PathBuf>,
 
         /// Path to PID file
         #[arg(long, default_value = "/tmp/picoflow.pid")]
         pid_file: PathBuf,
+
+        /// Maximum number of workflows running at once (default: unlimited)
+        #[arg(long, value_parser = clap::value_parser!(u64).range(1..))]
+        max_concurrent_workflows: Option<u64>,
+
+        /// Skip scheduled runs of a workflow after this many consecutive failures
+        /// (default: never)
+        #[arg(long, value_parser = clap::value_parser!(u32).range(1..))]
+        circuit_breaker_threshold: Option<u32>,
+
+        /// Skip workflow runs while less than this many MiB are free on the database's
+        /// filesystem (default: no check)
+        #[arg(long, value_parser = clap::value_parser!(u64).range(1..))]
+        min_free_disk_mb: Option<u64>,
+
+        /// Path of the control socket used by `picoflow trigger`
+        #[arg(long, default_value = "/tmp/picoflow.sock")]
+        control_socket: PathBuf,
+
+        /// Serve Prometheus metrics on http://127.0.0.1:<PORT>/metrics (0 picks a free
+        /// port); a port that is in use is logged and skipped
+        #[cfg(feature = "metrics")]
+        #[arg(long)]
+        metrics_port: Option<u16>,
+
+        /// Push metrics to this Prometheus Pushgateway URL (e.g. http://pushgateway:9091)
+        #[cfg(feature = "metrics")]
+        #[arg(long)]
+        push_gateway: Option<String>,
+
+        /// Pushgateway job name
+        #[cfg(feature = "metrics")]
+        #[arg(long, default_value = "picoflow")]
+        push_job: String,
+
+        /// Seconds between Pushgateway pushes
+        #[cfg(feature = "metrics")]
+        #[arg(long, default_value = "60", value_parser = clap::value_parser!(u64).range(1..))]
+        push_interval: u64,
     },
 
     /// Stop running daemon
@@ -162,35 +386,119 @@ pub enum DaemonCommands {
         /// Path to PID file
         #[arg(long, default_value = "/tmp/picoflow.pid")]
         pid_file: PathBuf,
+
+        /// Path of the daemon's control socket, queried for live status
+        #[arg(long, default_value = "/tmp/picoflow.sock")]
+        control_socket: PathBuf,
     },
 }
 
 impl Cli {
+    /// Parse command-line arguments, accepting `template --output <PATH>` for
+    /// `template --output-file <PATH>`
+    ///
+    /// clap propagates the global `--output` format flag into every subcommand, so
+    /// `template` cannot declare `--output` itself; the alias is rewritten here instead.
+    pub fn parse_args<I, T>(args: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<OsString>,
+    {
+        Self::parse_from(template_output_alias(args))
+    }
+
+    /// Log level selected by `-q`/`-v`, falling back to `--log-level`
+    pub fn resolved_log_level(&self) -> LogLevel {
+        match (self.quiet, self.verbose) {
+            (true, _) => LogLevel::Error,
+            (false, 0) => self.log_level.as_str().into(),
+            (false, 1) => LogLevel::Debug,
+            (false, _) => LogLevel::Trace,
+        }
+    }
+
     /// Initialize logging based on CLI arguments
-    pub fn init_logging(&self) -> anyhow::Result<()> {
-        let log_level: LogLevel = self.log_level.as_str().into();
-        let log_format = match self.log_format.as_str() {
-            "pretty" => LogFormat::Pretty,
-            _ => LogFormat::Json,
-        };
+    pub fn init_logging(&mut self) -> anyhow::Result<()> {
+        let log_level = self.resolved_log_level();
+        let log_format: LogFormat = self.log_format.as_str().into();
 
         let config = LogConfig {
             level: log_level,
             format: log_format,
         };
 
-        init_logging(&config)
+        self.log_level_handle = Some(init_logging(&config)?);
+        Ok(())
     }
 
     /// Execute the CLI command
     pub async fn execute(&self) -> anyhow::Result<()> {
         match &self.command {
-            Commands::Run { workflow } => {
-                self.run_workflow(workflow).await?;
+            Commands::Run {
+                workflow,
+                verify_sig,
+                pubkey,
+                task_log_dir,
+                env,
+                params,
+                max_parallel,
+                #[cfg(feature = "watch")]
+                watch,
+            } => {
+                let env: HashMap<String, String> = env.iter().cloned().collect();
+                let params: HashMap<String, String> = params.iter().cloned().collect();
+                #[cfg(feature = "watch")]
+                if *watch {
+                    // Edits would be run unverified, so watching can't honour a signature
+                    if verify_sig.is_some() {
+                        anyhow::bail!("--verify-sig cannot be combined with --watch");
+                    }
+                    if is_stdin(workflow) {
+                        anyhow::bail!("--watch needs a workflow file, not stdin");
+                    }
+                    return self
+                        .watch_workflow(
+                            workflow,
+                            &env,
+                            &params,
+                            *max_parallel,
+                            task_log_dir.as_deref(),
+                        )
+                        .await;
+                }
+                // The verified bytes are parsed as-is so the file can't be swapped in between
+                let verified = match (verify_sig, pubkey) {
+                    (Some(sig), Some(key)) => {
+                        if is_stdin(workflow) {
+                            anyhow::bail!("--verify-sig needs a workflow file, not stdin");
+                        }
+                        let content = crate::integrity::verify_file(workflow, sig, key)?;
+                        info!("Signature of {:?} verified", workflow);
+                        Some(String::from_utf8(content).map_err(|_| {
+                            anyhow::anyhow!("Workflow file {:?} is not valid UTF-8", workflow)
+                        })?)
+                    }
+                    _ => None,
+                };
+                self.run_workflow(
+                    workflow,
+                    verified.as_deref(),
+                    &env,
+                    &params,
+                    *max_parallel,
+                    task_log_dir.as_deref(),
+                )
+                .await?;
             }
             Commands::Validate { workflow } => {
                 self.validate_workflow(workflow)?;
             }
+            Commands::Graph { workflow, format } => {
+                self.print_graph(workflow, *format)?;
+            }
+            Commands::Explain { workflow, task } => {
+                self.explain_task(&mut std::io::stdout(), workflow, task)?;
+            }
             Commands::Status { workflow, limit } => {
                 self.show_status(workflow.as_deref(), *limit).await?;
             }
@@ -200,6 +508,12 @@ impl Cli {
             Commands::Daemon { command } => {
                 self.handle_daemon_command(command).await?;
             }
+            Commands::Trigger { workflow, socket } => {
+                self.trigger_workflow(workflow, socket).await?;
+            }
+            Commands::Db { command } => {
+                self.handle_db_command(command).await?;
+            }
             Commands::History {
                 workflow,
                 status,
@@ -212,11 +526,20 @@ impl Cli {
                 self.show_stats(workflow).await?;
             }
             Commands::Logs {
-                workflow,
+                target,
                 execution_id,
                 task,
             } => {
-                self.show_logs(workflow, *execution_id, task.as_deref())
+                self.show_logs(
+                    &mut std::io::stdout(),
+                    target,
+                    *execution_id,
+                    task.as_deref(),
+                )
+                .await?;
+            }
+            Commands::Export { format, out, tasks } => {
+                self.export_executions((*format).into(), out.as_deref(), *tasks)
                     .await?;
             }
             Commands::Template {
@@ -225,17 +548,40 @@ impl Cli {
             } => {
                 self.handle_template(template_type.as_ref(), output.as_ref())?;
             }
+            Commands::Init { dir } => {
+                self.init_project(&mut std::io::stdout(), dir)?;
+            }
         }
         Ok(())
     }
 
     /// Run a workflow once
-    async fn run_workflow(&self, workflow_path: &PathBuf) -> anyhow::Result<()> {
+    ///
+    /// `verified` is the file's content when its signature was checked; it is parsed
+    /// instead of reading `workflow_path` again.
+    async fn run_workflow(
+        &self,
+        workflow_path: &PathBuf,
+        verified: Option<&str>,
+        env: &HashMap<String, String>,
+        params: &HashMap<String, String>,
+        max_parallel: Option<usize>,
+        task_log_dir: Option<&std::path::Path>,
+    ) -> anyhow::Result<()> {
         info!("Loading workflow from: {:?}", workflow_path);
 
         // Parse workflow
-        let config = parse_workflow_file(workflow_path)?;
+        let mut config = match verified {
+            Some(content) => {
+                parse_workflow_source_with_params(content, workflow_path, env, params)?
+            }
+            None => load_workflow_arg(workflow_path, env, params, std::io::stdin().lock())?,
+        };
         info!("Workflow '{}' loaded successfully", config.name);
+        if let Some(max_parallel) = max_parallel {
+            info!("Overriding max_parallel with {}", max_parallel);
+            config.config.max_parallel = max_parallel;
+        }
 
         // Validate DAG
         let dag = DagEngine::build(&config.tasks)?;
@@ -252,8 +598,23 @@ impl Cli {
         }
 
         // Create scheduler and execute
-        let scheduler = TaskScheduler::new(state_manager);
-        let success = scheduler.execute_workflow(&config).await?;
+        let mut scheduler = TaskScheduler::new(state_manager);
+        if let Some(dir) = task_log_dir {
+            scheduler.set_task_log_dir(dir.to_path_buf());
+            prune_task_logs(&scheduler).await;
+        }
+        // Shell commands run in their own process groups and never see the terminal's
+        // Ctrl-C, so turn it into a cancellation that terminates them
+        let (stop_tx, stop_rx) = tokio::sync::watch::channel(false);
+        tokio::spawn(async move {
+            if tokio::signal::ctrl_c().await.is_ok() {
+                let _ = stop_tx.send(true);
+            }
+        });
+        let success = scheduler
+            .execute_workflow_with_shutdown(&config, stop_rx)
+            .await?
+            .succeeded();
 
         if success {
             info!("Workflow completed successfully");
@@ -264,17 +625,75 @@ impl Cli {
         }
     }
 
-    /// Validate a workflow without executing
+    /// Run a workflow now and after every change to its file, until Ctrl-C
+    #[cfg(feature = "watch")]
+    async fn watch_workflow(
+        &self,
+        workflow_path: &std::path::Path,
+        env: &HashMap<String, String>,
+        params: &HashMap<String, String>,
+        max_parallel: Option<usize>,
+        task_log_dir: Option<&std::path::Path>,
+    ) -> anyhow::Result<()> {
+        let state_manager = Arc::new(StateManager::new(&self.db_path).await?);
+        let crashed = state_manager.recover_from_crash().await?;
+        if !crashed.is_empty() {
+            info!("Recovered {} crashed executions", crashed.len());
+        }
+        let mut scheduler = TaskScheduler::new(state_manager);
+        if let Some(dir) = task_log_dir {
+            scheduler.set_task_log_dir(dir.to_path_buf());
+            prune_task_logs(&scheduler).await;
+        }
+
+        // Ctrl-C stops watching and cancels a run in progress
+        let (stop_tx, stop_rx) = tokio::sync::watch::channel(false);
+        tokio::spawn(async move {
+            if tokio::signal::ctrl_c().await.is_ok() {
+                let _ = stop_tx.send(true);
+            }
+        });
+
+        println!(
+            "Watching {} for changes (Ctrl-C to stop)",
+            workflow_path.display()
+        );
+        crate::watch::watch_workflow(
+            workflow_path,
+            env,
+            params,
+            max_parallel,
+            &scheduler,
+            stop_rx,
+            |run| println!("[{}] {}", chrono::Local::now().format("%H:%M:%S"), run),
+        )
+        .await?;
+        println!("Stopped watching");
+
+        Ok(())
+    }
+
+    /// Validate a workflow without executing, reporting every problem found
     fn validate_workflow(&self, workflow_path: &PathBuf) -> anyhow::Result<()> {
         info!("Validating workflow: {:?}", workflow_path);
 
-        // Parse workflow
-        let config = parse_workflow_file(workflow_path)?;
+        // Unreadable or malformed YAML leaves nothing to check, so fail immediately
+        let config = load_workflow_file(workflow_path)?;
         info!("Workflow '{}' parsed successfully", config.name);
 
-        // Validate DAG
+        let name = config.name.clone();
+        let config = match check_workflow(config) {
+            Ok(config) => config,
+            Err(errors) => {
+                println!("Workflow '{}' is invalid ({} errors):", name, errors.len());
+                for (i, problem) in errors.iter().enumerate() {
+                    println!("  {}. {}", i + 1, problem);
+                }
+                std::process::exit(1);
+            }
+        };
+
         let dag = DagEngine::build(&config.tasks)?;
-        dag.validate_acyclic()?;
 
         let execution_order = dag.topological_sort()?;
 
@@ -285,6 +704,110 @@ impl Cli {
         println!("Workflow '{}' is valid", config.name);
         println!("Tasks: {}", config.tasks.len());
         println!("Execution order: {}", execution_order.join(" -> "));
+        println!("Entry points: {}", dag.roots().join(", "));
+        println!("Terminal tasks: {}", dag.leaves().join(", "));
+        if let Some(schedule) = &config.schedule {
+            let timezone = match &config.timezone {
+                Some(name) => parse_timezone(name)?,
+                None => chrono_tz::Tz::UTC,
+            };
+            let runs = describe_schedule_in(schedule, timezone, 3)?
+                .iter()
+                .map(|run| {
+                    run.with_timezone(&timezone)
+                        .format("%Y-%m-%d %H:%M:%S %Z")
+                        .to_string()
+                })
+                .collect::<Vec<_>>();
+            println!("Next {} runs: {}", runs.len(), runs.join(", "));
+        }
+
+        // In a workflow that uses dependencies, a task with neither dependencies nor
+        // dependents is often a forgotten `depends_on`
+        if config.tasks.iter().any(|t| !t.depends_on.is_empty()) {
+            let leaves = dag.leaves();
+            for task in dag.roots().iter().filter(|t| leaves.contains(t)) {
+                println!(
+                    "Warning: task '{}' has no dependencies and no dependents",
+                    task
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Print the workflow DAG in the requested graph format
+    fn print_graph(&self, workflow_path: &PathBuf, format: GraphFormat) -> anyhow::Result<()> {
+        let config = parse_workflow_file(workflow_path)?;
+        let dag = DagEngine::build(&config.tasks)?;
+
+        let graph = match format {
+            GraphFormat::Dot => dag.to_dot(),
+            GraphFormat::Mermaid => dag.to_mermaid(),
+        };
+        print!("{}", graph);
+
+        Ok(())
+    }
+
+    /// Print where a task sits in the workflow DAG and the retry/timeout it runs with
+    fn explain_task(
+        &self,
+        out: &mut impl Write,
+        workflow_path: &PathBuf,
+        task_name: &str,
+    ) -> anyhow::Result<()> {
+        let config = parse_workflow_file(workflow_path)?;
+        let task = config
+            .tasks
+            .iter()
+            .find(|t| t.name == task_name)
+            .ok_or_else(|| {
+                anyhow::anyhow!(
+                    "Task '{}' not found in workflow '{}'",
+                    task_name,
+                    config.name
+                )
+            })?;
+        let dag = DagEngine::build(&config.tasks)?;
+
+        let list = |names: Vec<String>| {
+            if names.is_empty() {
+                "(none)".to_string()
+            } else {
+                names.join(", ")
+            }
+        };
+        let level = dag
+            .parallel_levels()
+            .iter()
+            .position(|level| level.iter().any(|t| t == task_name))
+            .unwrap_or(0);
+
+        writeln!(out, "Task: {}", task.name)?;
+        writeln!(
+            out,
+            "Type: {}",
+            format!("{:?}", task.task_type).to_lowercase()
+        )?;
+        writeln!(out, "Depends on: {}", list(dag.get_dependencies(task_name)))?;
+        writeln!(out, "Dependents: {}", list(dag.get_dependents(task_name)))?;
+        writeln!(out, "Ancestors: {}", list(dag.ancestors(task_name)))?;
+        writeln!(out, "Parallel level: {}", level)?;
+
+        let retries = task.effective_retry(&config.config);
+        let requested = task.retry.unwrap_or(config.config.retry_default);
+        if retries < requested {
+            writeln!(
+                out,
+                "Retries: {} (capped from {} by max_retries_cap)",
+                retries, requested
+            )?;
+        } else {
+            writeln!(out, "Retries: {}", retries)?;
+        }
+        writeln!(out, "Timeout: {}s", task.effective_timeout(&config.config))?;
 
         Ok(())
     }
@@ -347,14 +870,83 @@ impl Cli {
         Ok(())
     }
 
+    /// Handle the `init` subcommand: create `workflows/` and `state/` under `dir` and
+    /// write the `minimal` template to `workflows/example.yaml`
+    ///
+    /// Existing directories are reused, but an existing example file is never
+    /// overwritten.
+    fn init_project(&self, out: &mut impl Write, dir: &Path) -> anyhow::Result<()> {
+        use std::fs::{self, OpenOptions};
+
+        let workflows_dir = dir.join("workflows");
+        let state_dir = dir.join("state");
+        let example = workflows_dir.join("example.yaml");
+
+        fs::create_dir_all(&workflows_dir)?;
+        fs::create_dir_all(&state_dir)?;
+
+        let root = dir.canonicalize()?;
+        let db_path = root.join("state").join("picoflow.db");
+        let template = templates::get_template("minimal")
+            .ok_or_else(|| anyhow::anyhow!("Unknown template type: minimal"))?;
+        let content = format!(
+            "# Run once:\n\
+             #   picoflow --db-path {db} run {example}\n\
+             # Run on a schedule (add a `schedule:` first), e.g. as a systemd ExecStart=:\n\
+             #   picoflow --db-path {db} daemon start --workflows-dir {workflows}\n\
+             {template}",
+            db = db_path.display(),
+            example = root.join("workflows").join("example.yaml").display(),
+            workflows = root.join("workflows").display(),
+        );
+
+        let mut file = OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&example)
+            .map_err(|e| {
+                if e.kind() == std::io::ErrorKind::AlreadyExists {
+                    anyhow::anyhow!(
+                        "File '{}' already exists. Remove it first or choose a different directory.",
+                        example.display()
+                    )
+                } else {
+                    e.into()
+                }
+            })?;
+        file.write_all(content.as_bytes())?;
+
+        writeln!(out, "Created {}", workflows_dir.display())?;
+        writeln!(out, "Created {}", state_dir.display())?;
+        writeln!(out, "Wrote example workflow to {}", example.display())?;
+        writeln!(out)?;
+        writeln!(
+            out,
+            "Try it: picoflow --db-path {} run {}",
+            db_path.display(),
+            example.display()
+        )?;
+        Ok(())
+    }
+
     /// Show execution status
     async fn show_status(&self, workflow_name: Option<&str>, limit: usize) -> anyhow::Result<()> {
-        let state_manager = StateManager::new(&self.db_path).await?;
+        let state_manager = StateManager::new_readonly(&self.db_path).await?;
 
         if let Some(name) = workflow_name {
             // Show status for specific workflow
             let history = state_manager.get_execution_history(name, limit).await?;
 
+            if self.output == OutputFormat::Json {
+                let mut statuses = Vec::with_capacity(history.len());
+                for execution in history {
+                    let tasks = state_manager.get_task_executions(execution.id).await?;
+                    statuses.push(ExecutionStatus { execution, tasks });
+                }
+                println!("{}", serde_json::to_string_pretty(&statuses)?);
+                return Ok(());
+            }
+
             println!("Workflow: {}", name);
             println!("Recent executions (limit {}):", limit);
             println!();
@@ -381,13 +973,22 @@ impl Cli {
                 let tasks = state_manager.get_task_executions(exec.id).await?;
                 println!("  Tasks:");
                 for task in tasks {
-                    println!(
-                        "    - {} [{}] (attempt {})",
-                        task.task_name, task.status, task.attempt
-                    );
+                    if task.status == TaskStatus::Skipped {
+                        println!("    - {} [{}]", task.task_name, task.status);
+                    } else {
+                        println!(
+                            "    - {} [{}] (attempt {})",
+                            task.task_name, task.status, task.attempt
+                        );
+                    }
                 }
                 println!();
             }
+        } else if self.output == OutputFormat::Json {
+            // Keep stdout parseable; the hint is for humans
+            eprintln!(
+                "Use: picoflow status <name> to see execution history for a specific workflow"
+            );
         } else {
             println!(
                 "Use: picoflow status <name> to see execution history for a specific workflow"
@@ -400,28 +1001,34 @@ impl Cli {
     /// Handle workflow management commands
     async fn handle_workflow_command(&self, command: &WorkflowCommands) -> anyhow::Result<()> {
         match command {
-            WorkflowCommands::List => self.list_workflows().await?,
+            WorkflowCommands::List => self.list_workflows(&mut std::io::stdout()).await?,
         }
         Ok(())
     }
 
     /// List all workflows with execution statistics
-    async fn list_workflows(&self) -> anyhow::Result<()> {
-        let state_manager = StateManager::new(&self.db_path).await?;
+    async fn list_workflows(&self, out: &mut impl Write) -> anyhow::Result<()> {
+        let state_manager = StateManager::new_readonly(&self.db_path).await?;
         let workflows = state_manager.list_workflows().await?;
 
+        if self.output == OutputFormat::Json {
+            writeln!(out, "{}", serde_json::to_string_pretty(&workflows)?)?;
+            return Ok(());
+        }
+
         if workflows.is_empty() {
-            println!("No workflows found");
+            writeln!(out, "No workflows found")?;
             return Ok(());
         }
 
-        println!("Workflows:");
-        println!();
-        println!(
-            "{:<30} {:<12} {:<12} {:<10} {:<10} {:<20}",
-            "Name", "Type", "Total", "Success", "Failed", "Last Execution"
-        );
-        println!("{}", "-".repeat(110));
+        writeln!(out, "Workflows:")?;
+        writeln!(out)?;
+        writeln!(
+            out,
+            "{:<30} {:<12} {:<12} {:<10} {:<10} {:<20} {:<20}",
+            "Name", "Type", "Total", "Success", "Failed", "Last Execution", "Next Run"
+        )?;
+        writeln!(out, "{}", "-".repeat(131))?;
 
         for workflow in workflows {
             let workflow_type = if workflow.schedule.is_some() {
@@ -430,64 +1037,158 @@ impl Cli {
                 "On-Demand"
             };
 
+            // Convert UTC to local timezone
+            let format_local = |dt: chrono::DateTime<chrono::Utc>| {
+                let local_time = dt.with_timezone(&chrono::Local);
+                local_time.format("%Y-%m-%d %H:%M:%S").to_string()
+            };
+
             let last_exec = workflow
                 .last_execution
-                .map(|dt| {
-                    // Convert UTC to local timezone
-                    let local_time = dt.with_timezone(&chrono::Local);
-                    local_time.format("%Y-%m-%d %H:%M:%S").to_string()
-                })
+                .map(format_local)
                 .unwrap_or_else(|| "Never".to_string());
 
-            println!(
-                "{:<30} {:<12} {:<12} {:<10} {:<10} {:<20}",
+            let next_run = workflow
+                .next_run
+                .map(format_local)
+                .unwrap_or_else(|| "-".to_string());
+
+            writeln!(
+                out,
+                "{:<30} {:<12} {:<12} {:<10} {:<10} {:<20} {:<20}",
                 workflow.name,
                 workflow_type,
                 workflow.execution_count,
                 workflow.success_count,
                 workflow.failed_count,
-                last_exec
-            );
+                last_exec,
+                next_run
+            )?;
         }
 
         Ok(())
     }
 
+    /// Handle state database commands
+    async fn handle_db_command(&self, command: &DbCommands) -> anyhow::Result<()> {
+        match command {
+            DbCommands::Compact => self.compact_database().await?,
+        }
+        Ok(())
+    }
+
+    /// Compact the state database and report the space reclaimed
+    async fn compact_database(&self) -> anyhow::Result<()> {
+        let state_manager = StateManager::new(&self.db_path).await?;
+        let size = || -> u64 {
+            ["", "-wal"]
+                .iter()
+                .filter_map(|suffix| {
+                    let mut path = self.db_path.clone().into_os_string();
+                    path.push(suffix);
+                    std::fs::metadata(path).ok()
+                })
+                .map(|m| m.len())
+                .sum()
+        };
+
+        let before = size();
+        state_manager.compact().await?;
+        let after = size();
+
+        println!(
+            "Compacted {}: {} -> {} bytes ({} reclaimed)",
+            self.db_path.display(),
+            before,
+            after,
+            before.saturating_sub(after)
+        );
+        Ok(())
+    }
+
     /// Handle daemon management commands
     async fn handle_daemon_command(&self, command: &DaemonCommands) -> anyhow::Result<()> {
         use crate::daemon::{check_daemon_running, stop_daemon, Daemon};
 
         match command {
-            DaemonCommands::Start { workflow, pid_file } => {
+            DaemonCommands::Start {
+                workflow,
+                workflows_dir,
+                pid_file,
+                max_concurrent_workflows,
+                circuit_breaker_threshold,
+                min_free_disk_mb,
+                control_socket,
+                #[cfg(feature = "metrics")]
+                metrics_port,
+                #[cfg(feature = "metrics")]
+                push_gateway,
+                #[cfg(feature = "metrics")]
+                push_job,
+                #[cfg(feature = "metrics")]
+                push_interval,
+            } => {
                 info!("Starting daemon with workflow: {:?}", workflow);
 
                 // Parse workflow
-                let config = parse_workflow_file(workflow)?;
+                let config = workflow.as_ref().map(parse_workflow_file).transpose()?;
 
                 // Validate workflow has a schedule
-                if config.schedule.is_none() {
-                    error!("Workflow '{}' has no schedule defined", config.name);
-                    return Err(anyhow::anyhow!(
-                        "Cannot start daemon with workflow '{}': no schedule defined. \
-                         Add a 'schedule' field with a cron expression.",
-                        config.name
-                    ));
+                if let Some(config) = &config {
+                    let Some(schedule) = &config.schedule else {
+                        error!("Workflow '{}' has no schedule defined", config.name);
+                        return Err(anyhow::anyhow!(
+                            "Cannot start daemon with workflow '{}': no schedule defined. \
+                             Add a 'schedule' field with a cron expression.",
+                            config.name
+                        ));
+                    };
+
+                    info!(
+                        "Workflow '{}' loaded with schedule: {}",
+                        config.name, schedule
+                    );
                 }
 
-                info!(
-                    "Workflow '{}' loaded with schedule: {}",
-                    config.name,
-                    config.schedule.as_ref().unwrap()
-                );
-
                 // Create state manager
                 let state_manager = Arc::new(StateManager::new(&self.db_path).await?);
 
                 // Create daemon
                 let mut daemon = Daemon::new(state_manager, pid_file.clone()).await?;
+                daemon
+                    .set_max_concurrent_workflows(max_concurrent_workflows.map(|n| n as usize))?;
+                daemon.set_circuit_breaker_threshold(*circuit_breaker_threshold)?;
+                daemon.set_min_free_disk_mb(*min_free_disk_mb, &self.db_path)?;
+                if let Some(handle) = &self.log_level_handle {
+                    daemon.set_log_level_handle(handle.clone());
+                }
+                daemon.set_control_socket(control_socket.clone());
+
+                #[cfg(feature = "metrics")]
+                {
+                    // One registry, so scraped and pushed metrics agree
+                    let metrics = Arc::new(crate::metrics::MetricsServer::new());
+                    if let Some(port) = metrics_port {
+                        daemon.enable_metrics_server(metrics.clone(), *port);
+                    }
+                    if let Some(gateway_url) = push_gateway {
+                        daemon.enable_metrics_push(crate::daemon::MetricsPushConfig {
+                            metrics,
+                            gateway_url: gateway_url.clone(),
+                            job: push_job.clone(),
+                            interval: std::time::Duration::from_secs(*push_interval),
+                        });
+                    }
+                }
 
-                // Add workflow
-                daemon.add_workflow(config).await?;
+                // Add workflows
+                if let Some(config) = config {
+                    daemon.add_workflow(config).await?;
+                }
+                if let Some(dir) = workflows_dir {
+                    let loaded = daemon.load_workflows_from_dir(dir).await?;
+                    println!("Loaded {} workflow(s) from {:?}", loaded, dir);
+                }
 
                 println!("Starting PicoFlow daemon (PID file: {:?})", pid_file);
                 println!("Press Ctrl+C to stop");
@@ -513,20 +1214,136 @@ impl Cli {
                 }
             }
 
-            DaemonCommands::Status { pid_file } => {
+            DaemonCommands::Status {
+                pid_file,
+                control_socket,
+            } => {
+                use crate::control::{send_request, ControlRequest, ControlResponse};
+
                 info!("Checking daemon status (PID file: {:?})", pid_file);
 
-                match check_daemon_running(pid_file)? {
-                    Some(pid) => {
-                        println!("Daemon is running (PID: {})", pid);
-                    }
-                    None => {
-                        println!("Daemon is not running");
-                    }
-                }
+                let pid = check_daemon_running(pid_file)?;
+                let live = match pid {
+                    Some(_) => Some(
+                        match send_request(control_socket, &ControlRequest::Status).await {
+                            Ok(ControlResponse::Status(status)) => Ok(status),
+                            Ok(other) => Err(format!("unexpected response {:?}", other)),
+                            Err(e) => Err(e.to_string()),
+                        },
+                    ),
+                    None => None,
+                };
+                self.print_daemon_status(pid, live, &mut std::io::stdout())?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Ask the running daemon to run a workflow now
+    async fn trigger_workflow(&self, workflow: &str, socket: &Path) -> anyhow::Result<()> {
+        use crate::control::{send_request, ControlRequest, ControlResponse};
+
+        let request = ControlRequest::Trigger {
+            name: workflow.to_string(),
+        };
+        match send_request(socket, &request).await? {
+            ControlResponse::Triggered { name } => {
+                println!("Triggered workflow '{}'", name);
+                Ok(())
+            }
+            ControlResponse::Error { message } => Err(anyhow::anyhow!(message)),
+            other => Err(anyhow::anyhow!(
+                "Unexpected response from daemon: {:?}",
+                other
+            )),
+        }
+    }
+
+    /// Print whether the daemon is running and, if it answered, its live status
+    ///
+    /// `live` is the daemon's answer over the control socket, or why there is none. With
+    /// `--output json` only the [`DaemonStatus`] document goes to `out`, and a missing
+    /// live status is reported on stderr.
+    fn print_daemon_status(
+        &self,
+        pid: Option<u32>,
+        live: Option<std::result::Result<SchedulerStatus, String>>,
+        out: &mut impl Write,
+    ) -> anyhow::Result<()> {
+        let (scheduler, unavailable) = match live {
+            Some(Ok(status)) => (Some(status), None),
+            Some(Err(reason)) => (None, Some(reason)),
+            None => (None, None),
+        };
+
+        if self.output == OutputFormat::Json {
+            if let Some(reason) = unavailable {
+                eprintln!("Live status unavailable: {}", reason);
             }
+            let status = DaemonStatus {
+                running: pid.is_some(),
+                pid,
+                scheduler,
+            };
+            writeln!(out, "{}", serde_json::to_string_pretty(&status)?)?;
+            return Ok(());
         }
 
+        let Some(pid) = pid else {
+            writeln!(out, "Daemon is not running")?;
+            return Ok(());
+        };
+        writeln!(out, "Daemon is running (PID: {})", pid)?;
+        match (scheduler, unavailable) {
+            (Some(status), _) => self.print_scheduler_status(&status, out)?,
+            (None, Some(reason)) => writeln!(out, "Live status unavailable: {}", reason)?,
+            (None, None) => {}
+        }
+        Ok(())
+    }
+
+    /// Print the scheduled jobs and running workflows reported by the daemon
+    fn print_scheduler_status(
+        &self,
+        status: &SchedulerStatus,
+        out: &mut impl Write,
+    ) -> anyhow::Result<()> {
+        writeln!(out, "Scheduled jobs: {}", status.scheduled_jobs)?;
+        for job in &status.jobs {
+            let next_run = job
+                .next_run
+                .map(|t| t.format("%Y-%m-%d %H:%M:%S UTC").to_string())
+                .unwrap_or_else(|| "-".to_string());
+            let timezone = job
+                .timezone
+                .as_deref()
+                .map(|tz| format!(" ({})", tz))
+                .unwrap_or_default();
+            let circuit = if job.circuit_open {
+                format!(
+                    "  circuit: open ({} consecutive failures)",
+                    job.consecutive_failures
+                )
+            } else {
+                String::new()
+            };
+            writeln!(
+                out,
+                "  {:<30} {}{}  next run: {}{}",
+                job.workflow, job.schedule, timezone, next_run, circuit
+            )?;
+        }
+
+        writeln!(out, "Running workflows: {}", status.running.len())?;
+        for run in &status.running {
+            writeln!(
+                out,
+                "  {:<30} started {}",
+                run.workflow,
+                run.started_at.format("%Y-%m-%d %H:%M:%S UTC")
+            )?;
+        }
         Ok(())
     }
 
@@ -537,12 +1354,17 @@ impl Cli {
         status_filter: Option<&str>,
         limit: usize,
     ) -> anyhow::Result<()> {
-        let state_manager = StateManager::new(&self.db_path).await?;
+        let state_manager = StateManager::new_readonly(&self.db_path).await?;
 
         let executions = state_manager
             .get_execution_history_filtered(workflow_name, status_filter, limit)
             .await?;
 
+        if self.output == OutputFormat::Json {
+            println!("{}", serde_json::to_string_pretty(&executions)?);
+            return Ok(());
+        }
+
         if executions.is_empty() {
             println!(
                 "No execution history found for workflow '{}'",
@@ -590,9 +1412,35 @@ impl Cli {
         Ok(())
     }
 
+    /// Export all executions to a file, or stdout when `out` is `None`
+    async fn export_executions(
+        &self,
+        format: ExportFormat,
+        out: Option<&Path>,
+        include_tasks: bool,
+    ) -> anyhow::Result<()> {
+        let state_manager = StateManager::new_readonly(&self.db_path).await?;
+
+        let writer: Box<dyn Write + Send> = match out {
+            Some(path) => Box::new(
+                std::fs::File::create(path)
+                    .map_err(|e| anyhow::anyhow!("Failed to create '{}': {}", path.display(), e))?,
+            ),
+            None => Box::new(std::io::stdout()),
+        };
+        state_manager
+            .export_executions(std::io::BufWriter::new(writer), format, include_tasks)
+            .await?;
+
+        if let Some(path) = out {
+            info!("Exported executions to {}", path.display());
+        }
+        Ok(())
+    }
+
     /// Show workflow execution statistics
     async fn show_stats(&self, workflow_name: &str) -> anyhow::Result<()> {
-        let state_manager = StateManager::new(&self.db_path).await?;
+        let state_manager = StateManager::new_readonly(&self.db_path).await?;
 
         let stats = state_manager.get_workflow_statistics(workflow_name).await?;
 
@@ -628,38 +1476,39 @@ impl Cli {
         Ok(())
     }
 
-    /// Show task execution logs
+    /// Show the stored output of an execution's tasks
+    ///
+    /// `target` is an execution ID, or a workflow name whose latest execution (or
+    /// `execution_id`) is shown.
     async fn show_logs(
         &self,
-        workflow_name: &str,
+        out: &mut impl Write,
+        target: &str,
         execution_id: Option<i64>,
         task_filter: Option<&str>,
     ) -> anyhow::Result<()> {
-        let state_manager = StateManager::new(&self.db_path).await?;
-
-        // Get execution ID if not provided
-        let exec_id = if let Some(id) = execution_id {
-            id
-        } else {
-            // Get latest execution
-            let history = state_manager
-                .get_execution_history(workflow_name, 1)
-                .await?;
-            if history.is_empty() {
-                println!(
-                    "No execution history found for workflow '{}'",
-                    workflow_name
-                );
-                return Ok(());
+        let state_manager = StateManager::new_readonly(&self.db_path).await?;
+
+        let exec_id = match (target.parse::<i64>(), execution_id) {
+            (_, Some(id)) => id,
+            (Ok(id), None) => id,
+            (Err(_), None) => {
+                // Latest execution of the named workflow
+                let history = state_manager.get_execution_history(target, 1).await?;
+                match history.first() {
+                    Some(execution) => execution.id,
+                    None => {
+                        writeln!(out, "No execution history found for workflow '{}'", target)?;
+                        return Ok(());
+                    }
+                }
             }
-            history[0].id
         };
 
-        // Get task executions
         let tasks = state_manager.get_task_executions(exec_id).await?;
 
         if tasks.is_empty() {
-            println!("No task executions found for execution ID {}", exec_id);
+            writeln!(out, "No task executions found for execution ID {}", exec_id)?;
             return Ok(());
         }
 
@@ -675,56 +1524,204 @@ impl Cli {
 
         if filtered_tasks.is_empty() {
             if let Some(task_name) = task_filter {
-                println!(
+                writeln!(
+                    out,
                     "No tasks found matching '{}' for execution ID {}",
                     task_name, exec_id
-                );
+                )?;
             }
             return Ok(());
         }
 
-        println!("\nTask Logs for execution ID: {}", exec_id);
+        writeln!(out, "\nTask Logs for execution ID: {}", exec_id)?;
         if let Some(task_name) = task_filter {
-            println!("Filtered by task: {}", task_name);
+            writeln!(out, "Filtered by task: {}", task_name)?;
         }
-        println!();
+        writeln!(out)?;
 
         for task in &filtered_tasks {
-            println!("{:-<80}", "");
-            println!("Task: {}", task.task_name);
-            println!("Status: {}", task.status);
-            println!("Started: {}", task.started_at.format("%Y-%m-%d %H:%M:%S"));
+            writeln!(out, "{:-<80}", "")?;
+            writeln!(out, "Task: {}", task.task_name)?;
+            writeln!(out, "Status: {}", task.status)?;
+            writeln!(
+                out,
+                "Started: {}",
+                task.started_at.format("%Y-%m-%d %H:%M:%S")
+            )?;
             if let Some(completed) = task.completed_at {
-                println!("Completed: {}", completed.format("%Y-%m-%d %H:%M:%S"));
+                writeln!(out, "Completed: {}", completed.format("%Y-%m-%d %H:%M:%S"))?;
                 let duration = (completed - task.started_at).num_seconds();
-                println!("Duration: {}", format_duration(duration));
+                writeln!(out, "Duration: {}", format_duration(duration))?;
             }
             if let Some(exit_code) = task.exit_code {
-                println!("Exit Code: {}", exit_code);
+                writeln!(out, "Exit Code: {}", exit_code)?;
+            }
+            // Skipped tasks never ran, so there is no attempt to report
+            if task.status != TaskStatus::Skipped {
+                writeln!(out, "Attempt: {} / {}", task.attempt, task.retry_count + 1)?;
+            }
+            if task.cached {
+                writeln!(out, "Note: cached result (task did not run)")?;
             }
-            println!("Attempt: {} / {}", task.attempt, task.retry_count + 1);
 
             if let Some(stdout) = &task.stdout {
                 if !stdout.is_empty() {
-                    println!("\nStdout:");
-                    println!("{}", stdout);
+                    writeln!(out, "\nStdout:")?;
+                    writeln!(out, "{}", stdout)?;
                 }
             }
 
             if let Some(stderr) = &task.stderr {
                 if !stderr.is_empty() {
-                    println!("\nStderr:");
-                    println!("{}", stderr);
+                    writeln!(out, "\nStderr:")?;
+                    writeln!(out, "{}", stderr)?;
                 }
             }
 
-            println!();
+            if task.output_truncated {
+                writeln!(out, "\n[output truncated]")?;
+            }
+
+            writeln!(out)?;
         }
 
         Ok(())
     }
 }
 
+/// Whether a workflow path argument is `-`, meaning "read the workflow from stdin"
+/// Rename `--output` to `--output-file` in the arguments of a `template` subcommand
+fn template_output_alias<I, T>(args: I) -> Vec<OsString>
+where
+    I: IntoIterator<Item = T>,
+    T: Into<OsString>,
+{
+    // Global flags that take a separate value, which may itself read "template"
+    const VALUE_FLAGS: &[&str] = &["-l", "--log-level", "--log-format", "--db-path", "--output"];
+
+    let mut args: Vec<OsString> = args.into_iter().map(Into::into).collect();
+    let mut i = 1;
+    while let Some(arg) = args.get(i).and_then(|arg| arg.to_str()) {
+        if VALUE_FLAGS.contains(&arg) {
+            i += 2;
+        } else if arg.starts_with('-') {
+            i += 1;
+        } else {
+            break;
+        }
+    }
+    if args.get(i).is_some_and(|arg| arg == "template") {
+        for arg in args.iter_mut().skip(i + 1) {
+            if arg == "--" {
+                break;
+            } else if arg == "--output" {
+                *arg = "--output-file".into();
+            } else if let Some(path) = arg.to_str().and_then(|a| a.strip_prefix("--output=")) {
+                *arg = format!("--output-file={}", path).into();
+            }
+        }
+    }
+    args
+}
+
+fn is_stdin(path: &Path) -> bool {
+    path.as_os_str() == "-"
+}
+
+/// Remove the task logs of executions that retention has deleted, logging any failure
+async fn prune_task_logs(scheduler: &TaskScheduler) {
+    match scheduler.prune_task_logs().await {
+        Ok(0) => {}
+        Ok(removed) => info!(
+            "Removed {} task log directories of pruned executions",
+            removed
+        ),
+        Err(e) => warn!("Failed to prune task log directories: {}", e),
+    }
+}
+
+/// Parse the workflow named by a path argument, reading YAML from `stdin` for `-`
+///
+/// Stdin is read to the end, but no more than `MAX_YAML_SIZE` bytes are buffered.
+/// Workflows read from stdin cannot use `includes`, as there is no file to resolve
+/// them against.
+fn load_workflow_arg(
+    path: &Path,
+    env: &HashMap<String, String>,
+    params: &HashMap<String, String>,
+    stdin: impl Read,
+) -> crate::error::Result<WorkflowConfig> {
+    if !is_stdin(path) {
+        return parse_workflow_file_with_params(path, env, params);
+    }
+
+    let mut content = String::new();
+    stdin
+        .take(MAX_YAML_SIZE as u64 + 1)
+        .read_to_string(&mut content)?;
+    if content.len() > MAX_YAML_SIZE {
+        return Err(PicoFlowError::YamlSizeExceeded(content.len()));
+    }
+    parse_workflow_yaml_with_params(&content, env, params)
+}
+
+/// Parse a `--env KEY=VALUE` argument; keys must match `[A-Za-z_][A-Za-z0-9_]*`
+fn parse_env_assignment(arg: &str) -> Result<(String, String), String> {
+    let (key, value) = arg
+        .split_once('=')
+        .ok_or_else(|| format!("expected KEY=VALUE, got '{}'", arg))?;
+    let valid_key = key
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if !valid_key {
+        return Err(format!("invalid variable name '{}'", key));
+    }
+    Ok((key.to_string(), value.to_string()))
+}
+
+/// Parse a `--max-parallel` argument, within the same 1-256 range workflows are held to
+fn parse_max_parallel(arg: &str) -> Result<usize, String> {
+    let n: usize = arg
+        .parse()
+        .map_err(|_| format!("expected a number, got '{}'", arg))?;
+    if !(1..=256).contains(&n) {
+        return Err(format!("must be between 1 and 256, got {}", n));
+    }
+    Ok(n)
+}
+
+/// Collect every structural problem in a workflow.
+///
+/// The schedule and DAG are checked together via [`DagEngine::validate_all`]. Only when those
+/// pass are the remaining (first-error) checks from [`validate_workflow`] run, so that no
+/// problem is reported twice.
+fn check_workflow(config: WorkflowConfig) -> Result<WorkflowConfig, Vec<ValidationError>> {
+    let mut errors = Vec::new();
+
+    if let Some(schedule) = &config.schedule {
+        if let Err(e) = validate_cron_expression(schedule) {
+            let message = match e {
+                PicoFlowError::Validation(message) => message,
+                other => other.to_string(),
+            };
+            errors.push(ValidationError::InvalidSchedule(message));
+        }
+    }
+    if let Some(timezone) = &config.timezone {
+        if let Err(PicoFlowError::Validation(message)) = parse_timezone(timezone) {
+            errors.push(ValidationError::InvalidSchedule(message));
+        }
+    }
+    errors.extend(DagEngine::validate_all(&config.tasks));
+
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+    validate_workflow(config).map_err(|e| vec![ValidationError::Invalid(e.to_string())])
+}
+
 /// Format duration in seconds to human-readable string
 fn format_duration(seconds: i64) -> String {
     if seconds < 60 {
@@ -766,6 +1763,191 @@ mod tests {
         assert!(matches!(cli.command, Commands::Run { .. }));
     }
 
+    #[test]
+    fn test_load_workflow_arg_reads_stdin_for_dash() {
+        let yaml = "name: piped\ntasks:\n  - name: a\n    type: shell\n    config:\n      command: /bin/true\n";
+        let config = load_workflow_arg(
+            Path::new("-"),
+            &HashMap::new(),
+            &HashMap::new(),
+            yaml.as_bytes(),
+        )
+        .unwrap();
+        assert_eq!(config.name, "piped");
+
+        let oversized = format!("{}{}", yaml, "#".repeat(MAX_YAML_SIZE));
+        let err = load_workflow_arg(
+            Path::new("-"),
+            &HashMap::new(),
+            &HashMap::new(),
+            oversized.as_bytes(),
+        )
+        .unwrap_err();
+        assert!(matches!(err, PicoFlowError::YamlSizeExceeded(_)));
+    }
+
+    #[test]
+    fn test_cli_run_env_flag() {
+        let cli = Cli::parse_from([
+            "picoflow",
+            "run",
+            "--env",
+            "TARGET=prod",
+            "--env",
+            "URL=http://x/?a=b",
+            "--env",
+            "EMPTY=",
+            "wf.yaml",
+        ]);
+        let Commands::Run { env, .. } = cli.command else {
+            panic!("Expected Run command");
+        };
+        assert_eq!(
+            env,
+            vec![
+                ("TARGET".to_string(), "prod".to_string()),
+                ("URL".to_string(), "http://x/?a=b".to_string()),
+                ("EMPTY".to_string(), String::new()),
+            ]
+        );
+
+        for bad in ["NOVALUE", "=value", "1ABC=x", "MY-VAR=x"] {
+            assert!(
+                Cli::try_parse_from(["picoflow", "run", "--env", bad, "wf.yaml"]).is_err(),
+                "{bad}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_cli_run_param_flag() {
+        let cli = Cli::parse_from([
+            "picoflow",
+            "run",
+            "wf.yaml",
+            "-p",
+            "host=10.0.0.5",
+            "--param",
+            "count=3",
+        ]);
+        let Commands::Run { params, .. } = cli.command else {
+            panic!("Expected Run command");
+        };
+        assert_eq!(
+            params,
+            vec![
+                ("host".to_string(), "10.0.0.5".to_string()),
+                ("count".to_string(), "3".to_string()),
+            ]
+        );
+        assert!(Cli::try_parse_from(["picoflow", "run", "-p", "host", "wf.yaml"]).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_run_expands_env_flag_variables() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("picoflow.db");
+        let workflow = dir.path().join("greet.yaml");
+        std::fs::write(
+            &workflow,
+            "name: greet\ntasks:\n  - name: hello\n    type: shell\n    config:\n      command: /bin/echo\n      args: [\"${PICOFLOW_TEST_GREETING}\", \"${HOME}\"]\n",
+        )
+        .unwrap();
+
+        let cli = Cli::parse_from([
+            "picoflow",
+            "--db-path",
+            db_path.to_str().unwrap(),
+            "run",
+            "--env",
+            "PICOFLOW_TEST_GREETING=hello-from-flag",
+            "--env",
+            "HOME=/overridden",
+            workflow.to_str().unwrap(),
+        ]);
+        cli.execute().await.unwrap();
+
+        let state = StateManager::new(&db_path).await.unwrap();
+        let execution = &state.get_execution_history("greet", 1).await.unwrap()[0];
+        let (stdout, _) = state
+            .get_task_output(execution.id, "hello")
+            .await
+            .unwrap()
+            .unwrap();
+        // The flag supplies an unset variable and overrides a set one
+        assert_eq!(stdout.trim(), "hello-from-flag /overridden");
+        assert!(std::env::var("PICOFLOW_TEST_GREETING").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_run_max_parallel_override() {
+        for bad in ["0", "257", "many"] {
+            assert!(
+                Cli::try_parse_from(["picoflow", "run", "--max-parallel", bad, "wf.yaml"]).is_err(),
+                "{bad}"
+            );
+        }
+
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("picoflow.db");
+        let workflow = dir.path().join("pair.yaml");
+        std::fs::write(
+            &workflow,
+            "name: pair\nconfig:\n  max_parallel: 2\ntasks:\n  - name: a\n    type: shell\n    config:\n      command: /bin/sleep\n      args: [\"0.3\"]\n  - name: b\n    type: shell\n    config:\n      command: /bin/sleep\n      args: [\"0.3\"]\n",
+        )
+        .unwrap();
+
+        let cli = Cli::parse_from([
+            "picoflow",
+            "--db-path",
+            db_path.to_str().unwrap(),
+            "run",
+            "--max-parallel",
+            "1",
+            workflow.to_str().unwrap(),
+        ]);
+        cli.execute().await.unwrap();
+
+        // Independent tasks ran one after the other despite max_parallel: 2
+        let state = StateManager::new(&db_path).await.unwrap();
+        let execution = &state.get_execution_history("pair", 1).await.unwrap()[0];
+        let mut tasks = state.get_task_executions(execution.id).await.unwrap();
+        tasks.sort_by_key(|t| t.started_at);
+        assert_eq!(tasks.len(), 2);
+        assert!(tasks[0].completed_at.unwrap() <= tasks[1].started_at);
+    }
+
+    #[test]
+    fn test_cli_run_verify_sig_requires_pubkey() {
+        let cli = Cli::parse_from([
+            "picoflow",
+            "run",
+            "workflow.yaml",
+            "--verify-sig",
+            "workflow.yaml.sig",
+            "--pubkey",
+            "key.pub",
+        ]);
+        match cli.command {
+            Commands::Run {
+                verify_sig, pubkey, ..
+            } => {
+                assert_eq!(verify_sig, Some(PathBuf::from("workflow.yaml.sig")));
+                assert_eq!(pubkey, Some(PathBuf::from("key.pub")));
+            }
+            _ => panic!("expected run command"),
+        }
+
+        assert!(Cli::try_parse_from([
+            "picoflow",
+            "run",
+            "workflow.yaml",
+            "--verify-sig",
+            "workflow.yaml.sig"
+        ])
+        .is_err());
+    }
+
     #[test]
     fn test_cli_status_command() {
         let cli = Cli::parse_from(["picoflow", "status"]);
@@ -786,6 +1968,33 @@ mod tests {
         assert_eq!(cli.log_level, "debug");
     }
 
+    #[test]
+    fn test_cli_verbosity_flags() {
+        let level = |args: &[&str]| {
+            let mut argv = vec!["picoflow"];
+            argv.extend_from_slice(args);
+            argv.extend_from_slice(&["validate", "test.yaml"]);
+            Cli::parse_from(argv).resolved_log_level()
+        };
+
+        assert_eq!(level(&[]), LogLevel::Info);
+        assert_eq!(level(&["--log-level", "warn"]), LogLevel::Warn);
+        // Each -v is more verbose than the default
+        assert_eq!(level(&["-v"]), LogLevel::Debug);
+        assert_eq!(level(&["-vv"]), LogLevel::Trace);
+        assert_eq!(level(&["-vvv"]), LogLevel::Trace);
+        assert_eq!(level(&["--verbose"]), LogLevel::Debug);
+        assert_eq!(level(&["-q"]), LogLevel::Error);
+        // The flags override --log-level
+        assert_eq!(level(&["--log-level", "warn", "-v"]), LogLevel::Debug);
+        assert_eq!(level(&["--log-level", "trace", "--quiet"]), LogLevel::Error);
+        // Global flags are accepted after the subcommand too
+        let cli = Cli::parse_from(["picoflow", "validate", "test.yaml", "-q"]);
+        assert_eq!(cli.resolved_log_level(), LogLevel::Error);
+
+        assert!(Cli::try_parse_from(["picoflow", "-q", "-v", "validate", "test.yaml"]).is_err());
+    }
+
     #[test]
     fn test_cli_with_db_path() {
         let cli = Cli::parse_from([
@@ -838,6 +2047,35 @@ mod tests {
         } else {
             panic!("Expected Template command");
         }
+
+        // `--output` is kept as an alias, and the global format flag still parses
+        let cli = Cli::parse_args([
+            "picoflow", "--output", "json", "template", "--output", "out.yaml",
+        ]);
+        assert_eq!(cli.output, OutputFormat::Json);
+        assert!(matches!(
+            &cli.command,
+            Commands::Template { output: Some(path), .. } if path == &PathBuf::from("out.yaml")
+        ));
+        let cli = Cli::parse_args([
+            "picoflow",
+            "--db-path",
+            "template",
+            "template",
+            "--output=x",
+        ]);
+        assert_eq!(cli.db_path, PathBuf::from("template"));
+        assert!(matches!(
+            &cli.command,
+            Commands::Template { output: Some(path), .. } if path == &PathBuf::from("x")
+        ));
+        let cli = Cli::parse_args(["picoflow", "template"]);
+        assert_eq!(cli.output, OutputFormat::Table);
+
+        // Other subcommands keep the global format flag
+        let cli = Cli::parse_args(["picoflow", "workflow", "list", "--output", "json"]);
+        assert_eq!(cli.output, OutputFormat::Json);
+        assert!(Cli::try_parse_from(["picoflow", "template", "--output-format", "json"]).is_err());
     }
 
     #[test]
@@ -850,4 +2088,317 @@ mod tests {
             }
         ));
     }
+
+    #[test]
+    fn test_cli_export_command() {
+        let cli = Cli::parse_from(["picoflow", "export", "--format", "csv", "--out", "x.csv"]);
+        let Commands::Export { format, out, tasks } = cli.command else {
+            panic!("expected export command");
+        };
+        assert_eq!(ExportFormat::from(format), ExportFormat::Csv);
+        assert_eq!(out, Some(PathBuf::from("x.csv")));
+        assert!(!tasks);
+
+        let cli = Cli::parse_from(["picoflow", "export", "--tasks"]);
+        assert!(matches!(
+            cli.command,
+            Commands::Export {
+                format: ExportFormatArg::Ndjson,
+                out: None,
+                tasks: true
+            }
+        ));
+    }
+
+    #[test]
+    fn test_check_workflow_reports_all_errors() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("broken.yaml");
+        std::fs::write(
+            &path,
+            r#"
+name: broken
+schedule: "not a cron"
+tasks:
+  - name: a
+    type: shell
+    depends_on: [b]
+    config:
+      command: "/bin/true"
+  - name: b
+    type: shell
+    depends_on: [a]
+    config:
+      command: "/bin/true"
+  - name: a
+    type: shell
+    depends_on: [missing]
+    config:
+      command: "/bin/true"
+"#,
+        )
+        .unwrap();
+
+        let config = load_workflow_file(&path).unwrap();
+        let errors = check_workflow(config).unwrap_err();
+        assert_eq!(errors.len(), 4, "{errors:?}");
+        assert!(matches!(errors[0], ValidationError::InvalidSchedule(_)));
+        assert_eq!(errors[1], ValidationError::DuplicateTask("a".to_string()));
+        assert!(matches!(
+            &errors[2],
+            ValidationError::MissingDependency { dependency, .. } if dependency == "missing"
+        ));
+        assert_eq!(errors[3], ValidationError::Cycle("a -> b -> a".to_string()));
+    }
+
+    #[test]
+    fn test_check_workflow_runs_config_checks_when_dag_is_valid() {
+        let yaml = r#"
+name: relative
+tasks:
+  - name: a
+    type: shell
+    config:
+      command: "echo"
+"#;
+        let config: WorkflowConfig = serde_yaml::from_str(yaml).unwrap();
+        let errors = check_workflow(config).unwrap_err();
+        assert!(matches!(errors.as_slice(), [ValidationError::Invalid(_)]));
+    }
+
+    #[tokio::test]
+    async fn test_workflow_list_json_output() {
+        use crate::models::WorkflowSummary;
+
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("picoflow.db");
+        {
+            let state = StateManager::new(&db_path).await.unwrap();
+            let id = state
+                .get_or_create_workflow("nightly", Some("0 0 2 * * *"))
+                .await
+                .unwrap();
+            let exec = state.start_execution(id).await.unwrap();
+            state
+                .update_execution_status(exec, TaskStatus::Success)
+                .await
+                .unwrap();
+            state.get_or_create_workflow("adhoc", None).await.unwrap();
+        }
+
+        let cli = Cli::parse_from([
+            "picoflow",
+            "--db-path",
+            db_path.to_str().unwrap(),
+            "--output",
+            "json",
+            "workflow",
+            "list",
+        ]);
+        assert_eq!(cli.output, OutputFormat::Json);
+
+        let mut out = Vec::new();
+        cli.list_workflows(&mut out).await.unwrap();
+        let mut parsed: Vec<WorkflowSummary> = serde_json::from_slice(&out).unwrap();
+        parsed.sort_by(|a, b| a.name.cmp(&b.name));
+
+        assert_eq!(parsed.len(), 2);
+        assert_eq!(parsed[0].name, "adhoc");
+        assert_eq!(parsed[0].execution_count, 0);
+        assert!(parsed[0].next_run.is_none());
+        assert_eq!(parsed[1].name, "nightly");
+        assert_eq!(parsed[1].schedule.as_deref(), Some("0 0 2 * * *"));
+        assert_eq!(parsed[1].execution_count, 1);
+        assert_eq!(parsed[1].success_count, 1);
+        assert!(parsed[1].next_run.is_some());
+    }
+
+    #[test]
+    fn test_init_creates_layout_and_refuses_to_overwrite() {
+        let dir = tempfile::tempdir().unwrap();
+        let project = dir.path().join("edge");
+        let cli = Cli::parse_from(["picoflow", "init", project.to_str().unwrap()]);
+        let Commands::Init { dir: target } = &cli.command else {
+            panic!("expected init command");
+        };
+
+        let mut out = Vec::new();
+        cli.init_project(&mut out, target).unwrap();
+        assert!(project.join("state").is_dir());
+        let example = project.join("workflows").join("example.yaml");
+        let content = std::fs::read_to_string(&example).unwrap();
+        assert!(
+            content.contains("daemon start --workflows-dir"),
+            "{content}"
+        );
+        let workflow = crate::parser::parse_workflow_yaml(&content).unwrap();
+        assert_eq!(workflow.name, "minimal-workflow");
+
+        std::fs::write(&example, "edited").unwrap();
+        let err = cli.init_project(&mut Vec::new(), target).unwrap_err();
+        assert!(err.to_string().contains("already exists"), "{err}");
+        assert_eq!(std::fs::read_to_string(&example).unwrap(), "edited");
+    }
+
+    #[test]
+    fn test_explain_prints_ancestors() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("etl.yaml");
+        std::fs::write(
+            &path,
+            r#"
+name: etl
+config:
+  retry_default: 2
+  timeout_default: 60
+tasks:
+  - name: extract
+    type: shell
+    config:
+      command: /bin/true
+  - name: unrelated
+    type: shell
+    config:
+      command: /bin/true
+  - name: transform
+    type: shell
+    depends_on: [extract]
+    config:
+      command: /bin/true
+  - name: load
+    type: shell
+    depends_on: [transform]
+    timeout: 30
+    config:
+      command: /bin/true
+  - name: report
+    type: shell
+    depends_on: [load]
+    config:
+      command: /bin/true
+"#,
+        )
+        .unwrap();
+
+        let cli = Cli::parse_from(["picoflow", "explain", path.to_str().unwrap(), "load"]);
+        let mut out = Vec::new();
+        cli.explain_task(&mut out, &path, "load").unwrap();
+        let out = String::from_utf8(out).unwrap();
+
+        assert!(out.contains("Depends on: transform\n"), "{out}");
+        assert!(out.contains("Dependents: report\n"), "{out}");
+        assert!(out.contains("Ancestors: extract, transform\n"), "{out}");
+        assert!(out.contains("Parallel level: 2\n"), "{out}");
+        assert!(out.contains("Retries: 2\n"), "{out}");
+        assert!(out.contains("Timeout: 30s\n"), "{out}");
+
+        let err = cli
+            .explain_task(&mut Vec::new(), &path, "nope")
+            .unwrap_err();
+        assert!(err.to_string().contains("not found"));
+    }
+
+    #[tokio::test]
+    async fn test_logs_prints_task_output() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("picoflow.db");
+        let exec = {
+            let state = StateManager::new(&db_path).await.unwrap();
+            let id = state.get_or_create_workflow("etl", None).await.unwrap();
+            let exec = state.start_execution(id).await.unwrap();
+            let extract = state.start_task(exec, "extract", 1).await.unwrap();
+            state
+                .update_task_status(
+                    extract,
+                    TaskStatus::Success,
+                    Some(0),
+                    Some("rows: 42"),
+                    Some("slow query"),
+                )
+                .await
+                .unwrap();
+            state.mark_output_truncated(extract).await.unwrap();
+            let load = state.start_task(exec, "load", 1).await.unwrap();
+            state
+                .update_task_status(load, TaskStatus::Failed, Some(1), None, Some("disk full"))
+                .await
+                .unwrap();
+            exec
+        };
+
+        let logs = |args: &[&str]| {
+            let cli = Cli::parse_from(
+                ["picoflow", "--db-path", db_path.to_str().unwrap(), "logs"]
+                    .iter()
+                    .chain(args),
+            );
+            async move {
+                let Commands::Logs {
+                    target,
+                    execution_id,
+                    task,
+                } = &cli.command
+                else {
+                    panic!("expected logs command");
+                };
+                let mut out = Vec::new();
+                cli.show_logs(&mut out, target, *execution_id, task.as_deref())
+                    .await
+                    .unwrap();
+                String::from_utf8(out).unwrap()
+            }
+        };
+
+        let all = logs(&[&exec.to_string()]).await;
+        assert!(all.contains("Task: extract"), "{all}");
+        assert!(all.contains("rows: 42"), "{all}");
+        assert!(all.contains("slow query"), "{all}");
+        assert!(all.contains("output truncated"), "{all}");
+        assert!(all.contains("Task: load"), "{all}");
+        assert!(all.contains("disk full"), "{all}");
+
+        // A workflow name resolves to its latest execution
+        let one = logs(&["etl", "--task", "load"]).await;
+        assert!(one.contains("disk full"), "{one}");
+        assert!(!one.contains("rows: 42"), "{one}");
+        assert!(!one.contains("output truncated"), "{one}");
+
+        let none = logs(&["999"]).await;
+        assert!(none.contains("No task executions found for execution ID 999"));
+    }
+
+    #[test]
+    fn test_daemon_status_json_is_a_single_document() {
+        let status = SchedulerStatus {
+            scheduled_jobs: 0,
+            jobs: Vec::new(),
+            running: Vec::new(),
+        };
+        let render = |args: &[&str], pid, live| {
+            let cli = Cli::parse_from(["picoflow"].iter().chain(args).chain(&["daemon", "status"]));
+            let mut out = Vec::new();
+            cli.print_daemon_status(pid, live, &mut out).unwrap();
+            String::from_utf8(out).unwrap()
+        };
+
+        let json = render(&["--output", "json"], Some(42), Some(Ok(status.clone())));
+        let parsed: DaemonStatus = serde_json::from_str(&json).unwrap();
+        assert!(parsed.running);
+        assert_eq!(parsed.pid, Some(42));
+        assert_eq!(parsed.scheduler, Some(status.clone()));
+
+        // An unreachable daemon still yields valid JSON, without the live section
+        let json = render(&["--output", "json"], Some(42), Some(Err("refused".into())));
+        let parsed: DaemonStatus = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.pid, Some(42));
+        assert!(parsed.scheduler.is_none());
+
+        let json = render(&["--output", "json"], None, None);
+        let parsed: DaemonStatus = serde_json::from_str(&json).unwrap();
+        assert!(!parsed.running);
+
+        let text = render(&[], Some(42), Some(Err("refused".into())));
+        assert!(text.contains("Daemon is running (PID: 42)"), "{text}");
+        assert!(text.contains("Live status unavailable: refused"), "{text}");
+    }
 }
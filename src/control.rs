@@ -0,0 +1,316 @@
+//! Daemon control socket
+//!
+//! The daemon owns its scheduler in-process, so other `picoflow` invocations talk to it
+//! over a Unix domain socket. Each connection carries one request and one response.
+//!
+//! # Protocol
+//!
+//! Every message is a 4-byte big-endian length followed by that many bytes of JSON.
+//! Messages larger than [`MAX_MESSAGE_SIZE`] are rejected. Requests and responses are
+//! tagged by a `type` field:
+//!
+//! ```text
+//! -> {"type":"trigger","name":"nightly-backup"}
+//! <- {"type":"triggered","name":"nightly-backup"}
+//! <- {"type":"error","message":"Unknown workflow 'nightly-backup'"}
+//! -> {"type":"status"}
+//! <- {"type":"status","scheduled_jobs":1,"jobs":[...],"running":[...]}
+//! ```
+//!
+//! # Example
+//!
+//! ```no_run
+//! use picoflow::control::{send_request, ControlRequest};
+//! use std::path::Path;
+//!
+//! # async fn example() -> anyhow::Result<()> {
+//! let request = ControlRequest::Trigger {
+//!     name: "nightly-backup".to_string(),
+//! };
+//! let response = send_request(Path::new("/tmp/picoflow.sock"), &request).await?;
+//! println!("{:?}", response);
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::cron_scheduler::{SchedulerHandle, SchedulerStatus};
+use crate::error::{PicoFlowError, Result};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::os::unix::fs::PermissionsExt;
+use std::path::Path;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio::net::{UnixListener, UnixStream};
+use tokio::sync::watch;
+use tracing::{debug, info, warn};
+
+/// Largest accepted message body, in bytes
+pub const MAX_MESSAGE_SIZE: usize = 1_048_576; // 1 MB
+
+/// How long either side waits for the other to send its message
+const IO_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
+/// Request sent to the daemon
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ControlRequest {
+    /// Run a scheduled workflow now, outside its schedule
+    Trigger { name: String },
+    /// Report scheduled jobs and running workflows
+    Status,
+}
+
+/// Daemon reply to a [`ControlRequest`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ControlResponse {
+    /// The workflow run was started
+    Triggered { name: String },
+    /// Live scheduler state
+    Status(SchedulerStatus),
+    /// The request was rejected
+    Error { message: String },
+}
+
+/// Write `message` as a length-prefixed JSON frame
+pub async fn write_message<W, T>(writer: &mut W, message: &T) -> Result<()>
+where
+    W: AsyncWrite + Unpin,
+    T: Serialize,
+{
+    let body = serde_json::to_vec(message)
+        .map_err(|e| PicoFlowError::Other(format!("Failed to encode control message: {}", e)))?;
+    if body.len() > MAX_MESSAGE_SIZE {
+        return Err(PicoFlowError::Other(format!(
+            "Control message of {} bytes exceeds maximum of {}",
+            body.len(),
+            MAX_MESSAGE_SIZE
+        )));
+    }
+    writer.write_u32(body.len() as u32).await?;
+    writer.write_all(&body).await?;
+    writer.flush().await?;
+    Ok(())
+}
+
+/// Read one length-prefixed JSON frame
+///
+/// # Errors
+///
+/// * `PicoFlowError::Validation` - If the frame is too large or is not valid JSON for `T`
+/// * `PicoFlowError::Io` - If the stream ends early
+pub async fn read_message<R, T>(reader: &mut R) -> Result<T>
+where
+    R: AsyncRead + Unpin,
+    T: DeserializeOwned,
+{
+    let len = reader.read_u32().await? as usize;
+    if len > MAX_MESSAGE_SIZE {
+        return Err(PicoFlowError::Validation(format!(
+            "Control message of {} bytes exceeds maximum of {}",
+            len, MAX_MESSAGE_SIZE
+        )));
+    }
+    let mut body = vec![0; len];
+    reader.read_exact(&mut body).await?;
+    serde_json::from_slice(&body)
+        .map_err(|e| PicoFlowError::Validation(format!("Malformed control message: {}", e)))
+}
+
+/// Send `request` to the daemon listening on `socket_path` and wait for its response
+///
+/// # Errors
+///
+/// * `PicoFlowError::Other` - If the daemon is not listening or does not answer in time
+pub async fn send_request(socket_path: &Path, request: &ControlRequest) -> Result<ControlResponse> {
+    let mut stream = UnixStream::connect(socket_path).await.map_err(|e| {
+        PicoFlowError::Other(format!(
+            "Cannot connect to daemon control socket {}: {} (is the daemon running?)",
+            socket_path.display(),
+            e
+        ))
+    })?;
+
+    tokio::time::timeout(IO_TIMEOUT, async {
+        write_message(&mut stream, request).await?;
+        read_message(&mut stream).await
+    })
+    .await
+    .map_err(|_| PicoFlowError::Other("Timed out waiting for the daemon to respond".to_string()))?
+}
+
+/// Bind the control socket at `socket_path`, readable and writable by the owner only
+///
+/// A socket file left behind by a previous daemon is replaced; the PID file already
+/// guarantees a single running daemon.
+///
+/// # Errors
+///
+/// * `PicoFlowError::Io` - If the socket cannot be created
+pub fn bind(socket_path: &Path) -> Result<UnixListener> {
+    match std::fs::remove_file(socket_path) {
+        Ok(()) => debug!("Removed stale control socket {}", socket_path.display()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+        Err(e) => return Err(e.into()),
+    }
+    let listener = UnixListener::bind(socket_path)?;
+    std::fs::set_permissions(socket_path, std::fs::Permissions::from_mode(0o600))?;
+    info!("Control socket listening on {}", socket_path.display());
+    Ok(listener)
+}
+
+/// Answer control requests on `listener` until `shutdown` becomes `true`
+pub async fn serve(
+    listener: UnixListener,
+    scheduler: SchedulerHandle,
+    mut shutdown: watch::Receiver<bool>,
+) {
+    loop {
+        tokio::select! {
+            accepted = listener.accept() => match accepted {
+                Ok((stream, _)) => {
+                    let scheduler = scheduler.clone();
+                    tokio::spawn(async move {
+                        if let Err(e) = handle_connection(stream, &scheduler).await {
+                            warn!("Control connection failed: {}", e);
+                        }
+                    });
+                }
+                Err(e) => warn!("Failed to accept control connection: {}", e),
+            },
+            _ = crate::executors::shell::cancelled(&mut shutdown) => break,
+        }
+    }
+}
+
+/// Read one request from `stream`, act on it and write the response
+async fn handle_connection(mut stream: UnixStream, scheduler: &SchedulerHandle) -> Result<()> {
+    let request = match tokio::time::timeout(IO_TIMEOUT, read_message(&mut stream)).await {
+        Ok(Ok(request)) => request,
+        Ok(Err(e)) => {
+            let response = ControlResponse::Error {
+                message: e.to_string(),
+            };
+            return write_message(&mut stream, &response).await;
+        }
+        Err(_) => return Ok(()),
+    };
+    debug!("Control request: {:?}", request);
+
+    let response = match request {
+        ControlRequest::Trigger { name } => match scheduler.trigger(&name) {
+            Ok(()) => ControlResponse::Triggered { name },
+            Err(e) => ControlResponse::Error {
+                message: e.to_string(),
+            },
+        },
+        ControlRequest::Status => ControlResponse::Status(scheduler.status().await),
+    };
+    write_message(&mut stream, &response).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cron_scheduler::CronScheduler;
+    use crate::models::TaskStatus;
+    use crate::parser::parse_workflow_yaml;
+    use crate::state::StateManager;
+    use std::sync::Arc;
+    use std::time::{Duration, Instant};
+
+    #[tokio::test]
+    async fn test_trigger_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let socket_path = dir.path().join("picoflow.sock");
+        let state_manager = Arc::new(StateManager::in_memory().await.unwrap());
+        let mut scheduler = CronScheduler::new(state_manager.clone()).await.unwrap();
+
+        // Yearly schedule, so only the trigger can run it during the test
+        let workflow = parse_workflow_yaml(
+            r#"
+name: nightly
+schedule: "0 0 0 1 1 *"
+tasks:
+  - name: hello
+    type: shell
+    config:
+      command: /bin/true
+"#,
+        )
+        .unwrap();
+        scheduler.add_workflow(workflow).await.unwrap();
+
+        let (shutdown_tx, shutdown_rx) = watch::channel(false);
+        let listener = bind(&socket_path).unwrap();
+        let mode = std::fs::metadata(&socket_path)
+            .unwrap()
+            .permissions()
+            .mode();
+        assert_eq!(mode & 0o777, 0o600);
+        let server = tokio::spawn(serve(listener, scheduler.handle(), shutdown_rx));
+
+        let response = send_request(
+            &socket_path,
+            &ControlRequest::Trigger {
+                name: "nightly".to_string(),
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            response,
+            ControlResponse::Triggered {
+                name: "nightly".to_string()
+            }
+        );
+
+        let response = send_request(
+            &socket_path,
+            &ControlRequest::Trigger {
+                name: "unknown".to_string(),
+            },
+        )
+        .await
+        .unwrap();
+        assert!(
+            matches!(&response, ControlResponse::Error { message } if message.contains("unknown")),
+            "{response:?}"
+        );
+
+        // The triggered run is recorded like a scheduled one
+        let deadline = Instant::now() + Duration::from_secs(10);
+        loop {
+            let history = state_manager
+                .get_execution_history("nightly", 1)
+                .await
+                .unwrap();
+            if history.first().map(|e| &e.status) == Some(&TaskStatus::Success) {
+                break;
+            }
+            assert!(Instant::now() < deadline, "triggered run did not finish");
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+
+        shutdown_tx.send(true).unwrap();
+        server.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_oversized_and_malformed_frames_rejected() {
+        let (mut client, mut server) = tokio::io::duplex(64);
+        client.write_u32(MAX_MESSAGE_SIZE as u32 + 1).await.unwrap();
+        let err = read_message::<_, ControlRequest>(&mut server)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, PicoFlowError::Validation(_)), "{err}");
+
+        let body = br#"{"type":"reboot"}"#;
+        client.write_u32(body.len() as u32).await.unwrap();
+        client.write_all(body).await.unwrap();
+        let err = read_message::<_, ControlRequest>(&mut server)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("Malformed"), "{err}");
+    }
+}
@@ -2,7 +2,7 @@
 //!
 //! This module provides cron-based scheduling for workflow execution using
 //! tokio-cron-scheduler. It supports multiple workflows with different schedules
-//! running concurrently.
+//! running concurrently, optionally capped by [`CronScheduler::set_max_concurrent_workflows`].
 //!
 //! # Example
 //!
@@ -29,13 +29,26 @@
 //! # }
 //! ```
 
+use crate::disk::free_disk_bytes;
 use crate::error::{PicoFlowError, Result};
 use crate::models::WorkflowConfig;
 use crate::scheduler::TaskScheduler;
 use crate::state::StateManager;
-use std::sync::Arc;
+use chrono::{DateTime, Utc};
+use chrono_tz::Tz;
+use croner::parser::{CronParser, Seconds};
+use croner::Cron;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use tokio::sync::{watch, Semaphore};
 use tokio_cron_scheduler::{Job, JobScheduler};
-use tracing::{debug, error, info};
+use tracing::{debug, error, info, warn};
+use uuid::Uuid;
 
 /// Cron-based workflow scheduler
 ///
@@ -46,6 +59,21 @@ pub struct CronScheduler {
     scheduler: JobScheduler,
     /// Task scheduler for executing workflows
     task_scheduler: Arc<TaskScheduler>,
+    /// State manager, consulted for in-flight executions of non-overlapping workflows
+    state_manager: Arc<StateManager>,
+    /// Job and configuration of each scheduled workflow, keyed by workflow name and
+    /// shared with [`SchedulerHandle`]s
+    jobs: SharedJobs,
+    /// Workflow runs in progress, shared with [`SchedulerHandle`]s
+    active_runs: Arc<ActiveRuns>,
+    /// Permits shared by all jobs to bound concurrent workflow runs (`None` = unlimited)
+    run_permits: Option<Arc<Semaphore>>,
+    /// Shutdown signal passed to every workflow run so it can be cancelled
+    shutdown: watch::Receiver<bool>,
+    /// Consecutive-failure tracking that pauses scheduled runs of failing workflows
+    circuit_breaker: Arc<CircuitBreaker>,
+    /// Refuses to start runs while the state database's filesystem is nearly full
+    disk_guard: Option<Arc<DiskSpaceGuard>>,
 }
 
 impl CronScheduler {
@@ -82,11 +110,121 @@ impl CronScheduler {
         Ok(Self {
             scheduler,
             task_scheduler,
+            state_manager,
+            jobs: SharedJobs::default(),
+            active_runs: Arc::new(ActiveRuns::default()),
+            run_permits: None,
+            // Never fires unless replaced via `set_shutdown_signal`
+            shutdown: watch::channel(false).1,
+            circuit_breaker: Arc::new(CircuitBreaker::default()),
+            disk_guard: None,
         })
     }
 
+    /// Record workflow and task metrics (executions, durations, output sizes) in `metrics`
+    ///
+    /// Attached to the shared task scheduler, so this applies to every run that starts
+    /// afterwards, including those of workflows added before the call.
+    #[cfg(feature = "metrics")]
+    pub fn set_metrics(&mut self, metrics: Arc<crate::metrics::MetricsServer>) {
+        self.task_scheduler.set_metrics(metrics);
+    }
+
+    /// Cancel running workflows when `shutdown` becomes `true`
+    ///
+    /// See [`TaskScheduler::execute_workflow_with_shutdown`]. Like the concurrency limit,
+    /// this applies to workflows added after the call.
+    pub fn set_shutdown_signal(&mut self, shutdown: watch::Receiver<bool>) {
+        self.shutdown = shutdown;
+    }
+
+    /// Limit how many workflows may run at the same time
+    ///
+    /// A job that fires while `limit` workflows are already running waits for one of them
+    /// to finish instead of being dropped. `None` (the default) removes the limit.
+    ///
+    /// The limit applies to workflows added after this call, so set it before
+    /// [`CronScheduler::add_workflow`].
+    ///
+    /// # Arguments
+    ///
+    /// * `limit` - Maximum number of concurrently running workflows (must be at least 1)
+    ///
+    /// # Errors
+    ///
+    /// * `PicoFlowError::Validation` - If `limit` is `Some(0)`
+    pub fn set_max_concurrent_workflows(&mut self, limit: Option<usize>) -> Result<()> {
+        self.run_permits = match limit {
+            Some(0) => {
+                return Err(PicoFlowError::Validation(
+                    "max concurrent workflows must be at least 1".to_string(),
+                ))
+            }
+            Some(n) => Some(Arc::new(Semaphore::new(n))),
+            None => None,
+        };
+        Ok(())
+    }
+
+    /// Skip scheduled runs of a workflow after `threshold` consecutive failed runs
+    ///
+    /// Once a workflow's circuit is open, each cron trigger is skipped and logged as
+    /// `workflow '<name>' circuit-open` instead of hammering a broken dependency. Manual
+    /// triggers still run, and any successful run closes the circuit again. `None` (the
+    /// default) never opens a circuit.
+    ///
+    /// Like the concurrency limit, this applies to workflows added after the call.
+    ///
+    /// # Errors
+    ///
+    /// * `PicoFlowError::Validation` - If `threshold` is `Some(0)`
+    pub fn set_circuit_breaker_threshold(&mut self, threshold: Option<u32>) -> Result<()> {
+        if threshold == Some(0) {
+            return Err(PicoFlowError::Validation(
+                "circuit breaker threshold must be at least 1".to_string(),
+            ));
+        }
+        self.circuit_breaker = Arc::new(CircuitBreaker {
+            threshold,
+            failures: Mutex::default(),
+        });
+        Ok(())
+    }
+
+    /// Refuse to start workflow runs while less than `min_free_mb` MiB are free on the
+    /// filesystem holding `db_path`
+    ///
+    /// Writing execution rows to a nearly full SD card can corrupt the state database, so
+    /// a run that would start below the threshold is skipped: an error is logged and
+    /// nothing is recorded. This applies to scheduled and manual runs alike. If free space
+    /// cannot be determined, runs start as usual. `None` (the default) disables the check.
+    ///
+    /// Like the concurrency limit, this applies to workflows added after the call.
+    ///
+    /// # Errors
+    ///
+    /// * `PicoFlowError::Validation` - If `min_free_mb` is `Some(0)`
+    pub fn set_min_free_disk_mb(&mut self, min_free_mb: Option<u64>, db_path: &Path) -> Result<()> {
+        self.disk_guard = match min_free_mb {
+            Some(0) => {
+                return Err(PicoFlowError::Validation(
+                    "min free disk space must be at least 1 MB".to_string(),
+                ))
+            }
+            Some(mb) => Some(Arc::new(DiskSpaceGuard {
+                path: db_path.to_path_buf(),
+                min_free_bytes: mb * 1024 * 1024,
+                free_bytes: free_disk_bytes,
+            })),
+            None => None,
+        };
+        Ok(())
+    }
+
     /// Add a workflow with cron schedule to the scheduler
     ///
+    /// If a workflow with the same name is already scheduled, its job is replaced.
+    ///
     /// # Arguments
     ///
     /// * `workflow` - Workflow configuration with schedule
@@ -97,7 +235,8 @@ impl CronScheduler {
     ///
     /// # Errors
     ///
-    /// * `PicoFlowError::Validation` - If workflow has no schedule or invalid cron expression
+    /// * `PicoFlowError::Validation` - If workflow has no schedule, or an invalid cron
+    ///   expression or timezone
     pub async fn add_workflow(&mut self, workflow: WorkflowConfig) -> Result<()> {
         // Validate workflow has a schedule
         let schedule = workflow.schedule.as_ref().ok_or_else(|| {
@@ -107,54 +246,136 @@ impl CronScheduler {
             ))
         })?;
 
+        let timezone = workflow
+            .timezone
+            .as_deref()
+            .map(parse_timezone)
+            .transpose()?;
+
         info!(
-            "Adding workflow '{}' with schedule: {}",
-            workflow.name, schedule
+            "Adding workflow '{}' with schedule: {} ({})",
+            workflow.name,
+            schedule,
+            workflow.timezone.as_deref().unwrap_or("UTC")
         );
 
-        // Create a job for this workflow
-        let workflow_clone = workflow.clone();
-        let task_scheduler = self.task_scheduler.clone();
-        let workflow_name = workflow.name.clone();
-
         // Create the cron job
-        let job = Job::new_async(schedule.as_str(), move |_uuid, _lock| {
+        let workflow_clone = workflow.clone();
+        let runner = self.runner();
+        let run = move |_uuid, _lock| {
             let workflow = workflow_clone.clone();
-            let scheduler = task_scheduler.clone();
-            let name = workflow_name.clone();
+            let runner = runner.clone();
 
             Box::pin(async move {
-                info!("Cron trigger: executing workflow '{}'", name);
-
-                match scheduler.execute_workflow(&workflow).await {
-                    Ok(success) => {
-                        if success {
-                            info!("Cron workflow '{}' completed successfully", name);
-                        } else {
-                            error!("Cron workflow '{}' failed", name);
-                        }
-                    }
-                    Err(e) => {
-                        error!("Cron workflow '{}' execution error: {}", name, e);
-                    }
-                }
-            })
-        })
+                // "executing" is logged by the runner once the run actually starts
+                info!(
+                    workflow = %workflow.name,
+                    "Cron trigger: queued workflow '{}'", workflow.name
+                );
+                runner.run(&workflow, true).await;
+            }) as Pin<Box<dyn Future<Output = ()> + Send>>
+        };
+        let job = match timezone {
+            Some(tz) => Job::new_async_tz(schedule.as_str(), tz, run),
+            None => Job::new_async(schedule.as_str(), run),
+        }
         .map_err(|e| {
             PicoFlowError::Validation(format!("Invalid cron expression '{}': {}", schedule, e))
         })?;
 
+        // Replace any existing job for this workflow only once the new job is known to be valid
+        self.remove_workflow(&workflow.name).await?;
+
         // Add job to scheduler
-        self.scheduler
+        let job_id = self
+            .scheduler
             .add(job)
             .await
             .map_err(|e| PicoFlowError::Other(format!("Failed to add job: {}", e)))?;
+        self.jobs.lock().insert(
+            workflow.name.clone(),
+            ScheduledWorkflow {
+                job_id,
+                config: workflow.clone(),
+            },
+        );
 
         info!("Workflow '{}' added to scheduler", workflow.name);
 
         Ok(())
     }
 
+    /// Remove a scheduled workflow
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Name of the workflow to remove
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(true)` - The workflow was scheduled and has been removed
+    /// * `Ok(false)` - No workflow with that name was scheduled
+    ///
+    /// # Errors
+    ///
+    /// * `PicoFlowError::Other` - If the underlying job could not be removed
+    pub async fn remove_workflow(&mut self, name: &str) -> Result<bool> {
+        let Some(job_id) = self.job_id(name) else {
+            return Ok(false);
+        };
+
+        self.scheduler
+            .remove(&job_id)
+            .await
+            .map_err(|e| PicoFlowError::Other(format!("Failed to remove job: {}", e)))?;
+        self.jobs.lock().remove(name);
+
+        info!("Workflow '{}' removed from scheduler", name);
+
+        Ok(true)
+    }
+
+    /// Handle for running scheduled workflows on demand and reporting scheduler status,
+    /// e.g. from the daemon's control socket
+    ///
+    /// The handle sees workflows added or removed later, and uses the concurrency limit
+    /// and shutdown signal configured at the time of the call.
+    pub fn handle(&self) -> SchedulerHandle {
+        SchedulerHandle {
+            scheduler: self.scheduler.clone(),
+            runner: self.runner(),
+            jobs: self.jobs.clone(),
+        }
+    }
+
+    fn runner(&self) -> WorkflowRunner {
+        WorkflowRunner {
+            task_scheduler: Arc::clone(&self.task_scheduler),
+            state_manager: Arc::clone(&self.state_manager),
+            run_permits: self.run_permits.clone(),
+            shutdown: self.shutdown.clone(),
+            active_runs: Arc::clone(&self.active_runs),
+            circuit_breaker: Arc::clone(&self.circuit_breaker),
+            disk_guard: self.disk_guard.clone(),
+        }
+    }
+
+    fn job_id(&self, name: &str) -> Option<Uuid> {
+        self.jobs.lock().get(name).map(|job| job.job_id)
+    }
+
+    /// Number of scheduled workflow jobs
+    pub fn job_count(&self) -> usize {
+        self.jobs.lock().len()
+    }
+
+    /// Names of all scheduled workflows, sorted alphabetically
+    pub fn scheduled_workflows(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.jobs.lock().keys().cloned().collect();
+        names.sort();
+        names
+    }
+
     /// Start the cron scheduler
     ///
     /// This starts the background scheduler thread that will execute workflows
@@ -197,6 +418,391 @@ impl CronScheduler {
     }
 }
 
+/// A scheduled workflow and its cron job
+struct ScheduledWorkflow {
+    job_id: Uuid,
+    config: WorkflowConfig,
+}
+
+/// Scheduled workflows keyed by name, shared between a scheduler and its handles
+#[derive(Clone, Default)]
+struct SharedJobs(Arc<Mutex<HashMap<String, ScheduledWorkflow>>>);
+
+impl SharedJobs {
+    /// Lock the map; a panic while holding the lock cannot leave it inconsistent
+    fn lock(&self) -> MutexGuard<'_, HashMap<String, ScheduledWorkflow>> {
+        self.0
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// Workflow runs in progress, keyed by an internal run number
+#[derive(Default)]
+struct ActiveRuns {
+    next_id: AtomicU64,
+    runs: Mutex<HashMap<u64, RunningWorkflow>>,
+}
+
+impl ActiveRuns {
+    /// Record that `workflow` started running; the record is removed when the guard drops
+    fn start(self: &Arc<Self>, workflow: &str) -> ActiveRunGuard {
+        let mut runs = self.lock();
+        self.insert(&mut runs, workflow)
+    }
+
+    /// Like [`ActiveRuns::start`], unless a run of `workflow` is already in progress
+    ///
+    /// Checking and recording happen under one lock, so of two overlapping runs only one
+    /// can start.
+    fn start_exclusive(self: &Arc<Self>, workflow: &str) -> Option<ActiveRunGuard> {
+        let mut runs = self.lock();
+        if runs.values().any(|run| run.workflow == workflow) {
+            return None;
+        }
+        Some(self.insert(&mut runs, workflow))
+    }
+
+    fn insert(
+        self: &Arc<Self>,
+        runs: &mut HashMap<u64, RunningWorkflow>,
+        workflow: &str,
+    ) -> ActiveRunGuard {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        runs.insert(
+            id,
+            RunningWorkflow {
+                workflow: workflow.to_string(),
+                started_at: Utc::now(),
+            },
+        );
+        ActiveRunGuard {
+            runs: Arc::clone(self),
+            id,
+        }
+    }
+
+    fn snapshot(&self) -> Vec<RunningWorkflow> {
+        let mut runs: Vec<RunningWorkflow> = self.lock().values().cloned().collect();
+        runs.sort_by(|a, b| (a.started_at, &a.workflow).cmp(&(b.started_at, &b.workflow)));
+        runs
+    }
+
+    fn lock(&self) -> MutexGuard<'_, HashMap<u64, RunningWorkflow>> {
+        self.runs
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// Removes a run from [`ActiveRuns`] when it finishes or is cancelled
+struct ActiveRunGuard {
+    runs: Arc<ActiveRuns>,
+    id: u64,
+}
+
+impl Drop for ActiveRunGuard {
+    fn drop(&mut self) {
+        self.runs.lock().remove(&self.id);
+    }
+}
+
+/// Consecutive failed runs per workflow, used to pause scheduled runs that keep failing
+#[derive(Default)]
+struct CircuitBreaker {
+    /// Failed runs in a row after which scheduled runs are skipped (`None` = never)
+    threshold: Option<u32>,
+    failures: Mutex<HashMap<String, u32>>,
+}
+
+impl CircuitBreaker {
+    fn consecutive_failures(&self, workflow: &str) -> u32 {
+        self.lock().get(workflow).copied().unwrap_or(0)
+    }
+
+    fn is_open(&self, workflow: &str) -> bool {
+        self.threshold
+            .is_some_and(|threshold| self.consecutive_failures(workflow) >= threshold)
+    }
+
+    /// Record the outcome of a run of `workflow`; a success closes its circuit
+    fn record(&self, workflow: &str, success: bool) {
+        let mut failures = self.lock();
+        if success {
+            if failures
+                .remove(workflow)
+                .is_some_and(|count| self.threshold.is_some_and(|threshold| count >= threshold))
+            {
+                info!(workflow = %workflow, "workflow '{}' circuit-closed", workflow);
+            }
+            return;
+        }
+
+        let count = failures.entry(workflow.to_string()).or_insert(0);
+        *count += 1;
+        if self.threshold == Some(*count) {
+            warn!(
+                workflow = %workflow,
+                "workflow '{}' circuit-open after {} consecutive failures; skipping scheduled runs",
+                workflow,
+                count
+            );
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, HashMap<String, u32>> {
+        self.failures
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// Free-space threshold for the filesystem holding the state database
+struct DiskSpaceGuard {
+    /// State database path; its filesystem is the one measured
+    path: PathBuf,
+    min_free_bytes: u64,
+    /// Reads free space; replaced in tests to simulate a full disk
+    free_bytes: fn(&Path) -> Result<u64>,
+}
+
+impl DiskSpaceGuard {
+    /// Whether a run of `workflow` may start, logging an error if it may not
+    fn has_room(&self, workflow: &str) -> bool {
+        match (self.free_bytes)(&self.path) {
+            Ok(free) if free < self.min_free_bytes => {
+                error!(
+                    workflow = %workflow,
+                    "Not starting workflow '{}': {} MiB free on the filesystem of {}, below min_free_disk_mb={}",
+                    workflow,
+                    free / (1024 * 1024),
+                    self.path.display(),
+                    self.min_free_bytes / (1024 * 1024)
+                );
+                false
+            }
+            Ok(_) => true,
+            Err(e) => {
+                warn!("min_free_disk_mb ignored: {}", e);
+                true
+            }
+        }
+    }
+}
+
+/// A workflow run in progress
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RunningWorkflow {
+    pub workflow: String,
+    pub started_at: DateTime<Utc>,
+}
+
+/// A scheduled workflow and when it next runs
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScheduledJob {
+    pub workflow: String,
+    pub schedule: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timezone: Option<String>,
+    /// `None` if the scheduler is not running or the schedule never fires again
+    pub next_run: Option<DateTime<Utc>>,
+    /// Failed runs in a row since the last success
+    #[serde(default)]
+    pub consecutive_failures: u32,
+    /// Whether scheduled runs are being skipped by the circuit breaker
+    #[serde(default)]
+    pub circuit_open: bool,
+}
+
+/// Live view of a [`CronScheduler`], as reported by `picoflow daemon status`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SchedulerStatus {
+    /// Number of scheduled workflow jobs
+    pub scheduled_jobs: usize,
+    /// Scheduled workflows, sorted by name
+    pub jobs: Vec<ScheduledJob>,
+    /// Workflow runs in progress, oldest first
+    pub running: Vec<RunningWorkflow>,
+}
+
+/// Cloneable handle for running a [`CronScheduler`]'s workflows outside their schedule
+/// and inspecting its state
+#[derive(Clone)]
+pub struct SchedulerHandle {
+    scheduler: JobScheduler,
+    runner: WorkflowRunner,
+    jobs: SharedJobs,
+}
+
+impl SchedulerHandle {
+    /// Start a run of the scheduled workflow `name` now, in the background
+    ///
+    /// The run counts against the concurrency limit like a cron-triggered one and is
+    /// cancelled on shutdown.
+    ///
+    /// # Errors
+    ///
+    /// * `PicoFlowError::Validation` - If no workflow named `name` is scheduled
+    pub fn trigger(&self, name: &str) -> Result<()> {
+        let workflow = self
+            .jobs
+            .lock()
+            .get(name)
+            .map(|job| job.config.clone())
+            .ok_or_else(|| PicoFlowError::Validation(format!("Unknown workflow '{}'", name)))?;
+
+        info!(workflow = %name, "Manual trigger: executing workflow '{}'", name);
+        let runner = self.runner.clone();
+        tokio::spawn(async move { runner.run(&workflow, false).await });
+        Ok(())
+    }
+
+    /// Scheduled jobs with their next run times, and the runs in progress
+    pub async fn status(&self) -> SchedulerStatus {
+        let mut jobs: Vec<(Uuid, ScheduledJob)> = self
+            .jobs
+            .lock()
+            .iter()
+            .map(|(name, job)| {
+                let schedule = job.config.schedule.clone().unwrap_or_default();
+                let scheduled = ScheduledJob {
+                    workflow: name.clone(),
+                    schedule,
+                    timezone: job.config.timezone.clone(),
+                    next_run: None,
+                    consecutive_failures: self.runner.circuit_breaker.consecutive_failures(name),
+                    circuit_open: self.runner.circuit_breaker.is_open(name),
+                };
+                (job.job_id, scheduled)
+            })
+            .collect();
+        jobs.sort_by(|a, b| a.1.workflow.cmp(&b.1.workflow));
+
+        // The lock is released before awaiting the scheduler
+        let mut scheduler = self.scheduler.clone();
+        for (job_id, job) in &mut jobs {
+            job.next_run = scheduler
+                .next_tick_for_job(*job_id)
+                .await
+                .unwrap_or_else(|e| {
+                    debug!("No next run for workflow '{}': {}", job.workflow, e);
+                    None
+                });
+        }
+
+        SchedulerStatus {
+            scheduled_jobs: jobs.len(),
+            jobs: jobs.into_iter().map(|(_, job)| job).collect(),
+            running: self.runner.active_runs.snapshot(),
+        }
+    }
+}
+
+/// What a workflow run needs, shared by cron jobs and manual triggers
+#[derive(Clone)]
+struct WorkflowRunner {
+    task_scheduler: Arc<TaskScheduler>,
+    state_manager: Arc<StateManager>,
+    /// Permits bounding concurrent workflow runs (`None` = unlimited)
+    run_permits: Option<Arc<Semaphore>>,
+    shutdown: watch::Receiver<bool>,
+    active_runs: Arc<ActiveRuns>,
+    circuit_breaker: Arc<CircuitBreaker>,
+    disk_guard: Option<Arc<DiskSpaceGuard>>,
+}
+
+impl WorkflowRunner {
+    /// Execute `workflow` once a run slot is free, logging the outcome
+    ///
+    /// A `scheduled` (cron-triggered) run is skipped while the workflow's circuit is open or,
+    /// unless the workflow sets `allow_overlap`, while a previous run is still in progress.
+    /// Any run is skipped while disk space is below the `min_free_disk_mb` threshold.
+    async fn run(&self, workflow: &WorkflowConfig, scheduled: bool) {
+        let name = &workflow.name;
+
+        if scheduled && self.circuit_breaker.is_open(name) {
+            warn!(workflow = %name, "workflow '{}' circuit-open", name);
+            return;
+        }
+
+        // Held until this run finishes; the semaphore is never closed
+        let _permit = match &self.run_permits {
+            Some(permits) => {
+                if permits.available_permits() == 0 {
+                    info!(
+                        workflow = %name,
+                        "Workflow '{}' waiting for a free run slot", name
+                    );
+                }
+                Arc::clone(permits).acquire_owned().await.ok()
+            }
+            None => None,
+        };
+
+        // Checked once a slot is free, right before anything is written
+        if let Some(guard) = &self.disk_guard {
+            if !guard.has_room(name) {
+                return;
+            }
+        }
+
+        // Also checked only now: a run still queued for a slot has no execution row and
+        // is not active yet, so an earlier check would let overlapping runs through
+        let active = if scheduled && !workflow.allow_overlap {
+            // Runs started by other processes (e.g. `picoflow run`) are only in the database
+            let running_elsewhere = match self.state_manager.has_running_execution(name).await {
+                Ok(running) => running,
+                Err(e) => {
+                    warn!(
+                        workflow = %name,
+                        "Could not check for a running execution of '{}': {}", name, e
+                    );
+                    false
+                }
+            };
+            if running_elsewhere {
+                None
+            } else {
+                self.active_runs.start_exclusive(name)
+            }
+        } else {
+            Some(self.active_runs.start(name))
+        };
+        let Some(_active) = active else {
+            warn!(
+                workflow = %name,
+                "Skipping scheduled run of workflow '{}': previous run still in progress",
+                name
+            );
+            return;
+        };
+        if scheduled {
+            info!(workflow = %name, "Cron trigger: executing workflow '{}'", name);
+        }
+
+        let success = match self
+            .task_scheduler
+            .execute_workflow_with_shutdown(workflow, self.shutdown.clone())
+            .await
+            .map(|result| result.succeeded())
+        {
+            Ok(true) => {
+                info!(workflow = %name, "Workflow '{}' completed successfully", name);
+                true
+            }
+            Ok(false) => {
+                error!(workflow = %name, "Workflow '{}' failed", name);
+                false
+            }
+            Err(e) => {
+                error!(workflow = %name, "Workflow '{}' execution error: {}", name, e);
+                false
+            }
+        };
+        self.circuit_breaker.record(name, success);
+    }
+}
+
 /// Validate cron expression format
 ///
 /// This is a helper function to validate cron expressions before adding them to the scheduler.
@@ -232,11 +838,143 @@ pub fn validate_cron_expression(expression: &str) -> Result<()> {
     Ok(())
 }
 
+/// Parse an IANA timezone name such as `America/New_York`
+///
+/// tokio-cron-scheduler converts the zone to a fixed UTC offset when a job is added, so a
+/// daemon that keeps running across a daylight-saving change fires an hour off until the
+/// workflow is re-added (e.g. by restarting the daemon).
+///
+/// # Errors
+///
+/// * `PicoFlowError::Validation` - If the name is not in the timezone database
+///
+/// # Example
+///
+/// ```
+/// use picoflow::cron_scheduler::parse_timezone;
+///
+/// assert!(parse_timezone("Europe/Berlin").is_ok());
+/// assert!(parse_timezone("Mars/Olympus_Mons").is_err());
+/// ```
+pub fn parse_timezone(name: &str) -> Result<Tz> {
+    name.parse::<Tz>().map_err(|_| {
+        PicoFlowError::Validation(format!(
+            "Invalid timezone '{}': expected an IANA name such as 'America/New_York'",
+            name
+        ))
+    })
+}
+
+/// Compute the next time a cron schedule fires strictly after `after`
+///
+/// Uses the same parser configuration as tokio-cron-scheduler, so any expression accepted
+/// by [`validate_cron_expression`] is accepted here.
+///
+/// # Arguments
+///
+/// * `schedule` - Cron expression string in 6-field format (sec min hour day month dayofweek)
+/// * `after` - Instant to search forward from
+///
+/// # Returns
+///
+/// * `Result<DateTime<Utc>>` - Next trigger time
+///
+/// # Errors
+///
+/// * `PicoFlowError::Validation` - If the expression is invalid or never fires
+///
+/// # Example
+///
+/// ```
+/// use chrono::{TimeZone, Utc};
+/// use picoflow::cron_scheduler::next_run_time;
+///
+/// let after = Utc.with_ymd_and_hms(2025, 1, 1, 12, 0, 0).unwrap();
+/// let next = next_run_time("0 0 2 * * *", after).unwrap();
+/// assert_eq!(next, Utc.with_ymd_and_hms(2025, 1, 2, 2, 0, 0).unwrap());
+/// ```
+pub fn next_run_time(schedule: &str, after: DateTime<Utc>) -> Result<DateTime<Utc>> {
+    let runs = next_run_times(schedule, Tz::UTC, after, 1)?;
+    Ok(runs[0])
+}
+
+/// Validate a cron expression and list its next `count` trigger times from now (UTC)
+///
+/// Used by `picoflow validate` so users can confirm a schedule fires when they intended.
+///
+/// # Errors
+///
+/// * `PicoFlowError::Validation` - If the expression is invalid or stops firing before
+///   `count` runs
+///
+/// # Example
+///
+/// ```
+/// use picoflow::cron_scheduler::describe_schedule;
+///
+/// let runs = describe_schedule("0 0 2 * * *", 3).unwrap();
+/// assert_eq!(runs.len(), 3);
+/// assert!(describe_schedule("invalid", 3).is_err());
+/// ```
+pub fn describe_schedule(expression: &str, count: usize) -> Result<Vec<DateTime<Utc>>> {
+    describe_schedule_in(expression, Tz::UTC, count)
+}
+
+/// Like [`describe_schedule`], for a schedule interpreted in `timezone` (a workflow's
+/// `timezone` field)
+///
+/// # Errors
+///
+/// * `PicoFlowError::Validation` - If the expression is invalid or stops firing before
+///   `count` runs
+pub fn describe_schedule_in(
+    expression: &str,
+    timezone: Tz,
+    count: usize,
+) -> Result<Vec<DateTime<Utc>>> {
+    next_run_times(expression, timezone, Utc::now(), count)
+}
+
+/// The next `count` trigger times of `expression` in `timezone`, strictly after `after`
+fn next_run_times(
+    expression: &str,
+    timezone: Tz,
+    after: DateTime<Utc>,
+    count: usize,
+) -> Result<Vec<DateTime<Utc>>> {
+    let cron = parse_schedule(expression)?;
+
+    let mut runs = Vec::with_capacity(count);
+    let mut after = after.with_timezone(&timezone);
+    for _ in 0..count {
+        after = cron.find_next_occurrence(&after, false).map_err(|e| {
+            PicoFlowError::Validation(format!(
+                "Cron expression '{}' has no next run time: {}",
+                expression, e
+            ))
+        })?;
+        runs.push(after.with_timezone(&Utc));
+    }
+    Ok(runs)
+}
+
+/// Parse a cron expression with the same configuration as tokio-cron-scheduler
+fn parse_schedule(expression: &str) -> Result<Cron> {
+    CronParser::builder()
+        .seconds(Seconds::Required)
+        .dom_and_dow(true)
+        .build()
+        .parse(expression)
+        .map_err(|e| {
+            PicoFlowError::Validation(format!("Invalid cron expression '{}': {}", expression, e))
+        })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::models::{
-        ShellConfig, TaskConfig, TaskExecutorConfig, TaskType, WorkflowGlobalConfig,
+        ShellConfig, TaskConfig, TaskExecutorConfig, TaskStatus, TaskType, WorkflowGlobalConfig,
     };
 
     #[test]
@@ -248,12 +986,85 @@ mod tests {
         assert!(validate_cron_expression("0 */5 * * * *").is_ok()); // 6-field: Every 5 minutes
     }
 
+    #[test]
+    fn test_next_run_times_daily() {
+        use chrono::TimeZone;
+
+        let after = Utc.with_ymd_and_hms(2025, 3, 30, 12, 0, 0).unwrap();
+        let runs = next_run_times("0 0 2 * * *", Tz::UTC, after, 3).unwrap();
+        assert_eq!(
+            runs,
+            [
+                Utc.with_ymd_and_hms(2025, 3, 31, 2, 0, 0).unwrap(),
+                Utc.with_ymd_and_hms(2025, 4, 1, 2, 0, 0).unwrap(),
+                Utc.with_ymd_and_hms(2025, 4, 2, 2, 0, 0).unwrap(),
+            ]
+        );
+
+        // 2 AM Berlin time is midnight UTC in summer
+        let berlin = parse_timezone("Europe/Berlin").unwrap();
+        let runs = next_run_times("0 0 2 * * *", berlin, after, 1).unwrap();
+        assert_eq!(runs, [Utc.with_ymd_and_hms(2025, 3, 31, 0, 0, 0).unwrap()]);
+    }
+
+    #[test]
+    fn test_describe_schedule() {
+        let now = Utc::now();
+        let runs = describe_schedule("0 0 2 * * *", 3).unwrap();
+        assert_eq!(runs.len(), 3);
+        assert!(runs[0] > now && runs[0] <= now + chrono::Duration::days(1));
+        for pair in runs.windows(2) {
+            assert_eq!(pair[1] - pair[0], chrono::Duration::days(1));
+        }
+        assert!(runs
+            .iter()
+            .all(|run| run.format("%H:%M:%S").to_string() == "02:00:00"));
+
+        assert!(describe_schedule("invalid", 3).is_err());
+        assert!(describe_schedule("0 0 2 * * *", 0).unwrap().is_empty());
+    }
+
     #[test]
     fn test_validate_cron_expression_invalid() {
         assert!(validate_cron_expression("invalid").is_err());
         assert!(validate_cron_expression("60 * * * * *").is_err()); // Invalid minute
     }
 
+    #[test]
+    fn test_next_run_time_daily() {
+        use chrono::TimeZone;
+
+        let after = Utc.with_ymd_and_hms(2025, 3, 10, 1, 30, 0).unwrap();
+        assert_eq!(
+            next_run_time("0 0 2 * * *", after).unwrap(),
+            Utc.with_ymd_and_hms(2025, 3, 10, 2, 0, 0).unwrap()
+        );
+
+        // Exactly on a trigger: the next run is the following day
+        let after = Utc.with_ymd_and_hms(2025, 3, 10, 2, 0, 0).unwrap();
+        assert_eq!(
+            next_run_time("0 0 2 * * *", after).unwrap(),
+            Utc.with_ymd_and_hms(2025, 3, 11, 2, 0, 0).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_next_run_time_every_five_minutes() {
+        use chrono::TimeZone;
+
+        let after = Utc.with_ymd_and_hms(2025, 12, 31, 23, 57, 12).unwrap();
+        assert_eq!(
+            next_run_time("0 */5 * * * *", after).unwrap(),
+            Utc.with_ymd_and_hms(2026, 1, 1, 0, 0, 0).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_next_run_time_invalid() {
+        let result = next_run_time("invalid", Utc::now());
+        assert!(matches!(result, Err(PicoFlowError::Validation(_))));
+    }
+
     #[tokio::test]
     async fn test_cron_scheduler_new() {
         let state_manager = Arc::new(StateManager::in_memory().await.unwrap());
@@ -270,7 +1081,12 @@ mod tests {
             name: "test-workflow".to_string(),
             description: Some("Test workflow".to_string()),
             schedule: Some("0 2 * * * *".to_string()), // 6-field format: Daily at 2 AM
+            timezone: None,
             config: WorkflowGlobalConfig::default(),
+            labels: HashMap::new(),
+            allow_overlap: false,
+            on_complete: None,
+            params: HashMap::new(),
             tasks: vec![TaskConfig {
                 name: "test_task".to_string(),
                 task_type: TaskType::Shell,
@@ -278,12 +1094,21 @@ mod tests {
                 config: TaskExecutorConfig::Shell(ShellConfig {
                     command: "/bin/echo".to_string(),
                     args: vec!["test".to_string()],
-                    workdir: None,
-                    env: None,
+                    ..Default::default()
                 }),
                 retry: Some(1),
                 timeout: Some(10),
-                continue_on_failure: false,
+                delay_before: None,
+                continue_on_failure: None,
+                outputs: false,
+                run_condition: Default::default(),
+                enabled: None,
+                labels: HashMap::new(),
+                requires_files: vec![],
+                missing_files_action: Default::default(),
+                cache_key: None,
+                cache_ttl_secs: None,
+                priority: None,
             }],
         };
 
@@ -300,6 +1125,11 @@ mod tests {
             name: "test-workflow".to_string(),
             description: None,
             schedule: None, // No schedule
+            timezone: None,
+            labels: HashMap::new(),
+            allow_overlap: false,
+            on_complete: None,
+            params: HashMap::new(),
             config: WorkflowGlobalConfig::default(),
             tasks: vec![],
         };
@@ -318,6 +1148,11 @@ mod tests {
             name: "test-workflow".to_string(),
             description: None,
             schedule: Some("invalid cron".to_string()),
+            timezone: None,
+            labels: HashMap::new(),
+            allow_overlap: false,
+            on_complete: None,
+            params: HashMap::new(),
             config: WorkflowGlobalConfig::default(),
             tasks: vec![],
         };
@@ -326,6 +1161,98 @@ mod tests {
         assert!(result.is_err());
     }
 
+    fn scheduled_workflow(name: &str, schedule: &str) -> WorkflowConfig {
+        WorkflowConfig {
+            name: name.to_string(),
+            description: None,
+            schedule: Some(schedule.to_string()),
+            timezone: None,
+            labels: HashMap::new(),
+            allow_overlap: false,
+            on_complete: None,
+            params: HashMap::new(),
+            config: WorkflowGlobalConfig::default(),
+            tasks: vec![],
+        }
+    }
+
+    #[tokio::test]
+    async fn test_add_workflow_timezone() {
+        use chrono::Timelike;
+
+        let state_manager = Arc::new(StateManager::in_memory().await.unwrap());
+        let mut scheduler = CronScheduler::new(state_manager).await.unwrap();
+
+        let mut workflow = scheduled_workflow("nightly", "0 0 2 * * *");
+        workflow.timezone = Some("Mars/Olympus_Mons".to_string());
+        let err = scheduler.add_workflow(workflow.clone()).await.unwrap_err();
+        assert!(matches!(err, PicoFlowError::Validation(_)));
+        assert!(err.to_string().contains("Mars/Olympus_Mons"), "{err}");
+        assert_eq!(scheduler.job_count(), 0);
+
+        workflow.timezone = Some("America/New_York".to_string());
+        scheduler.add_workflow(workflow).await.unwrap();
+        assert_eq!(scheduler.job_count(), 1);
+
+        // 2 AM is interpreted in New York, not UTC
+        let job_id = scheduler.job_id("nightly").unwrap();
+        let next = scheduler
+            .scheduler
+            .next_tick_for_job(job_id)
+            .await
+            .unwrap()
+            .expect("job has a next tick");
+        let local = next.with_timezone(&chrono_tz::America::New_York);
+        assert_eq!((local.hour(), local.minute()), (2, 0));
+    }
+
+    #[tokio::test]
+    async fn test_job_tracking_add_replace_remove() {
+        let state_manager = Arc::new(StateManager::in_memory().await.unwrap());
+        let mut scheduler = CronScheduler::new(state_manager).await.unwrap();
+        assert_eq!(scheduler.job_count(), 0);
+
+        scheduler
+            .add_workflow(scheduled_workflow("beta", "0 0 2 * * *"))
+            .await
+            .unwrap();
+        scheduler
+            .add_workflow(scheduled_workflow("alpha", "0 */5 * * * *"))
+            .await
+            .unwrap();
+        assert_eq!(scheduler.job_count(), 2);
+        assert_eq!(scheduler.scheduled_workflows(), vec!["alpha", "beta"]);
+
+        // Re-adding a workflow replaces its job rather than adding a second one
+        let old_job = scheduler.job_id("alpha").unwrap();
+        scheduler
+            .add_workflow(scheduled_workflow("alpha", "0 0 * * * *"))
+            .await
+            .unwrap();
+        assert_eq!(scheduler.job_count(), 2);
+        assert_ne!(scheduler.job_id("alpha").unwrap(), old_job);
+
+        assert!(scheduler.remove_workflow("alpha").await.unwrap());
+        assert!(!scheduler.remove_workflow("alpha").await.unwrap());
+        assert_eq!(scheduler.scheduled_workflows(), vec!["beta"]);
+    }
+
+    #[tokio::test]
+    async fn test_invalid_replacement_keeps_existing_job() {
+        let state_manager = Arc::new(StateManager::in_memory().await.unwrap());
+        let mut scheduler = CronScheduler::new(state_manager).await.unwrap();
+
+        scheduler
+            .add_workflow(scheduled_workflow("alpha", "0 0 2 * * *"))
+            .await
+            .unwrap();
+        assert!(scheduler
+            .add_workflow(scheduled_workflow("alpha", "invalid cron"))
+            .await
+            .is_err());
+        assert_eq!(scheduler.scheduled_workflows(), vec!["alpha"]);
+    }
+
     #[tokio::test]
     async fn test_scheduler_start_stop() {
         let state_manager = Arc::new(StateManager::in_memory().await.unwrap());
@@ -339,4 +1266,296 @@ mod tests {
         let result = scheduler.shutdown().await;
         assert!(result.is_ok());
     }
+
+    #[tokio::test]
+    async fn test_max_concurrent_workflows_rejects_zero() {
+        let state_manager = Arc::new(StateManager::in_memory().await.unwrap());
+        let mut scheduler = CronScheduler::new(state_manager).await.unwrap();
+        assert!(matches!(
+            scheduler.set_max_concurrent_workflows(Some(0)),
+            Err(PicoFlowError::Validation(_))
+        ));
+        assert!(scheduler.set_max_concurrent_workflows(None).is_ok());
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_max_concurrent_workflows_serializes_runs() {
+        let state_manager = Arc::new(StateManager::in_memory().await.unwrap());
+        let mut scheduler = CronScheduler::new(state_manager.clone()).await.unwrap();
+        scheduler.set_max_concurrent_workflows(Some(1)).unwrap();
+
+        let names = ["w1", "w2", "w3"];
+        for name in names {
+            let mut workflow = scheduled_workflow(name, "* * * * * *");
+            workflow.tasks.push(TaskConfig {
+                name: "nap".to_string(),
+                task_type: TaskType::Shell,
+                depends_on: vec![],
+                config: TaskExecutorConfig::Shell(ShellConfig {
+                    command: "/bin/sleep".to_string(),
+                    args: vec!["0.2".to_string()],
+                    ..Default::default()
+                }),
+                retry: Some(0),
+                timeout: Some(10),
+                delay_before: None,
+                continue_on_failure: None,
+                outputs: false,
+                run_condition: Default::default(),
+                enabled: None,
+                labels: HashMap::new(),
+                requires_files: vec![],
+                missing_files_action: Default::default(),
+                cache_key: None,
+                cache_ttl_secs: None,
+                priority: None,
+            });
+            scheduler.add_workflow(workflow).await.unwrap();
+        }
+
+        scheduler.start().await.unwrap();
+        tokio::time::sleep(std::time::Duration::from_millis(2500)).await;
+        scheduler.shutdown().await.unwrap();
+
+        let mut runs = Vec::new();
+        for name in names {
+            for execution in state_manager
+                .get_execution_history(name, 100)
+                .await
+                .unwrap()
+            {
+                if let Some(completed_at) = execution.completed_at {
+                    runs.push((execution.started_at, completed_at));
+                }
+            }
+        }
+        // All three fire on the same second, so without the limit they would overlap
+        assert!(runs.len() >= 2, "expected several runs, got {}", runs.len());
+
+        runs.sort();
+        for pair in runs.windows(2) {
+            assert!(
+                pair[0].1 <= pair[1].0,
+                "runs overlap: {:?} and {:?}",
+                pair[0],
+                pair[1]
+            );
+        }
+    }
+
+    #[cfg(feature = "metrics")]
+    #[tokio::test]
+    async fn test_set_metrics_reaches_existing_runners() {
+        let state_manager = Arc::new(StateManager::in_memory().await.unwrap());
+        let mut scheduler = CronScheduler::new(state_manager).await.unwrap();
+        let workflow = scheduled_workflow("measured", "0 0 2 * * *");
+        scheduler.add_workflow(workflow.clone()).await.unwrap();
+        let runner = scheduler.runner();
+
+        let metrics = Arc::new(crate::metrics::MetricsServer::new());
+        scheduler.set_metrics(metrics.clone());
+        runner.run(&workflow, false).await;
+        assert_eq!(metrics.workflow_success_ratio("measured"), Some(1.0));
+    }
+
+    #[tokio::test]
+    async fn test_circuit_breaker_skips_scheduled_runs_until_success() {
+        let state_manager = Arc::new(StateManager::in_memory().await.unwrap());
+        let mut scheduler = CronScheduler::new(state_manager.clone()).await.unwrap();
+        assert!(matches!(
+            scheduler.set_circuit_breaker_threshold(Some(0)),
+            Err(PicoFlowError::Validation(_))
+        ));
+        scheduler.set_circuit_breaker_threshold(Some(2)).unwrap();
+
+        let shell_workflow = |command: &str| {
+            let mut workflow = scheduled_workflow("flaky", "0 0 2 * * *");
+            workflow.tasks.push(TaskConfig {
+                name: "probe".to_string(),
+                task_type: TaskType::Shell,
+                depends_on: vec![],
+                config: TaskExecutorConfig::Shell(ShellConfig {
+                    command: command.to_string(),
+                    ..Default::default()
+                }),
+                retry: Some(0),
+                timeout: Some(10),
+                delay_before: None,
+                continue_on_failure: None,
+                outputs: false,
+                run_condition: Default::default(),
+                enabled: None,
+                labels: HashMap::new(),
+                requires_files: vec![],
+                missing_files_action: Default::default(),
+                cache_key: None,
+                cache_ttl_secs: None,
+                priority: None,
+            });
+            workflow
+        };
+        let failing = shell_workflow("/bin/false");
+        scheduler.add_workflow(failing.clone()).await.unwrap();
+        let runner = scheduler.runner();
+        let handle = scheduler.handle();
+        let runs = || async {
+            state_manager
+                .get_execution_history("flaky", 100)
+                .await
+                .unwrap()
+                .len()
+        };
+
+        runner.run(&failing, true).await;
+        assert!(!handle.status().await.jobs[0].circuit_open);
+        runner.run(&failing, true).await;
+        let job = handle.status().await.jobs[0].clone();
+        assert_eq!(job.consecutive_failures, 2);
+        assert!(job.circuit_open);
+
+        // Scheduled runs are skipped while the circuit is open
+        runner.run(&failing, true).await;
+        assert_eq!(runs().await, 2);
+
+        // A manual run still goes through, and its success closes the circuit
+        runner.run(&shell_workflow("/bin/true"), false).await;
+        assert_eq!(runs().await, 3);
+        let job = handle.status().await.jobs[0].clone();
+        assert_eq!(job.consecutive_failures, 0);
+        assert!(!job.circuit_open);
+    }
+
+    #[tokio::test]
+    async fn test_min_free_disk_skips_runs_when_disk_is_low() {
+        let state_manager = Arc::new(StateManager::in_memory().await.unwrap());
+        let mut scheduler = CronScheduler::new(state_manager.clone()).await.unwrap();
+        assert!(matches!(
+            scheduler.set_min_free_disk_mb(Some(0), Path::new("picoflow.db")),
+            Err(PicoFlowError::Validation(_))
+        ));
+        scheduler
+            .set_min_free_disk_mb(Some(100), Path::new("picoflow.db"))
+            .unwrap();
+
+        let workflow = scheduled_workflow("disk-bound", "0 0 2 * * *");
+        let runs = || async {
+            state_manager
+                .get_execution_history("disk-bound", 100)
+                .await
+                .unwrap()
+                .len()
+        };
+        let runner_with_free_bytes = |free_bytes: fn(&Path) -> Result<u64>| {
+            let mut runner = scheduler.runner();
+            let guard = runner.disk_guard.as_ref().unwrap();
+            runner.disk_guard = Some(Arc::new(DiskSpaceGuard {
+                path: guard.path.clone(),
+                min_free_bytes: guard.min_free_bytes,
+                free_bytes,
+            }));
+            runner
+        };
+
+        // 50 MiB free: neither scheduled nor manual runs start, and nothing is recorded
+        let low = runner_with_free_bytes(|_| Ok(50 * 1024 * 1024));
+        low.run(&workflow, true).await;
+        low.run(&workflow, false).await;
+        assert_eq!(runs().await, 0);
+        assert_eq!(state_manager.list_workflows().await.unwrap().len(), 0);
+
+        runner_with_free_bytes(|_| Ok(200 * 1024 * 1024))
+            .run(&workflow, true)
+            .await;
+        assert_eq!(runs().await, 1);
+
+        // An unreadable filesystem doesn't block runs
+        runner_with_free_bytes(|_| Err(PicoFlowError::Other("statvfs failed".to_string())))
+            .run(&workflow, true)
+            .await;
+        assert_eq!(runs().await, 2);
+    }
+
+    #[tokio::test]
+    async fn test_overlapping_scheduled_run_is_skipped() {
+        let state_manager = Arc::new(StateManager::in_memory().await.unwrap());
+        let scheduler = CronScheduler::new(state_manager.clone()).await.unwrap();
+        let runner = scheduler.runner();
+        let runs = || async {
+            state_manager
+                .get_execution_history("slow-sync", 100)
+                .await
+                .unwrap()
+                .len()
+        };
+
+        // A previous run that is still going
+        let workflow_id = state_manager
+            .get_or_create_workflow("slow-sync", None)
+            .await
+            .unwrap();
+        let in_flight = state_manager.start_execution(workflow_id).await.unwrap();
+
+        let mut workflow = scheduled_workflow("slow-sync", "0 * * * * *");
+        runner.run(&workflow, true).await;
+        assert_eq!(runs().await, 1);
+
+        // Manual triggers and workflows that allow overlap still run
+        runner.run(&workflow, false).await;
+        assert_eq!(runs().await, 2);
+        workflow.allow_overlap = true;
+        runner.run(&workflow, true).await;
+        assert_eq!(runs().await, 3);
+
+        // Once the previous run finishes, scheduled runs go through again
+        state_manager
+            .update_execution_status(in_flight, TaskStatus::Success)
+            .await
+            .unwrap();
+        workflow.allow_overlap = false;
+        runner.run(&workflow, true).await;
+        assert_eq!(runs().await, 4);
+    }
+
+    #[tokio::test]
+    async fn test_queued_overlapping_runs_start_once() {
+        let state_manager = Arc::new(StateManager::in_memory().await.unwrap());
+        let mut scheduler = CronScheduler::new(state_manager.clone()).await.unwrap();
+        scheduler.set_max_concurrent_workflows(Some(2)).unwrap();
+        let runner = scheduler.runner();
+        let workflow = crate::parser::parse_workflow_yaml(
+            r#"
+name: queued
+schedule: "0 * * * * *"
+tasks:
+  - name: nap
+    type: shell
+    config:
+      command: /bin/sleep
+      args: ["0.3"]
+"#,
+        )
+        .unwrap();
+
+        // Both triggers queue for a slot, then get one at the same time
+        let permits = runner.run_permits.clone().unwrap();
+        let held = permits.acquire_many_owned(2).await.unwrap();
+        let first = tokio::spawn({
+            let (runner, workflow) = (runner.clone(), workflow.clone());
+            async move { runner.run(&workflow, true).await }
+        });
+        let second = tokio::spawn({
+            let (runner, workflow) = (runner.clone(), workflow.clone());
+            async move { runner.run(&workflow, true).await }
+        });
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        drop(held);
+        first.await.unwrap();
+        second.await.unwrap();
+
+        let runs = state_manager
+            .get_execution_history("queued", 10)
+            .await
+            .unwrap();
+        assert_eq!(runs.len(), 1);
+    }
 }
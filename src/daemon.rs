@@ -4,7 +4,11 @@
 //! - PID file management for single-instance enforcement
 //! - Signal handling (SIGTERM for graceful shutdown, SIGHUP for reload)
 //! - Cron scheduler integration for automated workflow execution
-//! - Graceful shutdown that waits for running tasks to complete
+//! - Periodic pruning of execution history according to retention policies
+//! - Optional `/metrics` endpoint and periodic push of metrics to a Prometheus
+//!   Pushgateway (`metrics` feature)
+//! - Optional control socket for `picoflow trigger` (see [`crate::control`])
+//! - Graceful shutdown that cancels running workflows (shell commands receive SIGTERM)
 //!
 //! # Example
 //!
@@ -35,8 +39,12 @@
 //! # }
 //! ```
 
+use crate::control;
 use crate::cron_scheduler::CronScheduler;
 use crate::error::{PicoFlowError, Result};
+use crate::logging::LogLevelHandle;
+#[cfg(feature = "metrics")]
+use crate::metrics::MetricsServer;
 use crate::models::WorkflowConfig;
 use crate::state::StateManager;
 use std::fs;
@@ -49,6 +57,23 @@ use tracing::{debug, error, info, warn};
 /// Shutdown timeout in seconds for graceful daemon termination
 const SHUTDOWN_TIMEOUT_SECONDS: u64 = 30;
 
+/// Interval between execution history retention passes
+const RETENTION_INTERVAL_SECONDS: u64 = 3600;
+
+/// Periodic Pushgateway push settings for the daemon
+#[cfg(feature = "metrics")]
+#[derive(Clone)]
+pub struct MetricsPushConfig {
+    /// Metrics to push
+    pub metrics: Arc<MetricsServer>,
+    /// Base URL of the Pushgateway (e.g. `http://pushgateway:9091`)
+    pub gateway_url: String,
+    /// Job label used for the grouping key
+    pub job: String,
+    /// Time between pushes
+    pub interval: std::time::Duration,
+}
+
 /// Daemon manager for PicoFlow background service
 ///
 /// Manages the lifecycle of the PicoFlow daemon including:
@@ -66,6 +91,16 @@ pub struct Daemon {
     shutdown_tx: watch::Sender<bool>,
     /// Shutdown signal receiver
     shutdown_rx: watch::Receiver<bool>,
+    /// Pushgateway settings, if metrics pushing is enabled
+    #[cfg(feature = "metrics")]
+    metrics_push: Option<MetricsPushConfig>,
+    /// Metrics to serve on `/metrics` and the port to serve them on, if enabled
+    #[cfg(feature = "metrics")]
+    metrics_server: Option<(Arc<MetricsServer>, u16)>,
+    /// Path of the control socket, if `picoflow trigger` should be accepted
+    control_socket: Option<PathBuf>,
+    /// Log level handle cycled on SIGUSR1
+    log_level_handle: Option<LogLevelHandle>,
 }
 
 impl Daemon {
@@ -99,18 +134,25 @@ impl Daemon {
             )));
         }
 
-        // Create cron scheduler
-        let cron_scheduler = CronScheduler::new(state_manager.clone()).await?;
-
         // Create shutdown channel
         let (shutdown_tx, shutdown_rx) = watch::channel(false);
 
+        // Create cron scheduler; running workflows are cancelled on shutdown
+        let mut cron_scheduler = CronScheduler::new(state_manager.clone()).await?;
+        cron_scheduler.set_shutdown_signal(shutdown_rx.clone());
+
         Ok(Self {
             state_manager,
             cron_scheduler,
             pid_file,
             shutdown_tx,
             shutdown_rx,
+            #[cfg(feature = "metrics")]
+            metrics_push: None,
+            #[cfg(feature = "metrics")]
+            metrics_server: None,
+            control_socket: None,
+            log_level_handle: None,
         })
     }
 
@@ -127,43 +169,224 @@ impl Daemon {
         self.cron_scheduler.add_workflow(workflow).await
     }
 
+    /// Register every scheduled workflow found in `dir`
+    ///
+    /// Reads each `*.yaml`/`*.yml` file (in file-name order) with
+    /// [`parse_workflow_file`](crate::parser::parse_workflow_file), so `includes` are
+    /// resolved relative to the file. Files that cannot be read or parsed, lack a
+    /// schedule, or are rejected by the scheduler are skipped with a warning so one broken
+    /// file does not keep the rest from running.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<usize>` - Number of workflows registered
+    ///
+    /// # Errors
+    ///
+    /// * `PicoFlowError::Io` - If the directory itself cannot be read
+    pub async fn load_workflows_from_dir(&mut self, dir: &Path) -> Result<usize> {
+        let mut paths = Vec::new();
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+            let is_yaml = path
+                .extension()
+                .and_then(|ext| ext.to_str())
+                .is_some_and(|ext| ext == "yaml" || ext == "yml");
+            if is_yaml && path.is_file() {
+                paths.push(path);
+            }
+        }
+        paths.sort();
+
+        let mut loaded = 0;
+        for path in paths {
+            let workflow = match crate::parser::parse_workflow_file(&path) {
+                Ok(workflow) => workflow,
+                Err(e) => {
+                    warn!("Skipping workflow file {:?}: {}", path, e);
+                    continue;
+                }
+            };
+            if workflow.schedule.is_none() {
+                warn!(
+                    "Skipping workflow '{}' from {:?}: no schedule defined",
+                    workflow.name, path
+                );
+                continue;
+            }
+            let name = workflow.name.clone();
+            match self.add_workflow(workflow).await {
+                Ok(()) => {
+                    info!("Loaded workflow '{}' from {:?}", name, path);
+                    loaded += 1;
+                }
+                Err(e) => warn!("Skipping workflow '{}' from {:?}: {}", name, path, e),
+            }
+        }
+
+        Ok(loaded)
+    }
+
+    /// Limit how many workflows the daemon runs at the same time
+    ///
+    /// Call before adding workflows; see [`CronScheduler::set_max_concurrent_workflows`].
+    pub fn set_max_concurrent_workflows(&mut self, limit: Option<usize>) -> Result<()> {
+        self.cron_scheduler.set_max_concurrent_workflows(limit)
+    }
+
+    /// Pause scheduled runs of a workflow after `threshold` consecutive failures
+    ///
+    /// Call before adding workflows; see [`CronScheduler::set_circuit_breaker_threshold`].
+    pub fn set_circuit_breaker_threshold(&mut self, threshold: Option<u32>) -> Result<()> {
+        self.cron_scheduler.set_circuit_breaker_threshold(threshold)
+    }
+
+    /// Refuse to start workflow runs while less than `min_free_mb` MiB are free on the
+    /// filesystem holding `db_path`
+    ///
+    /// Call before adding workflows; see [`CronScheduler::set_min_free_disk_mb`].
+    pub fn set_min_free_disk_mb(&mut self, min_free_mb: Option<u64>, db_path: &Path) -> Result<()> {
+        self.cron_scheduler
+            .set_min_free_disk_mb(min_free_mb, db_path)
+    }
+
+    /// Push metrics to a Prometheus Pushgateway on an interval while the daemon runs
+    ///
+    /// Pushing starts when [`Daemon::run`] is called and stops on shutdown. A failed push
+    /// is logged and retried on the next interval. Workflow and task executions are recorded
+    /// into `config.metrics`.
+    #[cfg(feature = "metrics")]
+    pub fn enable_metrics_push(&mut self, config: MetricsPushConfig) {
+        self.cron_scheduler.set_metrics(config.metrics.clone());
+        self.metrics_push = Some(config);
+    }
+
+    /// Serve `metrics` on `/metrics` at `port` while the daemon runs
+    ///
+    /// The server starts when [`Daemon::run`] is called, through
+    /// [`MetricsServer::start_best_effort`]: a port that cannot be bound is logged and
+    /// the daemon runs without it. Workflow and task executions are recorded into
+    /// `metrics`.
+    #[cfg(feature = "metrics")]
+    pub fn enable_metrics_server(&mut self, metrics: Arc<MetricsServer>, port: u16) {
+        self.cron_scheduler.set_metrics(metrics.clone());
+        self.metrics_server = Some((metrics, port));
+    }
+
+    /// Listen for control requests (see [`crate::control`]) on `path` while running
+    ///
+    /// The socket is created when [`Daemon::run`] is called and removed on shutdown.
+    pub fn set_control_socket(&mut self, path: PathBuf) {
+        self.control_socket = Some(path);
+    }
+
+    /// Cycle the log level (info → debug → trace → info) on SIGUSR1 while running
+    pub fn set_log_level_handle(&mut self, handle: LogLevelHandle) {
+        self.log_level_handle = Some(handle);
+    }
+
+    /// Spawn the Pushgateway push loop, which exits when shutdown is signalled
+    #[cfg(feature = "metrics")]
+    fn spawn_metrics_push(&self) {
+        let Some(config) = self.metrics_push.clone() else {
+            return;
+        };
+        let mut shutdown_rx = self.shutdown_rx.clone();
+
+        info!(
+            "Pushing metrics to {} every {:?}",
+            config.gateway_url, config.interval
+        );
+
+        tokio::spawn(async move {
+            let mut timer = tokio::time::interval(config.interval);
+            timer.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            loop {
+                tokio::select! {
+                    _ = timer.tick() => {
+                        if let Err(e) = config
+                            .metrics
+                            .push_to_gateway(&config.gateway_url, &config.job)
+                            .await
+                        {
+                            warn!("Failed to push metrics: {}", e);
+                        }
+                    }
+                    _ = shutdown_rx.changed() => break,
+                }
+            }
+        });
+    }
+
     /// Write PID file with current process ID
     ///
-    /// Uses atomic file creation (O_EXCL) to prevent race conditions.
+    /// The PID is written and synced to a temporary file in the same directory, which is
+    /// then hard-linked into place. Linking fails if the PID file already exists, so
+    /// this still refuses to overwrite another daemon's file, and readers never see a
+    /// partially written PID. On filesystems without hard links the PID file is instead
+    /// created with `create_new` and written in place, which is still exclusive but lets
+    /// a reader briefly see it empty.
     fn write_pid_file(&self) -> Result<()> {
-        use std::fs::OpenOptions;
+        use std::io::Write;
 
         let pid = std::process::id();
         info!("Writing PID file: {:?} (PID: {})", self.pid_file, pid);
 
-        // Use create_new for atomic creation (fails if file exists)
-        let mut file = OpenOptions::new()
-            .write(true)
-            .create_new(true)
-            .open(&self.pid_file)
-            .map_err(|e| {
-                if e.kind() == std::io::ErrorKind::AlreadyExists {
-                    PicoFlowError::Other(format!(
-                        "PID file already exists at {:?}. Another daemon may be running.",
-                        self.pid_file
-                    ))
-                } else {
-                    PicoFlowError::Io(std::io::Error::other(format!(
-                        "Failed to create PID file: {}",
-                        e
-                    )))
-                }
-            })?;
+        let already_exists = || {
+            PicoFlowError::Other(format!(
+                "PID file already exists at {:?}. Another daemon may be running.",
+                self.pid_file
+            ))
+        };
+        if self.pid_file.exists() {
+            return Err(already_exists());
+        }
 
-        use std::io::Write;
-        file.write_all(pid.to_string().as_bytes()).map_err(|e| {
-            PicoFlowError::Io(std::io::Error::other(format!(
+        let temp_file = pid_temp_path(&self.pid_file, pid);
+        let write_temp = || -> std::io::Result<()> {
+            let mut file = fs::File::create(&temp_file)?;
+            file.write_all(pid.to_string().as_bytes())?;
+            file.sync_all()
+        };
+        if let Err(e) = write_temp() {
+            let _ = fs::remove_file(&temp_file);
+            return Err(PicoFlowError::Io(std::io::Error::other(format!(
                 "Failed to write PID to file: {}",
                 e
-            )))
-        })?;
+            ))));
+        }
 
-        Ok(())
+        let published = match fs::hard_link(&temp_file, &self.pid_file) {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => Err(already_exists()),
+            Err(e) => {
+                debug!("Hard link failed ({}), creating PID file in place", e);
+                let write_in_place = || -> std::io::Result<()> {
+                    let mut file = fs::OpenOptions::new()
+                        .write(true)
+                        .create_new(true)
+                        .open(&self.pid_file)?;
+                    file.write_all(pid.to_string().as_bytes())?;
+                    file.sync_all()
+                };
+                match write_in_place() {
+                    Ok(()) => Ok(()),
+                    Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {
+                        Err(already_exists())
+                    }
+                    Err(e) => {
+                        let _ = fs::remove_file(&self.pid_file);
+                        Err(PicoFlowError::Io(std::io::Error::other(format!(
+                            "Failed to create PID file: {}",
+                            e
+                        ))))
+                    }
+                }
+            }
+        };
+        let _ = fs::remove_file(&temp_file);
+
+        published
     }
 
     /// Remove PID file (unused, cleanup handled by PidFileGuard)
@@ -187,6 +410,8 @@ impl Daemon {
     /// - SIGTERM: Graceful shutdown
     /// - SIGINT: Graceful shutdown (Ctrl+C)
     /// - SIGHUP: Reload configuration (not yet implemented)
+    /// - SIGUSR1: Cycle the log level, if a handle was set with
+    ///   [`Daemon::set_log_level_handle`]
     ///
     /// # Returns
     ///
@@ -210,6 +435,26 @@ impl Daemon {
         // Start cron scheduler
         self.cron_scheduler.start().await?;
 
+        let _socket_guard = match &self.control_socket {
+            Some(path) => {
+                let listener = control::bind(path)?;
+                tokio::spawn(control::serve(
+                    listener,
+                    self.cron_scheduler.handle(),
+                    self.shutdown_rx.clone(),
+                ));
+                Some(SocketFileGuard { path: path.clone() })
+            }
+            None => None,
+        };
+
+        #[cfg(feature = "metrics")]
+        if let Some((metrics, port)) = &self.metrics_server {
+            metrics.start_best_effort(*port).await;
+        }
+        #[cfg(feature = "metrics")]
+        self.spawn_metrics_push();
+
         info!("Daemon started successfully, waiting for signals...");
 
         // Setup signal handlers
@@ -222,9 +467,24 @@ impl Daemon {
         let mut sighup = signal(SignalKind::hangup())
             .map_err(|e| PicoFlowError::Other(format!("Failed to setup SIGHUP handler: {}", e)))?;
 
+        let mut sigusr1 = signal(SignalKind::user_defined1())
+            .map_err(|e| PicoFlowError::Other(format!("Failed to setup SIGUSR1 handler: {}", e)))?;
+
+        // Prune execution history on startup and then periodically
+        let mut retention_timer =
+            tokio::time::interval(std::time::Duration::from_secs(RETENTION_INTERVAL_SECONDS));
+        retention_timer.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
         // Wait for signals
         loop {
             tokio::select! {
+                _ = retention_timer.tick() => {
+                    match self.state_manager.apply_retention_policies().await {
+                        Ok(0) => debug!("Retention pass complete, nothing to prune"),
+                        Ok(deleted) => info!("Retention pass pruned {} executions", deleted),
+                        Err(e) => error!("Retention pass failed: {}", e),
+                    }
+                }
                 _ = sigterm.recv() => {
                     info!("Received SIGTERM, initiating graceful shutdown");
                     break;
@@ -238,6 +498,16 @@ impl Daemon {
                     // TODO: Implement config reload
                     // For now, just log and continue waiting
                 }
+                _ = sigusr1.recv() => {
+                    match &self.log_level_handle {
+                        Some(handle) => {
+                            if let Err(e) = handle.cycle() {
+                                error!("Failed to change log level: {}", e);
+                            }
+                        }
+                        None => warn!("Received SIGUSR1, but the log level cannot be changed"),
+                    }
+                }
             }
         }
 
@@ -251,7 +521,8 @@ impl Daemon {
 
     /// Gracefully shutdown the daemon
     ///
-    /// This stops the cron scheduler and waits for any running tasks to complete.
+    /// This signals running workflows to cancel (shell commands receive SIGTERM) and
+    /// stops the cron scheduler.
     /// Note: PID file cleanup is handled by PidFileGuard drop, not here.
     ///
     /// # Returns
@@ -260,7 +531,7 @@ impl Daemon {
     pub async fn shutdown(&mut self) -> Result<()> {
         info!("Shutting down daemon...");
 
-        // Signal shutdown
+        // Signal shutdown, cancelling in-flight workflows
         let _ = self.shutdown_tx.send(true);
 
         // Shutdown cron scheduler (this will wait for running jobs)
@@ -294,6 +565,28 @@ impl Drop for PidFileGuard {
     }
 }
 
+/// RAII guard that removes the control socket file
+struct SocketFileGuard {
+    path: PathBuf,
+}
+
+impl Drop for SocketFileGuard {
+    fn drop(&mut self) {
+        if let Err(e) = fs::remove_file(&self.path) {
+            if e.kind() != std::io::ErrorKind::NotFound {
+                error!("Failed to remove control socket {:?}: {}", self.path, e);
+            }
+        }
+    }
+}
+
+/// Temporary file `write_pid_file` writes before publishing `pid_file`
+fn pid_temp_path(pid_file: &Path, pid: u32) -> PathBuf {
+    let mut name = pid_file.file_name().unwrap_or_default().to_os_string();
+    name.push(format!(".{}.tmp", pid));
+    pid_file.with_file_name(name)
+}
+
 /// Check if daemon is running by reading PID file
 ///
 /// # Arguments
@@ -315,10 +608,19 @@ pub fn check_daemon_running(pid_file: &Path) -> Result<Option<u32>> {
         )))
     })?;
 
-    let pid: u32 = pid_str
-        .trim()
-        .parse()
-        .map_err(|e| PicoFlowError::Other(format!("Invalid PID in file: {}", e)))?;
+    // An empty or garbled file cannot belong to a live daemon (PID files are published
+    // atomically), so it is treated as stale
+    let pid: u32 = match pid_str.trim().parse() {
+        Ok(pid) => pid,
+        Err(e) => {
+            warn!(
+                "Invalid PID file {:?} ({}: {:?}), removing as stale",
+                pid_file, e, pid_str
+            );
+            let _ = fs::remove_file(pid_file);
+            return Ok(None);
+        }
+    };
 
     // Check if process is actually running
     // On Unix, we can use kill(pid, 0) to check without actually killing
@@ -455,6 +757,50 @@ mod tests {
         assert!(!pid_file.exists());
     }
 
+    #[test]
+    fn test_check_daemon_running_removes_garbled_pid_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let pid_file = temp_dir.path().join("test.pid");
+
+        // A crash mid-write could leave an empty or partial file behind
+        for contents in ["", "12\0", "not-a-pid"] {
+            fs::write(&pid_file, contents).unwrap();
+            assert_eq!(
+                check_daemon_running(&pid_file).unwrap(),
+                None,
+                "{contents:?}"
+            );
+            assert!(!pid_file.exists(), "{contents:?}");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_write_pid_file_is_atomic_and_exclusive() {
+        let temp_dir = TempDir::new().unwrap();
+        let pid_file = temp_dir.path().join("test.pid");
+        let state_manager = Arc::new(StateManager::in_memory().await.unwrap());
+        let daemon = Daemon::new(state_manager, pid_file.clone()).await.unwrap();
+
+        daemon.write_pid_file().unwrap();
+        assert_eq!(
+            fs::read_to_string(&pid_file).unwrap(),
+            std::process::id().to_string()
+        );
+        // Only the published file remains
+        let entries: Vec<_> = fs::read_dir(temp_dir.path())
+            .unwrap()
+            .map(|e| e.unwrap().file_name())
+            .collect();
+        assert_eq!(entries, vec![std::ffi::OsString::from("test.pid")]);
+
+        // Publishing again must not replace the existing file
+        fs::write(&pid_file, "12345").unwrap();
+        let err = daemon.write_pid_file().unwrap_err();
+        assert!(matches!(err, PicoFlowError::Other(_)), "{err}");
+        assert_eq!(fs::read_to_string(&pid_file).unwrap(), "12345");
+        assert!(!pid_temp_path(&pid_file, std::process::id()).exists());
+    }
+
     #[tokio::test]
     async fn test_daemon_already_running() {
         let temp_dir = TempDir::new().unwrap();
@@ -492,4 +838,151 @@ mod tests {
         // Clean up manually for test
         let _ = fs::remove_file(&pid_file);
     }
+
+    #[tokio::test]
+    async fn test_load_workflows_from_dir_skips_broken_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let workflows = temp_dir.path().join("workflows");
+        fs::create_dir(&workflows).unwrap();
+        fs::write(
+            workflows.join("backup.yaml"),
+            r#"
+name: backup
+schedule: "0 0 2 * * *"
+tasks:
+  - name: dump
+    type: shell
+    config:
+      command: "/bin/true"
+"#,
+        )
+        .unwrap();
+        fs::write(workflows.join("broken.yml"), "name: [unterminated").unwrap();
+        fs::write(
+            temp_dir.path().join("common.yaml"),
+            "tasks:\n  - name: setup\n    type: shell\n    config:\n      command: /bin/true\n",
+        )
+        .unwrap();
+        fs::write(
+            workflows.join("report.yaml"),
+            r#"
+name: report
+schedule: "0 0 3 * * *"
+includes: [../common.yaml]
+tasks:
+  - name: render
+    type: shell
+    depends_on: [setup]
+    config:
+      command: "/bin/true"
+"#,
+        )
+        .unwrap();
+        fs::write(workflows.join("notes.txt"), "not a workflow").unwrap();
+
+        let state_manager = Arc::new(StateManager::in_memory().await.unwrap());
+        let mut daemon = Daemon::new(state_manager, temp_dir.path().join("test.pid"))
+            .await
+            .unwrap();
+
+        assert_eq!(daemon.load_workflows_from_dir(&workflows).await.unwrap(), 2);
+        let mut scheduled = daemon.cron_scheduler.scheduled_workflows();
+        scheduled.sort();
+        assert_eq!(scheduled, vec!["backup".to_string(), "report".to_string()]);
+
+        assert!(daemon
+            .load_workflows_from_dir(&temp_dir.path().join("missing"))
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_status_over_control_socket() {
+        use crate::control::{send_request, ControlRequest, ControlResponse};
+        use crate::parser::parse_workflow_yaml;
+
+        let temp_dir = TempDir::new().unwrap();
+        let pid_file = temp_dir.path().join("test.pid");
+        let socket = temp_dir.path().join("test.sock");
+        let state_manager = Arc::new(StateManager::in_memory().await.unwrap());
+
+        let mut daemon = Daemon::new(state_manager, pid_file.clone()).await.unwrap();
+        daemon.set_control_socket(socket.clone());
+        let workflow = parse_workflow_yaml(
+            r#"
+name: backup
+schedule: "0 0 2 * * *"
+tasks:
+  - name: noop
+    type: shell
+    config:
+      command: /bin/true
+"#,
+        )
+        .unwrap();
+        daemon.add_workflow(workflow).await.unwrap();
+        let running = tokio::spawn(async move { daemon.run().await });
+
+        // Poll until the daemon has bound its socket
+        let deadline = std::time::Instant::now() + std::time::Duration::from_secs(10);
+        let status = loop {
+            match send_request(&socket, &ControlRequest::Status).await {
+                Ok(ControlResponse::Status(status)) => break status,
+                Ok(other) => panic!("unexpected response: {other:?}"),
+                Err(_) => {
+                    assert!(std::time::Instant::now() < deadline, "daemon did not start");
+                    tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+                }
+            }
+        };
+
+        assert_eq!(status.scheduled_jobs, 1);
+        assert_eq!(status.jobs[0].workflow, "backup");
+        assert_eq!(status.jobs[0].schedule, "0 0 2 * * *");
+        assert!(status.jobs[0].next_run.is_some());
+        assert!(status.running.is_empty());
+
+        // Dropping the run future removes the PID file and socket
+        running.abort();
+        let _ = running.await;
+        assert!(!pid_file.exists());
+        assert!(!socket.exists());
+    }
+
+    #[cfg(feature = "metrics")]
+    #[tokio::test]
+    async fn test_busy_metrics_port_does_not_stop_daemon() {
+        use crate::control::{send_request, ControlRequest, ControlResponse};
+
+        let temp_dir = TempDir::new().unwrap();
+        let socket = temp_dir.path().join("test.sock");
+        let state_manager = Arc::new(StateManager::in_memory().await.unwrap());
+        let busy = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+
+        let mut daemon = Daemon::new(state_manager, temp_dir.path().join("test.pid"))
+            .await
+            .unwrap();
+        daemon.set_control_socket(socket.clone());
+        daemon.enable_metrics_server(
+            Arc::new(MetricsServer::new()),
+            busy.local_addr().unwrap().port(),
+        );
+        let running = tokio::spawn(async move { daemon.run().await });
+
+        let deadline = std::time::Instant::now() + std::time::Duration::from_secs(10);
+        loop {
+            match send_request(&socket, &ControlRequest::Status).await {
+                Ok(ControlResponse::Status(_)) => break,
+                Ok(other) => panic!("unexpected response: {other:?}"),
+                Err(_) => {
+                    assert!(!running.is_finished(), "daemon exited");
+                    assert!(std::time::Instant::now() < deadline, "daemon did not start");
+                    tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+                }
+            }
+        }
+
+        running.abort();
+        let _ = running.await;
+    }
 }
@@ -1,10 +1,10 @@
 //! DAG (Directed Acyclic Graph) engine for task dependency resolution
 
-use crate::error::{PicoFlowError, Result};
-use crate::models::TaskConfig;
-use petgraph::algo::{is_cyclic_directed, toposort};
+use crate::error::{PicoFlowError, Result, ValidationError};
+use crate::models::{Dependency, TaskConfig, MAX_TASK_NAME_LEN};
+use petgraph::algo::{is_cyclic_directed, tarjan_scc, toposort};
 use petgraph::graph::{DiGraph, NodeIndex};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 /// DAG (Directed Acyclic Graph) engine for workflow task management.
 ///
@@ -38,6 +38,8 @@ impl DagEngine {
     ///
     /// # Errors
     ///
+    /// * `PicoFlowError::DuplicateTask` - If two tasks share a name
+    /// * `PicoFlowError::MissingDependency` - If a task depends on a task that does not exist
     /// * `PicoFlowError::CycleDetected` - If circular dependencies are found
     ///
     /// # Example
@@ -55,11 +57,25 @@ impl DagEngine {
     ///             command: "/bin/echo".to_string(),
     ///             args: vec!["hello".to_string()],
     ///             workdir: None,
+    ///             create_workdir: false,
     ///             env: None,
+    ///             env_file: None,
+    ///             fail_on_stderr_regex: None,
+    ///             shell: None,
     ///         }),
     ///         retry: Some(3),
     ///         timeout: Some(300),
-    ///         continue_on_failure: false,
+    ///         delay_before: None,
+    ///         continue_on_failure: None,
+    ///         outputs: false,
+    ///         run_condition: Default::default(),
+    ///         enabled: None,
+    ///         labels: Default::default(),
+    ///         requires_files: vec![],
+    ///         missing_files_action: Default::default(),
+    ///         cache_key: None,
+    ///         cache_ttl_secs: None,
+    ///         priority: None,
     ///     },
     /// ];
     ///
@@ -72,6 +88,9 @@ impl DagEngine {
 
         // Create nodes for all tasks
         for task in tasks {
+            if task_indices.contains_key(&task.name) {
+                return Err(PicoFlowError::DuplicateTask(task.name.clone()));
+            }
             let index = graph.add_node(task.name.clone());
             task_indices.insert(task.name.clone(), index);
         }
@@ -79,14 +98,14 @@ impl DagEngine {
         // Create edges from dependencies
         for task in tasks {
             let task_index = task_indices[&task.name];
-            for dep_name in &task.depends_on {
+            for dep in &task.depends_on {
                 // Validate that the dependency task exists
-                let dep_index = match task_indices.get(dep_name) {
+                let dep_index = match task_indices.get(dep.task()) {
                     Some(&index) => index,
                     None => {
                         return Err(PicoFlowError::MissingDependency {
                             task: task.name.clone(),
-                            dependency: dep_name.clone(),
+                            dependency: dep.task().to_string(),
                         });
                     }
                 };
@@ -106,6 +125,115 @@ impl DagEngine {
         Ok(engine)
     }
 
+    /// Check tasks for every structural problem without stopping at the first one.
+    ///
+    /// Unlike [`DagEngine::build`], this reports duplicate task names, over-long task names,
+    /// missing dependencies and every independent cycle together. Duplicate names resolve to
+    /// their first occurrence and missing dependencies are left out of the graph so that
+    /// cycle detection still runs on the rest.
+    ///
+    /// # Arguments
+    ///
+    /// * `tasks` - Array of task configurations with dependencies
+    ///
+    /// # Returns
+    ///
+    /// All problems found, in task order (empty if the tasks form a valid DAG)
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use picoflow::dag::DagEngine;
+    /// # use picoflow::models::TaskConfig;
+    /// # let tasks: Vec<TaskConfig> = vec![];
+    /// for problem in DagEngine::validate_all(&tasks) {
+    ///     eprintln!("{}", problem);
+    /// }
+    /// ```
+    pub fn validate_all(tasks: &[TaskConfig]) -> Vec<ValidationError> {
+        let mut errors = Vec::new();
+        let mut graph = DiGraph::new();
+        let mut task_indices = HashMap::new();
+        let mut reported_duplicates = HashSet::new();
+
+        for task in tasks {
+            if task.name.len() > MAX_TASK_NAME_LEN {
+                errors.push(ValidationError::TaskNameTooLong {
+                    name: task.name.clone(),
+                    max: MAX_TASK_NAME_LEN,
+                });
+            }
+            if task_indices.contains_key(&task.name) {
+                if reported_duplicates.insert(task.name.clone()) {
+                    errors.push(ValidationError::DuplicateTask(task.name.clone()));
+                }
+                continue;
+            }
+            let index = graph.add_node(task.name.clone());
+            task_indices.insert(task.name.clone(), index);
+        }
+
+        for task in tasks {
+            let task_index = task_indices[&task.name];
+            for dep_name in task.depends_on.iter().map(Dependency::task) {
+                if dep_name == task.name {
+                    // Reported on its own rather than as a one-task cycle
+                    errors.push(ValidationError::SelfDependency(task.name.clone()));
+                    continue;
+                }
+                match task_indices.get(dep_name) {
+                    Some(&dep_index) => {
+                        graph.add_edge(dep_index, task_index, ());
+                    }
+                    None => errors.push(ValidationError::MissingDependency {
+                        task: task.name.clone(),
+                        dependency: dep_name.to_string(),
+                    }),
+                }
+            }
+        }
+
+        // Each strongly connected component with more than one node (or a self-loop)
+        // contains at least one cycle; report one path per component.
+        let mut components = tarjan_scc(&graph);
+        components.sort_by_key(|component| component.iter().min().copied());
+        for component in components {
+            let is_cycle = component.len() > 1 || graph.contains_edge(component[0], component[0]);
+            if is_cycle {
+                errors.push(ValidationError::Cycle(Self::component_cycle(
+                    &graph, &component,
+                )));
+            }
+        }
+
+        errors
+    }
+
+    /// Walk a strongly connected component from its first-declared node until a node
+    /// repeats, returning the cycle as `a -> b -> a`.
+    fn component_cycle(graph: &DiGraph<String, ()>, component: &[NodeIndex]) -> String {
+        let members: HashSet<NodeIndex> = component.iter().copied().collect();
+        let start = *component.iter().min().expect("components are non-empty");
+        let mut path = vec![start];
+        let mut current = start;
+
+        loop {
+            // Every node in a cyclic component has a successor inside the component
+            let next = graph
+                .neighbors(current)
+                .filter(|n| members.contains(n))
+                .min()
+                .expect("cyclic component node has an in-component successor");
+            if let Some(pos) = path.iter().position(|&n| n == next) {
+                let mut names: Vec<&str> = path[pos..].iter().map(|&n| graph[n].as_str()).collect();
+                names.push(graph[next].as_str());
+                return names.join(" -> ");
+            }
+            path.push(next);
+            current = next;
+        }
+    }
+
     /// Validate that the graph contains no cycles.
     ///
     /// This method checks for circular dependencies in the task graph. If a cycle
@@ -290,6 +418,40 @@ impl DagEngine {
         levels
     }
 
+    /// Number of levels in the DAG: the length of its longest dependency chain.
+    ///
+    /// Equal to `parallel_levels().len()`; `0` for an empty workflow.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use picoflow::dag::DagEngine;
+    /// # use picoflow::models::TaskConfig;
+    /// # let tasks: Vec<TaskConfig> = vec![];
+    /// let dag = DagEngine::build(&tasks)?;
+    /// println!("Longest chain: {} tasks", dag.max_depth());
+    /// # Ok::<(), picoflow::error::PicoFlowError>(())
+    /// ```
+    pub fn max_depth(&self) -> usize {
+        self.parallel_levels().len()
+    }
+
+    /// Fail if the DAG is deeper than `limit` levels.
+    ///
+    /// # Errors
+    ///
+    /// * `PicoFlowError::Validation` - If [`max_depth`](Self::max_depth) exceeds `limit`
+    pub fn check_max_depth(&self, limit: usize) -> Result<()> {
+        let depth = self.max_depth();
+        if depth > limit {
+            return Err(PicoFlowError::Validation(format!(
+                "DAG depth {} exceeds max_depth {}; long dependency chains run one task at a time",
+                depth, limit
+            )));
+        }
+        Ok(())
+    }
+
     /// Get all tasks that directly depend on the given task.
     ///
     /// Returns the immediate children of a task in the dependency graph
@@ -359,6 +521,200 @@ impl DagEngine {
             Vec::new()
         }
     }
+
+    /// Get every task the given task transitively depends on.
+    ///
+    /// These are all tasks that must finish before `task_name` can start, not just its
+    /// direct dependencies. Tasks are returned in declaration order.
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<String>` - Names of all ancestor tasks (empty if none or task not found)
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use picoflow::dag::DagEngine;
+    /// # use picoflow::models::TaskConfig;
+    /// # let tasks: Vec<TaskConfig> = vec![];
+    /// let dag = DagEngine::build(&tasks)?;
+    /// println!("task_c waits for: {:?}", dag.ancestors("task_c"));
+    /// # Ok::<(), picoflow::error::PicoFlowError>(())
+    /// ```
+    pub fn ancestors(&self, task_name: &str) -> Vec<String> {
+        let Some(&start) = self.task_indices.get(task_name) else {
+            return Vec::new();
+        };
+
+        let mut seen: HashSet<NodeIndex> = HashSet::new();
+        let mut stack = vec![start];
+        while let Some(node) = stack.pop() {
+            for parent in self
+                .graph
+                .neighbors_directed(node, petgraph::Direction::Incoming)
+            {
+                if seen.insert(parent) {
+                    stack.push(parent);
+                }
+            }
+        }
+
+        let mut ancestors: Vec<NodeIndex> = seen.into_iter().collect();
+        ancestors.sort();
+        ancestors
+            .into_iter()
+            .map(|index| self.graph[index].clone())
+            .collect()
+    }
+
+    /// Get the entry points of the workflow: tasks with no dependencies.
+    ///
+    /// Tasks are returned in declaration order. Every non-empty DAG has at least one root.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use picoflow::dag::DagEngine;
+    /// # use picoflow::models::TaskConfig;
+    /// # let tasks: Vec<TaskConfig> = vec![];
+    /// let dag = DagEngine::build(&tasks)?;
+    /// println!("Starts with: {:?}", dag.roots());
+    /// # Ok::<(), picoflow::error::PicoFlowError>(())
+    /// ```
+    pub fn roots(&self) -> Vec<String> {
+        self.nodes_without_neighbors(petgraph::Direction::Incoming)
+    }
+
+    /// Get the terminal tasks of the workflow: tasks no other task depends on.
+    ///
+    /// Tasks are returned in declaration order. A task that is both a root and a leaf is
+    /// isolated from the rest of the DAG.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use picoflow::dag::DagEngine;
+    /// # use picoflow::models::TaskConfig;
+    /// # let tasks: Vec<TaskConfig> = vec![];
+    /// let dag = DagEngine::build(&tasks)?;
+    /// println!("Ends with: {:?}", dag.leaves());
+    /// # Ok::<(), picoflow::error::PicoFlowError>(())
+    /// ```
+    pub fn leaves(&self) -> Vec<String> {
+        self.nodes_without_neighbors(petgraph::Direction::Outgoing)
+    }
+
+    /// Names of nodes with no edges in `direction` (in-degree or out-degree zero)
+    fn nodes_without_neighbors(&self, direction: petgraph::Direction) -> Vec<String> {
+        self.graph
+            .node_indices()
+            .filter(|&index| {
+                self.graph
+                    .neighbors_directed(index, direction)
+                    .next()
+                    .is_none()
+            })
+            .map(|index| self.graph[index].clone())
+            .collect()
+    }
+
+    /// Render the DAG as a graphviz DOT digraph.
+    ///
+    /// Nodes are labeled by task name and emitted in declaration order, followed by one
+    /// `dependency -> task` edge per dependency. Names are always quoted, so spaces and
+    /// special characters are safe.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use picoflow::dag::DagEngine;
+    /// # use picoflow::models::TaskConfig;
+    /// # let tasks: Vec<TaskConfig> = vec![];
+    /// let dag = DagEngine::build(&tasks)?;
+    /// println!("{}", dag.to_dot()); // pipe into `dot -Tsvg`
+    /// # Ok::<(), picoflow::error::PicoFlowError>(())
+    /// ```
+    pub fn to_dot(&self) -> String {
+        let mut out = String::from("digraph workflow {\n");
+        for index in self.graph.node_indices() {
+            out.push_str(&format!("    {};\n", dot_quote(&self.graph[index])));
+        }
+        for edge in self.graph.raw_edges() {
+            out.push_str(&format!(
+                "    {} -> {};\n",
+                dot_quote(&self.graph[edge.source()]),
+                dot_quote(&self.graph[edge.target()])
+            ));
+        }
+        out.push_str("}\n");
+        out
+    }
+
+    /// Render the DAG as a Mermaid flowchart.
+    ///
+    /// Task names are not valid Mermaid node IDs in general, so each node gets a
+    /// positional ID (`n0`, `n1`, ...) and the task name as its quoted label.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use picoflow::dag::DagEngine;
+    /// # use picoflow::models::TaskConfig;
+    /// # let tasks: Vec<TaskConfig> = vec![];
+    /// let dag = DagEngine::build(&tasks)?;
+    /// println!("```mermaid\n{}```", dag.to_mermaid());
+    /// # Ok::<(), picoflow::error::PicoFlowError>(())
+    /// ```
+    pub fn to_mermaid(&self) -> String {
+        let mut out = String::from("flowchart TD\n");
+        for index in self.graph.node_indices() {
+            out.push_str(&format!(
+                "    n{}[\"{}\"]\n",
+                index.index(),
+                mermaid_escape(&self.graph[index])
+            ));
+        }
+        for edge in self.graph.raw_edges() {
+            out.push_str(&format!(
+                "    n{} --> n{}\n",
+                edge.source().index(),
+                edge.target().index()
+            ));
+        }
+        out
+    }
+}
+
+/// Quote a string as a DOT ID, escaping backslashes, quotes and newlines.
+fn dot_quote(name: &str) -> String {
+    let mut quoted = String::with_capacity(name.len() + 2);
+    quoted.push('"');
+    for c in name.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            _ => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Escape a Mermaid label for use inside `["..."]` using Mermaid entity codes.
+fn mermaid_escape(name: &str) -> String {
+    let mut escaped = String::with_capacity(name.len());
+    for c in name.chars() {
+        match c {
+            '"' => escaped.push_str("#quot;"),
+            '#' => escaped.push_str("#35;"),
+            '<' => escaped.push_str("#lt;"),
+            '>' => escaped.push_str("#gt;"),
+            '\n' => escaped.push(' '),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
 }
 
 #[cfg(test)]
@@ -370,16 +726,24 @@ mod tests {
         TaskConfig {
             name: name.to_string(),
             task_type: TaskType::Shell,
-            depends_on,
+            depends_on: depends_on.into_iter().map(Dependency::from).collect(),
             config: TaskExecutorConfig::Shell(ShellConfig {
                 command: "/bin/true".to_string(),
-                args: vec![],
-                workdir: None,
-                env: None,
+                ..Default::default()
             }),
             retry: Some(3),
             timeout: Some(300),
-            continue_on_failure: false,
+            delay_before: None,
+            continue_on_failure: None,
+            outputs: false,
+            run_condition: Default::default(),
+            enabled: None,
+            labels: HashMap::new(),
+            requires_files: vec![],
+            missing_files_action: Default::default(),
+            cache_key: None,
+            cache_ttl_secs: None,
+            priority: None,
         }
     }
 
@@ -439,6 +803,21 @@ mod tests {
         assert!(matches!(result, Err(PicoFlowError::CycleDetected(_))));
     }
 
+    #[test]
+    fn test_duplicate_task_name() {
+        // The duplicates also form a cycle; the duplicate must be reported first
+        let tasks = vec![
+            create_test_task("deploy", vec!["build".to_string()]),
+            create_test_task("build", vec!["deploy".to_string()]),
+            create_test_task("deploy", vec![]),
+        ];
+
+        match DagEngine::build(&tasks) {
+            Err(PicoFlowError::DuplicateTask(name)) => assert_eq!(name, "deploy"),
+            other => panic!("expected DuplicateTask, got {other:?}"),
+        }
+    }
+
     #[test]
     fn test_missing_dependency() {
         let tasks = vec![
@@ -468,6 +847,80 @@ mod tests {
         assert_eq!(sorted.len(), 3);
     }
 
+    #[test]
+    fn test_max_depth_of_chain_and_diamond() {
+        let chain: Vec<TaskConfig> = (0..5)
+            .map(|i| {
+                let deps = if i == 0 {
+                    vec![]
+                } else {
+                    vec![format!("t{}", i - 1)]
+                };
+                create_test_task(&format!("t{}", i), deps)
+            })
+            .collect();
+        let dag = DagEngine::build(&chain).unwrap();
+        assert_eq!(dag.max_depth(), 5);
+        assert!(dag.check_max_depth(5).is_ok());
+        let err = dag.check_max_depth(4).unwrap_err();
+        assert!(
+            matches!(err, PicoFlowError::Validation(ref m) if m.contains("depth 5 exceeds max_depth 4"))
+        );
+
+        let diamond = vec![
+            create_test_task("a", vec![]),
+            create_test_task("b", vec!["a".to_string()]),
+            create_test_task("c", vec!["a".to_string()]),
+            create_test_task("d", vec!["b".to_string(), "c".to_string()]),
+        ];
+        assert_eq!(DagEngine::build(&diamond).unwrap().max_depth(), 3);
+        assert_eq!(DagEngine::build(&[]).unwrap().max_depth(), 0);
+    }
+
+    #[test]
+    fn test_roots_and_leaves_diamond() {
+        let tasks = vec![
+            create_test_task("a", vec![]),
+            create_test_task("b", vec!["a".to_string()]),
+            create_test_task("c", vec!["a".to_string()]),
+            create_test_task("d", vec!["b".to_string(), "c".to_string()]),
+        ];
+        let dag = DagEngine::build(&tasks).unwrap();
+
+        assert_eq!(dag.roots(), vec!["a"]);
+        assert_eq!(dag.leaves(), vec!["d"]);
+    }
+
+    #[test]
+    fn test_roots_and_leaves_disconnected() {
+        let tasks = vec![
+            create_test_task("fetch", vec![]),
+            create_test_task("ping", vec![]),
+            create_test_task("process", vec!["fetch".to_string()]),
+            create_test_task("cleanup", vec![]),
+        ];
+        let dag = DagEngine::build(&tasks).unwrap();
+
+        assert_eq!(dag.roots(), vec!["fetch", "ping", "cleanup"]);
+        assert_eq!(dag.leaves(), vec!["ping", "process", "cleanup"]);
+    }
+
+    #[test]
+    fn test_ancestors_are_transitive() {
+        let tasks = vec![
+            create_test_task("a", vec![]),
+            create_test_task("b", vec!["a".to_string()]),
+            create_test_task("other", vec![]),
+            create_test_task("c", vec!["b".to_string()]),
+            create_test_task("d", vec!["c".to_string(), "a".to_string()]),
+        ];
+        let dag = DagEngine::build(&tasks).unwrap();
+
+        assert_eq!(dag.ancestors("d"), vec!["a", "b", "c"]);
+        assert!(dag.ancestors("a").is_empty());
+        assert!(dag.ancestors("missing").is_empty());
+    }
+
     #[test]
     fn test_get_dependencies() {
         let tasks = vec![
@@ -530,4 +983,109 @@ mod tests {
         assert_eq!(levels[2], vec!["d"]);
         assert_eq!(levels[3], vec!["e"]);
     }
+
+    #[test]
+    fn test_validate_all_reports_every_problem() {
+        let long_name = "x".repeat(MAX_TASK_NAME_LEN + 1);
+        let tasks = vec![
+            create_test_task("a", vec!["c".to_string()]),
+            create_test_task("b", vec!["a".to_string()]),
+            create_test_task("c", vec!["b".to_string()]),
+            create_test_task("loop", vec!["loop".to_string()]),
+            create_test_task("deploy", vec!["ghost".to_string()]),
+            create_test_task("deploy", vec![]),
+            create_test_task(&long_name, vec![]),
+        ];
+
+        let errors = DagEngine::validate_all(&tasks);
+        assert_eq!(
+            errors,
+            vec![
+                ValidationError::DuplicateTask("deploy".to_string()),
+                ValidationError::TaskNameTooLong {
+                    name: long_name,
+                    max: MAX_TASK_NAME_LEN,
+                },
+                ValidationError::SelfDependency("loop".to_string()),
+                ValidationError::MissingDependency {
+                    task: "deploy".to_string(),
+                    dependency: "ghost".to_string(),
+                },
+                ValidationError::Cycle("a -> b -> c -> a".to_string()),
+            ]
+        );
+
+        // A valid DAG yields no errors
+        let valid = vec![
+            create_test_task("a", vec![]),
+            create_test_task("b", vec!["a".to_string()]),
+        ];
+        assert!(DagEngine::validate_all(&valid).is_empty());
+    }
+
+    #[test]
+    fn test_to_dot_diamond() {
+        let tasks = vec![
+            create_test_task("a", vec![]),
+            create_test_task("b", vec!["a".to_string()]),
+            create_test_task("c", vec!["a".to_string()]),
+            create_test_task("d", vec!["b".to_string(), "c".to_string()]),
+        ];
+        let dag = DagEngine::build(&tasks).unwrap();
+
+        let expected = "\
+digraph workflow {
+    \"a\";
+    \"b\";
+    \"c\";
+    \"d\";
+    \"a\" -> \"b\";
+    \"a\" -> \"c\";
+    \"b\" -> \"d\";
+    \"c\" -> \"d\";
+}
+";
+        assert_eq!(dag.to_dot(), expected);
+    }
+
+    #[test]
+    fn test_to_mermaid_diamond() {
+        let tasks = vec![
+            create_test_task("a", vec![]),
+            create_test_task("b", vec!["a".to_string()]),
+            create_test_task("c", vec!["a".to_string()]),
+            create_test_task("d", vec!["b".to_string(), "c".to_string()]),
+        ];
+        let dag = DagEngine::build(&tasks).unwrap();
+
+        let expected = "\
+flowchart TD
+    n0[\"a\"]
+    n1[\"b\"]
+    n2[\"c\"]
+    n3[\"d\"]
+    n0 --> n1
+    n0 --> n2
+    n1 --> n3
+    n2 --> n3
+";
+        assert_eq!(dag.to_mermaid(), expected);
+    }
+
+    #[test]
+    fn test_graph_export_escapes_names() {
+        let tasks = vec![
+            create_test_task("fetch \"raw\" data", vec![]),
+            create_test_task("load #1 <db>\\x", vec!["fetch \"raw\" data".to_string()]),
+        ];
+        let dag = DagEngine::build(&tasks).unwrap();
+
+        let dot = dag.to_dot();
+        assert!(dot.contains(r#"    "fetch \"raw\" data" -> "load #1 <db>\\x";"#));
+
+        let mermaid = dag.to_mermaid();
+        assert!(mermaid.contains(r#"n0["fetch #quot;raw#quot; data"]"#));
+        assert!(mermaid.contains(r#"n1["load #35;1 #lt;db#gt;\x"]"#));
+        assert!(mermaid.contains("n0 --> n1"));
+    }
 }
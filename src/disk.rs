@@ -0,0 +1,69 @@
+//! Free disk space measurement
+//!
+//! Used by the daemon's `min_free_disk_mb` guard, which keeps new executions from
+//! writing to a nearly full state database filesystem.
+
+use crate::error::{PicoFlowError, Result};
+use std::path::Path;
+
+/// Bytes available to unprivileged users on the filesystem containing `path`
+///
+/// `path` may be a file that does not exist yet (e.g. a new database); its parent
+/// directory is measured instead.
+///
+/// # Errors
+///
+/// * `PicoFlowError::Io` - If `statvfs` fails for the path
+/// * `PicoFlowError::InvalidPath` - If the path contains a NUL byte
+#[cfg(unix)]
+pub fn free_disk_bytes(path: &Path) -> Result<u64> {
+    use std::os::unix::ffi::OsStrExt;
+
+    let path = if path.exists() {
+        path
+    } else {
+        match path.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent,
+            _ => Path::new("."),
+        }
+    };
+    let c_path = std::ffi::CString::new(path.as_os_str().as_bytes())
+        .map_err(|_| PicoFlowError::InvalidPath(path.display().to_string()))?;
+
+    // SAFETY: c_path is a valid NUL-terminated string, and statvfs only writes into `stat`
+    unsafe {
+        let mut stat: libc::statvfs = std::mem::zeroed();
+        if libc::statvfs(c_path.as_ptr(), &mut stat) != 0 {
+            return Err(std::io::Error::last_os_error().into());
+        }
+        #[allow(clippy::unnecessary_cast)] // the field types differ between platforms
+        Ok(stat.f_bavail as u64 * stat.f_frsize as u64)
+    }
+}
+
+/// Free disk space (unsupported platform)
+///
+/// # Errors
+///
+/// * `PicoFlowError::Other` - Always
+#[cfg(not(unix))]
+pub fn free_disk_bytes(_path: &Path) -> Result<u64> {
+    Err(PicoFlowError::Other(
+        "Disk space checks not supported on this platform".to_string(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(unix)]
+    #[test]
+    fn test_free_disk_bytes() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(free_disk_bytes(dir.path()).unwrap() > 0);
+        // A file that doesn't exist yet is measured through its directory
+        assert!(free_disk_bytes(&dir.path().join("new.db")).unwrap() > 0);
+        assert!(free_disk_bytes(Path::new("/nonexistent-dir/x/y")).is_err());
+    }
+}
@@ -33,10 +33,18 @@ pub enum PicoFlowError {
     #[error("Task '{task}' depends on non-existent task '{dependency}'")]
     MissingDependency { task: String, dependency: String },
 
+    /// Two tasks share a name
+    #[error("Duplicate task name '{0}'")]
+    DuplicateTask(String),
+
     /// Database errors
     #[error("Database error: {0}")]
     Database(#[from] rusqlite::Error),
 
+    /// Write attempted through a read-only state manager
+    #[error("State database is open read-only")]
+    ReadOnlyDatabase,
+
     /// I/O errors
     #[error("I/O error: {0}")]
     Io(#[from] std::io::Error),
@@ -45,6 +53,13 @@ pub enum PicoFlowError {
     #[error("Task '{task}' timed out after {timeout} seconds")]
     TaskTimeout { task: String, timeout: u64 },
 
+    /// Task process could not be started (missing binary, permission denied, ...)
+    #[error("Task '{task}' failed to start: {source}")]
+    TaskSpawn {
+        task: String,
+        source: std::io::Error,
+    },
+
     /// Command validation errors
     #[error("Command exceeds {limit} bytes")]
     CommandTooLong { limit: usize },
@@ -89,5 +104,40 @@ pub enum PicoFlowError {
     Other(String),
 }
 
+/// A single problem found while validating a workflow.
+///
+/// Unlike [`PicoFlowError`], these are collected rather than returned one at a time so that
+/// `picoflow validate` can report every problem in a workflow at once.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum ValidationError {
+    /// Two or more tasks share a name
+    #[error("Duplicate task name '{0}'")]
+    DuplicateTask(String),
+
+    /// Task name longer than `MAX_TASK_NAME_LEN`
+    #[error("Task name '{name}' exceeds {max} characters")]
+    TaskNameTooLong { name: String, max: usize },
+
+    /// Task lists itself in `depends_on`
+    #[error("Task '{0}' depends on itself")]
+    SelfDependency(String),
+
+    /// Dependency on a task that does not exist
+    #[error("Task '{task}' depends on non-existent task '{dependency}'")]
+    MissingDependency { task: String, dependency: String },
+
+    /// Circular dependency, with the cycle path
+    #[error("Cycle detected: {0}")]
+    Cycle(String),
+
+    /// Unparseable cron schedule (the message names the expression)
+    #[error("{0}")]
+    InvalidSchedule(String),
+
+    /// Any other workflow configuration error
+    #[error("{0}")]
+    Invalid(String),
+}
+
 /// Result type alias using PicoFlowError
 pub type Result<T> = std::result::Result<T, PicoFlowError>;
@@ -0,0 +1,363 @@
+//! Docker executor for containerized tasks
+//!
+//! Runs each task as `docker run --rm <image> [command] [args...]`. Like the SSH executor,
+//! every value is passed to the `docker` CLI as a discrete argument — never through a
+//! shell — so image names, args and env values cannot inject commands.
+//!
+//! # Timeouts
+//!
+//! Each container gets a unique `--name`. If the task future is dropped before the
+//! container exits (e.g. the scheduler's timeout fires), the `docker` client is killed and
+//! a `docker rm -f` is issued for the container so it does not keep running detached.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use picoflow::executors::docker::DockerExecutor;
+//! use picoflow::executors::ExecutorTrait;
+//! use picoflow::models::{DockerConfig, TaskExecutorConfig};
+//!
+//! # async fn example() -> anyhow::Result<()> {
+//! let executor = DockerExecutor::new();
+//! let config = TaskExecutorConfig::Docker(DockerConfig {
+//!     image: "alpine:3.19".to_string(),
+//!     command: Some("/bin/echo".to_string()),
+//!     args: vec!["hello".to_string()],
+//!     env: None,
+//!     volumes: vec![],
+//!     network: None,
+//! });
+//!
+//! let result = executor.execute(&config).await?;
+//! println!("Output: {:?}", result.stdout);
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::error::{PicoFlowError, Result};
+use crate::executors::ExecutorTrait;
+use crate::models::{
+    DockerConfig, ExecutionResult, TaskExecutorConfig, TaskStatus, MAX_OUTPUT_SIZE,
+};
+use crate::parser::validate_docker_config;
+use async_trait::async_trait;
+use std::time::Duration;
+use tokio::process::Command;
+use tracing::{debug, error, info, warn};
+
+/// Docker CLI binary (resolved via PATH)
+const DOCKER_BINARY: &str = "docker";
+
+/// Docker executor for running tasks in throwaway containers
+#[derive(Debug, Clone)]
+pub struct DockerExecutor;
+
+impl DockerExecutor {
+    /// Create a new Docker executor
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Build the argument list for `docker run`
+    ///
+    /// Options come before the image; everything after the image is passed to the
+    /// container untouched.
+    fn build_run_args(config: &DockerConfig, container_name: &str) -> Vec<String> {
+        let mut args = vec![
+            "run".to_string(),
+            "--rm".to_string(),
+            "--name".to_string(),
+            container_name.to_string(),
+        ];
+
+        if let Some(network) = &config.network {
+            args.push("--network".to_string());
+            args.push(network.clone());
+        }
+
+        if let Some(env) = &config.env {
+            // Sort for deterministic argument order (HashMap iteration order is random)
+            let mut vars: Vec<_> = env.iter().collect();
+            vars.sort();
+            for (key, value) in vars {
+                args.push("--env".to_string());
+                args.push(format!("{}={}", key, value));
+            }
+        }
+
+        for volume in &config.volumes {
+            args.push("--volume".to_string());
+            args.push(volume.clone());
+        }
+
+        args.push(config.image.clone());
+
+        if let Some(command) = &config.command {
+            args.push(command.clone());
+        }
+        args.extend(config.args.iter().cloned());
+
+        args
+    }
+
+    async fn execute_docker(
+        &self,
+        config: &DockerConfig,
+        timeout_secs: u64,
+        max_output_bytes: usize,
+    ) -> Result<ExecutionResult> {
+        // Validate configuration
+        validate_docker_config(config)?;
+
+        let container_name = format!("picoflow-{}", uuid::Uuid::new_v4());
+        let args = Self::build_run_args(config, &container_name);
+
+        info!(
+            "Running Docker container {} from image {}",
+            container_name, config.image
+        );
+        debug!("Docker args: {:?}", args);
+
+        let start = std::time::Instant::now();
+
+        let mut cmd = Command::new(DOCKER_BINARY);
+        cmd.args(&args);
+        cmd.stdout(std::process::Stdio::piped());
+        cmd.stderr(std::process::Stdio::piped());
+        // Kill the docker client if the future is dropped; the guard below removes the
+        // container itself, which would otherwise outlive its client.
+        cmd.kill_on_drop(true);
+
+        let mut guard = ContainerGuard::new(container_name);
+        let output_result =
+            tokio::time::timeout(Duration::from_secs(timeout_secs), cmd.output()).await;
+        let duration = start.elapsed();
+
+        match output_result {
+            Ok(Ok(output)) => {
+                // The container has exited and `--rm` cleans it up
+                guard.disarm();
+
+                let captured = crate::executors::capture_output(
+                    &output.stdout,
+                    &output.stderr,
+                    max_output_bytes,
+                );
+
+                let status = if output.status.success() {
+                    TaskStatus::Success
+                } else {
+                    TaskStatus::Failed
+                };
+
+                if captured.truncated {
+                    debug!("Output truncated to {} bytes", max_output_bytes);
+                }
+                if captured.binary {
+                    warn!("Output is not valid UTF-8 and was not stored");
+                }
+
+                info!(
+                    "Container completed with status: {} (exit code: {:?})",
+                    status,
+                    output.status.code()
+                );
+
+                Ok(ExecutionResult {
+                    status,
+                    stdout: Some(captured.stdout),
+                    stderr: Some(captured.stderr),
+                    exit_code: output.status.code(),
+                    duration,
+                    output_truncated: captured.truncated,
+                    output_binary: captured.binary,
+                })
+            }
+            Ok(Err(e)) => {
+                // The client never started, so there is no container to remove
+                guard.disarm();
+                error!("Failed to run docker: {}", e);
+                Err(PicoFlowError::TaskSpawn {
+                    task: DOCKER_BINARY.to_string(),
+                    source: e,
+                })
+            }
+            Err(_) => {
+                error!("Container timed out after {} seconds", timeout_secs);
+                Err(PicoFlowError::TaskTimeout {
+                    task: config.image.clone(),
+                    timeout: timeout_secs,
+                })
+            }
+        }
+    }
+}
+
+/// Force-removes a named container on drop unless disarmed
+///
+/// Drop cannot await, so removal is fire-and-forget: `docker rm -f` is spawned through
+/// tokio, which reaps the child in the background once it exits.
+struct ContainerGuard {
+    name: Option<String>,
+}
+
+impl ContainerGuard {
+    fn new(name: String) -> Self {
+        Self { name: Some(name) }
+    }
+
+    fn disarm(&mut self) {
+        self.name = None;
+    }
+}
+
+impl Drop for ContainerGuard {
+    fn drop(&mut self) {
+        if let Some(name) = self.name.take() {
+            warn!("Removing container {} after interrupted run", name);
+            let result = Command::new(DOCKER_BINARY)
+                .args(["rm", "-f", &name])
+                .stdin(std::process::Stdio::null())
+                .stdout(std::process::Stdio::null())
+                .stderr(std::process::Stdio::null())
+                .spawn();
+            if let Err(e) = result {
+                warn!("Failed to remove container {}: {}", name, e);
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl ExecutorTrait for DockerExecutor {
+    async fn execute(&self, config: &TaskExecutorConfig) -> anyhow::Result<ExecutionResult> {
+        self.execute_with_output_limit(config, MAX_OUTPUT_SIZE)
+            .await
+    }
+
+    async fn execute_with_output_limit(
+        &self,
+        config: &TaskExecutorConfig,
+        max_output_bytes: usize,
+    ) -> anyhow::Result<ExecutionResult> {
+        match config {
+            TaskExecutorConfig::Docker(docker_config) => {
+                // Scheduler applies the actual task timeout (see ShellExecutor)
+                let result = self
+                    .execute_docker(docker_config, 86400, max_output_bytes)
+                    .await?;
+                Ok(result)
+            }
+            _ => Err(anyhow::anyhow!("Invalid config type for DockerExecutor")),
+        }
+    }
+
+    async fn health_check(&self) -> anyhow::Result<()> {
+        // `docker version` fails if the CLI is missing or the daemon is unreachable
+        let output = Command::new(DOCKER_BINARY)
+            .arg("version")
+            .stdout(std::process::Stdio::null())
+            .stderr(std::process::Stdio::null())
+            .status()
+            .await?;
+
+        if output.success() {
+            Ok(())
+        } else {
+            Err(anyhow::anyhow!("Docker executor health check failed"))
+        }
+    }
+}
+
+impl Default for DockerExecutor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn config() -> DockerConfig {
+        DockerConfig {
+            image: "alpine:3.19".to_string(),
+            command: Some("/bin/sh".to_string()),
+            args: vec!["-c".to_string(), "echo $GREETING; exit 3".to_string()],
+            env: None,
+            volumes: vec![],
+            network: None,
+        }
+    }
+
+    #[test]
+    fn test_build_run_args_minimal() {
+        let config = DockerConfig {
+            command: None,
+            args: vec![],
+            ..config()
+        };
+        assert_eq!(
+            DockerExecutor::build_run_args(&config, "picoflow-x"),
+            vec!["run", "--rm", "--name", "picoflow-x", "alpine:3.19"]
+        );
+    }
+
+    #[test]
+    fn test_build_run_args_full() {
+        let mut env = HashMap::new();
+        env.insert("B".to_string(), "2".to_string());
+        env.insert("A".to_string(), "1; rm -rf /".to_string());
+        let config = DockerConfig {
+            env: Some(env),
+            volumes: vec!["/srv:/srv:ro".to_string()],
+            network: Some("edge".to_string()),
+            ..config()
+        };
+
+        let args = DockerExecutor::build_run_args(&config, "c1");
+        assert_eq!(
+            args,
+            vec![
+                "run",
+                "--rm",
+                "--name",
+                "c1",
+                "--network",
+                "edge",
+                "--env",
+                "A=1; rm -rf /",
+                "--env",
+                "B=2",
+                "--volume",
+                "/srv:/srv:ro",
+                "alpine:3.19",
+                "/bin/sh",
+                "-c",
+                "echo $GREETING; exit 3",
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_invalid_config_rejected_before_running() {
+        let executor = DockerExecutor::new();
+        let config = TaskExecutorConfig::Docker(DockerConfig {
+            image: String::new(),
+            ..config()
+        });
+        let err = executor.execute(&config).await.unwrap_err();
+        assert!(err.to_string().contains("image"));
+    }
+
+    #[tokio::test]
+    async fn test_wrong_config_type() {
+        let executor = DockerExecutor::new();
+        let config = TaskExecutorConfig::Shell(crate::models::ShellConfig {
+            command: "/bin/true".to_string(),
+            ..Default::default()
+        });
+        assert!(executor.execute(&config).await.is_err());
+    }
+}
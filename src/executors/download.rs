@@ -0,0 +1,225 @@
+//! Download executor for fetching files over HTTP(S)
+//!
+//! Streams a GET response body straight to `dest_path` rather than buffering it as task
+//! output, so downloads are not bound by `MAX_RESPONSE_SIZE`. The body is written to a
+//! `<dest_path>.part` file first and only renamed into place once the transfer (and the
+//! optional SHA-256 checksum) succeeds, so a failed download never leaves a truncated or
+//! corrupt file at `dest_path`.
+//!
+//! URLs go through the same SSRF validation, DNS pinning and no-redirect policy as the
+//! HTTP executor.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use picoflow::executors::download::DownloadExecutor;
+//! use picoflow::executors::ExecutorTrait;
+//! use picoflow::models::{DownloadConfig, TaskExecutorConfig};
+//!
+//! # async fn example() -> anyhow::Result<()> {
+//! let executor = DownloadExecutor::new();
+//! let config = TaskExecutorConfig::Download(DownloadConfig {
+//!     url: "https://example.com/firmware.bin".to_string(),
+//!     dest_path: "/var/lib/picoflow/firmware.bin".to_string(),
+//!     checksum: None,
+//!     timeout: 600,
+//!     allow_private_ips: false,
+//! });
+//!
+//! let result = executor.execute(&config).await?;
+//! println!("Bytes written: {:?}", result.stdout);
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::error::Result;
+use crate::executors::http::HttpExecutor;
+use crate::executors::ExecutorTrait;
+use crate::models::{DownloadConfig, ExecutionResult, TaskExecutorConfig, TaskStatus};
+use crate::parser::validate_download_config;
+use async_trait::async_trait;
+use openssl::sha::Sha256;
+use std::io::Write;
+use std::time::{Duration, Instant};
+use tracing::{error, info, warn};
+
+/// Download executor that saves HTTP responses to disk
+#[derive(Debug, Clone)]
+pub struct DownloadExecutor;
+
+impl DownloadExecutor {
+    /// Create a new download executor
+    pub fn new() -> Self {
+        Self
+    }
+
+    async fn execute_download(&self, config: &DownloadConfig) -> Result<ExecutionResult> {
+        validate_download_config(config)?;
+        HttpExecutor::validate_ssrf(&config.url, config.allow_private_ips)?;
+        let client =
+            HttpExecutor::build_secure_client(&config.url, config.allow_private_ips).await?;
+
+        info!("Downloading {} to {}", config.url, config.dest_path);
+        let start = Instant::now();
+        let part_path = format!("{}.part", config.dest_path);
+
+        let outcome = Self::fetch_to_file(&client, config, &part_path).await;
+        let duration = start.elapsed();
+
+        let failure = match outcome {
+            Ok((bytes_written, status)) => match std::fs::rename(&part_path, &config.dest_path) {
+                Ok(()) => {
+                    info!("Downloaded {} bytes to {}", bytes_written, config.dest_path);
+                    return Ok(ExecutionResult {
+                        status: TaskStatus::Success,
+                        stdout: Some(bytes_written.to_string()),
+                        stderr: None,
+                        exit_code: Some(i32::from(status)),
+                        duration,
+                        output_truncated: false,
+                        output_binary: false,
+                    });
+                }
+                Err(e) => DownloadFailure {
+                    message: format!("Failed to move download into place: {}", e),
+                    status: None,
+                },
+            },
+            Err(failure) => failure,
+        };
+
+        error!("Download of {} failed: {}", config.url, failure.message);
+        if let Err(e) = std::fs::remove_file(&part_path) {
+            if e.kind() != std::io::ErrorKind::NotFound {
+                warn!("Failed to remove partial download {}: {}", part_path, e);
+            }
+        }
+
+        Ok(ExecutionResult {
+            status: TaskStatus::Failed,
+            stdout: None,
+            stderr: Some(failure.message),
+            exit_code: failure.status.map(i32::from),
+            duration,
+            output_truncated: false,
+            output_binary: false,
+        })
+    }
+
+    /// Stream the response body into `part_path`, verifying the checksum if configured.
+    ///
+    /// Returns the number of bytes written and the HTTP status code.
+    async fn fetch_to_file(
+        client: &reqwest::Client,
+        config: &DownloadConfig,
+        part_path: &str,
+    ) -> std::result::Result<(u64, u16), DownloadFailure> {
+        let mut response = client
+            .get(&config.url)
+            .timeout(Duration::from_secs(config.timeout))
+            .send()
+            .await
+            .map_err(|e| DownloadFailure::request(e, config.timeout))?;
+
+        let status = response.status();
+        if !status.is_success() {
+            return Err(DownloadFailure {
+                message: format!("HTTP request failed with status code: {}", status.as_u16()),
+                status: Some(status.as_u16()),
+            });
+        }
+
+        // The tokio `fs` feature is not enabled (binary size), so chunks are written with
+        // blocking std I/O; each write is a single response chunk to local disk.
+        let mut file = std::fs::File::create(part_path)
+            .map_err(|e| DownloadFailure::io("create", part_path, e))?;
+        let mut hasher = Sha256::new();
+        let mut bytes_written: u64 = 0;
+
+        while let Some(chunk) = response
+            .chunk()
+            .await
+            .map_err(|e| DownloadFailure::request(e, config.timeout))?
+        {
+            file.write_all(&chunk)
+                .map_err(|e| DownloadFailure::io("write", part_path, e))?;
+            hasher.update(&chunk);
+            bytes_written += chunk.len() as u64;
+        }
+        file.sync_all()
+            .map_err(|e| DownloadFailure::io("sync", part_path, e))?;
+
+        if let Some(expected) = &config.checksum {
+            let actual = to_hex(&hasher.finish());
+            if !actual.eq_ignore_ascii_case(expected) {
+                return Err(DownloadFailure {
+                    message: format!(
+                        "Checksum mismatch: expected {}, got {}",
+                        expected.to_lowercase(),
+                        actual
+                    ),
+                    status: Some(status.as_u16()),
+                });
+            }
+        }
+
+        Ok((bytes_written, status.as_u16()))
+    }
+}
+
+/// Why a download failed, plus the HTTP status when one was received
+struct DownloadFailure {
+    message: String,
+    status: Option<u16>,
+}
+
+impl DownloadFailure {
+    fn request(e: reqwest::Error, timeout_secs: u64) -> Self {
+        let message = if e.is_timeout() {
+            format!("Download timed out after {} seconds", timeout_secs)
+        } else if e.is_connect() {
+            format!("Connection failed: {}", e)
+        } else {
+            format!("Request failed: {}", e)
+        };
+        Self {
+            message,
+            status: None,
+        }
+    }
+
+    fn io(action: &str, path: &str, e: std::io::Error) -> Self {
+        Self {
+            message: format!("Failed to {} {}: {}", action, path, e),
+            status: None,
+        }
+    }
+}
+
+/// Lowercase hex encoding of a digest
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+#[async_trait]
+impl ExecutorTrait for DownloadExecutor {
+    async fn execute(&self, config: &TaskExecutorConfig) -> anyhow::Result<ExecutionResult> {
+        match config {
+            TaskExecutorConfig::Download(download_config) => {
+                Ok(self.execute_download(download_config).await?)
+            }
+            _ => Err(anyhow::anyhow!("Invalid config type for DownloadExecutor")),
+        }
+    }
+
+    async fn health_check(&self) -> anyhow::Result<()> {
+        // Nothing to probe without a target URL; network problems surface per task
+        Ok(())
+    }
+}
+
+impl Default for DownloadExecutor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -2,15 +2,20 @@
 //!
 //! This module provides HTTP/HTTPS request execution with the following features:
 //! - **Methods:** GET, POST, PUT, DELETE
-//! - **Request bodies:** JSON serialization from YAML config
+//! - **Request bodies:** JSON, form-urlencoded or raw text from YAML config (`body_type`)
 //! - **Custom headers:** User-defined headers for authentication, content-type, etc.
 //! - **Configurable timeouts:** Per-request timeout enforcement
 //! - **Status code handling:** 2xx = success, 4xx/5xx = failed
+//! - **Status retries:** Optional in-request retries on listed status codes (`retry_on_status`)
+//! - **Body assertions:** Optionally require a JSON body value at a path (`expect_json_path`)
 //!
 //! # Security
 //!
 //! This executor implements security best practices:
 //! - TLS/SSL verification enabled by default
+//! - Optional client certificate for mutual TLS (`client_cert_path`/`client_key_path`)
+//! - SSRF protection: private/metadata addresses are blocked, or only `allowed_hosts` are
+//!   permitted when that list is set
 //! - Response body size limits (MAX_RESPONSE_SIZE = 10MB)
 //! - Timeout enforcement to prevent hanging requests
 //! - Input validation for URLs and configuration
@@ -37,9 +42,17 @@
 //!     url: "https://api.example.com/health".to_string(),
 //!     method: HttpMethod::Get,
 //!     body: None,
+//!     body_type: Default::default(),
 //!     headers: HashMap::new(),
 //!     timeout: 30,
 //!     allow_private_ips: false,
+//!     auth: None,
+//!     retry_on_status: None,
+//!     rate_limit_per_sec: None,
+//!     expect_json_path: None,
+//!     allowed_hosts: None,
+//!     client_cert_path: None,
+//!     client_key_path: None,
 //! });
 //!
 //! let result = executor.execute(&config).await?;
@@ -49,20 +62,47 @@
 //! ```
 
 use crate::error::{PicoFlowError, Result};
+use crate::executors::rate_limit::{throttle, HostBuckets};
 use crate::executors::ExecutorTrait;
 use crate::models::{
-    ExecutionResult, HttpConfig, HttpMethod, TaskExecutorConfig, TaskStatus, MAX_RESPONSE_SIZE,
+    BodyType, ExecutionResult, HttpAuth, HttpConfig, HttpMethod, TaskExecutorConfig, TaskStatus,
+    MAX_RESPONSE_SIZE,
 };
+use crate::parser::validate_path;
 use async_trait::async_trait;
-use reqwest::{Client, Method};
+use reqwest::{Client, ClientBuilder, Identity, Method};
+use std::collections::HashMap;
 use std::net::{Ipv4Addr, Ipv6Addr, SocketAddr};
-use std::time::Duration;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime};
 use tracing::{debug, error, info, warn};
 use url::Host;
 
+/// Maximum number of in-executor retries driven by `retry_on_status`
+const MAX_STATUS_RETRIES: u32 = 3;
+
+/// Delay between in-executor retries when the server sends no usable `Retry-After`
+const DEFAULT_STATUS_RETRY_DELAY: Duration = Duration::from_secs(1);
+
+/// Longest wait for DNS resolution of a URL's host before the SSRF check gives up
+const DNS_RESOLVE_TIMEOUT: Duration = Duration::from_secs(5);
+
 /// HTTP executor for REST API calls
+///
+/// Clones share the same per-host rate limits and loaded client certificates.
+#[derive(Debug, Clone, Default)]
+pub struct HttpExecutor {
+    rate_limits: HostBuckets,
+    identities: Arc<Mutex<HashMap<(String, String), CachedIdentity>>>,
+}
+
+/// A client certificate and key loaded for mutual TLS
 #[derive(Debug, Clone)]
-pub struct HttpExecutor;
+struct CachedIdentity {
+    /// Modification times of the certificate and key files when they were read
+    modified: (SystemTime, SystemTime),
+    identity: Identity,
+}
 
 impl HttpExecutor {
     /// Create a new HTTP executor.
@@ -71,8 +111,10 @@ impl HttpExecutor {
     /// per request (see [`HttpExecutor::build_secure_client`]) so DNS resolution is
     /// validated for each target. Note this means requests do not share a connection
     /// pool across tasks — an accepted trade-off for per-request SSRF validation.
+    /// The only shared state is the per-host `rate_limit_per_sec` buckets and the parsed
+    /// `client_cert_path`/`client_key_path` identities.
     pub fn new() -> Self {
-        Self
+        Self::default()
     }
 
     /// Build a per-request reqwest client hardened against SSRF.
@@ -91,7 +133,16 @@ impl HttpExecutor {
     ///    that resolves to a private IP), so redirect-following is disabled entirely. A 3xx
     ///    is returned as-is and surfaces as a non-2xx (failed) task rather than being
     ///    chased to an attacker-chosen target. `allow_private_ips` does not re-enable it.
-    async fn build_secure_client(url: &str, allow_private_ips: bool) -> Result<Client> {
+    pub(crate) async fn build_secure_client(url: &str, allow_private_ips: bool) -> Result<Client> {
+        Self::secure_client_builder(url, allow_private_ips)
+            .await?
+            .build()
+            .map_err(|e| PicoFlowError::Http(format!("Failed to build HTTP client: {}", e)))
+    }
+
+    /// The [`reqwest::ClientBuilder`] behind [`HttpExecutor::build_secure_client`], for
+    /// callers that add to it (e.g. a client certificate) before building
+    async fn secure_client_builder(url: &str, allow_private_ips: bool) -> Result<ClientBuilder> {
         let mut builder = Client::builder()
             .user_agent(format!("PicoFlow/{}", env!("CARGO_PKG_VERSION")))
             // Do not chase redirects to unvalidated hosts (SSRF hardening).
@@ -114,9 +165,69 @@ impl HttpExecutor {
             }
         }
 
-        builder
-            .build()
-            .map_err(|e| PicoFlowError::Http(format!("Failed to build HTTP client: {}", e)))
+        Ok(builder)
+    }
+
+    /// The mutual TLS identity for `config`'s `client_cert_path`/`client_key_path`, if set
+    ///
+    /// Parsed identities are cached per file pair and reloaded when either file's
+    /// modification time changes, so rotated certificates are picked up.
+    ///
+    /// # Errors
+    ///
+    /// * `PicoFlowError::Validation` - If a file cannot be read or the pair is not a valid
+    ///   PEM certificate and PKCS#8 PEM key
+    fn client_identity(&self, config: &HttpConfig) -> Result<Option<Identity>> {
+        let (Some(cert_path), Some(key_path)) = (&config.client_cert_path, &config.client_key_path)
+        else {
+            return Ok(None);
+        };
+
+        let modified = |path: &str| {
+            std::fs::metadata(path)
+                .and_then(|meta| meta.modified())
+                .map_err(|e| {
+                    PicoFlowError::Validation(format!(
+                        "Cannot read client TLS file {}: {}",
+                        path, e
+                    ))
+                })
+        };
+        let modified = (modified(cert_path)?, modified(key_path)?);
+        let cache_key = (cert_path.clone(), key_path.clone());
+
+        let mut identities = self
+            .identities
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        if let Some(cached) = identities.get(&cache_key) {
+            if cached.modified == modified {
+                return Ok(Some(cached.identity.clone()));
+            }
+        }
+
+        let read = |path: &str| {
+            std::fs::read(path).map_err(|e| {
+                PicoFlowError::Validation(format!("Cannot read client TLS file {}: {}", path, e))
+            })
+        };
+        let identity =
+            Identity::from_pkcs8_pem(&read(cert_path)?, &read(key_path)?).map_err(|e| {
+                PicoFlowError::Validation(format!(
+                    "Invalid client certificate {} or key {} (expected PEM certificate and \
+                     PKCS#8 PEM key): {}",
+                    cert_path, key_path, e
+                ))
+            })?;
+        debug!("Loaded client certificate {}", cert_path);
+        identities.insert(
+            cache_key,
+            CachedIdentity {
+                modified,
+                identity: identity.clone(),
+            },
+        );
+        Ok(Some(identity))
     }
 
     /// Validate URL for SSRF (Server-Side Request Forgery) protection
@@ -137,7 +248,7 @@ impl HttpExecutor {
     /// # Errors
     ///
     /// Returns error if URL targets a blocked address or domain
-    fn validate_ssrf(url: &str, allow_private_ips: bool) -> Result<()> {
+    pub(crate) fn validate_ssrf(url: &str, allow_private_ips: bool) -> Result<()> {
         let parsed_url = reqwest::Url::parse(url)
             .map_err(|e| PicoFlowError::Validation(format!("Invalid URL for SSRF check: {}", e)))?;
 
@@ -176,6 +287,47 @@ impl HttpExecutor {
         Ok(())
     }
 
+    /// Validate that the URL's host is one of `allowed_hosts` or a subdomain of one
+    ///
+    /// Entries are compared case-insensitively; IPv6 entries may be bracketed
+    /// (`[fd00::1]`). The scheme is checked as in [`HttpExecutor::validate_ssrf`], but no
+    /// private IP checks apply to an allowed host.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the URL is invalid or its host is not listed
+    pub(crate) fn validate_allowed_host(url: &str, allowed_hosts: &[String]) -> Result<()> {
+        Self::validate_ssrf(url, true)?;
+        let parsed_url = reqwest::Url::parse(url)
+            .map_err(|e| PicoFlowError::Validation(format!("Invalid URL: {}", e)))?;
+        let host = match parsed_url.host() {
+            Some(Host::Ipv6(ip)) => ip.to_string(),
+            Some(host) => host.to_string().to_lowercase(),
+            None => {
+                return Err(PicoFlowError::Validation(
+                    "URL must contain a host".to_string(),
+                ))
+            }
+        };
+
+        let allowed = allowed_hosts.iter().any(|entry| {
+            let entry = entry
+                .trim()
+                .trim_start_matches('[')
+                .trim_end_matches(']')
+                .to_lowercase();
+            !entry.is_empty() && (host == entry || host.ends_with(&format!(".{}", entry)))
+        });
+        if !allowed {
+            return Err(PicoFlowError::Http(format!(
+                "SSRF protection: Requests to '{}' are blocked (not in allowed_hosts)",
+                host
+            )));
+        }
+
+        Ok(())
+    }
+
     /// Validate IPv4 address is not private/local
     fn validate_ipv4_not_private(ip: Ipv4Addr) -> Result<()> {
         if ip.is_private() {
@@ -271,7 +423,27 @@ impl HttpExecutor {
     /// Returns an empty vec when `allow_private_ips` is set or when the host is a literal
     /// IP (already validated by [`HttpExecutor::validate_ssrf`]); callers should not pin in
     /// those cases.
+    ///
+    /// Resolution is abandoned after [`DNS_RESOLVE_TIMEOUT`].
     async fn resolve_and_validate(url: &str, allow_private_ips: bool) -> Result<Vec<SocketAddr>> {
+        Self::resolve_and_validate_with(url, allow_private_ips, |host, port| async move {
+            tokio::net::lookup_host((host.as_str(), port))
+                .await
+                .map(Iterator::collect)
+        })
+        .await
+    }
+
+    /// [`HttpExecutor::resolve_and_validate`] with the DNS lookup supplied by `resolve`
+    async fn resolve_and_validate_with<F, Fut>(
+        url: &str,
+        allow_private_ips: bool,
+        resolve: F,
+    ) -> Result<Vec<SocketAddr>>
+    where
+        F: FnOnce(String, u16) -> Fut,
+        Fut: std::future::Future<Output = std::io::Result<Vec<SocketAddr>>>,
+    {
         if allow_private_ips {
             return Ok(Vec::new());
         }
@@ -287,8 +459,14 @@ impl HttpExecutor {
 
         let port = parsed_url.port_or_known_default().unwrap_or(443);
 
-        let resolved = tokio::net::lookup_host((host.as_str(), port))
+        let resolved = tokio::time::timeout(DNS_RESOLVE_TIMEOUT, resolve(host.clone(), port))
             .await
+            .map_err(|_| {
+                PicoFlowError::Http(format!(
+                    "DNS resolution timed out for {} after {:?}",
+                    host, DNS_RESOLVE_TIMEOUT
+                ))
+            })?
             .map_err(|e| {
                 PicoFlowError::Http(format!("DNS resolution failed for {}: {}", host, e))
             })?;
@@ -333,8 +511,11 @@ impl HttpExecutor {
             )));
         }
 
-        // SSRF protection
-        Self::validate_ssrf(&config.url, config.allow_private_ips)?;
+        // SSRF protection: an allowlist replaces the private IP blocklist
+        match &config.allowed_hosts {
+            Some(allowed_hosts) => Self::validate_allowed_host(&config.url, allowed_hosts)?,
+            None => Self::validate_ssrf(&config.url, config.allow_private_ips)?,
+        }
 
         // Validate timeout is reasonable (1 second to 1 hour)
         if config.timeout == 0 || config.timeout > 3600 {
@@ -344,8 +525,40 @@ impl HttpExecutor {
             )));
         }
 
+        if config.rate_limit_per_sec == Some(0) {
+            return Err(PicoFlowError::Validation(
+                "rate_limit_per_sec must be at least 1".to_string(),
+            ));
+        }
+
+        if let Some(statuses) = &config.retry_on_status {
+            if let Some(status) = statuses.iter().find(|s| !(100..=599).contains(*s)) {
+                return Err(PicoFlowError::Validation(format!(
+                    "retry_on_status contains invalid HTTP status code: {}",
+                    status
+                )));
+            }
+        }
+
+        if let Some((path, _)) = &config.expect_json_path {
+            parse_json_path(path)?;
+        }
+
+        match (&config.client_cert_path, &config.client_key_path) {
+            (Some(cert_path), Some(key_path)) => {
+                validate_path(cert_path)?;
+                validate_path(key_path)?;
+            }
+            (None, None) => {}
+            _ => {
+                return Err(PicoFlowError::Validation(
+                    "client_cert_path and client_key_path must be set together".to_string(),
+                ))
+            }
+        }
+
         // Log warning if private IPs are allowed
-        if config.allow_private_ips {
+        if config.allow_private_ips && config.allowed_hosts.is_none() {
             warn!(
                 "SECURITY WARNING: allow_private_ips is enabled for URL {} - SSRF protection disabled",
                 config.url
@@ -365,30 +578,139 @@ impl HttpExecutor {
         }
     }
 
+    /// Encode `body` according to `body_type`, returning the payload and its default
+    /// `Content-Type`.
+    fn encode_body(
+        body: &serde_yaml::Value,
+        body_type: BodyType,
+    ) -> Result<(String, &'static str)> {
+        match body_type {
+            BodyType::Json => {
+                let json = serde_json::to_string(body).map_err(|e| {
+                    PicoFlowError::Http(format!("Failed to serialize request body: {}", e))
+                })?;
+                Ok((json, "application/json"))
+            }
+            BodyType::Form => {
+                let map = body.as_mapping().ok_or_else(|| {
+                    PicoFlowError::Validation(
+                        "Form request body must be a mapping of key: value pairs".to_string(),
+                    )
+                })?;
+                let mut form = url::form_urlencoded::Serializer::new(String::new());
+                for (key, value) in map {
+                    form.append_pair(&Self::form_scalar(key)?, &Self::form_scalar(value)?);
+                }
+                Ok((form.finish(), "application/x-www-form-urlencoded"))
+            }
+            BodyType::Raw => match body {
+                serde_yaml::Value::String(text) => Ok((text.clone(), "text/plain; charset=utf-8")),
+                _ => Err(PicoFlowError::Validation(
+                    "Raw request body must be a string".to_string(),
+                )),
+            },
+        }
+    }
+
+    /// Render a scalar YAML value as a form field; nested values have no form encoding.
+    fn form_scalar(value: &serde_yaml::Value) -> Result<String> {
+        match value {
+            serde_yaml::Value::String(s) => Ok(s.clone()),
+            serde_yaml::Value::Number(n) => Ok(n.to_string()),
+            serde_yaml::Value::Bool(b) => Ok(b.to_string()),
+            serde_yaml::Value::Null => Ok(String::new()),
+            _ => Err(PicoFlowError::Validation(
+                "Form request body values must be scalars (no nested lists or mappings)"
+                    .to_string(),
+            )),
+        }
+    }
+
+    /// Whether a request may be safely repeated after a connection error
+    fn is_idempotent(method: &HttpMethod) -> bool {
+        !matches!(method, HttpMethod::Post)
+    }
+
+    /// Parse a `Retry-After` header value (delay in seconds or an HTTP date).
+    fn parse_retry_after(value: &str) -> Option<Duration> {
+        let value = value.trim();
+        if let Ok(seconds) = value.parse::<u64>() {
+            return Some(Duration::from_secs(seconds));
+        }
+        let at = chrono::DateTime::parse_from_rfc2822(value).ok()?;
+        let delay = at.with_timezone(&chrono::Utc) - chrono::Utc::now();
+        // A date in the past means "retry now"
+        Some(delay.to_std().unwrap_or(Duration::ZERO))
+    }
+
+    /// Decide whether an attempt's outcome should be retried and after what delay.
+    ///
+    /// Listed statuses are retried for every method; connection errors only for idempotent
+    /// methods, since a POST may have reached the server before the connection dropped.
+    fn retry_delay(
+        config: &HttpConfig,
+        outcome: &std::result::Result<reqwest::Response, reqwest::Error>,
+    ) -> Option<Duration> {
+        let statuses = config.retry_on_status.as_ref()?;
+        match outcome {
+            Ok(response) if statuses.contains(&response.status().as_u16()) => Some(
+                response
+                    .headers()
+                    .get(reqwest::header::RETRY_AFTER)
+                    .and_then(|v| v.to_str().ok())
+                    .and_then(Self::parse_retry_after)
+                    .unwrap_or(DEFAULT_STATUS_RETRY_DELAY),
+            ),
+            Err(e) if e.is_connect() && Self::is_idempotent(&config.method) => {
+                Some(DEFAULT_STATUS_RETRY_DELAY)
+            }
+            _ => None,
+        }
+    }
+
     /// Execute HTTP request
     ///
+    /// # Retries
+    ///
+    /// With `retry_on_status` set, a response with a listed status is retried up to
+    /// `MAX_STATUS_RETRIES` times, waiting for the `Retry-After` delay when present. GET,
+    /// PUT and DELETE are also retried on connection errors. All attempts and waits share
+    /// the request `timeout`; a retry that would overrun it is not attempted.
+    ///
     /// # Success Criteria
     ///
     /// - HTTP status code 2xx (200-299) = TaskStatus::Success
     /// - HTTP status code 4xx/5xx = TaskStatus::Failed
+    /// - With `expect_json_path`, a 2xx body that is not JSON, lacks the path or has a
+    ///   different value there = TaskStatus::Failed
     /// - Network errors, timeouts, SSL errors = TaskStatus::Failed
     ///
     /// # Response Handling
     ///
-    /// - Response body is truncated to MAX_RESPONSE_SIZE (10MB)
+    /// - Response body is truncated to `max_response_bytes` (MAX_RESPONSE_SIZE, 10MB, unless
+    ///   the workflow sets `max_output_bytes`)
     /// - output_truncated flag is set if truncation occurs
     /// - HTTP status code is returned as exit_code
     async fn execute_http(
         &self,
         config: &HttpConfig,
         timeout_secs: u64,
+        max_response_bytes: usize,
     ) -> Result<ExecutionResult> {
         // Validate configuration (scheme, literal-host SSRF checks, timeout bounds)
         Self::validate_config(config)?;
 
         // Build a hardened client for this request: resolves + validates + pins the host's
-        // DNS (anti-rebinding), and does not follow redirects to unvalidated hosts.
-        let client = Self::build_secure_client(&config.url, config.allow_private_ips).await?;
+        // DNS (anti-rebinding), and does not follow redirects to unvalidated hosts. A host
+        // from `allowed_hosts` is trusted to resolve to private addresses.
+        let allow_private_ips = config.allow_private_ips || config.allowed_hosts.is_some();
+        let mut builder = Self::secure_client_builder(&config.url, allow_private_ips).await?;
+        if let Some(identity) = self.client_identity(config)? {
+            builder = builder.identity(identity);
+        }
+        let client = builder
+            .build()
+            .map_err(|e| PicoFlowError::Http(format!("Failed to build HTTP client: {}", e)))?;
 
         info!(
             "Executing HTTP {} request to {}",
@@ -396,36 +718,88 @@ impl HttpExecutor {
             config.url
         );
         debug!(
-            "Request headers: {:?}",
-            crate::executors::redact_headers(&config.headers)
+            "Request headers: {:?}, auth: {:?}",
+            crate::executors::redact_headers(&config.headers),
+            config.auth
         );
 
-        let start = std::time::Instant::now();
+        let start = Instant::now();
+        let deadline = start + Duration::from_secs(timeout_secs);
 
-        // Build request
+        // Build request (the per-attempt timeout is applied when sending)
         let method = Self::convert_method(&config.method);
-        let mut request = client
-            .request(method, &config.url)
-            .timeout(Duration::from_secs(timeout_secs));
+        let mut request = client.request(method, &config.url);
 
         // Add custom headers
         for (key, value) in &config.headers {
             request = request.header(key, value);
         }
 
-        // Add JSON body if provided
+        // Configured auth must not add a second Authorization header next to an explicit one
+        let has_explicit_authorization = config
+            .headers
+            .keys()
+            .any(|key| key.eq_ignore_ascii_case("authorization"));
+        match &config.auth {
+            Some(_) if has_explicit_authorization => {
+                debug!("Explicit Authorization header set; ignoring configured auth");
+            }
+            Some(HttpAuth::Basic { username, password }) => {
+                request = request.basic_auth(username, Some(password));
+            }
+            Some(HttpAuth::Bearer { token }) => {
+                request = request.bearer_auth(token);
+            }
+            None => {}
+        }
+
+        // Add body if provided, keeping a user-supplied Content-Type
         if let Some(body_value) = &config.body {
-            // Convert serde_yaml::Value to serde_json::Value
-            let json_body = serde_json::to_value(body_value).map_err(|e| {
-                PicoFlowError::Http(format!("Failed to serialize request body: {}", e))
-            })?;
+            let (payload, content_type) = Self::encode_body(body_value, config.body_type)?;
+            let has_content_type = config
+                .headers
+                .keys()
+                .any(|key| key.eq_ignore_ascii_case("content-type"));
+            if !has_content_type {
+                request = request.header(reqwest::header::CONTENT_TYPE, content_type);
+            }
 
-            tracing::trace!("Request body: {}", json_body);
-            request = request.json(&json_body);
+            tracing::trace!("Request body: {}", payload);
+            request = request.body(payload);
         }
 
-        // Execute request
-        let response_result = request.send().await;
+        // Execute request, retrying listed statuses within the overall timeout
+        let mut retries = 0;
+        let response_result = loop {
+            let attempt = request.try_clone().ok_or_else(|| {
+                PicoFlowError::Http("Request body cannot be cloned for retry".to_string())
+            })?;
+            if let Some(rate) = config.rate_limit_per_sec {
+                let url = reqwest::Url::parse(&config.url)
+                    .map_err(|e| PicoFlowError::Validation(format!("Invalid HTTP URL: {}", e)))?;
+                throttle(&self.rate_limits, url.host_str().unwrap_or_default(), rate).await;
+            }
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            let outcome = attempt.timeout(remaining).send().await;
+
+            match Self::retry_delay(config, &outcome) {
+                Some(delay)
+                    if retries < MAX_STATUS_RETRIES && Instant::now() + delay < deadline =>
+                {
+                    retries += 1;
+                    let reason = match &outcome {
+                        Ok(response) => format!("status {}", response.status().as_u16()),
+                        Err(e) => e.to_string(),
+                    };
+                    warn!(
+                        "HTTP request got {}; retry {}/{} in {:?}",
+                        reason, retries, MAX_STATUS_RETRIES, delay
+                    );
+                    tokio::time::sleep(delay).await;
+                }
+                _ => break outcome,
+            }
+        };
 
         let duration = start.elapsed();
 
@@ -442,31 +816,50 @@ impl HttpExecutor {
                 // Read response body with size limit
                 let body_result = response.bytes().await;
 
-                let (response_body, output_truncated) = match body_result {
+                // Checked against the whole body, before it is truncated for storage
+                let mut json_mismatch = None;
+
+                let (response_body, output_truncated, binary_len) = match body_result {
                     Ok(bytes) => {
-                        let truncated = bytes.len() > MAX_RESPONSE_SIZE;
+                        if let Some((path, expected)) = &config.expect_json_path {
+                            json_mismatch = check_json_path(&bytes, path, expected).err();
+                        }
+
+                        let truncated = bytes.len() > max_response_bytes;
                         let body_bytes = if truncated {
                             warn!(
                                 "Response body truncated from {} to {} bytes",
                                 bytes.len(),
-                                MAX_RESPONSE_SIZE
+                                max_response_bytes
                             );
-                            &bytes[..MAX_RESPONSE_SIZE]
+                            &bytes[..max_response_bytes]
                         } else {
                             &bytes
                         };
 
-                        let body_string = String::from_utf8_lossy(body_bytes).to_string();
-                        (Some(body_string), truncated)
+                        match crate::executors::decode_output(body_bytes, truncated) {
+                            Some(body_string) => (Some(body_string), truncated, None),
+                            None => {
+                                warn!("Response body is not valid UTF-8 and was not stored");
+                                (None, truncated, Some(bytes.len()))
+                            }
+                        }
                     }
                     Err(e) => {
                         warn!("Failed to read response body: {}", e);
-                        (Some(format!("Failed to read response body: {}", e)), false)
+                        if config.expect_json_path.is_some() {
+                            json_mismatch = Some("response body could not be read".to_string());
+                        }
+                        (
+                            Some(format!("Failed to read response body: {}", e)),
+                            false,
+                            None,
+                        )
                     }
                 };
 
                 // Determine task status based on HTTP status code
-                let task_status = if status_code.is_success() {
+                let task_status = if status_code.is_success() && json_mismatch.is_none() {
                     // 2xx = success
                     TaskStatus::Success
                 } else {
@@ -479,20 +872,28 @@ impl HttpExecutor {
                     status_code_u16, task_status
                 );
 
+                let mut stderr_lines = Vec::new();
+                if let (true, Some(mismatch)) = (status_code.is_success(), &json_mismatch) {
+                    warn!("HTTP response failed expect_json_path: {}", mismatch);
+                    stderr_lines.push(format!("expect_json_path failed: {}", mismatch));
+                } else if task_status == TaskStatus::Failed {
+                    stderr_lines.push(format!(
+                        "HTTP request failed with status code: {}",
+                        status_code_u16
+                    ));
+                }
+                if let Some(len) = binary_len {
+                    stderr_lines.push(crate::executors::binary_output_note("response body", len));
+                }
+
                 Ok(ExecutionResult {
                     status: task_status.clone(),
                     stdout: response_body,
-                    stderr: if task_status == TaskStatus::Failed {
-                        Some(format!(
-                            "HTTP request failed with status code: {}",
-                            status_code_u16
-                        ))
-                    } else {
-                        None
-                    },
+                    stderr: (!stderr_lines.is_empty()).then(|| stderr_lines.join("\n")),
                     exit_code: Some(status_code_u16 as i32),
                     duration,
                     output_truncated,
+                    output_binary: binary_len.is_some(),
                 })
             }
             Err(e) => {
@@ -519,19 +920,113 @@ impl HttpExecutor {
                     exit_code: None,
                     duration,
                     output_truncated: false,
+                    output_binary: false,
                 })
             }
         }
     }
 }
 
+/// One step of an `expect_json_path` path: an object key or an array index
+#[derive(Debug, PartialEq)]
+enum JsonPathSegment {
+    Key(String),
+    Index(usize),
+}
+
+/// Parse a JSONPath-like path such as `$.data.items[0].state` or `data.items.0.state`
+///
+/// The leading `$` is optional; keys are separated by `.` and array indices may be
+/// written as `[n]` or as a numeric key.
+fn parse_json_path(path: &str) -> Result<Vec<JsonPathSegment>> {
+    let invalid = || PicoFlowError::Validation(format!("Invalid expect_json_path: '{}'", path));
+    let trimmed = path.strip_prefix('$').unwrap_or(path);
+    let trimmed = trimmed.strip_prefix('.').unwrap_or(trimmed);
+    if trimmed.is_empty() {
+        return Err(invalid());
+    }
+
+    let mut segments = Vec::new();
+    for part in trimmed.split('.') {
+        let (key, mut indices) = match part.find('[') {
+            Some(open) => part.split_at(open),
+            None => (part, ""),
+        };
+        if key.is_empty() && indices.is_empty() {
+            return Err(invalid());
+        }
+        if !key.is_empty() {
+            segments.push(match key.parse() {
+                Ok(index) => JsonPathSegment::Index(index),
+                Err(_) => JsonPathSegment::Key(key.to_string()),
+            });
+        }
+        while !indices.is_empty() {
+            let close = indices.find(']').ok_or_else(invalid)?;
+            let index = indices[1..close].parse().map_err(|_| invalid())?;
+            segments.push(JsonPathSegment::Index(index));
+            indices = &indices[close + 1..];
+            if !indices.is_empty() && !indices.starts_with('[') {
+                return Err(invalid());
+            }
+        }
+    }
+    Ok(segments)
+}
+
+/// Check that the JSON `body` holds `expected` at `path`
+///
+/// Strings are compared by content; any other value by its JSON text (`true`, `42`,
+/// `null`). Returns a description of the mismatch on failure.
+fn check_json_path(body: &[u8], path: &str, expected: &str) -> std::result::Result<(), String> {
+    let segments = parse_json_path(path).map_err(|e| e.to_string())?;
+    let json: serde_json::Value =
+        serde_json::from_slice(body).map_err(|e| format!("response body is not JSON: {}", e))?;
+
+    let mut current = &json;
+    for segment in &segments {
+        let next = match segment {
+            JsonPathSegment::Key(key) => current.get(key),
+            // A numeric key also addresses objects, e.g. `{"0": ...}`
+            JsonPathSegment::Index(index) => current
+                .get(index)
+                .or_else(|| current.get(index.to_string())),
+        };
+        current = next.ok_or_else(|| format!("path '{}' not found in response", path))?;
+    }
+
+    let actual = match current {
+        serde_json::Value::String(text) => text.clone(),
+        other => other.to_string(),
+    };
+    if actual == expected {
+        Ok(())
+    } else {
+        Err(format!(
+            "expected '{}' at '{}', got '{}'",
+            expected, path, actual
+        ))
+    }
+}
+
 #[async_trait]
 impl ExecutorTrait for HttpExecutor {
     async fn execute(&self, config: &TaskExecutorConfig) -> anyhow::Result<ExecutionResult> {
+        self.execute_with_output_limit(config, MAX_RESPONSE_SIZE)
+            .await
+    }
+
+    async fn execute_with_output_limit(
+        &self,
+        config: &TaskExecutorConfig,
+        max_output_bytes: usize,
+    ) -> anyhow::Result<ExecutionResult> {
         match config {
             TaskExecutorConfig::Http(http_config) => {
                 // Use timeout from config
-                let result = self.execute_http(http_config, http_config.timeout).await?;
+                let result = self
+                    .execute_http(http_config, http_config.timeout, max_output_bytes)
+                    .await?;
                 Ok(result)
             }
             _ => Err(anyhow::anyhow!("Invalid config type for HttpExecutor")),
@@ -545,12 +1040,20 @@ impl ExecutorTrait for HttpExecutor {
             url: "https://www.google.com".to_string(),
             method: HttpMethod::Get,
             body: None,
+            body_type: Default::default(),
             headers: std::collections::HashMap::new(),
             timeout: 5,
             allow_private_ips: false,
+            auth: None,
+            retry_on_status: None,
+            rate_limit_per_sec: None,
+            expect_json_path: None,
+            allowed_hosts: None,
+            client_cert_path: None,
+            client_key_path: None,
         };
 
-        let result = self.execute_http(&config, 5).await?;
+        let result = self.execute_http(&config, 5, MAX_RESPONSE_SIZE).await?;
 
         if result.status == TaskStatus::Success {
             Ok(())
@@ -560,27 +1063,34 @@ impl ExecutorTrait for HttpExecutor {
     }
 }
 
-impl Default for HttpExecutor {
-    fn default() -> Self {
-        Self::new()
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
     use std::collections::HashMap;
 
-    #[test]
-    fn test_validate_config_empty_url() {
-        let config = HttpConfig {
-            url: "".to_string(),
+    /// A GET request to `url` with every optional setting left unset
+    fn http_config(url: impl Into<String>) -> HttpConfig {
+        HttpConfig {
+            url: url.into(),
             method: HttpMethod::Get,
             body: None,
+            body_type: Default::default(),
             headers: HashMap::new(),
             timeout: 30,
             allow_private_ips: false,
-        };
+            auth: None,
+            retry_on_status: None,
+            rate_limit_per_sec: None,
+            expect_json_path: None,
+            allowed_hosts: None,
+            client_cert_path: None,
+            client_key_path: None,
+        }
+    }
+
+    #[test]
+    fn test_validate_config_empty_url() {
+        let config = http_config("");
 
         let result = HttpExecutor::validate_config(&config);
         assert!(result.is_err());
@@ -589,14 +1099,7 @@ mod tests {
 
     #[test]
     fn test_validate_config_invalid_url() {
-        let config = HttpConfig {
-            url: "not a valid url".to_string(),
-            method: HttpMethod::Get,
-            body: None,
-            headers: HashMap::new(),
-            timeout: 30,
-            allow_private_ips: false,
-        };
+        let config = http_config("not a valid url");
 
         let result = HttpExecutor::validate_config(&config);
         assert!(result.is_err());
@@ -605,24 +1108,16 @@ mod tests {
     #[test]
     fn test_validate_config_invalid_timeout() {
         let config = HttpConfig {
-            url: "https://example.com".to_string(),
-            method: HttpMethod::Get,
-            body: None,
-            headers: HashMap::new(),
             timeout: 0,
-            allow_private_ips: false,
+            ..http_config("https://example.com")
         };
 
         let result = HttpExecutor::validate_config(&config);
         assert!(result.is_err());
 
         let config = HttpConfig {
-            url: "https://example.com".to_string(),
-            method: HttpMethod::Get,
-            body: None,
-            headers: HashMap::new(),
             timeout: 4000,
-            allow_private_ips: false,
+            ..http_config("https://example.com")
         };
 
         let result = HttpExecutor::validate_config(&config);
@@ -631,14 +1126,7 @@ mod tests {
 
     #[test]
     fn test_validate_config_valid() {
-        let config = HttpConfig {
-            url: "https://example.com".to_string(),
-            method: HttpMethod::Get,
-            body: None,
-            headers: HashMap::new(),
-            timeout: 30,
-            allow_private_ips: false,
-        };
+        let config = http_config("https://example.com");
 
         let result = HttpExecutor::validate_config(&config);
         assert!(result.is_ok());
@@ -655,14 +1143,7 @@ mod tests {
         ];
 
         for url in private_ips {
-            let config = HttpConfig {
-                url: url.to_string(),
-                method: HttpMethod::Get,
-                body: None,
-                headers: HashMap::new(),
-                timeout: 30,
-                allow_private_ips: false,
-            };
+            let config = http_config(url);
 
             let result = HttpExecutor::validate_config(&config);
             assert!(result.is_err(), "Should block private IP: {}", url);
@@ -673,14 +1154,7 @@ mod tests {
     #[test]
     fn test_ssrf_protection_metadata_service() {
         // Test blocking cloud metadata service
-        let config = HttpConfig {
-            url: "http://169.254.169.254/latest/meta-data/".to_string(),
-            method: HttpMethod::Get,
-            body: None,
-            headers: HashMap::new(),
-            timeout: 30,
-            allow_private_ips: false,
-        };
+        let config = http_config("http://169.254.169.254/latest/meta-data/");
 
         let result = HttpExecutor::validate_config(&config);
         assert!(result.is_err());
@@ -690,14 +1164,7 @@ mod tests {
     #[test]
     fn test_ssrf_protection_localhost_domain() {
         // Test blocking localhost domain
-        let config = HttpConfig {
-            url: "http://localhost:8080/".to_string(),
-            method: HttpMethod::Get,
-            body: None,
-            headers: HashMap::new(),
-            timeout: 30,
-            allow_private_ips: false,
-        };
+        let config = http_config("http://localhost:8080/");
 
         let result = HttpExecutor::validate_config(&config);
         assert!(result.is_err());
@@ -708,12 +1175,8 @@ mod tests {
     fn test_ssrf_protection_allow_private_ips() {
         // Test that allow_private_ips flag works
         let config = HttpConfig {
-            url: "http://192.168.1.1/".to_string(),
-            method: HttpMethod::Get,
-            body: None,
-            headers: HashMap::new(),
-            timeout: 30,
             allow_private_ips: true,
+            ..http_config("http://192.168.1.1/")
         };
 
         let result = HttpExecutor::validate_config(&config);
@@ -730,20 +1193,43 @@ mod tests {
         ];
 
         for url in public_urls {
-            let config = HttpConfig {
-                url: url.to_string(),
-                method: HttpMethod::Get,
-                body: None,
-                headers: HashMap::new(),
-                timeout: 30,
-                allow_private_ips: false,
-            };
+            let config = http_config(url);
 
             let result = HttpExecutor::validate_config(&config);
             assert!(result.is_ok(), "Should allow public URL: {}", url);
         }
     }
 
+    #[test]
+    fn test_parse_json_path() {
+        use JsonPathSegment::{Index, Key};
+        let key = |k: &str| Key(k.to_string());
+
+        assert_eq!(parse_json_path("$.status").unwrap(), [key("status")]);
+        assert_eq!(
+            parse_json_path("data.items[0][1].state").unwrap(),
+            [key("data"), key("items"), Index(0), Index(1), key("state")]
+        );
+        assert_eq!(
+            parse_json_path("$.items.2").unwrap(),
+            [key("items"), Index(2)]
+        );
+        for bad in ["", "$", "$.", "a..b", "a[x]", "a[0", "a[0]b"] {
+            assert!(parse_json_path(bad).is_err(), "{bad:?} should be rejected");
+        }
+    }
+
+    #[test]
+    fn test_check_json_path_compares_non_strings_as_json() {
+        let body = br#"{"ok": true, "count": 3, "items": [{"id": "a"}], "none": null}"#;
+        assert!(check_json_path(body, "ok", "true").is_ok());
+        assert!(check_json_path(body, "count", "3").is_ok());
+        assert!(check_json_path(body, "items[0].id", "a").is_ok());
+        assert!(check_json_path(body, "none", "null").is_ok());
+        assert!(check_json_path(body, "count", "4").is_err());
+        assert!(check_json_path(body, "items[1].id", "a").is_err());
+    }
+
     #[test]
     fn test_convert_method() {
         assert_eq!(HttpExecutor::convert_method(&HttpMethod::Get), Method::GET);
@@ -804,6 +1290,190 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[tokio::test]
+    async fn test_ssrf_resolved_private_ip_blocked() {
+        // A public-looking domain whose DNS answer points at a blocked address
+        let resolve_to = |ips: Vec<&'static str>| {
+            move |host: String, port: u16| async move {
+                assert_eq!(host, "public.example");
+                Ok(ips
+                    .iter()
+                    .map(|ip| SocketAddr::new(ip.parse().unwrap(), port))
+                    .collect())
+            }
+        };
+        let url = "https://public.example/hook";
+
+        for ips in [
+            vec!["169.254.169.254"],
+            vec!["10.0.0.1"],
+            vec!["fd00::1"],
+            vec!["93.184.216.34", "127.0.0.1"],
+        ] {
+            let result =
+                HttpExecutor::resolve_and_validate_with(url, false, resolve_to(ips.clone())).await;
+            assert!(
+                matches!(result, Err(PicoFlowError::Http(ref e)) if e.contains("SSRF protection")),
+                "{ips:?}: {result:?}"
+            );
+        }
+
+        let addrs =
+            HttpExecutor::resolve_and_validate_with(url, false, resolve_to(vec!["93.184.216.34"]))
+                .await
+                .unwrap();
+        assert_eq!(addrs, vec!["93.184.216.34:443".parse().unwrap()]);
+
+        // Resolution errors fail the check rather than letting the request through
+        let result = HttpExecutor::resolve_and_validate_with(url, false, |_, _| async {
+            Err(std::io::Error::other("no such host"))
+        })
+        .await;
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_parse_retry_after() {
+        assert_eq!(
+            HttpExecutor::parse_retry_after("120"),
+            Some(Duration::from_secs(120))
+        );
+        assert_eq!(
+            HttpExecutor::parse_retry_after("Wed, 21 Oct 2015 07:28:00 GMT"),
+            Some(Duration::ZERO)
+        );
+        assert_eq!(HttpExecutor::parse_retry_after("soon"), None);
+    }
+
+    #[test]
+    fn test_validate_config_allowed_hosts() {
+        let mut config = HttpConfig {
+            allowed_hosts: Some(vec!["10.0.0.5".to_string(), "Example.com".to_string()]),
+            ..http_config("http://10.0.0.5:8080/health")
+        };
+        // A listed private host passes without allow_private_ips
+        assert!(HttpExecutor::validate_config(&config).is_ok());
+
+        // Listed domains match case-insensitively, including subdomains
+        for url in ["https://example.com/", "https://API.example.com/v1"] {
+            config.url = url.to_string();
+            assert!(HttpExecutor::validate_config(&config).is_ok(), "{url}");
+        }
+
+        // Everything else is blocked, even with allow_private_ips
+        config.allow_private_ips = true;
+        for url in [
+            "http://10.0.0.6/",
+            "https://notexample.com/",
+            "https://example.com.evil.net/",
+            "http://169.254.169.254/",
+        ] {
+            config.url = url.to_string();
+            let err = HttpExecutor::validate_config(&config).unwrap_err();
+            assert!(
+                err.to_string().contains("not in allowed_hosts"),
+                "{url}: {err}"
+            );
+        }
+
+        // Bracketed IPv6 entries, and the scheme is still checked
+        config.allowed_hosts = Some(vec!["[fd00::1]".to_string()]);
+        config.url = "http://[fd00::1]/".to_string();
+        assert!(HttpExecutor::validate_config(&config).is_ok());
+        config.url = "ftp://[fd00::1]/".to_string();
+        assert!(HttpExecutor::validate_config(&config).is_err());
+    }
+
+    #[test]
+    fn test_validate_config_retry_on_status_range() {
+        let mut config = HttpConfig {
+            retry_on_status: Some(vec![429, 503]),
+            ..http_config("https://example.com")
+        };
+        assert!(HttpExecutor::validate_config(&config).is_ok());
+
+        config.retry_on_status = Some(vec![503, 700]);
+        assert!(HttpExecutor::validate_config(&config).is_err());
+    }
+
+    #[test]
+    fn test_encode_body_rejects_unencodable_values() {
+        let nested: serde_yaml::Value = serde_yaml::from_str("outer: {inner: 1}").unwrap();
+        assert!(HttpExecutor::encode_body(&nested, BodyType::Form).is_err());
+        assert!(HttpExecutor::encode_body(&nested, BodyType::Raw).is_err());
+
+        let (raw, content_type) =
+            HttpExecutor::encode_body(&serde_yaml::Value::from("hi"), BodyType::Raw).unwrap();
+        assert_eq!(raw, "hi");
+        assert_eq!(content_type, "text/plain; charset=utf-8");
+    }
+
+    /// Write a self-signed certificate and PKCS#8 key as PEM files into `dir`
+    fn write_client_identity(dir: &std::path::Path) -> (String, String) {
+        use openssl::{asn1::Asn1Time, hash::MessageDigest, pkey::PKey, rsa::Rsa, x509};
+
+        let key = PKey::from_rsa(Rsa::generate(2048).unwrap()).unwrap();
+        let mut name = x509::X509NameBuilder::new().unwrap();
+        name.append_entry_by_text("CN", "picoflow-client").unwrap();
+        let name = name.build();
+        let mut cert = x509::X509Builder::new().unwrap();
+        cert.set_version(2).unwrap();
+        cert.set_subject_name(&name).unwrap();
+        cert.set_issuer_name(&name).unwrap();
+        cert.set_pubkey(&key).unwrap();
+        cert.set_not_before(&Asn1Time::days_from_now(0).unwrap())
+            .unwrap();
+        cert.set_not_after(&Asn1Time::days_from_now(1).unwrap())
+            .unwrap();
+        cert.sign(&key, MessageDigest::sha256()).unwrap();
+
+        let cert_path = dir.join("client.crt");
+        let key_path = dir.join("client.key");
+        std::fs::write(&cert_path, cert.build().to_pem().unwrap()).unwrap();
+        std::fs::write(&key_path, key.private_key_to_pem_pkcs8().unwrap()).unwrap();
+        (
+            cert_path.to_string_lossy().into_owned(),
+            key_path.to_string_lossy().into_owned(),
+        )
+    }
+
+    #[test]
+    fn test_client_identity() {
+        let dir = tempfile::tempdir().unwrap();
+        let (cert_path, key_path) = write_client_identity(dir.path());
+        let mut config = http_config("https://example.com");
+        let executor = HttpExecutor::new();
+        assert!(executor.client_identity(&config).unwrap().is_none());
+
+        // Both paths are required together
+        config.client_cert_path = Some(cert_path.clone());
+        assert!(HttpExecutor::validate_config(&config).is_err());
+        config.client_key_path = Some(key_path.clone());
+        assert!(HttpExecutor::validate_config(&config).is_ok());
+
+        // A valid pair loads once and is then served from the cache
+        assert!(executor.client_identity(&config).unwrap().is_some());
+        assert!(executor.client_identity(&config).unwrap().is_some());
+        assert_eq!(executor.identities.lock().unwrap().len(), 1);
+        let identity = executor.client_identity(&config).unwrap().unwrap();
+        assert!(Client::builder().identity(identity).build().is_ok());
+
+        // Missing files are reported by path
+        config.client_key_path = Some(dir.path().join("missing.key").display().to_string());
+        let err = executor.client_identity(&config).unwrap_err().to_string();
+        assert!(err.contains("missing.key"), "{}", err);
+
+        // Files that aren't PEM are rejected
+        let garbage = dir.path().join("garbage.pem");
+        std::fs::write(&garbage, "not a key").unwrap();
+        config.client_key_path = Some(garbage.display().to_string());
+        let err = executor.client_identity(&config).unwrap_err().to_string();
+        assert!(err.contains("Invalid client certificate"), "{}", err);
+        config.client_cert_path = Some(key_path);
+        config.client_key_path = Some(cert_path);
+        assert!(executor.client_identity(&config).is_err());
+    }
+
     // Note: Integration tests with mock HTTP server are in tests/http_executor_integration.rs
     // to keep unit tests fast and focused on logic validation
 }
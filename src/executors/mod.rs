@@ -1,10 +1,14 @@
 //! Task executors
 
+pub mod docker;
+pub mod download;
 pub mod http;
+pub(crate) mod rate_limit;
 pub mod shell;
 pub mod ssh;
+pub(crate) mod task_log;
 
-use crate::models::{ExecutionResult, TaskExecutorConfig, MAX_OUTPUT_SIZE};
+use crate::models::{ExecutionResult, TaskExecutorConfig};
 use async_trait::async_trait;
 use std::collections::HashMap;
 
@@ -14,6 +18,18 @@ pub trait ExecutorTrait: Send + Sync {
     /// Execute a task with the given configuration
     async fn execute(&self, config: &TaskExecutorConfig) -> anyhow::Result<ExecutionResult>;
 
+    /// Execute a task, storing at most `max_output_bytes` of each output stream
+    ///
+    /// Executors that capture output override this; the default ignores the limit.
+    async fn execute_with_output_limit(
+        &self,
+        config: &TaskExecutorConfig,
+        max_output_bytes: usize,
+    ) -> anyhow::Result<ExecutionResult> {
+        let _ = max_output_bytes;
+        self.execute(config).await
+    }
+
     /// Perform a health check
     async fn health_check(&self) -> anyhow::Result<()>;
 }
@@ -43,33 +59,77 @@ pub(crate) fn redact_headers(headers: &HashMap<String, String>) -> HashMap<Strin
         .collect()
 }
 
-/// Truncate string output to MAX_OUTPUT_SIZE
+/// Stdout and stderr of a finished command, ready to store in an [`ExecutionResult`]
+pub(crate) struct CapturedOutput {
+    pub stdout: String,
+    pub stderr: String,
+    /// Either stream exceeded the output limit and was cut off
+    pub truncated: bool,
+    /// Either stream was not valid UTF-8 and was replaced by a note in `stderr`
+    pub binary: bool,
+}
+
+/// Truncate each of a command's stdout and stderr to `limit` bytes and decode them
 ///
-/// Returns (truncated_string, was_truncated)
-pub(crate) fn truncate_output_str(data: &str) -> (String, bool) {
-    let bytes = data.as_bytes();
-    let truncated = bytes.len() > MAX_OUTPUT_SIZE;
+/// A stream that is not valid UTF-8 is not stored; instead `stderr` ends with a note
+/// giving its size, so binary output is visible rather than silently mangled.
+pub(crate) fn capture_output(stdout: &[u8], stderr: &[u8], limit: usize) -> CapturedOutput {
+    let (stdout_text, stdout_truncated) = truncate_output_bytes(stdout, limit);
+    let (stderr_text, stderr_truncated) = truncate_output_bytes(stderr, limit);
+    let binary = stdout_text.is_none() || stderr_text.is_none();
+
+    let mut notes = Vec::new();
+    if stdout_text.is_none() {
+        notes.push(binary_output_note("stdout", stdout.len()));
+    }
+    if stderr_text.is_none() {
+        notes.push(binary_output_note("stderr", stderr.len()));
+    }
+    let mut stderr = stderr_text.unwrap_or_default();
+    for note in notes {
+        if !stderr.is_empty() && !stderr.ends_with('\n') {
+            stderr.push('\n');
+        }
+        stderr.push_str(&note);
+    }
 
-    if truncated {
-        let truncated_bytes = &bytes[..MAX_OUTPUT_SIZE];
-        let output = String::from_utf8_lossy(truncated_bytes).to_string();
-        (output, true)
-    } else {
-        (data.to_string(), false)
+    CapturedOutput {
+        stdout: stdout_text.unwrap_or_default(),
+        stderr,
+        truncated: stdout_truncated || stderr_truncated,
+        binary,
     }
 }
 
-/// Truncate byte output to MAX_OUTPUT_SIZE
+/// Truncate byte output to `limit` bytes and decode it as UTF-8
 ///
-/// Returns (truncated_string, was_truncated)
-pub(crate) fn truncate_output_bytes(data: &[u8]) -> (String, bool) {
-    let truncated = data.len() > MAX_OUTPUT_SIZE;
-    let bytes = if truncated {
-        &data[..MAX_OUTPUT_SIZE]
-    } else {
-        data
-    };
+/// Returns (text, was_truncated); `text` is `None` if the output is binary.
+pub(crate) fn truncate_output_bytes(data: &[u8], limit: usize) -> (Option<String>, bool) {
+    let truncated = data.len() > limit;
+    let bytes = if truncated { &data[..limit] } else { data };
+
+    (decode_output(bytes, truncated), truncated)
+}
+
+/// Decode captured output as UTF-8, or `None` if it is binary
+///
+/// When `truncated`, a multi-byte character cut in half at the end is dropped rather
+/// than taken as a sign of binary data.
+pub(crate) fn decode_output(bytes: &[u8], truncated: bool) -> Option<String> {
+    match std::str::from_utf8(bytes) {
+        Ok(text) => Some(text.to_string()),
+        // `error_len() == None` means the input ended inside a character
+        Err(e) if truncated && e.error_len().is_none() => {
+            Some(String::from_utf8_lossy(&bytes[..e.valid_up_to()]).into_owned())
+        }
+        Err(_) => None,
+    }
+}
 
-    let output = String::from_utf8_lossy(bytes).to_string();
-    (output, truncated)
+/// Note stored in place of binary output on `stream`
+pub(crate) fn binary_output_note(stream: &str, len: usize) -> String {
+    format!(
+        "[{} is not valid UTF-8: {} bytes of binary output not stored]",
+        stream, len
+    )
 }
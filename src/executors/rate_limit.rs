@@ -0,0 +1,91 @@
+//! Per-host request rate limiting for network executors
+//!
+//! Tasks that set `rate_limit_per_sec` draw a token from their host's bucket before each
+//! request. Buckets are shared by every task an executor runs, so concurrent tasks
+//! hitting the same host throttle together. A bucket holds at most one second's worth of
+//! tokens, allowing a burst of `rate_limit_per_sec` requests before callers are spaced
+//! `1 / rate_limit_per_sec` seconds apart.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tracing::debug;
+
+/// Token buckets keyed by host
+pub(crate) type HostBuckets = Arc<Mutex<HashMap<String, TokenBucket>>>;
+
+/// Token bucket refilled continuously at a fixed rate
+#[derive(Debug)]
+pub(crate) struct TokenBucket {
+    rate_per_sec: u32,
+    /// Available tokens; negative once callers have reserved tokens that are still owed
+    tokens: f64,
+    updated: Instant,
+}
+
+impl TokenBucket {
+    /// Create a full bucket
+    pub(crate) fn new(rate_per_sec: u32, now: Instant) -> Self {
+        Self {
+            rate_per_sec,
+            tokens: rate_per_sec as f64,
+            updated: now,
+        }
+    }
+
+    /// Take a token, returning how long the caller must wait before using it
+    ///
+    /// A changed `rate_per_sec` (another task configured a different limit for the same
+    /// host) takes effect from now on.
+    pub(crate) fn reserve(&mut self, rate_per_sec: u32, now: Instant) -> Duration {
+        let elapsed = now.saturating_duration_since(self.updated).as_secs_f64();
+        let capacity = rate_per_sec as f64;
+        self.rate_per_sec = rate_per_sec;
+        self.tokens = (self.tokens + elapsed * capacity).min(capacity);
+        self.updated = now;
+
+        self.tokens -= 1.0;
+        if self.tokens >= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(-self.tokens / capacity)
+        }
+    }
+}
+
+/// Wait until a request to `host` is allowed under `rate_per_sec`
+pub(crate) async fn throttle(buckets: &HostBuckets, host: &str, rate_per_sec: u32) {
+    let now = Instant::now();
+    let wait = buckets
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .entry(host.to_string())
+        .or_insert_with(|| TokenBucket::new(rate_per_sec, now))
+        .reserve(rate_per_sec, now);
+
+    if !wait.is_zero() {
+        debug!("Rate limiting request to {} for {:?}", host, wait);
+        tokio::time::sleep(wait).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_token_bucket_allows_burst_then_spaces_requests() {
+        let start = Instant::now();
+        let mut bucket = TokenBucket::new(2, start);
+
+        assert_eq!(bucket.reserve(2, start), Duration::ZERO);
+        assert_eq!(bucket.reserve(2, start), Duration::ZERO);
+        assert_eq!(bucket.reserve(2, start), Duration::from_millis(500));
+        assert_eq!(bucket.reserve(2, start), Duration::from_secs(1));
+
+        // Refilling pays back the owed tokens before new ones accumulate
+        let later = start + Duration::from_millis(1500);
+        assert_eq!(bucket.reserve(2, later), Duration::ZERO);
+        assert_eq!(bucket.reserve(2, later), Duration::from_millis(500));
+    }
+}
@@ -1,40 +1,169 @@
 //! Shell command executor
 
 use crate::error::{PicoFlowError, Result};
+use crate::executors::task_log::{TaskLog, MAX_TASK_LOG_BYTES};
 use crate::executors::ExecutorTrait;
 use crate::models::{
     ExecutionResult, ShellConfig, TaskExecutorConfig, TaskStatus, MAX_OUTPUT_SIZE,
 };
-use crate::parser::validate_shell_config;
+use crate::parser::{check_shell_config, compile_stderr_regex};
 use async_trait::async_trait;
+use regex::Regex;
+use std::collections::HashMap;
+use std::path::Path;
+use std::process::Output;
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
-use tokio::process::Command;
-use tracing::{debug, error, info};
+use tokio::io::{AsyncBufReadExt, AsyncRead, BufReader};
+use tokio::process::{Child, Command};
+use tokio::sync::watch;
+use tracing::{debug, error, info, warn};
+
+/// How long a cancelled or timed-out command gets to exit after SIGTERM before it is
+/// killed
+pub(crate) const TERMINATE_GRACE_PERIOD: Duration = Duration::from_secs(5);
+
+/// How long to wait for a killed command to be reaped before giving up on its output
+const KILL_REAP_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Why a command was stopped before it exited on its own
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Stopped {
+    Cancelled,
+    TimedOut,
+}
+
+/// Resolve once `cancel` reads `true`; never resolves if the sender is gone without
+/// having cancelled
+pub(crate) async fn cancelled(cancel: &mut watch::Receiver<bool>) {
+    if cancel.wait_for(|cancelled| *cancelled).await.is_err() {
+        std::future::pending::<()>().await;
+    }
+}
 
 /// Shell executor for local command execution
 #[derive(Debug, Clone)]
-pub struct ShellExecutor;
+pub struct ShellExecutor {
+    /// Compiled `fail_on_stderr_regex` patterns, so retries and later runs of a task
+    /// reuse them
+    stderr_patterns: Arc<Mutex<HashMap<String, Regex>>>,
+}
 
 impl ShellExecutor {
     pub fn new() -> Self {
-        Self
+        Self {
+            stderr_patterns: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// The compiled `fail_on_stderr_regex` `pattern`, compiling it on first use
+    fn stderr_regex(&self, pattern: &str) -> Result<Regex> {
+        let mut patterns = self
+            .stderr_patterns
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        if let Some(regex) = patterns.get(pattern) {
+            return Ok(regex.clone());
+        }
+        let regex = compile_stderr_regex(pattern)?;
+        patterns.insert(pattern.to_string(), regex.clone());
+        Ok(regex)
+    }
+
+    /// Execute a shell task that stops when `cancel` becomes `true` or after `timeout_secs`
+    ///
+    /// The command runs in its own process group. On cancellation or timeout the group
+    /// receives SIGTERM and has [`TERMINATE_GRACE_PERIOD`] to exit before it is sent
+    /// SIGKILL, so no process the command started is left running. A cancelled command
+    /// results in `TaskStatus::Cancelled` with whatever output it produced; a timed-out one
+    /// fails with `PicoFlowError::TaskTimeout`. At most `max_output_bytes` of each stream
+    /// are stored.
+    pub async fn execute_cancellable(
+        &self,
+        config: &TaskExecutorConfig,
+        cancel: watch::Receiver<bool>,
+        timeout_secs: u64,
+        max_output_bytes: usize,
+    ) -> anyhow::Result<ExecutionResult> {
+        match config {
+            TaskExecutorConfig::Shell(shell_config) => Ok(self
+                .execute_shell(
+                    shell_config,
+                    timeout_secs,
+                    Some(cancel),
+                    max_output_bytes,
+                    None,
+                )
+                .await?),
+            _ => Err(anyhow::anyhow!("Invalid config type for ShellExecutor")),
+        }
+    }
+
+    /// Like [`Self::execute_cancellable`], also appending each output line to the file at
+    /// `log_path` as soon as the command writes it
+    ///
+    /// The file is rotated to `<log_path>.1` once it would exceed 10 MB. Failing to open
+    /// or write it is logged and does not affect the task.
+    pub async fn execute_logged(
+        &self,
+        config: &TaskExecutorConfig,
+        cancel: watch::Receiver<bool>,
+        timeout_secs: u64,
+        max_output_bytes: usize,
+        log_path: &Path,
+    ) -> anyhow::Result<ExecutionResult> {
+        match config {
+            TaskExecutorConfig::Shell(shell_config) => Ok(self
+                .execute_shell(
+                    shell_config,
+                    timeout_secs,
+                    Some(cancel),
+                    max_output_bytes,
+                    Some(log_path),
+                )
+                .await?),
+            _ => Err(anyhow::anyhow!("Invalid config type for ShellExecutor")),
+        }
     }
 
     async fn execute_shell(
         &self,
         config: &ShellConfig,
         timeout_secs: u64,
+        cancel: Option<watch::Receiver<bool>>,
+        max_output_bytes: usize,
+        log_path: Option<&Path>,
     ) -> Result<ExecutionResult> {
         // Validate configuration
-        validate_shell_config(config)?;
+        check_shell_config(config)?;
+        let stderr_failure = config
+            .fail_on_stderr_regex
+            .as_deref()
+            .map(|pattern| self.stderr_regex(pattern))
+            .transpose()?;
+        Self::prepare_workdir(config)?;
+        let log = log_path.and_then(|path| {
+            TaskLog::open(path, MAX_TASK_LOG_BYTES)
+                .map_err(|e| warn!("Cannot open task log {}: {}", path.display(), e))
+                .ok()
+        });
 
         info!("Executing shell command: {}", config.command);
         debug!("Command args: {:?}", config.args);
 
         let start = std::time::Instant::now();
 
-        // Create command with individual args (no shell interpolation)
-        let mut cmd = Command::new(&config.command);
+        // Create command with individual args (no shell interpolation), unless the task
+        // opted into running `command` as a shell script. `$0` is the shell itself, so
+        // `args` line up with `$1`, `$2`, ...
+        let mut cmd = match &config.shell {
+            Some(shell) => {
+                let mut cmd = Command::new(shell);
+                cmd.arg("-c").arg(&config.command).arg(shell);
+                cmd
+            }
+            None => Command::new(&config.command),
+        };
         cmd.args(&config.args);
 
         // Set working directory if specified
@@ -42,6 +171,13 @@ impl ShellExecutor {
             cmd.current_dir(workdir);
         }
 
+        // Variables from env_file first, so inline env entries override them
+        if let Some(env_file) = &config.env_file {
+            for (key, value) in load_env_file(env_file)? {
+                cmd.env(key, value);
+            }
+        }
+
         // Set environment variables if specified
         if let Some(env) = &config.env {
             for (key, value) in env {
@@ -53,33 +189,50 @@ impl ShellExecutor {
         cmd.stdout(std::process::Stdio::piped());
         cmd.stderr(std::process::Stdio::piped());
 
-        // Ensure child process is killed when the future is dropped (e.g. on timeout).
-        // Without this, timed-out processes become orphan zombies.
+        // Ensure child process is killed if the future is dropped (e.g. by a caller's own
+        // timeout). Without this, abandoned processes become orphan zombies.
         cmd.kill_on_drop(true);
 
-        // Execute with timeout
-        let output_result =
-            tokio::time::timeout(Duration::from_secs(timeout_secs), cmd.output()).await;
+        let output_result = Self::run(cmd, cancel, Duration::from_secs(timeout_secs), log).await;
 
         let duration = start.elapsed();
 
         match output_result {
-            Ok(Ok(output)) => {
+            Ok((_, Some(Stopped::TimedOut))) => {
+                error!("Command timed out after {} seconds", timeout_secs);
+                Err(PicoFlowError::TaskTimeout {
+                    task: config.command.clone(),
+                    timeout: timeout_secs,
+                })
+            }
+            Ok((output, stopped)) => {
                 // Truncate output if needed
-                let (stdout, stdout_truncated) =
-                    crate::executors::truncate_output_bytes(&output.stdout);
-                let (stderr, stderr_truncated) =
-                    crate::executors::truncate_output_bytes(&output.stderr);
-                let output_truncated = stdout_truncated || stderr_truncated;
+                let captured = crate::executors::capture_output(
+                    &output.stdout,
+                    &output.stderr,
+                    max_output_bytes,
+                );
 
-                let status = if output.status.success() {
+                // Matched against the full stderr, not just the part that is stored
+                let stderr_matched = stderr_failure
+                    .is_some_and(|re| re.is_match(&String::from_utf8_lossy(&output.stderr)));
+
+                let status = if stopped == Some(Stopped::Cancelled) {
+                    TaskStatus::Cancelled
+                } else if stderr_matched {
+                    warn!("stderr matched fail_on_stderr_regex, marking task failed");
+                    TaskStatus::Failed
+                } else if output.status.success() {
                     TaskStatus::Success
                 } else {
                     TaskStatus::Failed
                 };
 
-                if output_truncated {
-                    debug!("Output truncated to {} bytes", MAX_OUTPUT_SIZE);
+                if captured.truncated {
+                    debug!("Output truncated to {} bytes", max_output_bytes);
+                }
+                if captured.binary {
+                    warn!("Output is not valid UTF-8 and was not stored");
                 }
 
                 info!(
@@ -90,36 +243,287 @@ impl ShellExecutor {
 
                 Ok(ExecutionResult {
                     status,
-                    stdout: Some(stdout),
-                    stderr: Some(stderr),
+                    stdout: Some(captured.stdout),
+                    stderr: Some(captured.stderr),
                     exit_code: output.status.code(),
                     duration,
-                    output_truncated,
+                    output_truncated: captured.truncated,
+                    output_binary: captured.binary,
                 })
             }
-            Ok(Err(e)) => {
+            Err(e) => {
                 error!("Command execution failed: {}", e);
-                Err(PicoFlowError::Io(e))
+                Err(e)
             }
-            Err(_) => {
-                error!("Command timed out after {} seconds", timeout_secs);
-                Err(PicoFlowError::TaskTimeout {
-                    task: config.command.clone(),
-                    timeout: timeout_secs,
-                })
+        }
+    }
+
+    /// Make sure the configured working directory exists, creating it if `create_workdir`
+    /// is set. Without this check a missing directory surfaces as a bare OS error from
+    /// spawning the command.
+    fn prepare_workdir(config: &ShellConfig) -> Result<()> {
+        let Some(workdir) = &config.workdir else {
+            return Ok(());
+        };
+        let path = std::path::Path::new(workdir);
+
+        if path.is_dir() {
+            return Ok(());
+        }
+        if path.exists() {
+            return Err(PicoFlowError::Validation(format!(
+                "Working directory '{}' is not a directory",
+                workdir
+            )));
+        }
+        if !config.create_workdir {
+            return Err(PicoFlowError::Validation(format!(
+                "Working directory '{}' does not exist (set create_workdir: true to create it)",
+                workdir
+            )));
+        }
+
+        info!("Creating working directory {}", workdir);
+        std::fs::create_dir_all(path)?;
+        Ok(())
+    }
+
+    /// Spawn the command and collect its output, terminating it if `cancel` fires or
+    /// `timeout` elapses
+    ///
+    /// Returns the output and why the command was stopped, if it was. A command that cannot
+    /// be started fails with `PicoFlowError::TaskSpawn`.
+    async fn run(
+        mut cmd: Command,
+        mut cancel: Option<watch::Receiver<bool>>,
+        timeout: Duration,
+        log: Option<TaskLog>,
+    ) -> Result<(std::process::Output, Option<Stopped>)> {
+        if cancel.is_none() {
+            // Match `Command::output`, which does not let the child read our stdin
+            cmd.stdin(std::process::Stdio::null());
+        }
+        // A process group of its own lets termination reach everything the command starts
+        cmd.process_group(0);
+        let child = cmd.spawn().map_err(|source| PicoFlowError::TaskSpawn {
+            task: cmd.as_std().get_program().to_string_lossy().into_owned(),
+            source,
+        })?;
+
+        let pid = child.id();
+        let output = wait_for_output(child, log);
+        tokio::pin!(output);
+
+        let stopped = tokio::select! {
+            result = &mut output => return Ok((result?, None)),
+            _ = async {
+                match cancel.as_mut() {
+                    Some(cancel) => cancelled(cancel).await,
+                    None => std::future::pending().await,
+                }
+            } => Stopped::Cancelled,
+            _ = tokio::time::sleep(timeout) => Stopped::TimedOut,
+        };
+
+        match stopped {
+            Stopped::Cancelled => info!("Cancelling command (PID: {:?})", pid),
+            Stopped::TimedOut => warn!(
+                "Command timed out after {:?}, terminating it (PID: {:?})",
+                timeout, pid
+            ),
+        }
+        signal_process_group(pid, libc::SIGTERM);
+
+        if let Ok(result) = tokio::time::timeout(TERMINATE_GRACE_PERIOD, &mut output).await {
+            return Ok((result?, Some(stopped)));
+        }
+        warn!(
+            "Command did not exit within {:?} of SIGTERM, killing it",
+            TERMINATE_GRACE_PERIOD
+        );
+        signal_process_group(pid, libc::SIGKILL);
+
+        // Reap the child; a process that left the group could still hold its pipes open,
+        // in which case dropping the future leaves the rest to kill_on_drop
+        match tokio::time::timeout(KILL_REAP_TIMEOUT, &mut output).await {
+            Ok(Ok(output)) => Ok((output, Some(stopped))),
+            _ => Ok((
+                std::process::Output {
+                    status: std::os::unix::process::ExitStatusExt::from_raw(libc::SIGKILL),
+                    stdout: Vec::new(),
+                    stderr: Vec::new(),
+                },
+                Some(stopped),
+            )),
+        }
+    }
+}
+
+/// Send `signal` to the process group led by the child with `pid`
+fn signal_process_group(pid: Option<u32>, signal: libc::c_int) {
+    if let Some(pid) = pid {
+        // SAFETY: kill(2) has no memory-safety preconditions. The target is the process
+        // group of our own child, signalled only while its output is still being collected.
+        unsafe {
+            libc::kill(-(pid as libc::pid_t), signal);
+        }
+    }
+}
+
+/// Wait for `child` to exit and collect its output, appending each line to `log` as it
+/// arrives
+async fn wait_for_output(mut child: Child, log: Option<TaskLog>) -> std::io::Result<Output> {
+    let Some(log) = log else {
+        return child.wait_with_output().await;
+    };
+    let log = Mutex::new(log);
+    let stdout = child.stdout.take();
+    let stderr = child.stderr.take();
+    let (status, stdout, stderr) = tokio::try_join!(
+        child.wait(),
+        tee_lines(stdout, &log),
+        tee_lines(stderr, &log)
+    )?;
+    Ok(Output {
+        status,
+        stdout,
+        stderr,
+    })
+}
+
+/// Read `stream` to the end, writing every line to `log` as soon as it is complete
+async fn tee_lines<R: AsyncRead + Unpin>(
+    stream: Option<R>,
+    log: &Mutex<TaskLog>,
+) -> std::io::Result<Vec<u8>> {
+    let Some(stream) = stream else {
+        return Ok(Vec::new());
+    };
+    let mut reader = BufReader::new(stream);
+    let mut output = Vec::new();
+    loop {
+        let start = output.len();
+        if reader.read_until(b'\n', &mut output).await? == 0 {
+            return Ok(output);
+        }
+        log.lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .write(&output[start..]);
+    }
+}
+
+/// Read and parse a dotenv file
+///
+/// # Errors
+///
+/// * `PicoFlowError::Validation` - If the file does not exist or has a malformed line
+/// * `PicoFlowError::Io` - If the file exists but cannot be read
+fn load_env_file(path: &str) -> Result<Vec<(String, String)>> {
+    let content = std::fs::read_to_string(path).map_err(|e| {
+        if e.kind() == std::io::ErrorKind::NotFound {
+            PicoFlowError::Validation(format!("env_file '{}' does not exist", path))
+        } else {
+            PicoFlowError::Io(e)
+        }
+    })?;
+    parse_env_file(&content)
+        .map_err(|message| PicoFlowError::Validation(format!("env_file '{}': {}", path, message)))
+}
+
+/// Parse dotenv syntax into `(key, value)` pairs in file order
+///
+/// - Blank lines and lines starting with `#` are ignored
+/// - An optional `export ` prefix is allowed
+/// - Keys must match `[A-Za-z_][A-Za-z0-9_]*`
+/// - `"double quoted"` values support `\n`, `\t`, `\"` and `\\` escapes
+/// - `'single quoted'` values are taken literally
+/// - Unquoted values are trimmed and end at ` #` (an inline comment)
+///
+/// Later duplicates win when the pairs are applied in order. Errors name the 1-based line.
+fn parse_env_file(content: &str) -> std::result::Result<Vec<(String, String)>, String> {
+    let mut vars = Vec::new();
+
+    for (index, raw) in content.lines().enumerate() {
+        let line_no = index + 1;
+        let line = raw.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let line = line.strip_prefix("export ").unwrap_or(line);
+
+        let (key, value) = line
+            .split_once('=')
+            .ok_or_else(|| format!("line {}: expected KEY=VALUE", line_no))?;
+        let key = key.trim();
+        let valid_key = key
+            .chars()
+            .next()
+            .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+            && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+        if !valid_key {
+            return Err(format!("line {}: invalid variable name '{}'", line_no, key));
+        }
+
+        let value = value.trim();
+        let value = if let Some(rest) = value.strip_prefix('"') {
+            parse_double_quoted(rest)
+                .ok_or_else(|| format!("line {}: unterminated double-quoted value", line_no))?
+        } else if let Some(rest) = value.strip_prefix('\'') {
+            let end = rest
+                .find('\'')
+                .ok_or_else(|| format!("line {}: unterminated single-quoted value", line_no))?;
+            rest[..end].to_string()
+        } else {
+            match value.find(" #") {
+                Some(comment) => value[..comment].trim_end().to_string(),
+                None => value.to_string(),
             }
+        };
+
+        vars.push((key.to_string(), value));
+    }
+
+    Ok(vars)
+}
+
+/// Unescape a double-quoted value up to its closing quote (`rest` starts after the
+/// opening quote). Returns `None` if the quote is never closed.
+fn parse_double_quoted(rest: &str) -> Option<String> {
+    let mut value = String::new();
+    let mut chars = rest.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => return Some(value),
+            '\\' => match chars.next()? {
+                'n' => value.push('\n'),
+                't' => value.push('\t'),
+                other => value.push(other),
+            },
+            other => value.push(other),
         }
     }
+    None
 }
 
 #[async_trait]
 impl ExecutorTrait for ShellExecutor {
     async fn execute(&self, config: &TaskExecutorConfig) -> anyhow::Result<ExecutionResult> {
+        self.execute_with_output_limit(config, MAX_OUTPUT_SIZE)
+            .await
+    }
+
+    async fn execute_with_output_limit(
+        &self,
+        config: &TaskExecutorConfig,
+        max_output_bytes: usize,
+    ) -> anyhow::Result<ExecutionResult> {
         match config {
             TaskExecutorConfig::Shell(shell_config) => {
                 // Use a very large timeout here since scheduler applies the actual timeout
                 // This prevents double-timeout issues and ensures scheduler timeout takes precedence
-                let result = self.execute_shell(shell_config, 86400).await?;
+                let result = self
+                    .execute_shell(shell_config, 86400, None, max_output_bytes, None)
+                    .await?;
                 Ok(result)
             }
             _ => Err(anyhow::anyhow!("Invalid config type for ShellExecutor")),
@@ -159,8 +563,7 @@ mod tests {
         let config = TaskExecutorConfig::Shell(ShellConfig {
             command: "/bin/echo".to_string(),
             args: vec!["hello".to_string()],
-            workdir: None,
-            env: None,
+            ..Default::default()
         });
 
         let result = executor.execute(&config).await.unwrap();
@@ -175,8 +578,7 @@ mod tests {
         let config = TaskExecutorConfig::Shell(ShellConfig {
             command: "/bin/sh".to_string(),
             args: vec!["-c".to_string(), "exit 1".to_string()],
-            workdir: None,
-            env: None,
+            ..Default::default()
         });
 
         let result = executor.execute(&config).await.unwrap();
@@ -184,6 +586,95 @@ mod tests {
         assert_eq!(result.exit_code, Some(1));
     }
 
+    #[tokio::test]
+    async fn test_fail_on_stderr_regex() {
+        let executor = ShellExecutor::new();
+        let config = |script: &str| {
+            TaskExecutorConfig::Shell(ShellConfig {
+                command: "/bin/sh".to_string(),
+                args: vec!["-c".to_string(), script.to_string()],
+                fail_on_stderr_regex: Some("^FATAL:".to_string()),
+                ..Default::default()
+            })
+        };
+
+        // Exit code 0, but stderr reports a fatal error
+        let result = executor
+            .execute(&config("echo 'FATAL: disk not mounted' >&2"))
+            .await
+            .unwrap();
+        assert_eq!(result.status, TaskStatus::Failed);
+        assert_eq!(result.exit_code, Some(0));
+
+        let result = executor
+            .execute(&config("echo 'warning: FATAL: later' >&2"))
+            .await
+            .unwrap();
+        assert_eq!(result.status, TaskStatus::Success);
+
+        // Compiled once, then reused by every run
+        assert_eq!(executor.stderr_patterns.lock().unwrap().len(), 1);
+        let clone = executor.clone();
+        clone
+            .execute(&config("echo 'FATAL: again' >&2"))
+            .await
+            .unwrap();
+        assert_eq!(executor.stderr_patterns.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_shell_option_runs_script() {
+        let executor = ShellExecutor::new();
+        let config = |command: &str, args: &[&str], shell: Option<&str>| {
+            TaskExecutorConfig::Shell(ShellConfig {
+                command: command.to_string(),
+                args: args.iter().map(|a| a.to_string()).collect(),
+                shell: shell.map(str::to_string),
+                ..Default::default()
+            })
+        };
+
+        let result = executor
+            .execute(&config(
+                "printf 'b\\na\\n' | sort | tr '\\n' ' '",
+                &[],
+                Some("/bin/sh"),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(result.status, TaskStatus::Success);
+        assert_eq!(result.stdout.as_deref(), Some("a b "));
+
+        // Args are positional parameters, never parsed as shell syntax
+        let result = executor
+            .execute(&config(
+                "echo \"$1\" | wc -c",
+                &["x; exit 3"],
+                Some("/bin/sh"),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(result.status, TaskStatus::Success);
+        assert_eq!(result.stdout.as_deref().map(str::trim), Some("10"));
+
+        // The pipeline's exit status decides the outcome
+        let result = executor
+            .execute(&config("echo hi | false", &[], Some("/bin/sh")))
+            .await
+            .unwrap();
+        assert_eq!(result.status, TaskStatus::Failed);
+
+        // Without `shell` the command is a path, not a script
+        assert!(executor
+            .execute(&config("echo hi | cat", &[], None))
+            .await
+            .is_err());
+        assert!(executor
+            .execute(&config("echo hi", &[], Some("sh")))
+            .await
+            .is_err());
+    }
+
     #[tokio::test]
     async fn test_shell_executor_with_env() {
         let executor = ShellExecutor::new();
@@ -193,8 +684,8 @@ mod tests {
         let config = TaskExecutorConfig::Shell(ShellConfig {
             command: "/bin/sh".to_string(),
             args: vec!["-c".to_string(), "echo $TEST_VAR".to_string()],
-            workdir: None,
             env: Some(env),
+            ..Default::default()
         });
 
         let result = executor.execute(&config).await.unwrap();
@@ -208,13 +699,159 @@ mod tests {
         let config = ShellConfig {
             command: "/bin/sleep".to_string(),
             args: vec!["10".to_string()],
-            workdir: None,
-            env: None,
+            ..Default::default()
         };
 
         // Execute with 1 second timeout
-        let result = executor.execute_shell(&config, 1).await;
+        let result = executor
+            .execute_shell(&config, 1, None, MAX_OUTPUT_SIZE, None)
+            .await;
+        assert!(matches!(result, Err(PicoFlowError::TaskTimeout { .. })));
+    }
+
+    /// Whether `pid` is a running (not zombie) process
+    #[cfg(target_os = "linux")]
+    fn process_alive(pid: &str) -> bool {
+        match std::fs::read_to_string(format!("/proc/{}/stat", pid)) {
+            // The state letter follows the parenthesised command name
+            Ok(stat) => stat
+                .rsplit_once(')')
+                .is_some_and(|(_, rest)| !rest.trim_start().starts_with('Z')),
+            Err(_) => false,
+        }
+    }
+
+    /// Run `script` (which writes its PIDs to the file passed as `$1`) with a 1s timeout,
+    /// returning the result, how long it took and the PIDs it wrote
+    #[cfg(target_os = "linux")]
+    async fn run_until_timeout(script: &str) -> (Result<ExecutionResult>, Duration, Vec<String>) {
+        let dir = tempfile::tempdir().unwrap();
+        let pid_file = dir.path().join("pids");
+        let config = ShellConfig {
+            command: "/bin/sh".to_string(),
+            args: vec![
+                "-c".to_string(),
+                script.to_string(),
+                "sh".to_string(),
+                pid_file.to_str().unwrap().to_string(),
+            ],
+            ..Default::default()
+        };
+
+        let start = std::time::Instant::now();
+        let result = ShellExecutor::new()
+            .execute_shell(&config, 1, None, MAX_OUTPUT_SIZE, None)
+            .await;
+        let elapsed = start.elapsed();
+        let pids = std::fs::read_to_string(&pid_file)
+            .unwrap()
+            .split_whitespace()
+            .map(str::to_string)
+            .collect();
+        (result, elapsed, pids)
+    }
+
+    #[cfg(target_os = "linux")]
+    #[tokio::test]
+    async fn test_shell_timeout_terminates_process_group() {
+        let (result, elapsed, pids) =
+            run_until_timeout("sleep 60 & echo $$ $! > \"$1\"; wait").await;
+
         assert!(matches!(result, Err(PicoFlowError::TaskTimeout { .. })));
+        // SIGTERM was enough, so the grace period was not used up
+        assert!(elapsed < TERMINATE_GRACE_PERIOD, "{elapsed:?}");
+        assert_eq!(pids.len(), 2);
+        for pid in &pids {
+            assert!(!process_alive(pid), "process {pid} is still running");
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    #[tokio::test]
+    async fn test_shell_timeout_kills_processes_ignoring_sigterm() {
+        let (result, elapsed, pids) =
+            run_until_timeout("trap '' TERM; sleep 60 & echo $$ $! > \"$1\"; wait").await;
+
+        assert!(matches!(result, Err(PicoFlowError::TaskTimeout { .. })));
+        assert!(elapsed >= TERMINATE_GRACE_PERIOD, "{elapsed:?}");
+        assert_eq!(pids.len(), 2);
+        for pid in &pids {
+            assert!(!process_alive(pid), "process {pid} is still running");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_shell_executor_cancel_terminates_child() {
+        let executor = ShellExecutor::new();
+        let config = TaskExecutorConfig::Shell(ShellConfig {
+            command: "/bin/sh".to_string(),
+            args: vec![
+                "-c".to_string(),
+                "trap 'kill $!; echo terminated; exit 143' TERM; echo started; sleep 30 & wait"
+                    .to_string(),
+            ],
+            ..Default::default()
+        });
+        let (cancel_tx, cancel_rx) = watch::channel(false);
+
+        let run = tokio::spawn(async move {
+            executor
+                .execute_cancellable(&config, cancel_rx, 60, MAX_OUTPUT_SIZE)
+                .await
+        });
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        let start = std::time::Instant::now();
+        cancel_tx.send(true).unwrap();
+
+        let result = run.await.unwrap().unwrap();
+        assert!(start.elapsed() < TERMINATE_GRACE_PERIOD);
+        assert_eq!(result.status, TaskStatus::Cancelled);
+        // The command saw SIGTERM and got to run its handler
+        let stdout = result.stdout.unwrap();
+        assert!(
+            stdout.contains("started") && stdout.contains("terminated"),
+            "{stdout}"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_execute_logged_streams_lines_while_running() {
+        let dir = tempfile::tempdir().unwrap();
+        let log_path = dir.path().join("7").join("build.log");
+        let config = TaskExecutorConfig::Shell(ShellConfig {
+            command: "/bin/sh".to_string(),
+            args: vec![
+                "-c".to_string(),
+                "echo first; echo oops >&2; sleep 2; echo second".to_string(),
+            ],
+            ..Default::default()
+        });
+        let (_tx, cancel) = watch::channel(false);
+
+        let task_log_path = log_path.clone();
+        let handle = tokio::spawn(async move {
+            ShellExecutor::new()
+                .execute_logged(&config, cancel, 60, MAX_OUTPUT_SIZE, &task_log_path)
+                .await
+        });
+
+        // The first lines reach the file well before the command finishes
+        let read_log = || std::fs::read_to_string(&log_path).unwrap_or_default();
+        let deadline = std::time::Instant::now() + Duration::from_secs(1);
+        while !read_log().contains("oops") && std::time::Instant::now() < deadline {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        let partial = read_log();
+        assert!(partial.contains("first\n"), "{partial:?}");
+        assert!(partial.contains("oops\n"), "{partial:?}");
+        assert!(!partial.contains("second"), "{partial:?}");
+        assert!(!handle.is_finished());
+
+        let result = handle.await.unwrap().unwrap();
+        assert_eq!(result.status, TaskStatus::Success);
+        assert_eq!(result.stdout.as_deref(), Some("first\nsecond\n"));
+        assert_eq!(result.stderr.as_deref(), Some("oops\n"));
+        assert!(read_log().ends_with("second\n"));
     }
 
     #[tokio::test]
@@ -229,15 +866,82 @@ mod tests {
         use crate::executors::truncate_output_bytes;
 
         let small_data = b"hello";
-        let (output, truncated) = truncate_output_bytes(small_data);
-        assert_eq!(output, "hello");
+        let (output, truncated) = truncate_output_bytes(small_data, MAX_OUTPUT_SIZE);
+        assert_eq!(output.as_deref(), Some("hello"));
         assert!(!truncated);
 
         // Create large data
         let large_data = vec![b'x'; MAX_OUTPUT_SIZE + 1000];
-        let (output, truncated) = truncate_output_bytes(&large_data);
-        assert_eq!(output.len(), MAX_OUTPUT_SIZE);
+        let (output, truncated) = truncate_output_bytes(&large_data, MAX_OUTPUT_SIZE);
+        assert_eq!(output.unwrap().len(), MAX_OUTPUT_SIZE);
         assert!(truncated);
+
+        // A character split by the cut is dropped, not treated as binary
+        let mut split_char = vec![b'x'; MAX_OUTPUT_SIZE - 1];
+        split_char.extend_from_slice("é".as_bytes());
+        let (output, truncated) = truncate_output_bytes(&split_char, MAX_OUTPUT_SIZE);
+        assert_eq!(output.unwrap().len(), MAX_OUTPUT_SIZE - 1);
+        assert!(truncated);
+    }
+
+    #[test]
+    fn test_binary_output_detected() {
+        use crate::executors::capture_output;
+
+        let binary = [0x89, b'P', b'N', b'G', 0xff, 0xfe, 0x00];
+        let captured = capture_output(&binary, b"warning\n", MAX_OUTPUT_SIZE);
+        assert!(captured.binary);
+        assert!(!captured.truncated);
+        assert_eq!(captured.stdout, "");
+        assert_eq!(
+            captured.stderr,
+            "warning\n[stdout is not valid UTF-8: 7 bytes of binary output not stored]"
+        );
+
+        let captured = capture_output(b"ok", &binary, MAX_OUTPUT_SIZE);
+        assert!(captured.binary);
+        assert_eq!(captured.stdout, "ok");
+        assert_eq!(
+            captured.stderr,
+            "[stderr is not valid UTF-8: 7 bytes of binary output not stored]"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_binary_command_output_sets_flag() {
+        let executor = ShellExecutor::new();
+        let config = TaskExecutorConfig::Shell(ShellConfig {
+            command: "/usr/bin/printf".to_string(),
+            args: vec!["\\377\\376binary".to_string()],
+            ..Default::default()
+        });
+
+        let result = executor.execute(&config).await.unwrap();
+        assert_eq!(result.status, TaskStatus::Success);
+        assert!(result.output_binary);
+        assert_eq!(result.stdout.as_deref(), Some(""));
+        assert!(result.stderr.unwrap().contains("stdout is not valid UTF-8"));
+    }
+
+    #[tokio::test]
+    async fn test_output_limit_truncates() {
+        let executor = ShellExecutor::new();
+        let config = TaskExecutorConfig::Shell(ShellConfig {
+            command: "/bin/echo".to_string(),
+            args: vec!["hello world".to_string()],
+            ..Default::default()
+        });
+
+        let result = executor.execute(&config).await.unwrap();
+        assert_eq!(result.stdout.as_deref(), Some("hello world\n"));
+        assert!(!result.output_truncated);
+
+        let result = executor
+            .execute_with_output_limit(&config, 5)
+            .await
+            .unwrap();
+        assert_eq!(result.stdout.as_deref(), Some("hello"));
+        assert!(result.output_truncated);
     }
 
     #[tokio::test]
@@ -245,12 +949,143 @@ mod tests {
         let executor = ShellExecutor::new();
         let config = TaskExecutorConfig::Shell(ShellConfig {
             command: "/nonexistent/command".to_string(),
-            args: vec![],
-            workdir: None,
-            env: None,
+            ..Default::default()
+        });
+
+        let err = executor.execute(&config).await.unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<PicoFlowError>(),
+            Some(PicoFlowError::TaskSpawn { task, .. }) if task == "/nonexistent/command"
+        ));
+    }
+
+    fn pwd_config(workdir: &str, create_workdir: bool) -> TaskExecutorConfig {
+        TaskExecutorConfig::Shell(ShellConfig {
+            command: "/bin/pwd".to_string(),
+            workdir: Some(workdir.to_string()),
+            create_workdir,
+            ..Default::default()
+        })
+    }
+
+    #[tokio::test]
+    async fn test_missing_workdir_is_validation_error() {
+        let dir = tempfile::tempdir().unwrap();
+        let missing = dir.path().join("missing");
+
+        let err = ShellExecutor::new()
+            .execute(&pwd_config(missing.to_str().unwrap(), false))
+            .await
+            .unwrap_err();
+        match err.downcast_ref::<PicoFlowError>() {
+            Some(PicoFlowError::Validation(message)) => {
+                assert!(message.contains("does not exist"), "{message}")
+            }
+            other => panic!("expected a validation error, got {other:?}"),
+        }
+        assert!(!missing.exists());
+    }
+
+    #[tokio::test]
+    async fn test_relative_workdir_escape_rejected() {
+        let err = ShellExecutor::new()
+            .execute(&pwd_config("../outside", true))
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<PicoFlowError>(),
+            Some(PicoFlowError::PathTraversal(_))
+        ));
+        assert!(!std::path::Path::new("../outside").exists());
+    }
+
+    #[tokio::test]
+    async fn test_create_workdir() {
+        let dir = tempfile::tempdir().unwrap();
+        let nested = dir.path().join("a/b/c");
+
+        let result = ShellExecutor::new()
+            .execute(&pwd_config(nested.to_str().unwrap(), true))
+            .await
+            .unwrap();
+        assert_eq!(result.status, TaskStatus::Success);
+        assert!(nested.is_dir());
+        assert_eq!(result.stdout.unwrap().trim(), nested.to_str().unwrap());
+    }
+
+    #[test]
+    fn test_parse_env_file_syntax() {
+        let content = r#"
+# database settings
+DB_HOST=localhost
+export DB_PORT = 5432
+PASSWORD="s3cr#t \"quoted\"\nline2"
+LITERAL='$HOME stays \n raw'
+TRAILING=value # inline comment
+EMPTY=
+"#;
+        let vars = parse_env_file(content).unwrap();
+        assert_eq!(
+            vars,
+            vec![
+                ("DB_HOST".to_string(), "localhost".to_string()),
+                ("DB_PORT".to_string(), "5432".to_string()),
+                (
+                    "PASSWORD".to_string(),
+                    "s3cr#t \"quoted\"\nline2".to_string()
+                ),
+                ("LITERAL".to_string(), "$HOME stays \\n raw".to_string()),
+                ("TRAILING".to_string(), "value".to_string()),
+                ("EMPTY".to_string(), String::new()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_env_file_malformed_lines() {
+        for (content, expected) in [
+            ("OK=1\nNOT_AN_ASSIGNMENT\n", "line 2: expected KEY=VALUE"),
+            ("1BAD=x", "line 1: invalid variable name '1BAD'"),
+            ("BAD-NAME=x", "line 1: invalid variable name 'BAD-NAME'"),
+            ("A=\"open", "line 1: unterminated double-quoted value"),
+            ("A='open", "line 1: unterminated single-quoted value"),
+        ] {
+            assert_eq!(parse_env_file(content).unwrap_err(), expected, "{content}");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_env_file_merged_under_inline_env() {
+        let dir = tempfile::tempdir().unwrap();
+        let env_file = dir.path().join("app.env");
+        std::fs::write(&env_file, "FROM_FILE=file\nSHARED=file\n").unwrap();
+
+        let mut env = HashMap::new();
+        env.insert("SHARED".to_string(), "inline".to_string());
+        let config = TaskExecutorConfig::Shell(ShellConfig {
+            command: "/bin/sh".to_string(),
+            args: vec!["-c".to_string(), "echo \"$FROM_FILE $SHARED\"".to_string()],
+            env: Some(env),
+            env_file: Some(env_file.to_str().unwrap().to_string()),
+            ..Default::default()
+        });
+
+        let result = ShellExecutor::new().execute(&config).await.unwrap();
+        assert_eq!(result.stdout.unwrap().trim(), "file inline");
+    }
+
+    #[tokio::test]
+    async fn test_missing_env_file() {
+        let config = TaskExecutorConfig::Shell(ShellConfig {
+            command: "/bin/true".to_string(),
+            env_file: Some("/nonexistent/picoflow.env".to_string()),
+            ..Default::default()
         });
 
-        let result = executor.execute(&config).await;
-        assert!(result.is_err());
+        let err = ShellExecutor::new().execute(&config).await.unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<PicoFlowError>(),
+            Some(PicoFlowError::Validation(message)) if message.contains("does not exist")
+        ));
     }
 }
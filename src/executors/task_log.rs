@@ -0,0 +1,104 @@
+//! Per-task log files that shell output is streamed to while the command runs
+//!
+//! With a task log directory configured, each line a shell task writes to stdout or stderr
+//! is appended to `<log_dir>/<execution_id>/<task>.log` as soon as it arrives, so
+//! long-running commands can be followed with `tail -f`. The database still only receives
+//! the (truncated) output once the task finishes.
+//!
+//! A file is capped at `max_bytes`: once a write would exceed it, the file is renamed to
+//! `<task>.log.1` (replacing an older one) and a new file is started, so a task never
+//! uses more than about twice the cap on disk.
+
+use std::fs::{File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use tracing::warn;
+
+/// Default size at which a task log file is rotated (10 MB)
+pub(crate) const MAX_TASK_LOG_BYTES: u64 = 10 * 1024 * 1024;
+
+/// Log file of one task, rotated when it grows past its size cap
+#[derive(Debug)]
+pub(crate) struct TaskLog {
+    path: PathBuf,
+    /// `None` after a write failed; the task keeps running without a log
+    file: Option<File>,
+    written: u64,
+    max_bytes: u64,
+}
+
+impl TaskLog {
+    /// Open (appending) the log file at `path`, creating its directory if needed
+    pub(crate) fn open(path: &Path, max_bytes: u64) -> io::Result<Self> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        let written = file.metadata()?.len();
+        Ok(Self {
+            path: path.to_path_buf(),
+            file: Some(file),
+            written,
+            max_bytes,
+        })
+    }
+
+    /// Append `bytes`, rotating first if they would push the file past its cap
+    ///
+    /// Errors are logged once and disable the log rather than failing the task.
+    pub(crate) fn write(&mut self, bytes: &[u8]) {
+        if self.file.is_none() {
+            return;
+        }
+        let result = self.rotate_if_full(bytes.len() as u64).and_then(|()| {
+            let file = self.file.as_mut().expect("checked above");
+            file.write_all(bytes)
+        });
+        match result {
+            Ok(()) => self.written += bytes.len() as u64,
+            Err(e) => {
+                warn!(
+                    "Failed to write task log {}, disabling it: {}",
+                    self.path.display(),
+                    e
+                );
+                self.file = None;
+            }
+        }
+    }
+
+    fn rotate_if_full(&mut self, incoming: u64) -> io::Result<()> {
+        if self.written == 0 || self.written + incoming <= self.max_bytes {
+            return Ok(());
+        }
+        let mut rotated = self.path.clone().into_os_string();
+        rotated.push(".1");
+        std::fs::rename(&self.path, rotated)?;
+        self.file = Some(File::create(&self.path)?);
+        self.written = 0;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_task_log_rotates_at_cap() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("1").join("build.log");
+
+        let mut log = TaskLog::open(&path, 20).unwrap();
+        log.write(b"line one\n");
+        log.write(b"line two\n");
+        log.write(b"three\n");
+
+        let rotated = dir.path().join("1").join("build.log.1");
+        assert_eq!(
+            std::fs::read_to_string(&rotated).unwrap(),
+            "line one\nline two\n"
+        );
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "three\n");
+    }
+}
@@ -0,0 +1,190 @@
+//! Ed25519 signature verification for workflow files
+//!
+//! `picoflow run --verify-sig <sig> --pubkey <key>` checks the workflow file against a
+//! detached signature before it is parsed, so a tampered file is never run. Signatures are
+//! produced over the raw file bytes, e.g. with
+//! `openssl pkeyutl -sign -inkey key.pem -rawin -in workflow.yaml -out workflow.yaml.sig`.
+//!
+//! Only the signed file itself is covered; files pulled in through `includes` are not.
+
+use crate::error::{PicoFlowError, Result};
+use openssl::pkey::{Id, PKey, Public};
+use openssl::sign::Verifier;
+use std::path::Path;
+
+/// Length of a raw Ed25519 public key
+const PUBLIC_KEY_LEN: usize = 32;
+
+/// Length of a raw Ed25519 signature
+const SIGNATURE_LEN: usize = 64;
+
+/// Verify an Ed25519 signature over `data`
+///
+/// `pubkey` may be a PEM or DER encoded public key (as written by
+/// `openssl pkey -pubout`), a raw 32-byte key, or the base64 encoding of one.
+/// `sig` may be the raw 64-byte signature or its base64 encoding.
+///
+/// # Errors
+///
+/// * `PicoFlowError::Validation` - If the key or signature is malformed, or the signature
+///   does not match `data`
+///
+/// # Example
+///
+/// ```no_run
+/// use picoflow::integrity::verify_signature;
+///
+/// let data = std::fs::read("workflow.yaml")?;
+/// let sig = std::fs::read("workflow.yaml.sig")?;
+/// let pubkey = std::fs::read("key.pub")?;
+/// verify_signature(&data, &sig, &pubkey)?;
+/// # Ok::<(), picoflow::error::PicoFlowError>(())
+/// ```
+pub fn verify_signature(data: &[u8], sig: &[u8], pubkey: &[u8]) -> Result<()> {
+    let key = parse_public_key(pubkey)?;
+    let sig = decode_fixed(sig, SIGNATURE_LEN).ok_or_else(|| {
+        PicoFlowError::Validation(format!(
+            "Signature must be {} raw bytes or their base64 encoding",
+            SIGNATURE_LEN
+        ))
+    })?;
+
+    let valid = Verifier::new_without_digest(&key)
+        .and_then(|mut verifier| verifier.verify_oneshot(&sig, data))
+        .map_err(|e| PicoFlowError::Validation(format!("Signature verification failed: {}", e)))?;
+    if !valid {
+        return Err(PicoFlowError::Validation(
+            "Signature does not match file contents".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Verify the file at `path` against the signature and public key files
+///
+/// Returns the verified contents. Parse those rather than reading `path` again, which
+/// could pick up a file swapped in after the check.
+///
+/// # Errors
+///
+/// * `PicoFlowError::Io` - If any of the files cannot be read
+/// * `PicoFlowError::Validation` - See [`verify_signature`]
+pub fn verify_file(path: &Path, sig_path: &Path, pubkey_path: &Path) -> Result<Vec<u8>> {
+    let data = std::fs::read(path)?;
+    let sig = std::fs::read(sig_path)?;
+    let pubkey = std::fs::read(pubkey_path)?;
+    verify_signature(&data, &sig, &pubkey)?;
+    Ok(data)
+}
+
+fn parse_public_key(pubkey: &[u8]) -> Result<PKey<Public>> {
+    let key = if pubkey.trim_ascii_start().starts_with(b"-----BEGIN") {
+        PKey::public_key_from_pem(pubkey)
+            .map_err(|e| PicoFlowError::Validation(format!("Invalid PEM public key: {}", e)))?
+    } else if let Some(raw) = decode_fixed(pubkey, PUBLIC_KEY_LEN) {
+        PKey::public_key_from_raw_bytes(&raw, Id::ED25519)
+            .map_err(|e| PicoFlowError::Validation(format!("Invalid public key: {}", e)))?
+    } else {
+        PKey::public_key_from_der(pubkey).map_err(|_| {
+            PicoFlowError::Validation(
+                "Public key must be PEM, DER, or 32 raw bytes (optionally base64)".to_string(),
+            )
+        })?
+    };
+
+    if key.id() != Id::ED25519 {
+        return Err(PicoFlowError::Validation(
+            "Public key is not an Ed25519 key".to_string(),
+        ));
+    }
+    Ok(key)
+}
+
+/// Accept `len` raw bytes, or base64 text decoding to `len` bytes
+fn decode_fixed(bytes: &[u8], len: usize) -> Option<Vec<u8>> {
+    if bytes.len() == len {
+        return Some(bytes.to_vec());
+    }
+    let text = std::str::from_utf8(bytes).ok()?.trim();
+    openssl::base64::decode_block(text)
+        .ok()
+        .filter(|decoded| decoded.len() == len)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use openssl::pkey::Private;
+    use openssl::sign::Signer;
+
+    // RFC 8032, section 7.1, test 1
+    const SECRET: &str = "9d61b19deffd5a60ba844af492ec2cc44449c5697b326919703bac031cae7f60";
+    const PUBLIC: &str = "d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a";
+    const EMPTY_SIG: &str = "e5564300c360ac729086e2cc806e828a84877f1eb8e5d974d873e065224901555fb8821590a33bacc61e39701cf9b46bd25bf5f0595bbe24655141438e7a100b";
+
+    fn hex(s: &str) -> Vec<u8> {
+        (0..s.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    fn private_key() -> PKey<Private> {
+        PKey::private_key_from_raw_bytes(&hex(SECRET), Id::ED25519).unwrap()
+    }
+
+    fn sign(data: &[u8]) -> Vec<u8> {
+        Signer::new_without_digest(&private_key())
+            .unwrap()
+            .sign_oneshot_to_vec(data)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_verify_signature_known_vector() {
+        verify_signature(b"", &hex(EMPTY_SIG), &hex(PUBLIC)).unwrap();
+        assert_eq!(sign(b""), hex(EMPTY_SIG));
+    }
+
+    #[test]
+    fn test_verify_signature_accepts_base64_and_pem() {
+        let data = b"name: signed\ntasks: []\n";
+        let sig = openssl::base64::encode_block(&sign(data));
+        let pem = private_key().public_key_to_pem().unwrap();
+        let raw_b64 = openssl::base64::encode_block(&hex(PUBLIC));
+
+        verify_signature(data, sig.as_bytes(), &pem).unwrap();
+        verify_signature(data, format!("{}\n", sig).as_bytes(), raw_b64.as_bytes()).unwrap();
+    }
+
+    #[test]
+    fn test_verify_signature_rejects_tampered_data() {
+        let data = b"name: signed\ntasks: []\n";
+        let sig = sign(data);
+
+        let err = verify_signature(b"name: signed\ntasks: [x]\n", &sig, &hex(PUBLIC))
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("does not match"), "{}", err);
+
+        let other = PKey::generate_ed25519().unwrap().raw_public_key().unwrap();
+        assert!(verify_signature(data, &sig, &other).is_err());
+        assert!(verify_signature(data, &sig[..10], &hex(PUBLIC)).is_err());
+    }
+
+    #[test]
+    fn test_verify_file_rejects_tampered_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let workflow = dir.path().join("workflow.yaml");
+        let sig = dir.path().join("workflow.yaml.sig");
+        let key = dir.path().join("key.pub");
+        std::fs::write(&workflow, "name: signed\n").unwrap();
+        std::fs::write(&sig, sign(b"name: signed\n")).unwrap();
+        std::fs::write(&key, hex(PUBLIC)).unwrap();
+
+        verify_file(&workflow, &sig, &key).unwrap();
+
+        std::fs::write(&workflow, "name: tampered\n").unwrap();
+        assert!(verify_file(&workflow, &sig, &key).is_err());
+    }
+}
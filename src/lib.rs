@@ -1,12 +1,16 @@
 //! PicoFlow - Lightweight DAG workflow orchestrator for edge devices
 
 pub mod cli;
+pub mod control;
 pub mod cron_scheduler;
 pub mod daemon;
 pub mod dag;
+pub mod disk;
 pub mod error;
 pub mod executors;
+pub mod integrity;
 pub mod logging;
+pub mod memory;
 /// Prometheus metrics endpoint. Behind the optional `metrics` feature (off by default)
 /// so the edge binary doesn't pay for it unless explicitly enabled.
 #[cfg(feature = "metrics")]
@@ -16,4 +20,8 @@ pub mod parser;
 pub mod retry;
 pub mod scheduler;
 pub mod state;
+pub mod substitution;
 pub mod templates;
+/// `run --watch` support. Behind the optional `watch` feature (off by default).
+#[cfg(feature = "watch")]
+pub mod watch;
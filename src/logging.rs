@@ -1,11 +1,14 @@
 //! Structured logging configuration using tracing
 
-use tracing::Level;
+use tracing::{info, Level, Subscriber};
+use tracing_subscriber::filter::LevelFilter;
 use tracing_subscriber::fmt::format::FmtSpan;
-use tracing_subscriber::FmtSubscriber;
+use tracing_subscriber::fmt::MakeWriter;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::{reload, Registry};
 
 /// Log level configuration
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum LogLevel {
     Error,
     Warn,
@@ -42,8 +45,19 @@ impl From<&str> for LogLevel {
 /// Log format configuration
 #[derive(Debug, Clone, Copy)]
 pub enum LogFormat {
+    /// Human-readable lines
+    Text,
+    /// One JSON object per line, for log collectors
     Json,
-    Pretty,
+}
+
+impl From<&str> for LogFormat {
+    fn from(s: &str) -> Self {
+        match s.to_lowercase().as_str() {
+            "json" => LogFormat::Json,
+            _ => LogFormat::Text, // "text", and "pretty" from older configs
+        }
+    }
 }
 
 /// Logging configuration
@@ -57,43 +71,124 @@ impl Default for LogConfig {
     fn default() -> Self {
         Self {
             level: LogLevel::Info,
-            format: LogFormat::Json,
+            format: LogFormat::Text,
         }
     }
 }
 
-/// Initialize logging with the given configuration
-pub fn init_logging(config: &LogConfig) -> anyhow::Result<()> {
-    let level: Level = config.level.into();
+/// Handle for changing the log level of the running process
+///
+/// Returned by [`init_logging`]; the daemon uses it to raise verbosity on SIGUSR1 without
+/// a restart.
+#[derive(Debug, Clone)]
+pub struct LogLevelHandle(reload::Handle<LevelFilter, Registry>);
 
-    match config.format {
-        LogFormat::Json => {
-            let subscriber = FmtSubscriber::builder()
-                .json()
-                .with_max_level(level)
-                .with_span_events(FmtSpan::CLOSE) // Only log on span close
-                .with_writer(std::io::stderr) // Write to stderr, no buffering
-                .finish();
+impl LogLevelHandle {
+    /// Currently active maximum level (`None` if logging is off)
+    pub fn level(&self) -> anyhow::Result<Option<Level>> {
+        Ok(self.0.with_current(|filter| filter.into_level())?)
+    }
 
-            tracing::subscriber::set_global_default(subscriber)?;
-        }
-        LogFormat::Pretty => {
-            let subscriber = FmtSubscriber::builder()
-                .with_max_level(level)
-                .with_span_events(FmtSpan::CLOSE)
-                .with_writer(std::io::stderr)
-                .finish();
-
-            tracing::subscriber::set_global_default(subscriber)?;
-        }
+    /// Replace the active maximum level
+    pub fn set_level(&self, level: Level) -> anyhow::Result<()> {
+        self.0.reload(LevelFilter::from_level(level))?;
+        Ok(())
     }
 
-    Ok(())
+    /// Step to the next, more verbose level: info → debug → trace → info
+    ///
+    /// Levels quieter than info (error, warn) step to debug. Returns the new level.
+    pub fn cycle(&self) -> anyhow::Result<Level> {
+        let next = match self.level()? {
+            Some(Level::DEBUG) => Level::TRACE,
+            Some(Level::TRACE) => Level::INFO,
+            _ => Level::DEBUG,
+        };
+        self.set_level(next)?;
+        info!("Log level set to {}", next);
+        Ok(next)
+    }
+}
+
+/// Initialize logging with the given configuration
+///
+/// Returns a handle that changes the log level after initialization.
+pub fn init_logging(config: &LogConfig) -> anyhow::Result<LogLevelHandle> {
+    let (subscriber, handle) = build_subscriber(config, std::io::stderr);
+    tracing::subscriber::set_global_default(subscriber)?;
+    Ok(handle)
+}
+
+/// Build the subscriber for `config` writing to `writer` (stderr, no buffering, in
+/// production), behind a reloadable level filter.
+///
+/// JSON lines carry `timestamp`, `level`, `target`, the event's structured `fields`
+/// (e.g. `workflow`, `task`), the innermost `span` and the full `spans` list.
+fn build_subscriber<W>(
+    config: &LogConfig,
+    writer: W,
+) -> (Box<dyn Subscriber + Send + Sync>, LogLevelHandle)
+where
+    W: for<'w> MakeWriter<'w> + Send + Sync + 'static,
+{
+    let level: Level = config.level.into();
+    let (filter, handle) = reload::Layer::new(LevelFilter::from_level(level));
+    let registry = tracing_subscriber::registry().with(filter);
+
+    let subscriber: Box<dyn Subscriber + Send + Sync> = match config.format {
+        LogFormat::Json => Box::new(
+            registry.with(
+                tracing_subscriber::fmt::layer()
+                    .json()
+                    .with_target(true)
+                    .with_current_span(true)
+                    .with_span_list(true)
+                    .with_span_events(FmtSpan::CLOSE) // Only log on span close
+                    .with_writer(writer),
+            ),
+        ),
+        LogFormat::Text => Box::new(
+            registry.with(
+                tracing_subscriber::fmt::layer()
+                    .with_span_events(FmtSpan::CLOSE)
+                    .with_writer(writer),
+            ),
+        ),
+    };
+
+    (subscriber, LogLevelHandle(handle))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::io::Write;
+    use std::sync::{Arc, Mutex};
+
+    /// Log writer that keeps everything written to it in memory
+    #[derive(Clone, Default)]
+    struct Capture(Arc<Mutex<Vec<u8>>>);
+
+    impl Capture {
+        /// A `MakeWriter` handing out clones that share this buffer
+        fn writer(&self) -> impl Fn() -> Capture + Send + Sync + 'static {
+            let capture = self.clone();
+            move || capture.clone()
+        }
+
+        fn output(&self) -> String {
+            String::from_utf8(self.0.lock().unwrap().clone()).unwrap()
+        }
+    }
+
+    impl Write for Capture {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
 
     #[test]
     fn test_log_level_from_str() {
@@ -127,6 +222,73 @@ mod tests {
     fn test_default_log_config() {
         let config = LogConfig::default();
         assert!(matches!(config.level, LogLevel::Info));
-        assert!(matches!(config.format, LogFormat::Json));
+        assert!(matches!(config.format, LogFormat::Text));
+    }
+
+    #[test]
+    fn test_log_format_from_str() {
+        assert!(matches!(LogFormat::from("json"), LogFormat::Json));
+        assert!(matches!(LogFormat::from("text"), LogFormat::Text));
+        assert!(matches!(LogFormat::from("pretty"), LogFormat::Text));
+    }
+
+    #[test]
+    fn test_json_subscriber_emits_json_lines() {
+        let capture = Capture::default();
+        let config = LogConfig {
+            level: LogLevel::Info,
+            format: LogFormat::Json,
+        };
+        let (subscriber, _) = build_subscriber(&config, capture.writer());
+
+        tracing::subscriber::with_default(subscriber, || {
+            let span = tracing::info_span!("run", execution_id = 7);
+            let _guard = span.enter();
+            tracing::info!(workflow = %"backup", task = "dump", "Executing task");
+            tracing::debug!("filtered out by level");
+        });
+
+        let output = capture.output();
+        let lines: Vec<serde_json::Value> = output
+            .lines()
+            .map(|line| serde_json::from_str(line).expect("every line is JSON"))
+            .collect();
+
+        // The event, then the span-close event
+        assert_eq!(lines.len(), 2, "{output}");
+        let event = &lines[0];
+        assert!(event["timestamp"].is_string());
+        assert_eq!(event["level"], "INFO");
+        assert_eq!(event["target"], "picoflow::logging::tests");
+        assert_eq!(event["fields"]["workflow"], "backup");
+        assert_eq!(event["fields"]["task"], "dump");
+        assert_eq!(event["span"]["execution_id"], 7);
+        assert_eq!(event["spans"][0]["name"], "run");
+    }
+
+    #[test]
+    fn test_log_level_handle_cycles_active_filter() {
+        let capture = Capture::default();
+        let (subscriber, handle) = build_subscriber(&LogConfig::default(), capture.writer());
+
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::debug!("debug-1");
+            assert_eq!(handle.cycle().unwrap(), Level::DEBUG);
+            tracing::debug!("debug-2");
+            tracing::trace!("trace-1");
+            assert_eq!(handle.cycle().unwrap(), Level::TRACE);
+            tracing::trace!("trace-2");
+            assert_eq!(handle.cycle().unwrap(), Level::INFO);
+            tracing::debug!("debug-3");
+            assert_eq!(handle.level().unwrap(), Some(Level::INFO));
+        });
+
+        let output = capture.output();
+        for emitted in ["debug-2", "trace-2"] {
+            assert!(output.contains(emitted), "{emitted} missing: {output}");
+        }
+        for filtered in ["debug-1", "trace-1", "debug-3"] {
+            assert!(!output.contains(filtered), "{filtered} emitted: {output}");
+        }
     }
 }
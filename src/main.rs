@@ -1,4 +1,3 @@
-use clap::Parser;
 use picoflow::cli::Cli;
 
 #[tokio::main]
@@ -12,7 +11,7 @@ async fn main() -> anyhow::Result<()> {
     }
 
     // Parse CLI arguments
-    let cli = Cli::parse();
+    let mut cli = Cli::parse_args(std::env::args_os());
 
     // Initialize logging
     cli.init_logging()?;
@@ -0,0 +1,88 @@
+//! Process memory measurement
+//!
+//! Shared by the `/metrics` memory gauge and the scheduler's `memory_limit_mb`
+//! backpressure.
+
+use crate::error::{PicoFlowError, Result};
+
+/// Resident set size of this process in bytes
+///
+/// On Linux this is the current RSS from `/proc/self/statm`, falling back to the peak
+/// RSS reported by `getrusage` if procfs is unavailable. On macOS it is the peak RSS.
+///
+/// # Errors
+///
+/// * `PicoFlowError::Other` - If memory usage cannot be determined on this platform
+#[cfg(target_os = "linux")]
+pub fn process_memory_bytes() -> Result<u64> {
+    if let Some(rss) = statm_rss_bytes() {
+        return Ok(rss);
+    }
+    // ru_maxrss is in kilobytes on Linux
+    peak_rss().map(|kb| kb * 1024)
+}
+
+/// Resident set size of this process in bytes
+///
+/// On Linux this is the current RSS from `/proc/self/statm`, falling back to the peak
+/// RSS reported by `getrusage` if procfs is unavailable. On macOS it is the peak RSS.
+///
+/// # Errors
+///
+/// * `PicoFlowError::Other` - If memory usage cannot be determined on this platform
+#[cfg(target_os = "macos")]
+pub fn process_memory_bytes() -> Result<u64> {
+    // ru_maxrss is in bytes on macOS
+    peak_rss()
+}
+
+/// Process memory usage (unsupported platform)
+///
+/// # Errors
+///
+/// * `PicoFlowError::Other` - Always
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+pub fn process_memory_bytes() -> Result<u64> {
+    Err(PicoFlowError::Other(
+        "Memory usage tracking not supported on this platform".to_string(),
+    ))
+}
+
+/// Current RSS from the second field of `/proc/self/statm` (in pages)
+#[cfg(target_os = "linux")]
+fn statm_rss_bytes() -> Option<u64> {
+    let statm = std::fs::read_to_string("/proc/self/statm").ok()?;
+    let pages: u64 = statm.split_whitespace().nth(1)?.parse().ok()?;
+    // SAFETY: sysconf has no preconditions
+    let page_size = unsafe { libc::sysconf(libc::_SC_PAGESIZE) };
+    u64::try_from(page_size).ok().map(|size| pages * size)
+}
+
+/// Peak RSS from `getrusage`, in the platform's native unit
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+fn peak_rss() -> Result<u64> {
+    // SAFETY: rusage is plain data, and getrusage only writes into it
+    unsafe {
+        let mut info: libc::rusage = std::mem::zeroed();
+        if libc::getrusage(libc::RUSAGE_SELF, &mut info) == 0 {
+            Ok(info.ru_maxrss as u64)
+        } else {
+            Err(PicoFlowError::Other(
+                "Failed to get memory usage".to_string(),
+            ))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(any(target_os = "linux", target_os = "macos"))]
+    #[test]
+    fn test_process_memory_bytes() {
+        let memory = process_memory_bytes().unwrap();
+        // Anything running a test harness uses more than a megabyte
+        assert!(memory > 1024 * 1024, "{memory}");
+    }
+}
@@ -6,12 +6,19 @@
 //! # Available Metrics
 //!
 //! - `picoflow_workflow_executions_total{workflow, status}` - Counter of workflow executions
+//! - `picoflow_workflow_success_ratio{workflow}` - Gauge of successful / total executions
 //! - `picoflow_task_executions_total{workflow, task, status}` - Counter of task executions
 //! - `picoflow_task_duration_seconds{workflow, task}` - Histogram of task durations
+//! - `picoflow_task_output_bytes{workflow, task}` - Histogram of captured stdout+stderr sizes
 //! - `picoflow_active_workflows` - Gauge of currently running workflows
 //! - `picoflow_active_tasks` - Gauge of currently running tasks
 //! - `picoflow_memory_bytes` - Gauge of process memory usage (RSS)
 //!
+//! # Pushgateway
+//!
+//! Devices that cannot be scraped (e.g. behind NAT) can push the same metrics to a
+//! Prometheus Pushgateway with [`MetricsServer::push_to_gateway`].
+//!
 //! # Performance
 //!
 //! Target: <5MB additional memory overhead (PRD Phase 3)
@@ -25,8 +32,8 @@
 //! # async fn example() -> anyhow::Result<()> {
 //! let metrics = Arc::new(MetricsServer::new());
 //!
-//! // Start metrics server on port 9090
-//! metrics.start(9090).await?;
+//! // Start metrics server on port 9090, or the next free port up to 9099
+//! metrics.start_in_range(9090..=9099).await?;
 //!
 //! // Record workflow execution
 //! metrics.record_workflow_execution("my-workflow", "success");
@@ -38,22 +45,37 @@
 //! ```
 
 use prometheus::{
-    CounterVec, Encoder, Gauge, HistogramOpts, HistogramVec, Opts, Registry, TextEncoder,
+    CounterVec, Encoder, Gauge, GaugeVec, HistogramOpts, HistogramVec, Opts, Registry, TextEncoder,
 };
-use std::sync::Arc;
+use std::collections::HashMap;
+use std::ops::RangeInclusive;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 use tokio::net::TcpListener;
-use tracing::{error, info};
+use tracing::{debug, error, info, warn};
 
 /// Histogram bucket boundaries for task duration metrics (in seconds)
 const TASK_DURATION_BUCKETS: &[f64] = &[0.1, 0.5, 1.0, 2.0, 5.0, 10.0, 30.0, 60.0, 300.0];
 
+/// Histogram bucket boundaries for task output size metrics (in bytes, 1KB to 10MB)
+const TASK_OUTPUT_BYTES_BUCKETS: &[f64] = &[
+    1024.0, 4096.0, 16384.0, 65536.0, 262144.0, 1048576.0, 4194304.0, 10485760.0,
+];
+
+/// Timeout for a single Pushgateway request
+const PUSH_TIMEOUT_SECONDS: u64 = 10;
+
 /// Prometheus metrics server
 #[derive(Clone)]
 pub struct MetricsServer {
     registry: Arc<Registry>,
     workflow_executions: Arc<CounterVec>,
+    workflow_success_ratio: Arc<GaugeVec>,
+    /// Per-workflow (success, total) execution tallies backing `workflow_success_ratio`
+    workflow_tallies: Arc<Mutex<HashMap<String, (u64, u64)>>>,
     task_executions: Arc<CounterVec>,
     task_duration: Arc<HistogramVec>,
+    task_output_bytes: Arc<HistogramVec>,
     active_workflows: Arc<Gauge>,
     active_tasks: Arc<Gauge>,
     memory_bytes: Arc<Gauge>,
@@ -75,6 +97,16 @@ impl MetricsServer {
         )
         .unwrap();
 
+        // Workflow success ratio gauge
+        let workflow_success_ratio = GaugeVec::new(
+            Opts::new(
+                "picoflow_workflow_success_ratio",
+                "Ratio of successful to total workflow executions",
+            ),
+            &["workflow"],
+        )
+        .unwrap();
+
         // Task execution counter
         let task_executions = CounterVec::new(
             Opts::new(
@@ -96,6 +128,17 @@ impl MetricsServer {
         )
         .unwrap();
 
+        // Task output size histogram
+        let task_output_bytes = HistogramVec::new(
+            HistogramOpts::new(
+                "picoflow_task_output_bytes",
+                "Captured task output (stdout + stderr) in bytes",
+            )
+            .buckets(TASK_OUTPUT_BYTES_BUCKETS.to_vec()),
+            &["workflow", "task"],
+        )
+        .unwrap();
+
         // Active workflows gauge
         let active_workflows =
             Gauge::with_opts(Opts::new("picoflow_active_workflows", "Active workflows")).unwrap();
@@ -115,10 +158,16 @@ impl MetricsServer {
         registry
             .register(Box::new(workflow_executions.clone()))
             .unwrap();
+        registry
+            .register(Box::new(workflow_success_ratio.clone()))
+            .unwrap();
         registry
             .register(Box::new(task_executions.clone()))
             .unwrap();
         registry.register(Box::new(task_duration.clone())).unwrap();
+        registry
+            .register(Box::new(task_output_bytes.clone()))
+            .unwrap();
         registry
             .register(Box::new(active_workflows.clone()))
             .unwrap();
@@ -128,8 +177,11 @@ impl MetricsServer {
         Self {
             registry: Arc::new(registry),
             workflow_executions: Arc::new(workflow_executions),
+            workflow_success_ratio: Arc::new(workflow_success_ratio),
+            workflow_tallies: Arc::new(Mutex::new(HashMap::new())),
             task_executions: Arc::new(task_executions),
             task_duration: Arc::new(task_duration),
+            task_output_bytes: Arc::new(task_output_bytes),
             active_workflows: Arc::new(active_workflows),
             active_tasks: Arc::new(active_tasks),
             memory_bytes: Arc::new(memory_bytes),
@@ -150,29 +202,81 @@ impl MetricsServer {
 
     /// Start the HTTP metrics server on the specified port
     ///
-    /// The server exposes `/metrics` endpoint in Prometheus text format.
+    /// The server exposes `/metrics` endpoint in Prometheus text format. Port 0 lets the
+    /// OS pick a free port.
     ///
     /// # Arguments
     ///
     /// * `port` - TCP port to listen on (default: 9090)
     ///
+    /// # Returns
+    ///
+    /// The port actually bound
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the port cannot be bound (e.g. it is already in use)
+    ///
     /// # Example
     ///
     /// ```no_run
     /// # use picoflow::metrics::MetricsServer;
     /// # async fn example() -> anyhow::Result<()> {
     /// let metrics = MetricsServer::new();
-    /// metrics.start(9090).await?;
+    /// let port = metrics.start(0).await?;
+    /// println!("metrics on port {}", port);
     /// # Ok(())
     /// # }
     /// ```
-    pub async fn start(&self, port: u16) -> anyhow::Result<()> {
-        let addr = format!("127.0.0.1:{}", port);
-        let listener = TcpListener::bind(&addr).await?;
+    pub async fn start(&self, port: u16) -> anyhow::Result<u16> {
+        let listener = TcpListener::bind(("127.0.0.1", port)).await?;
+        Ok(self.serve(listener)?)
+    }
+
+    /// Start the metrics server on the first port in `ports` that can be bound
+    ///
+    /// # Errors
+    ///
+    /// Returns the bind error of the last port tried if none is free
+    pub async fn start_in_range(&self, ports: RangeInclusive<u16>) -> anyhow::Result<u16> {
+        let mut last_error = None;
+        for port in ports {
+            match TcpListener::bind(("127.0.0.1", port)).await {
+                Ok(listener) => return Ok(self.serve(listener)?),
+                Err(e) => {
+                    debug!("Metrics port {} unavailable: {}", port, e);
+                    last_error = Some(e);
+                }
+            }
+        }
+        Err(last_error
+            .map(anyhow::Error::from)
+            .unwrap_or_else(|| anyhow::anyhow!("empty metrics port range")))
+    }
+
+    /// Like [`MetricsServer::start`], but a port that cannot be bound is logged rather than
+    /// returned as an error, so metrics never stop the daemon from starting
+    ///
+    /// Returns the bound port, or `None` if the server is not running.
+    pub async fn start_best_effort(&self, port: u16) -> Option<u16> {
+        match self.start(port).await {
+            Ok(port) => Some(port),
+            Err(e) => {
+                warn!(
+                    "Metrics server not started, port {} unavailable: {}",
+                    port, e
+                );
+                None
+            }
+        }
+    }
+
+    /// Serve `/metrics` on `listener` in the background, returning its port
+    fn serve(&self, listener: TcpListener) -> std::io::Result<u16> {
+        let addr = listener.local_addr()?;
         info!("Metrics server listening on http://{}/metrics", addr);
 
-        let registry = self.registry.clone();
-        let memory_bytes = self.memory_bytes.clone();
+        let server = self.clone();
 
         // Env var takes precedence over the token passed via with_token()
         let auth_token = std::env::var("PICOFLOW_METRICS_TOKEN")
@@ -183,15 +287,11 @@ impl MetricsServer {
             loop {
                 match listener.accept().await {
                     Ok((stream, _addr)) => {
-                        let registry = registry.clone();
-                        let memory_bytes = memory_bytes.clone();
+                        let server = server.clone();
                         let auth_token = auth_token.clone();
 
                         tokio::spawn(async move {
-                            if let Err(e) =
-                                Self::handle_request(stream, registry, memory_bytes, auth_token)
-                                    .await
-                            {
+                            if let Err(e) = server.handle_request(stream, auth_token).await {
                                 error!("Error handling metrics request: {}", e);
                             }
                         });
@@ -203,7 +303,7 @@ impl MetricsServer {
             }
         });
 
-        Ok(())
+        Ok(addr.port())
     }
 
     /// Extract bearer token from HTTP request headers
@@ -218,9 +318,8 @@ impl MetricsServer {
 
     /// Handle incoming HTTP request
     async fn handle_request(
+        &self,
         mut stream: tokio::net::TcpStream,
-        registry: Arc<Registry>,
-        memory_bytes: Arc<Gauge>,
         auth_token: Option<String>,
     ) -> anyhow::Result<()> {
         use tokio::io::{AsyncReadExt, AsyncWriteExt};
@@ -266,18 +365,15 @@ impl MetricsServer {
             }
         }
 
-        // Parse HTTP request (simple parser for GET /metrics)
+        // Parse HTTP request (simple parser for GET /metrics and POST /reset)
         if request.starts_with("GET /metrics") {
             // Update memory usage before exporting
-            if let Ok(memory) = Self::get_memory_usage() {
-                memory_bytes.set(memory as f64);
+            if let Ok(memory) = crate::memory::process_memory_bytes() {
+                self.memory_bytes.set(memory as f64);
             }
 
             // Gather metrics
-            let encoder = TextEncoder::new();
-            let metric_families = registry.gather();
-            let mut buffer = Vec::new();
-            encoder.encode(&metric_families, &mut buffer)?;
+            let buffer = Self::encode_registry(&self.registry)?;
 
             // Send HTTP response
             let response = format!(
@@ -286,6 +382,16 @@ impl MetricsServer {
                 String::from_utf8_lossy(&buffer)
             );
 
+            stream.write_all(response.as_bytes()).await?;
+        } else if request.starts_with("POST /reset") {
+            // Resetting is destructive, so it is never allowed on an unauthenticated endpoint
+            let response = if auth_token.is_some() {
+                self.reset();
+                info!("Metrics reset via HTTP");
+                "HTTP/1.1 200 OK\r\nContent-Length: 5\r\n\r\nReset"
+            } else {
+                "HTTP/1.1 403 Forbidden\r\nContent-Length: 9\r\n\r\nForbidden"
+            };
             stream.write_all(response.as_bytes()).await?;
         } else {
             // 404 for other paths
@@ -296,44 +402,81 @@ impl MetricsServer {
         Ok(())
     }
 
-    /// Get process memory usage in bytes (RSS)
-    #[cfg(target_os = "macos")]
-    fn get_memory_usage() -> anyhow::Result<u64> {
-        use std::mem;
+    /// Encode every metric in the registry in Prometheus text format
+    fn encode_registry(registry: &Registry) -> anyhow::Result<Vec<u8>> {
+        let encoder = TextEncoder::new();
+        let metric_families = registry.gather();
+        let mut buffer = Vec::new();
+        encoder.encode(&metric_families, &mut buffer)?;
+        Ok(buffer)
+    }
 
-        unsafe {
-            let mut info: libc::rusage = mem::zeroed();
-            if libc::getrusage(libc::RUSAGE_SELF, &mut info) == 0 {
-                // ru_maxrss is in bytes on macOS
-                Ok(info.ru_maxrss as u64)
-            } else {
-                Err(anyhow::anyhow!("Failed to get memory usage"))
-            }
+    /// Push all metrics to a Prometheus Pushgateway
+    ///
+    /// POSTs the registry in text format to `<url>/metrics/job/<job>`, replacing any
+    /// previously pushed metrics with the same names in that group.
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - Base URL of the Pushgateway (e.g. `http://pushgateway:9091`)
+    /// * `job` - Job label used for the grouping key
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the URL is invalid, `job` is empty, the request fails, or the
+    /// Pushgateway responds with a non-2xx status.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use picoflow::metrics::MetricsServer;
+    /// # async fn example() -> anyhow::Result<()> {
+    /// let metrics = MetricsServer::new();
+    /// metrics.push_to_gateway("http://pushgateway:9091", "edge-device-1").await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn push_to_gateway(&self, url: &str, job: &str) -> anyhow::Result<()> {
+        if job.is_empty() {
+            return Err(anyhow::anyhow!("Pushgateway job name must not be empty"));
         }
-    }
 
-    /// Get process memory usage in bytes (RSS)
-    #[cfg(target_os = "linux")]
-    fn get_memory_usage() -> anyhow::Result<u64> {
-        use std::mem;
+        // Build the grouping-key path via path segments so `job` is percent-encoded
+        let mut push_url = url::Url::parse(url)?;
+        push_url
+            .path_segments_mut()
+            .map_err(|_| anyhow::anyhow!("Invalid Pushgateway URL: {}", url))?
+            .pop_if_empty()
+            .extend(["metrics", "job", job]);
 
-        unsafe {
-            let mut info: libc::rusage = mem::zeroed();
-            if libc::getrusage(libc::RUSAGE_SELF, &mut info) == 0 {
-                // ru_maxrss is in kilobytes on Linux
-                Ok((info.ru_maxrss as u64) * 1024)
-            } else {
-                Err(anyhow::anyhow!("Failed to get memory usage"))
-            }
+        if let Ok(memory) = crate::memory::process_memory_bytes() {
+            self.memory_bytes.set(memory as f64);
+        }
+        let body = Self::encode_registry(&self.registry)?;
+
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(PUSH_TIMEOUT_SECONDS))
+            .build()?;
+        let response = client
+            .post(push_url.clone())
+            .header("Content-Type", TextEncoder::new().format_type())
+            .body(body)
+            .send()
+            .await?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let text = response.text().await.unwrap_or_default();
+            return Err(anyhow::anyhow!(
+                "Pushgateway {} returned {}: {}",
+                push_url,
+                status,
+                text.trim()
+            ));
         }
-    }
 
-    /// Get process memory usage (fallback for unsupported platforms)
-    #[cfg(not(any(target_os = "linux", target_os = "macos")))]
-    fn get_memory_usage() -> anyhow::Result<u64> {
-        Err(anyhow::anyhow!(
-            "Memory usage tracking not supported on this platform"
-        ))
+        info!("Pushed metrics to {}", push_url);
+        Ok(())
     }
 
     /// Record a workflow execution
@@ -346,6 +489,33 @@ impl MetricsServer {
         self.workflow_executions
             .with_label_values(&[workflow, status])
             .inc();
+
+        let mut tallies = self
+            .workflow_tallies
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let (success, total) = tallies.entry(workflow.to_string()).or_insert((0, 0));
+        *total += 1;
+        if status == "success" {
+            *success += 1;
+        }
+        self.workflow_success_ratio
+            .with_label_values(&[workflow])
+            .set(*success as f64 / *total as f64);
+    }
+
+    /// Current success ratio (successful / total executions) of a workflow
+    ///
+    /// Returns `None` if no execution of the workflow has been recorded.
+    pub fn workflow_success_ratio(&self, workflow: &str) -> Option<f64> {
+        let tallies = self
+            .workflow_tallies
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        tallies
+            .get(workflow)
+            .filter(|(_, total)| *total > 0)
+            .map(|(success, total)| *success as f64 / *total as f64)
     }
 
     /// Record a task execution
@@ -372,6 +542,42 @@ impl MetricsServer {
             .observe(duration_secs);
     }
 
+    /// Record the size of a task attempt's captured output
+    ///
+    /// # Arguments
+    ///
+    /// * `workflow` - Workflow name
+    /// * `task` - Task name
+    /// * `bytes` - Combined length of the captured stdout and stderr
+    pub fn record_task_output_size(&self, workflow: &str, task: &str, bytes: usize) {
+        self.task_output_bytes
+            .with_label_values(&[workflow, task])
+            .observe(bytes as f64);
+    }
+
+    /// Reset all execution counters, the success ratio and the duration and output size
+    /// histograms
+    ///
+    /// Every labelled series is dropped from the existing vectors, so the registry and a
+    /// running `/metrics` endpoint keep working and the next scrape shows only samples
+    /// recorded after the reset. The active-workflow/task and memory gauges reflect live
+    /// state and are left untouched.
+    ///
+    /// Resetting counters is generally discouraged: Prometheus handles a counter drop as
+    /// a process restart, and any increments since the last scrape are lost. Prefer
+    /// `rate()`/`increase()` over the raw totals instead of resetting them.
+    pub fn reset(&self) {
+        self.workflow_executions.reset();
+        self.workflow_success_ratio.reset();
+        self.task_executions.reset();
+        self.task_duration.reset();
+        self.task_output_bytes.reset();
+        self.workflow_tallies
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .clear();
+    }
+
     /// Increment active workflows counter
     pub fn inc_active_workflows(&self) {
         self.active_workflows.inc();
@@ -437,6 +643,28 @@ mod tests {
         // Actual metric gathering requires proto dependencies
     }
 
+    #[test]
+    fn test_workflow_success_ratio() {
+        let metrics = MetricsServer::new();
+        assert_eq!(metrics.workflow_success_ratio("backup"), None);
+
+        metrics.record_workflow_execution("backup", "success");
+        metrics.record_workflow_execution("backup", "failed");
+        metrics.record_workflow_execution("backup", "success");
+        metrics.record_workflow_execution("other", "failed");
+
+        let ratio = metrics.workflow_success_ratio("backup").unwrap();
+        assert!((ratio - 2.0 / 3.0).abs() < 1e-9);
+        assert_eq!(metrics.workflow_success_ratio("other"), Some(0.0));
+
+        // The exported gauge matches the tallies
+        let gauge = metrics
+            .workflow_success_ratio
+            .with_label_values(&["backup"])
+            .get();
+        assert!((gauge - 2.0 / 3.0).abs() < 1e-9);
+    }
+
     #[test]
     fn test_record_task_execution() {
         let metrics = MetricsServer::new();
@@ -445,6 +673,26 @@ mod tests {
         // Just verify metrics can be recorded without error
     }
 
+    #[test]
+    fn test_record_task_output_size() {
+        let metrics = MetricsServer::new();
+        metrics.record_task_output_size("backup", "dump", 5000);
+
+        let output =
+            String::from_utf8(MetricsServer::encode_registry(&metrics.registry).unwrap()).unwrap();
+        assert!(
+            output.contains(
+                r#"picoflow_task_output_bytes_bucket{task="dump",workflow="backup",le="4096"} 0"#
+            ),
+            "{output}"
+        );
+        assert!(output.contains(
+            r#"picoflow_task_output_bytes_bucket{task="dump",workflow="backup",le="16384"} 1"#
+        ));
+        assert!(output
+            .contains(r#"picoflow_task_output_bytes_sum{task="dump",workflow="backup"} 5000"#));
+    }
+
     #[test]
     fn test_active_counters() {
         let metrics = MetricsServer::new();
@@ -492,11 +740,105 @@ mod tests {
         assert_eq!(MetricsServer::extract_bearer_token(request), None);
     }
 
-    #[cfg(any(target_os = "linux", target_os = "macos"))]
     #[test]
-    fn test_memory_usage() {
-        let memory = MetricsServer::get_memory_usage();
-        assert!(memory.is_ok());
-        assert!(memory.unwrap() > 0);
+    fn test_reset_drops_recorded_samples() {
+        let metrics = MetricsServer::new();
+        metrics.record_workflow_execution("backup", "success");
+        metrics.record_task_execution("backup", "dump", "success", 0.3);
+
+        let before =
+            String::from_utf8(MetricsServer::encode_registry(&metrics.registry).unwrap()).unwrap();
+        assert!(before.contains(r#"workflow="backup""#));
+
+        metrics.reset();
+
+        let after =
+            String::from_utf8(MetricsServer::encode_registry(&metrics.registry).unwrap()).unwrap();
+        assert!(!after.contains(r#"workflow="backup""#), "{after}");
+        assert_eq!(metrics.workflow_success_ratio("backup"), None);
+
+        // Recording keeps working against the same registry
+        metrics.record_workflow_execution("backup", "failed");
+        assert_eq!(metrics.workflow_success_ratio("backup"), Some(0.0));
+    }
+
+    #[tokio::test]
+    async fn test_push_to_gateway() {
+        use wiremock::matchers::{body_string_contains, method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/metrics/job/edge-1"))
+            .and(body_string_contains("picoflow_workflow_executions_total"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let metrics = MetricsServer::new();
+        metrics.record_workflow_execution("backup", "success");
+        metrics
+            .push_to_gateway(&server.uri(), "edge-1")
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_push_to_gateway_non_2xx_is_error() {
+        use wiremock::matchers::method;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(400).set_body_string("bad metrics"))
+            .mount(&server)
+            .await;
+
+        let metrics = MetricsServer::new();
+        let err = metrics
+            .push_to_gateway(&server.uri(), "edge-1")
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("400"), "{err}");
+
+        assert!(metrics.push_to_gateway(&server.uri(), "").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_start_on_os_assigned_port() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let metrics = MetricsServer::new();
+        metrics.record_workflow_execution("backup", "success");
+        let port = metrics.start(0).await.unwrap();
+        assert_ne!(port, 0);
+
+        let mut stream = tokio::net::TcpStream::connect(("127.0.0.1", port))
+            .await
+            .unwrap();
+        stream
+            .write_all(b"GET /metrics HTTP/1.1\r\n\r\n")
+            .await
+            .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        assert!(response.starts_with("HTTP/1.1 200 OK"), "{response}");
+        assert!(response.contains("picoflow_workflow_executions_total"));
+    }
+
+    #[tokio::test]
+    async fn test_busy_port_fallbacks() {
+        let busy = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let busy_port = busy.local_addr().unwrap().port();
+        let metrics = MetricsServer::new();
+
+        assert!(metrics.start(busy_port).await.is_err());
+        assert_eq!(metrics.start_best_effort(busy_port).await, None);
+        assert!(metrics.start_in_range(busy_port..=busy_port).await.is_err());
+
+        let upper = busy_port.saturating_add(20);
+        let port = metrics.start_in_range(busy_port..=upper).await.unwrap();
+        assert!(port > busy_port && port <= upper);
     }
 }
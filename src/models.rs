@@ -1,5 +1,6 @@
 //! Core data models for PicoFlow workflow orchestrator
 
+use crate::retry::BackoffStrategy;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -15,6 +16,7 @@ pub const MAX_ARG_COUNT: usize = 256;
 pub const MAX_ARG_LEN: usize = 4_096; // 4 KB
 pub const MAX_OUTPUT_SIZE: usize = 10_485_760; // 10 MB
 pub const MAX_RESPONSE_SIZE: usize = 10_485_760; // 10 MB
+pub const MAX_INCLUDE_DEPTH: usize = 8; // Nesting limit for workflow `includes`
 
 /// Workflow configuration parsed from YAML
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -24,20 +26,84 @@ pub struct WorkflowConfig {
     pub description: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub schedule: Option<String>, // Cron expression
+    /// IANA timezone the schedule is interpreted in (e.g. `America/New_York`); UTC if unset
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timezone: Option<String>,
+    /// Free-form key/value labels, persisted for filtering (see
+    /// `StateManager::list_workflows_by_label`)
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub labels: HashMap<String, String>,
+    /// Let a scheduled run start while a previous run of the workflow is still running;
+    /// by default such a cron trigger is skipped
+    #[serde(default)]
+    pub allow_overlap: bool,
+    /// HTTP request sent after every run, with a JSON body of `workflow`, `execution_id`,
+    /// `status` and `duration` (seconds) replacing any configured `body`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub on_complete: Option<HttpConfig>,
+    /// Inputs referenced as `${params.<name>}`, supplied with `picoflow run -p name=value`
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub params: HashMap<String, ParamSpec>,
     #[serde(default)]
     pub config: WorkflowGlobalConfig,
     pub tasks: Vec<TaskConfig>,
 }
 
+/// A workflow input declared under `params`
+///
+/// A param without a `default` is required: loading the workflow fails unless a value
+/// is supplied.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct ParamSpec {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
 /// Global workflow configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WorkflowGlobalConfig {
-    #[serde(default = "default_max_parallel")]
+    /// Tasks run at once; the string `auto` resolves to the CPU count when the workflow is
+    /// loaded, capped at `MAX_AUTO_PARALLEL`
+    #[serde(
+        default = "default_max_parallel",
+        deserialize_with = "deserialize_max_parallel"
+    )]
     pub max_parallel: usize,
     #[serde(default = "default_retry")]
     pub retry_default: u32,
     #[serde(default = "default_timeout")]
     pub timeout_default: u64, // seconds
+    /// Randomize retry backoff (full jitter) to avoid synchronized retries across devices
+    #[serde(default)]
+    pub retry_jitter: bool,
+    /// Upper bound on any task's retries, whatever its `retry` says
+    #[serde(default = "default_max_retries_cap")]
+    pub max_retries_cap: u32,
+    /// `continue_on_failure` for tasks that do not set their own
+    #[serde(default)]
+    pub continue_on_failure_default: bool,
+    /// Delay launching tasks while the picoflow process's RSS exceeds this many MiB
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub memory_limit_mb: Option<u64>,
+    /// Bytes of each output stream (or HTTP response body) stored per task, overriding
+    /// `MAX_OUTPUT_SIZE`/`MAX_RESPONSE_SIZE`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_output_bytes: Option<usize>,
+    /// Which task runs keep their stdout/stderr in the state database
+    #[serde(default)]
+    pub store_output: OutputPolicy,
+    /// How retry delays grow (`retry_jitter` adds full jitter to the non-random strategies)
+    #[serde(default)]
+    pub backoff_strategy: BackoffStrategy,
+    /// Connection settings for SSH tasks that do not set their own
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ssh_defaults: Option<SshDefaults>,
+    /// Reject workflows whose longest dependency chain has more levels than this
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_depth: Option<usize>,
 }
 
 impl Default for WorkflowGlobalConfig {
@@ -46,6 +112,39 @@ impl Default for WorkflowGlobalConfig {
             max_parallel: default_max_parallel(),
             retry_default: default_retry(),
             timeout_default: default_timeout(),
+            retry_jitter: false,
+            max_retries_cap: default_max_retries_cap(),
+            continue_on_failure_default: false,
+            memory_limit_mb: None,
+            max_output_bytes: None,
+            store_output: OutputPolicy::default(),
+            backoff_strategy: BackoffStrategy::default(),
+            ssh_defaults: None,
+            max_depth: None,
+        }
+    }
+}
+
+/// Which task runs have their stdout/stderr stored
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum OutputPolicy {
+    /// Store output for every run
+    #[default]
+    Always,
+    /// Store output only for runs that did not succeed
+    OnFailureOnly,
+    /// Never store output
+    Never,
+}
+
+impl OutputPolicy {
+    /// Whether output of a run that finished with `status` should be stored
+    pub fn stores(&self, status: &TaskStatus) -> bool {
+        match self {
+            OutputPolicy::Always => true,
+            OutputPolicy::OnFailureOnly => *status != TaskStatus::Success,
+            OutputPolicy::Never => false,
         }
     }
 }
@@ -54,6 +153,37 @@ fn default_max_parallel() -> usize {
     4
 }
 
+/// Upper bound for `max_parallel: auto`, however many CPUs the device has
+pub const MAX_AUTO_PARALLEL: usize = 32;
+
+/// `max_parallel` for `auto`: the available CPUs, between 1 and `MAX_AUTO_PARALLEL`
+pub fn auto_max_parallel() -> usize {
+    std::thread::available_parallelism()
+        .map_or(1, |n| n.get())
+        .min(MAX_AUTO_PARALLEL)
+}
+
+/// Accept either an integer or `auto` for `max_parallel`
+fn deserialize_max_parallel<'de, D>(deserializer: D) -> Result<usize, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum MaxParallel {
+        Count(usize),
+        Keyword(String),
+    }
+
+    match MaxParallel::deserialize(deserializer)? {
+        MaxParallel::Count(n) => Ok(n),
+        MaxParallel::Keyword(s) if s == "auto" => Ok(auto_max_parallel()),
+        MaxParallel::Keyword(s) => Err(serde::de::Error::custom(format!(
+            "invalid max_parallel '{s}': expected an integer or \"auto\""
+        ))),
+    }
+}
+
 fn default_retry() -> u32 {
     3
 }
@@ -62,21 +192,220 @@ fn default_timeout() -> u64 {
     300
 }
 
+fn default_max_retries_cap() -> u32 {
+    10
+}
+
 /// Individual task configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TaskConfig {
     pub name: String,
     #[serde(rename = "type")]
     pub task_type: TaskType,
+    /// Tasks that must finish first: names, or `{task, on}` entries that also require an
+    /// outcome
     #[serde(default)]
-    pub depends_on: Vec<String>,
+    pub depends_on: Vec<Dependency>,
+    /// May be omitted for `noop` tasks
+    #[serde(default = "default_executor_config")]
     pub config: TaskExecutorConfig,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub retry: Option<u32>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub timeout: Option<u64>, // seconds
+    /// Seconds to wait after dependencies finish before starting the task. The wait
+    /// happens once (not before retries) and does not count against `timeout`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub delay_before: Option<u64>,
+    /// Keep running independent tasks if this one fails (defaults to the workflow's
+    /// `continue_on_failure_default`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub continue_on_failure: Option<bool>,
+    /// Capture trimmed stdout on success so dependents can reference it as
+    /// `${{ tasks.<name>.stdout }}`
+    #[serde(default)]
+    pub outputs: bool,
+    /// When to run relative to the outcome of direct dependencies
+    #[serde(default)]
+    pub run_condition: RunCondition,
+    /// Set to `false` to skip the task without removing it; dependents run as if it
+    /// succeeded (defaults to enabled)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub enabled: Option<bool>,
+    /// Free-form key/value labels
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub labels: HashMap<String, String>,
+    /// Paths that must exist and be readable for the task to run
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub requires_files: Vec<String>,
+    /// What happens when a `requires_files` path is missing
     #[serde(default)]
-    pub continue_on_failure: bool,
+    pub missing_files_action: MissingFilesAction,
+    /// Reuse the last successful result of this task while the key and the task's
+    /// (resolved) config are unchanged, instead of running it again
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache_key: Option<String>,
+    /// How long a cached result stays valid, in seconds (no expiry when unset)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache_ttl_secs: Option<u64>,
+    /// Launch order within a parallel level when `max_parallel` limits concurrency:
+    /// higher first, ties in definition order (default 0)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub priority: Option<i32>,
+}
+
+impl TaskConfig {
+    /// Whether the task should run (`enabled` unset or `true`)
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.unwrap_or(true)
+    }
+
+    /// The first `requires_files` path that does not exist or cannot be opened for reading
+    pub fn missing_required_file(&self) -> Option<&str> {
+        self.requires_files
+            .iter()
+            .find(|path| std::fs::File::open(path).is_err())
+            .map(String::as_str)
+    }
+
+    /// Whether a failure of this task lets the workflow continue, falling back to the
+    /// workflow-wide default when the task does not say
+    pub fn continues_on_failure(&self, settings: &WorkflowGlobalConfig) -> bool {
+        self.continue_on_failure
+            .unwrap_or(settings.continue_on_failure_default)
+    }
+
+    /// Number of retries the scheduler allows this task: its own `retry` (or the workflow
+    /// `retry_default`), capped at `max_retries_cap`
+    pub fn effective_retry(&self, settings: &WorkflowGlobalConfig) -> u32 {
+        self.retry
+            .unwrap_or(settings.retry_default)
+            .min(settings.max_retries_cap)
+    }
+
+    /// Timeout in seconds: the task's own `timeout`, or the workflow `timeout_default`
+    pub fn effective_timeout(&self, settings: &WorkflowGlobalConfig) -> u64 {
+        self.timeout.unwrap_or(settings.timeout_default)
+    }
+}
+
+/// What the scheduler does with a task whose `requires_files` are not all present
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum MissingFilesAction {
+    /// Record the task as skipped; `on_success` dependents are skipped too
+    #[default]
+    Skip,
+    /// Fail the task without running it (no retries)
+    Fail,
+}
+
+/// Condition under which a task runs, evaluated against its direct dependencies
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RunCondition {
+    /// Run regardless of how dependencies finished (even after the workflow halted)
+    Always,
+    /// Run only if every dependency succeeded (or failed with `continue_on_failure`)
+    #[default]
+    OnSuccess,
+    /// Run only if at least one dependency failed, e.g. a cleanup or alerting hook
+    OnFailure,
+}
+
+impl std::fmt::Display for RunCondition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RunCondition::Always => write!(f, "always"),
+            RunCondition::OnSuccess => write!(f, "on_success"),
+            RunCondition::OnFailure => write!(f, "on_failure"),
+        }
+    }
+}
+
+/// A `depends_on` entry
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(untagged, deny_unknown_fields)]
+pub enum Dependency {
+    /// `- build`: the dependency's outcome is judged by the task's `run_condition`
+    Task(String),
+    /// `- {task: build, on: failure}`: the dependency must finish with this outcome
+    Conditional {
+        task: String,
+        on: DependencyCondition,
+    },
+}
+
+impl Dependency {
+    /// Name of the task depended on
+    pub fn task(&self) -> &str {
+        match self {
+            Dependency::Task(task) | Dependency::Conditional { task, .. } => task,
+        }
+    }
+
+    /// Outcome the dependency must have, if the entry sets one
+    pub fn condition(&self) -> Option<DependencyCondition> {
+        match self {
+            Dependency::Task(_) => None,
+            Dependency::Conditional { on, .. } => Some(*on),
+        }
+    }
+}
+
+impl From<&str> for Dependency {
+    fn from(task: &str) -> Self {
+        Dependency::Task(task.to_string())
+    }
+}
+
+impl From<String> for Dependency {
+    fn from(task: String) -> Self {
+        Dependency::Task(task)
+    }
+}
+
+impl std::fmt::Display for Dependency {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Dependency::Task(task) => write!(f, "{}", task),
+            Dependency::Conditional { task, on } => write!(f, "{} (on {})", task, on),
+        }
+    }
+}
+
+/// Outcome a `{task, on}` dependency must finish with
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DependencyCondition {
+    /// The dependency succeeded
+    Success,
+    /// The dependency failed or timed out
+    Failure,
+    /// The dependency ran, whatever its outcome (not skipped or cancelled)
+    Complete,
+}
+
+impl DependencyCondition {
+    /// Whether a dependency that finished with `status` satisfies the condition
+    pub fn is_met_by(self, status: &TaskStatus) -> bool {
+        let failed = matches!(status, TaskStatus::Failed | TaskStatus::Timeout);
+        match self {
+            DependencyCondition::Success => *status == TaskStatus::Success,
+            DependencyCondition::Failure => failed,
+            DependencyCondition::Complete => failed || *status == TaskStatus::Success,
+        }
+    }
+}
+
+impl std::fmt::Display for DependencyCondition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DependencyCondition::Success => write!(f, "success"),
+            DependencyCondition::Failure => write!(f, "failure"),
+            DependencyCondition::Complete => write!(f, "complete"),
+        }
+    }
 }
 
 /// Task type variants
@@ -86,6 +415,11 @@ pub enum TaskType {
     Shell,
     Ssh,
     Http,
+    Docker,
+    Download,
+    /// Join point that succeeds immediately without running anything
+    #[serde(alias = "barrier")]
+    Noop,
 }
 
 /// Executor-specific configuration (enum for different task types)
@@ -98,14 +432,20 @@ pub enum TaskType {
 pub enum TaskExecutorConfig {
     /// SSH config requires host and user (most specific)
     Ssh(SshConfig),
+    /// Download config requires url and dest_path (must precede Http)
+    Download(DownloadConfig),
     /// HTTP config requires url (specific)
     Http(HttpConfig),
-    /// Shell config only requires command (least specific, must be last)
+    /// Docker config requires image (specific)
+    Docker(DockerConfig),
+    /// Shell config only requires command
     Shell(ShellConfig),
+    /// Noop config has no fields (matches only an empty map, so it must be last)
+    Noop(NoopConfig),
 }
 
 /// Shell executor configuration
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 #[serde(deny_unknown_fields)]
 pub struct ShellConfig {
     pub command: String, // Absolute path to binary
@@ -113,8 +453,60 @@ pub struct ShellConfig {
     pub args: Vec<String>, // Arguments as list
     #[serde(skip_serializing_if = "Option::is_none")]
     pub workdir: Option<String>, // Working directory
+    /// Create `workdir` (and missing parents) if it does not exist, instead of failing
+    #[serde(default)]
+    pub create_workdir: bool,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub env: Option<HashMap<String, String>>, // Environment variables
+    /// Dotenv file loaded before `env`; inline `env` entries override it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub env_file: Option<String>,
+    /// Fail the task when its stderr matches this regex, even if it exits 0. The whole
+    /// stderr is searched, so `^`/`$` match only at its start and end unless `(?m)` is used
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fail_on_stderr_regex: Option<String>,
+    /// Run `command` as a script with `<shell> -c` (absolute path, e.g. `/bin/sh`) so it
+    /// may use pipes and redirection; `args` become `$1`, `$2`, ... Unset (the default)
+    /// runs `command` directly, with no shell interpretation
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shell: Option<String>,
+}
+
+/// Workflow-wide SSH settings, filled into every SSH task that leaves them unset
+///
+/// ```yaml
+/// config:
+///   ssh_defaults:
+///     user: pi
+///     key_path: ~/.ssh/id_ed25519
+///     port: 2222
+/// ```
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct SshDefaults {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub key_path: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub port: Option<u16>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user: Option<String>,
+}
+
+impl SshDefaults {
+    /// Fill the fields `ssh` leaves unset (an empty `user` counts as unset)
+    pub fn apply_to(&self, ssh: &mut SshConfig) {
+        if ssh.key_path.is_none() {
+            ssh.key_path = self.key_path.clone();
+        }
+        if ssh.port.is_none() {
+            ssh.port = self.port;
+        }
+        if ssh.user.is_empty() {
+            if let Some(user) = &self.user {
+                ssh.user = user.clone();
+            }
+        }
+    }
 }
 
 /// SSH executor configuration
@@ -122,6 +514,8 @@ pub struct ShellConfig {
 #[serde(deny_unknown_fields)]
 pub struct SshConfig {
     pub host: String,
+    /// May be omitted when the workflow's `ssh_defaults` sets a user
+    #[serde(default)]
     pub user: String,
     pub command: String,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -130,6 +524,19 @@ pub struct SshConfig {
     pub port: Option<u16>, // Default: 22
     #[serde(default = "default_verify_host_key")]
     pub verify_host_key: bool, // Default: true (for security)
+    /// Maximum commands per second started on `host`, shared by all tasks
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rate_limit_per_sec: Option<u32>,
+    /// Run `command` through `bash -lc` so the remote login profile (PATH etc.) is loaded
+    #[serde(default)]
+    pub use_login_shell: bool,
+    /// Seconds allowed for the TCP connect, handshake and authentication (default: 10)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub connect_timeout_secs: Option<u64>,
+    /// Times a failed TCP connect or SSH handshake is retried, with exponential backoff,
+    /// before the task fails (default: 0). Authentication failures are never retried
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub connect_retries: Option<u32>,
 }
 
 fn default_verify_host_key() -> bool {
@@ -144,7 +551,9 @@ pub struct HttpConfig {
     #[serde(default = "default_http_method")]
     pub method: HttpMethod,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub body: Option<serde_yaml::Value>, // JSON body
+    pub body: Option<serde_yaml::Value>, // Encoded according to `body_type`
+    #[serde(default)]
+    pub body_type: BodyType,
     #[serde(default)]
     pub headers: HashMap<String, String>,
     /// HTTP request timeout in seconds.
@@ -157,6 +566,121 @@ pub struct HttpConfig {
     pub timeout: u64,
     #[serde(default = "default_allow_private_ips")]
     pub allow_private_ips: bool, // Default: false (for security, blocks SSRF)
+    /// Credentials sent as an `Authorization` header unless `headers` already sets one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub auth: Option<HttpAuth>,
+    /// Response status codes (e.g. 429, 503) retried within the executor, honoring
+    /// `Retry-After`. Independent of task-level `retry`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retry_on_status: Option<Vec<u16>>,
+    /// Maximum requests per second sent to the URL's host, shared by all tasks
+    /// (including in-executor retries)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rate_limit_per_sec: Option<u32>,
+    /// `[path, value]`: a response only succeeds if its JSON body has `value` at `path`
+    /// (e.g. `$.status` or `data.items[0].state`), even when the status is 2xx
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expect_json_path: Option<(String, String)>,
+    /// Only these hosts (and their subdomains) may be requested; every other host is
+    /// rejected, even with `allow_private_ips`. Listed hosts skip the private IP checks.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub allowed_hosts: Option<Vec<String>>,
+    /// PEM certificate (optionally followed by its chain) presented for mutual TLS;
+    /// requires `client_key_path`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_cert_path: Option<String>,
+    /// PKCS#8 PEM private key for `client_cert_path`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_key_path: Option<String>,
+}
+
+/// How an HTTP task's `body` is encoded
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum BodyType {
+    /// Serialize the YAML value as JSON (`application/json`)
+    #[default]
+    Json,
+    /// Encode a flat mapping as `key=value&...` (`application/x-www-form-urlencoded`)
+    Form,
+    /// Send a string body unchanged (`text/plain`)
+    Raw,
+}
+
+/// HTTP request authentication
+///
+/// ```yaml
+/// auth:
+///   type: basic
+///   username: admin
+///   password: ${API_PASSWORD}
+/// ```
+#[derive(Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+pub enum HttpAuth {
+    Basic { username: String, password: String },
+    Bearer { token: String },
+}
+
+// Hand-written so credentials never end up in logs via `{:?}` of a task config
+impl std::fmt::Debug for HttpAuth {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HttpAuth::Basic { username, .. } => f
+                .debug_struct("Basic")
+                .field("username", username)
+                .field("password", &"[REDACTED]")
+                .finish(),
+            HttpAuth::Bearer { .. } => f
+                .debug_struct("Bearer")
+                .field("token", &"[REDACTED]")
+                .finish(),
+        }
+    }
+}
+
+/// Download executor configuration
+///
+/// Streams an HTTP(S) GET response to `dest_path` instead of capturing it as output.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DownloadConfig {
+    pub url: String,
+    pub dest_path: String, // Absolute path; parent directory must exist
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub checksum: Option<String>, // Expected SHA-256 of the body (hex)
+    #[serde(default = "default_timeout")]
+    pub timeout: u64,
+    #[serde(default = "default_allow_private_ips")]
+    pub allow_private_ips: bool, // Same SSRF protection as the HTTP executor
+}
+
+/// Noop task configuration (no fields; `config` may be left out entirely)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct NoopConfig {}
+
+fn default_executor_config() -> TaskExecutorConfig {
+    TaskExecutorConfig::Noop(NoopConfig::default())
+}
+
+/// Docker executor configuration
+///
+/// Runs `docker run --rm <image> [command] [args...]` without going through a shell.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DockerConfig {
+    pub image: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub command: Option<String>, // Overrides the image's default command
+    #[serde(default)]
+    pub args: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub env: Option<HashMap<String, String>>, // Container environment variables
+    #[serde(default)]
+    pub volumes: Vec<String>, // "host:container" or "host:container:ro|rw"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub network: Option<String>, // Docker network to attach to
 }
 
 fn default_allow_private_ips() -> bool {
@@ -187,6 +711,10 @@ pub enum TaskStatus {
     Failed,
     Retrying,
     Timeout,
+    /// Not run because its run condition was not met
+    Skipped,
+    /// Stopped before finishing because the daemon shut down
+    Cancelled,
 }
 
 impl std::fmt::Display for TaskStatus {
@@ -198,6 +726,33 @@ impl std::fmt::Display for TaskStatus {
             TaskStatus::Failed => write!(f, "failed"),
             TaskStatus::Retrying => write!(f, "retrying"),
             TaskStatus::Timeout => write!(f, "timeout"),
+            TaskStatus::Skipped => write!(f, "skipped"),
+            TaskStatus::Cancelled => write!(f, "cancelled"),
+        }
+    }
+}
+
+/// Why a task attempt failed, stored alongside its execution record
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FailureReason {
+    /// The task ran and reported failure (non-zero exit code, error HTTP status, ...)
+    Failed,
+    /// The task exceeded its timeout
+    Timeout,
+    /// The task process could not be started
+    Spawn,
+    /// Any other execution error (invalid config, connection failure, ...)
+    Error,
+}
+
+impl std::fmt::Display for FailureReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FailureReason::Failed => write!(f, "failed"),
+            FailureReason::Timeout => write!(f, "timeout"),
+            FailureReason::Spawn => write!(f, "spawn"),
+            FailureReason::Error => write!(f, "error"),
         }
     }
 }
@@ -215,6 +770,8 @@ pub struct ExecutionResult {
     pub duration: Duration,
     #[serde(default)]
     pub output_truncated: bool, // True if output exceeded MAX_OUTPUT_SIZE
+    #[serde(default)]
+    pub output_binary: bool, // True if output was not valid UTF-8 and was not stored
 }
 
 /// Workflow execution record
@@ -242,6 +799,83 @@ pub struct TaskExecution {
     pub attempt: i32,
     pub retry_count: i32,
     pub next_retry_at: Option<DateTime<Utc>>,
+    /// Whether stdout or stderr was cut off at `MAX_OUTPUT_SIZE` before being stored
+    #[serde(default)]
+    pub output_truncated: bool,
+    /// Why the attempt failed; `None` for attempts that did not fail
+    #[serde(default)]
+    pub failure_reason: Option<FailureReason>,
+    /// Whether the result was reused from the task cache rather than produced by running
+    #[serde(default)]
+    pub cached: bool,
+}
+
+/// File format written by `picoflow export`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportFormat {
+    /// Comma-separated values with a header row
+    Csv,
+    /// One JSON object per line
+    Ndjson,
+}
+
+/// One execution as written to an NDJSON export
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportedExecution {
+    /// Name of the execution's workflow
+    pub workflow: String,
+    #[serde(flatten)]
+    pub execution: WorkflowExecution,
+    /// Task attempts of the execution; empty unless tasks were exported
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tasks: Vec<TaskExecution>,
+}
+
+/// Dependency graph of an execution with the latest status of each task, as returned by
+/// [`crate::state::StateManager::get_execution_graph`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExecutionGraph {
+    pub execution_id: i64,
+    pub workflow: String,
+    pub status: TaskStatus,
+    pub started_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+    /// Tasks in workflow definition order
+    pub nodes: Vec<ExecutionGraphNode>,
+    pub edges: Vec<ExecutionGraphEdge>,
+}
+
+/// A task in an [`ExecutionGraph`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExecutionGraphNode {
+    pub task: String,
+    /// Status of the latest attempt; `pending` until the task starts
+    pub status: TaskStatus,
+    /// Number of the latest attempt (0 if the task has not started or was skipped)
+    pub attempt: i32,
+    pub started_at: Option<DateTime<Utc>>,
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+/// A `depends_on` entry in an [`ExecutionGraph`]: `to` depends on `from`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExecutionGraphEdge {
+    pub from: String,
+    pub to: String,
+    /// Outcome `from` must have, for `{task, on}` entries
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub on: Option<DependencyCondition>,
+}
+
+/// A successful task result kept for reuse by tasks with a `cache_key`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskCacheEntry {
+    pub exit_code: Option<i32>,
+    pub stdout: Option<String>,
+    pub stderr: Option<String>,
+    /// When the result was produced
+    pub created_at: DateTime<Utc>,
 }
 
 /// Workflow summary with execution statistics
@@ -253,6 +887,8 @@ pub struct WorkflowSummary {
     pub success_count: i64,
     pub failed_count: i64,
     pub last_execution: Option<DateTime<Utc>>,
+    /// Next scheduled run, computed from `schedule` (None for on-demand workflows)
+    pub next_run: Option<DateTime<Utc>>,
 }
 
 /// Detailed workflow execution statistics
@@ -268,6 +904,40 @@ pub struct WorkflowStatistics {
     pub last_execution: Option<DateTime<Utc>>,
 }
 
+/// Historical run-time distribution of a single task
+///
+/// All durations are in seconds. With no completed runs, `sample_count` is 0 and
+/// every duration is 0.0.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DurationStats {
+    pub sample_count: usize,
+    pub min_seconds: f64,
+    pub max_seconds: f64,
+    pub avg_seconds: f64,
+    pub p95_seconds: f64, // Nearest-rank 95th percentile
+}
+
+impl DurationStats {
+    /// Compute statistics from durations in seconds (order does not matter)
+    pub fn from_durations(mut durations: Vec<f64>) -> Self {
+        if durations.is_empty() {
+            return Self::default();
+        }
+        durations.sort_by(f64::total_cmp);
+
+        let n = durations.len();
+        // Nearest-rank: the smallest value with at least 95% of samples at or below it
+        let p95_rank = (0.95 * n as f64).ceil() as usize;
+        Self {
+            sample_count: n,
+            min_seconds: durations[0],
+            max_seconds: durations[n - 1],
+            avg_seconds: durations.iter().sum::<f64>() / n as f64,
+            p95_seconds: durations[p95_rank.max(1) - 1],
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -278,6 +948,21 @@ mod tests {
         assert_eq!(config.max_parallel, 4);
         assert_eq!(config.retry_default, 3);
         assert_eq!(config.timeout_default, 300);
+        assert!(!config.retry_jitter);
+    }
+
+    #[test]
+    fn test_max_parallel_integer_or_auto() {
+        let config: WorkflowGlobalConfig = serde_yaml::from_str("max_parallel: 3").unwrap();
+        assert_eq!(config.max_parallel, 3);
+
+        let config: WorkflowGlobalConfig = serde_yaml::from_str("max_parallel: auto").unwrap();
+        let cpus = std::thread::available_parallelism().unwrap().get();
+        assert_eq!(config.max_parallel, cpus.min(MAX_AUTO_PARALLEL));
+        assert!(config.max_parallel >= 1);
+
+        let err = serde_yaml::from_str::<WorkflowGlobalConfig>("max_parallel: lots").unwrap_err();
+        assert!(err.to_string().contains("invalid max_parallel 'lots'"));
     }
 
     #[test]
@@ -303,6 +988,10 @@ mod tests {
         let yaml = r#"http"#;
         let task_type: TaskType = serde_yaml::from_str(yaml).unwrap();
         assert_eq!(task_type, TaskType::Http);
+
+        let yaml = r#"docker"#;
+        let task_type: TaskType = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(task_type, TaskType::Docker);
     }
 
     #[test]
@@ -331,4 +1020,54 @@ workdir: "/home/user"
         assert_eq!(config.args[0], "-la");
         assert_eq!(config.workdir, Some("/home/user".to_string()));
     }
+
+    #[test]
+    fn test_docker_config_untagged_deserialization() {
+        let yaml = r#"
+image: "alpine:3.19"
+command: "/bin/echo"
+args: ["hi"]
+volumes: ["/data:/data:ro"]
+"#;
+        let config: TaskExecutorConfig = serde_yaml::from_str(yaml).unwrap();
+        match config {
+            TaskExecutorConfig::Docker(docker) => {
+                assert_eq!(docker.image, "alpine:3.19");
+                assert_eq!(docker.command.as_deref(), Some("/bin/echo"));
+                assert_eq!(docker.volumes, vec!["/data:/data:ro"]);
+                assert!(docker.network.is_none());
+            }
+            other => panic!("expected Docker config, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_http_auth_serde_and_redacted_debug() {
+        let yaml = r#"
+url: "https://api.example.com"
+auth:
+  type: basic
+  username: admin
+  password: hunter2
+"#;
+        let config: HttpConfig = serde_yaml::from_str(yaml).unwrap();
+        let auth = config.auth.unwrap();
+        assert_eq!(
+            auth,
+            HttpAuth::Basic {
+                username: "admin".to_string(),
+                password: "hunter2".to_string(),
+            }
+        );
+        assert!(!format!("{auth:?}").contains("hunter2"));
+
+        let bearer: HttpAuth = serde_yaml::from_str("type: bearer\ntoken: abc123").unwrap();
+        assert_eq!(
+            bearer,
+            HttpAuth::Bearer {
+                token: "abc123".to_string()
+            }
+        );
+        assert!(!format!("{bearer:?}").contains("abc123"));
+    }
 }
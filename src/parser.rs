@@ -1,9 +1,14 @@
 //! YAML parser with validation for workflow configurations
 
+use crate::dag::DagEngine;
 use crate::error::{PicoFlowError, Result};
 use crate::models::*;
+use serde_yaml::{Mapping, Value};
+use std::collections::HashMap;
 use std::fs;
-use std::path::Path;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use tracing::warn;
 
 /// Parse a workflow configuration from a YAML file.
 ///
@@ -14,6 +19,14 @@ use std::path::Path;
 /// - Task names must be alphanumeric + underscore/dash only
 /// - All paths are validated for traversal attacks
 ///
+/// # Includes
+///
+/// A top-level `includes: [common.yaml, ...]` list pulls in other files, resolved relative
+/// to the including file's directory. An included file may only define `tasks`, `config`
+/// and its own `includes`. Included tasks are placed before the file's own tasks, and the
+/// file's own `config` keys override included ones. Includes may nest up to
+/// `MAX_INCLUDE_DEPTH` levels; a file that (transitively) includes itself is an error.
+///
 /// # Arguments
 ///
 /// * `path` - Path to the YAML workflow file
@@ -29,6 +42,8 @@ use std::path::Path;
 /// * `PicoFlowError::TaskCountExceeded` - If workflow has > 1,000 tasks
 /// * `PicoFlowError::YamlParse` - If YAML is malformed
 /// * `PicoFlowError::InvalidTaskName` - If task names are invalid
+/// * `PicoFlowError::Validation` - If an include is missing, malformed, nested too deeply
+///   or forms a cycle
 ///
 /// # Example
 ///
@@ -40,8 +55,471 @@ use std::path::Path;
 /// # Ok::<(), picoflow::error::PicoFlowError>(())
 /// ```
 pub fn parse_workflow_file<P: AsRef<Path>>(path: P) -> Result<WorkflowConfig> {
-    let content = fs::read_to_string(path)?;
-    parse_workflow_yaml(&content)
+    parse_workflow_file_with_env(path, &HashMap::new())
+}
+
+/// Like [`parse_workflow_file`], with `env` taking precedence over the process
+/// environment when expanding `${VAR}` references (including in included files)
+pub fn parse_workflow_file_with_env<P: AsRef<Path>>(
+    path: P,
+    env: &HashMap<String, String>,
+) -> Result<WorkflowConfig> {
+    validate_workflow(load_workflow_file_with_env(path.as_ref(), env, None)?)
+}
+
+/// Like [`parse_workflow_file_with_env`], also supplying values for the workflow's
+/// `params`, which are substituted wherever `${params.<name>}` appears in a string value
+///
+/// The functions without `params` accept a workflow whose required params are unset
+/// and leave those references as-is, so it can still be validated or planned.
+///
+/// # Errors
+///
+/// In addition to those of [`parse_workflow_file`]:
+///
+/// * `PicoFlowError::Validation` - If a param without a default is not supplied, a
+///   supplied param is not declared, or `${params.<name>}` names an undeclared param
+pub fn parse_workflow_file_with_params<P: AsRef<Path>>(
+    path: P,
+    env: &HashMap<String, String>,
+    params: &HashMap<String, String>,
+) -> Result<WorkflowConfig> {
+    validate_workflow(load_workflow_file_with_env(
+        path.as_ref(),
+        env,
+        Some(params),
+    )?)
+}
+
+/// Like [`parse_workflow_file_with_params`], parsing `content` already read from `path`
+///
+/// For callers that must parse exactly the bytes they checked, e.g. after verifying the
+/// file's signature. Includes are still read from disk, relative to `path`.
+///
+/// # Errors
+///
+/// As for [`parse_workflow_file_with_params`]
+pub fn parse_workflow_source_with_params<P: AsRef<Path>>(
+    content: &str,
+    path: P,
+    env: &HashMap<String, String>,
+    params: &HashMap<String, String>,
+) -> Result<WorkflowConfig> {
+    if content.len() > MAX_YAML_SIZE {
+        return Err(PicoFlowError::YamlSizeExceeded(content.len()));
+    }
+    validate_workflow(load_workflow_source(
+        content,
+        path.as_ref(),
+        env,
+        Some(params),
+    )?)
+}
+
+/// Load a workflow file (resolving includes) without semantic validation.
+///
+/// Only file-level limits (size, YAML syntax, include resolution) are enforced. Pair with
+/// [`crate::dag::DagEngine::validate_all`] to collect every problem in a workflow, or pass
+/// the result to [`validate_workflow`] for the checks [`parse_workflow_file`] performs.
+///
+/// # Errors
+///
+/// * `PicoFlowError::Io` - If file cannot be read
+/// * `PicoFlowError::YamlSizeExceeded` - If file exceeds 1MB
+/// * `PicoFlowError::YamlParse` - If YAML is malformed
+/// * `PicoFlowError::Validation` - If an include cannot be resolved
+pub fn load_workflow_file<P: AsRef<Path>>(path: P) -> Result<WorkflowConfig> {
+    load_workflow_file_with_env(path.as_ref(), &HashMap::new(), None)
+}
+
+/// [`load_workflow_file`] with `${VAR}` overrides and params (see
+/// [`parse_workflow_file_with_params`]); `params` is `None` when not running the
+/// workflow, so required params need not be supplied
+fn load_workflow_file_with_env(
+    path: &Path,
+    env: &HashMap<String, String>,
+    params: Option<&HashMap<String, String>>,
+) -> Result<WorkflowConfig> {
+    load_workflow_source(&read_workflow_text(path)?, path, env, params)
+}
+
+/// [`load_workflow_file_with_env`] for the already read (and size-checked) `raw` content
+/// of `path`
+fn load_workflow_source(
+    raw: &str,
+    path: &Path,
+    env: &HashMap<String, String>,
+    params: Option<&HashMap<String, String>>,
+) -> Result<WorkflowConfig> {
+    let params = &resolve_params(raw, params)?;
+    let content = expand_env_vars_overriding(raw, env, params)?;
+
+    let mut value: Value = serde_yaml::from_str(&content)?;
+    if value.get("includes").is_some() {
+        let mut stack = vec![path.canonicalize()?];
+        value = resolve_includes(value, path, env, params, &mut stack)?;
+    } else if params.is_empty() {
+        // Deserialize from the text (not the Value) to keep line numbers in errors
+        return Ok(serde_yaml::from_str(&content)?);
+    }
+
+    substitute_params(&mut value, params)?;
+    Ok(serde_yaml::from_value(value)?)
+}
+
+/// Read a workflow (or included) file and enforce the size limit
+///
+/// An oversized file is rejected from its metadata, and no more than `MAX_YAML_SIZE`
+/// bytes are ever buffered.
+fn read_workflow_text(path: &Path) -> Result<String> {
+    let file = fs::File::open(path)?;
+    let size = file.metadata()?.len();
+    if size > MAX_YAML_SIZE as u64 {
+        return Err(PicoFlowError::YamlSizeExceeded(size as usize));
+    }
+
+    let mut content = String::new();
+    file.take(MAX_YAML_SIZE as u64 + 1)
+        .read_to_string(&mut content)?;
+    if content.len() > MAX_YAML_SIZE {
+        // Grew after the metadata was read
+        return Err(PicoFlowError::YamlSizeExceeded(content.len()));
+    }
+    Ok(content)
+}
+
+/// Read a workflow (or included) file, enforce the size limit and expand `${VAR}`s.
+fn read_workflow_source(
+    path: &Path,
+    env: &HashMap<String, String>,
+    params: &Params,
+) -> Result<String> {
+    expand_env_vars_overriding(&read_workflow_text(path)?, env, params)
+}
+
+/// Expand `${VAR}`s from `env`, falling back to the process environment.
+///
+/// When the workflow declares params, references to them (and `$$` escapes) are kept
+/// for [`substitute_params`] to resolve once the YAML has been parsed. The expanded
+/// text is held to `MAX_YAML_SIZE` too, so large variables cannot get around the limit.
+fn expand_env_vars_overriding(
+    yaml: &str,
+    env: &HashMap<String, String>,
+    params: &Params,
+) -> Result<String> {
+    let lookup = |name: &str| {
+        if let Some(param) = name.strip_prefix(PARAMS_PREFIX) {
+            // Never looked up in the environment
+            return params.contains_key(param).then(|| format!("${{{}}}", name));
+        }
+        env.get(name).cloned().or_else(|| std::env::var(name).ok())
+    };
+    let expanded = if params.is_empty() {
+        expand_env_vars_with(yaml, lookup)?
+    } else {
+        expand_vars(yaml, lookup, true)?
+    };
+    if expanded.len() > MAX_YAML_SIZE {
+        return Err(PicoFlowError::YamlSizeExceeded(expanded.len()));
+    }
+    Ok(expanded)
+}
+
+/// Prefix of the names `${params.<name>}` references are looked up under
+const PARAMS_PREFIX: &str = "params.";
+
+/// Value of each declared param; `None` when a required param was not supplied because
+/// the workflow is only being loaded, not run
+type Params = HashMap<String, Option<String>>;
+
+/// Return the value of every param the workflow in `yaml` declares, taken from
+/// `supplied` or else the param's default.
+///
+/// `params` are read from the unexpanded text, so their defaults are taken literally.
+/// With `supplied` set to `None`, a param without a default maps to `None` instead of
+/// being an error.
+fn resolve_params(yaml: &str, supplied: Option<&HashMap<String, String>>) -> Result<Params> {
+    let require = supplied.is_some();
+    let empty = HashMap::new();
+    let supplied = supplied.unwrap_or(&empty);
+
+    // Skip the extra parse for the common workflow without params
+    if supplied.is_empty() && !yaml.contains("params") {
+        return Ok(Params::new());
+    }
+
+    // Malformed YAML is reported by the full parse that follows
+    let declared = match serde_yaml::from_str::<Value>(yaml) {
+        Ok(Value::Mapping(mut map)) => map.remove("params"),
+        _ => None,
+    };
+    let declared: HashMap<String, ParamSpec> = match declared {
+        None | Some(Value::Null) => HashMap::new(),
+        Some(value) => serde_yaml::from_value(value)
+            .map_err(|e| PicoFlowError::Validation(format!("Invalid 'params': {}", e)))?,
+    };
+
+    if let Some(name) = supplied.keys().find(|name| !declared.contains_key(*name)) {
+        return Err(PicoFlowError::Validation(format!(
+            "Unknown param '{}': the workflow does not declare it under 'params'",
+            name
+        )));
+    }
+
+    let mut params = Params::new();
+    for (name, spec) in &declared {
+        let valid_name = name
+            .chars()
+            .next()
+            .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+            && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+        if !valid_name {
+            return Err(PicoFlowError::Validation(format!(
+                "Invalid param name '{}': only letters, digits and underscores allowed",
+                name
+            )));
+        }
+        let value = supplied.get(name).or(spec.default.as_ref()).cloned();
+        if value.is_none() && require {
+            return Err(PicoFlowError::Validation(format!(
+                "Missing required param '{}' (pass it with -p {}=VALUE)",
+                name, name
+            )));
+        }
+        params.insert(name.clone(), value);
+    }
+    Ok(params)
+}
+
+/// Substitute `${params.<name>}` references in every string of `value`, keys included.
+///
+/// Runs on the parsed YAML so that a param value is always part of a single string and
+/// cannot change the structure of the workflow. Also turns the `$$` escapes kept by
+/// [`expand_env_vars_overriding`] back into `$`.
+fn substitute_params(value: &mut Value, params: &Params) -> Result<()> {
+    if params.is_empty() {
+        return Ok(());
+    }
+    match value {
+        Value::String(s) if s.contains('$') => *s = substitute_param_refs(s, params)?,
+        Value::Sequence(seq) => {
+            for item in seq {
+                substitute_params(item, params)?;
+            }
+        }
+        Value::Mapping(map) => {
+            for (mut key, mut item) in std::mem::take(map) {
+                substitute_params(&mut key, params)?;
+                substitute_params(&mut item, params)?;
+                map.insert(key, item);
+            }
+        }
+        Value::Tagged(tagged) => substitute_params(&mut tagged.value, params)?,
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Substitute `${params.<name>}` references and `$$` escapes in one string
+fn substitute_param_refs(s: &str, params: &Params) -> Result<String> {
+    let mut result = String::with_capacity(s.len());
+    let mut rest = s;
+    while let Some(pos) = rest.find('$') {
+        result.push_str(&rest[..pos]);
+        let after = &rest[pos + 1..];
+
+        if let Some(stripped) = after.strip_prefix('$') {
+            result.push('$');
+            rest = stripped;
+            continue;
+        }
+
+        match parse_env_reference(after) {
+            Some((name, _, consumed)) if name.starts_with(PARAMS_PREFIX) => {
+                let param = &name[PARAMS_PREFIX.len()..];
+                match params.get(param) {
+                    Some(Some(value)) => result.push_str(value),
+                    // Required param left unset while only loading the workflow
+                    Some(None) => result.push_str(&rest[pos..pos + 1 + consumed]),
+                    None => {
+                        return Err(PicoFlowError::Validation(format!(
+                            "Param '{}' is not declared under 'params'",
+                            param
+                        )))
+                    }
+                }
+                rest = &after[consumed..];
+            }
+            _ => {
+                result.push('$');
+                rest = after;
+            }
+        }
+    }
+    result.push_str(rest);
+    Ok(result)
+}
+
+/// Replace the `includes` key of `value` (parsed from `path`) with the merged tasks and
+/// config of the included files, recursively.
+///
+/// `stack` holds the canonical paths of the files currently being resolved, outermost
+/// first, and is used to detect cycles and bound the nesting depth.
+fn resolve_includes(
+    mut value: Value,
+    path: &Path,
+    env: &HashMap<String, String>,
+    params: &Params,
+    stack: &mut Vec<PathBuf>,
+) -> Result<Value> {
+    let Some(map) = value.as_mapping_mut() else {
+        return Ok(value);
+    };
+    let Some(includes) = map.remove("includes") else {
+        return Ok(value);
+    };
+    let includes: Vec<String> = serde_yaml::from_value(includes).map_err(|_| {
+        PicoFlowError::Validation(format!(
+            "'includes' in {} must be a list of file paths",
+            path.display()
+        ))
+    })?;
+
+    let base_dir = path.parent().unwrap_or_else(|| Path::new("."));
+    let mut tasks: Vec<Value> = Vec::new();
+    let mut config = Mapping::new();
+
+    for include in &includes {
+        let include_path = base_dir.join(include);
+        let canonical = include_path.canonicalize().map_err(|e| {
+            PicoFlowError::Validation(format!(
+                "Include '{}' in {} could not be read: {}",
+                include,
+                path.display(),
+                e
+            ))
+        })?;
+
+        if let Some(start) = stack.iter().position(|p| *p == canonical) {
+            let cycle: Vec<String> = stack[start..]
+                .iter()
+                .chain(std::iter::once(&canonical))
+                .map(|p| p.display().to_string())
+                .collect();
+            return Err(PicoFlowError::Validation(format!(
+                "Include cycle detected: {}",
+                cycle.join(" -> ")
+            )));
+        }
+        if stack.len() > MAX_INCLUDE_DEPTH {
+            return Err(PicoFlowError::Validation(format!(
+                "Includes nested deeper than {} levels at {}",
+                MAX_INCLUDE_DEPTH,
+                include_path.display()
+            )));
+        }
+
+        stack.push(canonical);
+        let content = read_workflow_source(&include_path, env, params)?;
+        let fragment = resolve_includes(
+            serde_yaml::from_str(&content)?,
+            &include_path,
+            env,
+            params,
+            stack,
+        )?;
+        stack.pop();
+
+        merge_fragment(fragment, &include_path, &mut tasks, &mut config)?;
+
+        // Fail early rather than accumulating an unbounded merged task list
+        if tasks.len() > MAX_TASK_COUNT {
+            return Err(PicoFlowError::TaskCountExceeded {
+                count: tasks.len(),
+                limit: MAX_TASK_COUNT,
+            });
+        }
+    }
+
+    // The including file's own tasks come last and its own config keys win
+    let own = value
+        .as_mapping_mut()
+        .expect("value was checked to be a mapping");
+    take_tasks(own, path, &mut tasks)?;
+    take_config(own, path, &mut config)?;
+    own.insert(Value::from("tasks"), Value::Sequence(tasks));
+    if !config.is_empty() {
+        own.insert(Value::from("config"), Value::Mapping(config));
+    }
+
+    Ok(value)
+}
+
+/// Append an included file's tasks and overlay its config onto the accumulated ones.
+fn merge_fragment(
+    fragment: Value,
+    path: &Path,
+    tasks: &mut Vec<Value>,
+    config: &mut Mapping,
+) -> Result<()> {
+    let mut fragment = match fragment {
+        Value::Mapping(map) => map,
+        // An empty include file contributes nothing
+        Value::Null => return Ok(()),
+        _ => {
+            return Err(PicoFlowError::Validation(format!(
+                "Included file {} must be a mapping",
+                path.display()
+            )))
+        }
+    };
+
+    take_tasks(&mut fragment, path, tasks)?;
+    take_config(&mut fragment, path, config)?;
+
+    if let Some((key, _)) = fragment.iter().next() {
+        return Err(PicoFlowError::Validation(format!(
+            "Included file {} may only define 'includes', 'tasks' and 'config' (found '{}')",
+            path.display(),
+            key.as_str().unwrap_or("<non-string key>")
+        )));
+    }
+
+    Ok(())
+}
+
+/// Move the `tasks` sequence out of `map` and append it to `tasks`.
+fn take_tasks(map: &mut Mapping, path: &Path, tasks: &mut Vec<Value>) -> Result<()> {
+    match map.remove("tasks") {
+        Some(Value::Sequence(seq)) => tasks.extend(seq),
+        None | Some(Value::Null) => {}
+        Some(_) => {
+            return Err(PicoFlowError::Validation(format!(
+                "'tasks' in {} must be a list",
+                path.display()
+            )))
+        }
+    }
+    Ok(())
+}
+
+/// Move the `config` mapping out of `map` and overlay its keys onto `config`.
+fn take_config(map: &mut Mapping, path: &Path, config: &mut Mapping) -> Result<()> {
+    match map.remove("config") {
+        Some(Value::Mapping(own)) => {
+            for (key, value) in own {
+                config.insert(key, value);
+            }
+        }
+        None | Some(Value::Null) => {}
+        Some(_) => {
+            return Err(PicoFlowError::Validation(format!(
+                "'config' in {} must be a mapping",
+                path.display()
+            )))
+        }
+    }
+    Ok(())
 }
 
 /// Parse a workflow configuration from a YAML string.
@@ -83,14 +561,67 @@ pub fn parse_workflow_file<P: AsRef<Path>>(path: P) -> Result<WorkflowConfig> {
 /// # Ok::<(), picoflow::error::PicoFlowError>(())
 /// ```
 pub fn parse_workflow_yaml(content: &str) -> Result<WorkflowConfig> {
+    parse_workflow_yaml_with_env(content, &HashMap::new())
+}
+
+/// Like [`parse_workflow_yaml`], with `env` taking precedence over the process
+/// environment when expanding `${VAR}` references
+pub fn parse_workflow_yaml_with_env(
+    content: &str,
+    env: &HashMap<String, String>,
+) -> Result<WorkflowConfig> {
+    parse_yaml_with_params(content, env, None)
+}
+
+/// Like [`parse_workflow_yaml_with_env`], also supplying values for the workflow's
+/// `params` (see [`parse_workflow_file_with_params`])
+pub fn parse_workflow_yaml_with_params(
+    content: &str,
+    env: &HashMap<String, String>,
+    params: &HashMap<String, String>,
+) -> Result<WorkflowConfig> {
+    parse_yaml_with_params(content, env, Some(params))
+}
+
+/// Shared body of the `parse_workflow_yaml*` functions (see [`load_workflow_file_with_env`]
+/// for `params`)
+fn parse_yaml_with_params(
+    content: &str,
+    env: &HashMap<String, String>,
+    params: Option<&HashMap<String, String>>,
+) -> Result<WorkflowConfig> {
     // Validate YAML size limit
     if content.len() > MAX_YAML_SIZE {
         return Err(PicoFlowError::YamlSizeExceeded(content.len()));
     }
 
-    // Parse YAML
-    let mut config: WorkflowConfig = serde_yaml::from_str(content)?;
+    // Expand ${VAR} references from `env` and the host environment
+    let params = resolve_params(content, params)?;
+    let content = expand_env_vars_overriding(content, env, &params)?;
+
+    // Includes are resolved relative to the including file, so a bare string has no base
+    let mut value: Value = serde_yaml::from_str(&content)?;
+    if value.get("includes").is_some() {
+        return Err(PicoFlowError::Validation(
+            "'includes' is only supported when loading a workflow from a file".to_string(),
+        ));
+    }
+
+    // Parse YAML, keeping line numbers in errors when there are no params to substitute
+    let config: WorkflowConfig = if params.is_empty() {
+        serde_yaml::from_str(&content)?
+    } else {
+        substitute_params(&mut value, &params)?;
+        serde_yaml::from_value(value)?
+    };
+
+    validate_workflow(config)
+}
 
+/// Validate a deserialized workflow and apply global defaults to its tasks.
+///
+/// Stops at the first problem found.
+pub fn validate_workflow(mut config: WorkflowConfig) -> Result<WorkflowConfig> {
     // Validate task count
     if config.tasks.len() > MAX_TASK_COUNT {
         return Err(PicoFlowError::TaskCountExceeded {
@@ -113,17 +644,66 @@ pub fn parse_workflow_yaml(content: &str) -> Result<WorkflowConfig> {
         }
     }
 
+    if let Some(defaults) = &config.config.ssh_defaults {
+        apply_ssh_defaults(&mut config.tasks, defaults);
+    }
+
     // Validate task executor config matches type, and enforce shell security
     // constraints (absolute-path command, arg/workdir limits) statically so `validate`
     // catches them up front rather than the shell executor failing at run time.
     for task in &config.tasks {
         validate_task_executor_config(task)?;
-        if let TaskExecutorConfig::Shell(shell) = &task.config {
-            validate_shell_config(shell)?;
+        if task
+            .requires_files
+            .iter()
+            .any(|path| path.trim().is_empty())
+        {
+            return Err(PicoFlowError::Validation(format!(
+                "Task '{}' has an empty requires_files entry",
+                task.name
+            )));
+        }
+        if task
+            .cache_key
+            .as_deref()
+            .is_some_and(|key| key.trim().is_empty())
+        {
+            return Err(PicoFlowError::Validation(format!(
+                "Task '{}' has an empty cache_key",
+                task.name
+            )));
+        }
+        match (task.cache_key.is_some(), task.cache_ttl_secs) {
+            (_, Some(0)) => {
+                return Err(PicoFlowError::Validation(format!(
+                    "Task '{}' cache_ttl_secs must be greater than 0",
+                    task.name
+                )));
+            }
+            (false, Some(_)) => {
+                return Err(PicoFlowError::Validation(format!(
+                    "Task '{}' sets cache_ttl_secs without a cache_key",
+                    task.name
+                )));
+            }
+            _ => {}
+        }
+        match &task.config {
+            TaskExecutorConfig::Shell(shell) => validate_shell_config(shell)?,
+            TaskExecutorConfig::Docker(docker) => validate_docker_config(docker)?,
+            TaskExecutorConfig::Download(download) => validate_download_config(download)?,
+            TaskExecutorConfig::Ssh(ssh) if ssh.user.is_empty() => {
+                return Err(PicoFlowError::Validation(format!(
+                    "Task '{}' has no SSH user (set config.user or ssh_defaults.user)",
+                    task.name
+                )));
+            }
+            _ => {}
         }
     }
 
     // Validate task dependencies exist
+    dedupe_dependencies(&mut config)?;
     validate_dependencies(&config)?;
 
     // Validate max_parallel is at least 1 and not excessively large
@@ -138,6 +718,27 @@ pub fn parse_workflow_yaml(content: &str) -> Result<WorkflowConfig> {
         ));
     }
 
+    if config.config.memory_limit_mb == Some(0) {
+        return Err(PicoFlowError::Validation(
+            "memory_limit_mb must be at least 1".to_string(),
+        ));
+    }
+
+    if config.config.max_output_bytes == Some(0) {
+        return Err(PicoFlowError::Validation(
+            "max_output_bytes must be at least 1".to_string(),
+        ));
+    }
+
+    if let Some(max_depth) = config.config.max_depth {
+        if max_depth == 0 {
+            return Err(PicoFlowError::Validation(
+                "max_depth must be at least 1".to_string(),
+            ));
+        }
+        DagEngine::build(&config.tasks)?.check_max_depth(max_depth)?;
+    }
+
     // Validate retry counts are within bounds (prevents integer overflow in the
     // scheduler's `1..=retries + 1` loop and unbounded retry abuse).
     validate_retry_counts(&config)?;
@@ -148,6 +749,157 @@ pub fn parse_workflow_yaml(content: &str) -> Result<WorkflowConfig> {
     Ok(config)
 }
 
+/// Expand `${VAR}` references in workflow YAML from the process environment.
+///
+/// Runs on the raw YAML text before deserialization, so it applies to every field.
+///
+/// - `${VAR}` is replaced with the value of `VAR`; an undefined variable is an error
+/// - `${params.<name>}` is looked up as `params.<name>` (see
+///   [`parse_workflow_file_with_params`])
+/// - `${VAR:-default}` falls back to `default` when `VAR` is undefined
+/// - `$$` is a literal `$` (use `$${VAR}` to pass `${VAR}` through, e.g. to a shell)
+/// - `${{ ... }}` task-output expressions are left untouched
+/// - Full-line YAML comments are not expanded
+///
+/// Only names matching `[A-Za-z_][A-Za-z0-9_]*` are expanded; any other `${...}` text is
+/// kept as-is. Values are inserted verbatim, so variables are trusted input just like
+/// the workflow file itself.
+///
+/// # Errors
+///
+/// * `PicoFlowError::Validation` - If a referenced variable is undefined and has no default
+///
+/// # Example
+///
+/// ```
+/// use picoflow::parser::expand_env_vars;
+///
+/// let yaml = "url: \"https://${PICOFLOW_DOC_HOST:-localhost}/health\"";
+/// assert_eq!(expand_env_vars(yaml)?, "url: \"https://localhost/health\"");
+/// # Ok::<(), picoflow::error::PicoFlowError>(())
+/// ```
+pub fn expand_env_vars(yaml: &str) -> Result<String> {
+    expand_env_vars_with(yaml, |name| std::env::var(name).ok())
+}
+
+/// Like [`expand_env_vars`], resolving variables through `lookup` instead of the
+/// process environment.
+pub fn expand_env_vars_with<F>(yaml: &str, lookup: F) -> Result<String>
+where
+    F: Fn(&str) -> Option<String>,
+{
+    expand_vars(yaml, lookup, false)
+}
+
+/// Body of [`expand_env_vars_with`].
+///
+/// With `keep_escapes`, `$$` is kept as-is and every `$` in an inserted value is doubled,
+/// so that a later pass over the parsed strings (see [`substitute_params`]) can tell
+/// escapes from references.
+fn expand_vars<F>(yaml: &str, lookup: F, keep_escapes: bool) -> Result<String>
+where
+    F: Fn(&str) -> Option<String>,
+{
+    let escape = |value: String| {
+        if keep_escapes {
+            value.replace('$', "$$")
+        } else {
+            value
+        }
+    };
+    let mut result = String::with_capacity(yaml.len());
+
+    for (line_idx, line) in yaml.split_inclusive('\n').enumerate() {
+        if line.trim_start().starts_with('#') {
+            result.push_str(line);
+            continue;
+        }
+
+        let mut rest = line;
+        while let Some(pos) = rest.find('$') {
+            result.push_str(&rest[..pos]);
+            let after = &rest[pos + 1..];
+
+            if let Some(stripped) = after.strip_prefix('$') {
+                result.push_str(if keep_escapes { "$$" } else { "$" });
+                rest = stripped;
+                continue;
+            }
+
+            match parse_env_reference(after) {
+                // Left for `substitute_params`
+                Some((name, _, consumed))
+                    if keep_escapes
+                        && name.starts_with(PARAMS_PREFIX)
+                        && lookup(name).is_some() =>
+                {
+                    result.push_str(&rest[pos..pos + 1 + consumed]);
+                    rest = &after[consumed..];
+                    continue;
+                }
+                Some((name, default, consumed)) => {
+                    let value = match (lookup(name), default) {
+                        (Some(value), _) => escape(value),
+                        (None, Some(default)) => escape(default.to_string()),
+                        (None, None) if name.starts_with(PARAMS_PREFIX) => {
+                            return Err(PicoFlowError::Validation(format!(
+                                "Param '{}' on line {} is not declared under 'params'",
+                                &name[PARAMS_PREFIX.len()..],
+                                line_idx + 1
+                            )))
+                        }
+                        (None, None) => {
+                            return Err(PicoFlowError::Validation(format!(
+                                "Undefined environment variable '{}' on line {} \
+                                 (use ${{{}:-default}} to provide a fallback)",
+                                name,
+                                line_idx + 1,
+                                name
+                            )))
+                        }
+                    };
+                    result.push_str(&value);
+                    rest = &after[consumed..];
+                }
+                None => {
+                    result.push('$');
+                    rest = after;
+                }
+            }
+        }
+        result.push_str(rest);
+    }
+
+    Ok(result)
+}
+
+/// Parse `{NAME}` or `{NAME:-default}` at the start of `s` (the text after a `$`), where
+/// `NAME` may carry the `params.` prefix.
+///
+/// Returns the name, optional default, and the number of bytes consumed, or `None` if
+/// `s` is not a well-formed reference.
+fn parse_env_reference(s: &str) -> Option<(&str, Option<&str>, usize)> {
+    let inner_and_rest = s.strip_prefix('{')?;
+    let close = inner_and_rest.find('}')?;
+    let inner = &inner_and_rest[..close];
+
+    let (name, default) = match inner.split_once(":-") {
+        Some((name, default)) => (name, Some(default)),
+        None => (inner, None),
+    };
+
+    let mut chars = name.strip_prefix(PARAMS_PREFIX).unwrap_or(name).chars();
+    let valid_start = chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_');
+    if !valid_start || !chars.all(|c| c.is_ascii_alphanumeric() || c == '_') {
+        return None;
+    }
+
+    // '{' + inner + '}'
+    Some((name, default, close + 2))
+}
+
 /// Validate task name format and length
 fn validate_task_name(name: &str) -> Result<()> {
     // Check empty name
@@ -185,6 +937,9 @@ pub(crate) fn validate_task_executor_config(task: &TaskConfig) -> Result<()> {
         (TaskType::Shell, TaskExecutorConfig::Shell(_))
             | (TaskType::Ssh, TaskExecutorConfig::Ssh(_))
             | (TaskType::Http, TaskExecutorConfig::Http(_))
+            | (TaskType::Docker, TaskExecutorConfig::Docker(_))
+            | (TaskType::Download, TaskExecutorConfig::Download(_))
+            | (TaskType::Noop, TaskExecutorConfig::Noop(_))
     );
 
     if !config_matches {
@@ -223,16 +978,75 @@ fn validate_retry_counts(config: &WorkflowConfig) -> Result<()> {
     Ok(())
 }
 
+/// Drop repeated `depends_on` entries, keeping the first occurrence of each
+///
+/// # Errors
+///
+/// * `PicoFlowError::Validation` - If a task lists the same dependency with different
+///   `on` conditions
+fn dedupe_dependencies(config: &mut WorkflowConfig) -> Result<()> {
+    for task in &mut config.tasks {
+        let mut seen: HashMap<String, Option<DependencyCondition>> =
+            HashMap::with_capacity(task.depends_on.len());
+        for dep in &task.depends_on {
+            match seen.get(dep.task()) {
+                Some(condition) if *condition != dep.condition() => {
+                    return Err(PicoFlowError::Validation(format!(
+                        "Task '{}' lists dependency '{}' with conflicting conditions",
+                        task.name,
+                        dep.task()
+                    )));
+                }
+                Some(_) => {}
+                None => {
+                    seen.insert(dep.task().to_string(), dep.condition());
+                }
+            }
+        }
+
+        let before = task.depends_on.len();
+        let mut kept = std::collections::HashSet::with_capacity(before);
+        task.depends_on
+            .retain(|dep| kept.insert(dep.task().to_string()));
+        if task.depends_on.len() != before {
+            warn!(
+                "Task '{}' lists the same dependency more than once; duplicates ignored",
+                task.name
+            );
+        }
+    }
+    Ok(())
+}
+
 /// Validate that all task dependencies reference existing tasks
+///
+/// Also rejects a task that depends on itself, and `run_condition: on_failure` on a task
+/// without plain (non-`{task, on}`) dependencies, since it could never run.
 fn validate_dependencies(config: &WorkflowConfig) -> Result<()> {
-    let task_names: std::collections::HashSet<_> = config.tasks.iter().map(|t| &t.name).collect();
+    let task_names: std::collections::HashSet<_> =
+        config.tasks.iter().map(|t| t.name.as_str()).collect();
 
     for task in &config.tasks {
+        if task.run_condition == RunCondition::OnFailure
+            && task.depends_on.iter().all(|dep| dep.condition().is_some())
+        {
+            return Err(PicoFlowError::Validation(format!(
+                "Task '{}' has run_condition on_failure but no depends_on without an `on` condition, so it can never run",
+                task.name
+            )));
+        }
+
         for dep in &task.depends_on {
-            if !task_names.contains(dep) {
+            if dep.task() == task.name {
+                return Err(PicoFlowError::Validation(format!(
+                    "Task '{}' depends on itself",
+                    task.name
+                )));
+            }
+            if !task_names.contains(dep.task()) {
                 return Err(PicoFlowError::MissingDependency {
                     task: task.name.clone(),
-                    dependency: dep.clone(),
+                    dependency: dep.task().to_string(),
                 });
             }
         }
@@ -256,6 +1070,42 @@ fn apply_defaults(config: &mut WorkflowConfig) {
     }
 }
 
+/// Fill unset SSH connection settings of every SSH task from the workflow's `ssh_defaults`
+///
+/// A leading `~/` in the default `key_path` is expanded to the home directory.
+fn apply_ssh_defaults(tasks: &mut [TaskConfig], defaults: &SshDefaults) {
+    let mut defaults = defaults.clone();
+    defaults.key_path = defaults.key_path.map(|path| expand_home(&path));
+
+    for task in tasks {
+        if let TaskExecutorConfig::Ssh(ssh) = &mut task.config {
+            defaults.apply_to(ssh);
+        }
+    }
+}
+
+/// Expand a leading `~/` (or a bare `~`) in `path` to `$HOME`
+///
+/// Other paths, and all paths when `HOME` is unset, are returned unchanged.
+///
+/// # Example
+///
+/// ```
+/// use picoflow::parser::expand_home;
+///
+/// assert_eq!(expand_home("/etc/hosts"), "/etc/hosts");
+/// ```
+pub fn expand_home(path: &str) -> String {
+    let rest = match path.strip_prefix('~') {
+        Some(rest) if rest.is_empty() || rest.starts_with('/') => rest,
+        _ => return path.to_string(),
+    };
+    match std::env::var("HOME") {
+        Ok(home) => format!("{}{}", home.trim_end_matches('/'), rest),
+        Err(_) => path.to_string(),
+    }
+}
+
 /// Validate shell executor configuration for security compliance.
 ///
 /// This function enforces strict security constraints on shell commands to prevent
@@ -264,7 +1114,8 @@ fn apply_defaults(config: &mut WorkflowConfig) {
 /// - Command length must be <= `MAX_COMMAND_LEN` (4096) characters
 /// - Argument count must be <= `MAX_ARG_COUNT` (256)
 /// - Each argument must be <= `MAX_ARG_LEN` (4096) characters
-/// - Working directory must be absolute with no `..` traversal
+/// - Working directory and `env_file` must be absolute with no `..` traversal
+/// - `fail_on_stderr_regex` must be a valid regex
 ///
 /// # Arguments
 ///
@@ -280,7 +1131,8 @@ fn apply_defaults(config: &mut WorkflowConfig) {
 /// * `PicoFlowError::InvalidPath` - If command is not an absolute path
 /// * `PicoFlowError::ArgCountExceeded` - If more than `MAX_ARG_COUNT` (256) arguments
 /// * `PicoFlowError::ArgTooLong` - If any argument exceeds `MAX_ARG_LEN` (4096) characters
-/// * `PicoFlowError::PathTraversal` - If workdir contains `..`
+/// * `PicoFlowError::PathTraversal` - If workdir or env_file contains `..`
+/// * `PicoFlowError::Validation` - If `fail_on_stderr_regex` does not compile
 ///
 /// # Example
 ///
@@ -292,13 +1144,27 @@ fn apply_defaults(config: &mut WorkflowConfig) {
 ///     command: "/bin/echo".to_string(),
 ///     args: vec!["hello".to_string()],
 ///     workdir: Some("/tmp".to_string()),
+///     create_workdir: false,
 ///     env: None,
+///     env_file: None,
+///     fail_on_stderr_regex: None,
+///     shell: None,
 /// };
 ///
 /// validate_shell_config(&config)?;
 /// # Ok::<(), picoflow::error::PicoFlowError>(())
 /// ```
 pub fn validate_shell_config(config: &ShellConfig) -> Result<()> {
+    check_shell_config(config)?;
+    if let Some(pattern) = &config.fail_on_stderr_regex {
+        compile_stderr_regex(pattern)?;
+    }
+    Ok(())
+}
+
+/// [`validate_shell_config`] without compiling `fail_on_stderr_regex`, for the shell
+/// executor, which compiles each pattern once and caches it
+pub(crate) fn check_shell_config(config: &ShellConfig) -> Result<()> {
     // Validate command length
     if config.command.len() > MAX_COMMAND_LEN {
         return Err(PicoFlowError::CommandTooLong {
@@ -306,11 +1172,15 @@ pub fn validate_shell_config(config: &ShellConfig) -> Result<()> {
         });
     }
 
-    // Validate command is absolute path
-    if !config.command.starts_with('/') {
-        return Err(PicoFlowError::InvalidPath(
-            "Command must be an absolute path".to_string(),
-        ));
+    // Validate command is absolute path, unless it is a script for `shell`
+    match &config.shell {
+        Some(shell) => validate_path(shell)?,
+        None if !config.command.starts_with('/') => {
+            return Err(PicoFlowError::InvalidPath(
+                "Command must be an absolute path".to_string(),
+            ));
+        }
+        None => {}
     }
 
     // Validate argument count
@@ -333,18 +1203,190 @@ pub fn validate_shell_config(config: &ShellConfig) -> Result<()> {
         validate_path(workdir)?;
     }
 
+    if let Some(env_file) = &config.env_file {
+        validate_path(env_file)?;
+    }
+
     Ok(())
 }
 
-/// Validate filesystem path for security compliance.
+/// Compile a `fail_on_stderr_regex` pattern
 ///
-/// This function enforces path security constraints to prevent directory traversal
-/// attacks and ensure predictable path resolution:
-/// - Path must be absolute (starts with `/`)
-/// - Path must not contain `..` (parent directory references)
+/// The pattern is matched against the whole stderr, so `^` and `$` anchor to its start
+/// and end unless the pattern enables multi-line mode with `(?m)`.
+pub(crate) fn compile_stderr_regex(pattern: &str) -> Result<regex::Regex> {
+    regex::Regex::new(pattern)
+        .map_err(|e| PicoFlowError::Validation(format!("Invalid fail_on_stderr_regex: {}", e)))
+}
+
+/// Validate Docker executor configuration.
 ///
-/// These constraints prevent attackers from accessing files outside intended directories
-/// (e.g., `/tmp/../etc/passwd` would be rejected).
+/// Enforces the same argument limits as the shell executor, plus Docker-specific checks:
+/// - Image name must be non-empty and must not start with `-` (would be parsed as a flag)
+/// - Each volume must be `host:container` or `host:container:ro|rw`, with absolute paths
+///   and no `..` traversal on either side; `host` may also name a Docker volume
+/// - Environment variable names must be non-empty and must not contain `=`
+/// - Network name, if set, must be non-empty and must not start with `-`
+///
+/// # Errors
+///
+/// * `PicoFlowError::Validation` - If the image, a volume spec, an env name or the network
+///   is malformed
+/// * `PicoFlowError::CommandTooLong` - If command exceeds `MAX_COMMAND_LEN` (4096) characters
+/// * `PicoFlowError::ArgCountExceeded` - If more than `MAX_ARG_COUNT` (256) arguments
+/// * `PicoFlowError::ArgTooLong` - If any argument exceeds `MAX_ARG_LEN` (4096) characters
+/// * `PicoFlowError::PathTraversal` / `PicoFlowError::InvalidPath` - If a volume path is unsafe
+///
+/// # Example
+///
+/// ```
+/// use picoflow::parser::validate_docker_config;
+/// use picoflow::models::DockerConfig;
+///
+/// let config = DockerConfig {
+///     image: "alpine:3.19".to_string(),
+///     command: Some("/bin/echo".to_string()),
+///     args: vec!["hello".to_string()],
+///     env: None,
+///     volumes: vec!["/srv/data:/data:ro".to_string()],
+///     network: None,
+/// };
+///
+/// validate_docker_config(&config)?;
+/// # Ok::<(), picoflow::error::PicoFlowError>(())
+/// ```
+pub fn validate_docker_config(config: &DockerConfig) -> Result<()> {
+    if config.image.trim().is_empty() {
+        return Err(PicoFlowError::Validation(
+            "Docker image cannot be empty".to_string(),
+        ));
+    }
+    if config.image.starts_with('-') {
+        return Err(PicoFlowError::Validation(format!(
+            "Invalid Docker image '{}'",
+            config.image
+        )));
+    }
+
+    if let Some(command) = &config.command {
+        if command.len() > MAX_COMMAND_LEN {
+            return Err(PicoFlowError::CommandTooLong {
+                limit: MAX_COMMAND_LEN,
+            });
+        }
+    }
+
+    if config.args.len() > MAX_ARG_COUNT {
+        return Err(PicoFlowError::ArgCountExceeded {
+            count: config.args.len(),
+            limit: MAX_ARG_COUNT,
+        });
+    }
+    for arg in &config.args {
+        if arg.len() > MAX_ARG_LEN {
+            return Err(PicoFlowError::ArgTooLong { limit: MAX_ARG_LEN });
+        }
+    }
+
+    for volume in &config.volumes {
+        validate_volume_spec(volume)?;
+    }
+
+    if let Some(env) = &config.env {
+        for key in env.keys() {
+            if key.is_empty() || key.contains('=') {
+                return Err(PicoFlowError::Validation(format!(
+                    "Invalid Docker environment variable name '{}'",
+                    key
+                )));
+            }
+        }
+    }
+
+    if let Some(network) = &config.network {
+        if network.is_empty() || network.starts_with('-') {
+            return Err(PicoFlowError::Validation(format!(
+                "Invalid Docker network '{}'",
+                network
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+/// Validate a `host:container[:ro|rw]` volume spec, where `host` is an absolute path or
+/// the name of a Docker volume
+fn validate_volume_spec(spec: &str) -> Result<()> {
+    let parts: Vec<&str> = spec.split(':').collect();
+    let (host, container) = match parts.as_slice() {
+        [host, container] => (*host, *container),
+        [host, container, "ro" | "rw"] => (*host, *container),
+        _ => {
+            return Err(PicoFlowError::Validation(format!(
+                "Invalid volume '{}': expected 'host:container' or 'host:container:ro|rw'",
+                spec
+            )))
+        }
+    };
+
+    if !is_docker_volume_name(host) {
+        validate_path(host)?;
+    }
+    validate_path(container)?;
+    Ok(())
+}
+
+/// Whether `name` is a Docker named volume (`[A-Za-z0-9][A-Za-z0-9_.-]*`)
+fn is_docker_volume_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars.next().is_some_and(|c| c.is_ascii_alphanumeric())
+        && chars.all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '-'))
+}
+
+/// Validate a download task's configuration
+///
+/// Requires a parseable URL, an absolute `dest_path` without traversal, a timeout of
+/// 1-86400 seconds and, if given, a 64-character hex SHA-256 `checksum`. SSRF checks
+/// on the URL run in the executor.
+pub fn validate_download_config(config: &DownloadConfig) -> Result<()> {
+    if let Err(e) = url::Url::parse(&config.url) {
+        return Err(PicoFlowError::Validation(format!(
+            "Invalid download URL '{}': {}",
+            config.url, e
+        )));
+    }
+
+    validate_path(&config.dest_path)?;
+
+    if config.timeout == 0 || config.timeout > 86400 {
+        return Err(PicoFlowError::Validation(format!(
+            "Download timeout must be between 1 and 86400 seconds, got: {}",
+            config.timeout
+        )));
+    }
+
+    if let Some(checksum) = &config.checksum {
+        if checksum.len() != 64 || !checksum.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(PicoFlowError::Validation(format!(
+                "Download checksum must be a 64-character hex SHA-256 digest, got '{}'",
+                checksum
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+/// Validate filesystem path for security compliance.
+///
+/// This function enforces path security constraints to prevent directory traversal
+/// attacks and ensure predictable path resolution:
+/// - Path must be absolute (starts with `/`)
+/// - Path must not contain `..` (parent directory references)
+///
+/// These constraints prevent attackers from accessing files outside intended directories
+/// (e.g., `/tmp/../etc/passwd` would be rejected).
 ///
 /// # Arguments
 ///
@@ -456,6 +1498,20 @@ tasks:
         assert!(parse_workflow_yaml(&yaml).is_ok());
     }
 
+    #[test]
+    fn test_max_output_bytes_must_be_positive() {
+        let yaml = "name: t\nconfig:\n  max_output_bytes: {}\ntasks:\n  - name: a\n    type: shell\n    config:\n      command: /bin/true\n";
+
+        let config = parse_workflow_yaml(&yaml.replace("{}", "4096")).unwrap();
+        assert_eq!(config.config.max_output_bytes, Some(4096));
+
+        let result = parse_workflow_yaml(&yaml.replace("{}", "0"));
+        assert!(
+            matches!(result, Err(PicoFlowError::Validation(ref m)) if m.contains("max_output_bytes")),
+            "got {result:?}"
+        );
+    }
+
     #[test]
     fn test_validate_rejects_non_absolute_shell_command() {
         // `validate`/parse must reject a non-absolute shell command up front, not defer
@@ -517,6 +1573,316 @@ tasks:
         ));
     }
 
+    #[test]
+    fn test_self_dependency() {
+        let yaml = r#"
+name: test
+tasks:
+  - name: task1
+    type: shell
+    depends_on: [task1]
+    config:
+      command: "/bin/true"
+"#;
+        match parse_workflow_yaml(yaml) {
+            Err(PicoFlowError::Validation(message)) => {
+                assert_eq!(message, "Task 'task1' depends on itself")
+            }
+            other => panic!("expected a self-dependency error, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_duplicate_dependencies_deduplicated() {
+        let yaml = r#"
+name: test
+tasks:
+  - name: setup
+    type: shell
+    config:
+      command: "/bin/true"
+  - name: lint
+    type: shell
+    config:
+      command: "/bin/true"
+  - name: build
+    type: shell
+    depends_on: [setup, lint, setup, setup]
+    config:
+      command: "/bin/true"
+"#;
+        let config = parse_workflow_yaml(yaml).unwrap();
+        assert_eq!(
+            config.tasks[2].depends_on,
+            vec![Dependency::from("setup"), Dependency::from("lint")]
+        );
+    }
+
+    #[test]
+    fn test_dependency_conditions() {
+        let workflow = |deps: &str| {
+            format!(
+                "name: test\ntasks:\n  - name: a\n    type: noop\n  - name: b\n    type: noop\n  - name: c\n    type: noop\n    depends_on: {deps}\n"
+            )
+        };
+
+        let err = parse_workflow_yaml(&workflow(
+            "[a, {task: b, on: failure}, {task: a, on: complete}]",
+        ))
+        .unwrap_err();
+        assert!(err.to_string().contains("conflicting conditions"), "{err}");
+
+        let config = parse_workflow_yaml(&workflow("[a, {task: b, on: failure}]")).unwrap();
+        assert_eq!(
+            config.tasks[2].depends_on,
+            vec![
+                Dependency::from("a"),
+                Dependency::Conditional {
+                    task: "b".to_string(),
+                    on: DependencyCondition::Failure
+                }
+            ]
+        );
+        // Plain names still serialize as strings
+        let yaml = serde_yaml::to_string(&config.tasks[2].depends_on).unwrap();
+        assert_eq!(yaml, "- a\n- task: b\n  on: failure\n");
+
+        for bad in [
+            "[{task: b, on: skipped}]",
+            "[{task: b}]",
+            "[{task: b, on: success, extra: 1}]",
+            "[{task: missing, on: success}]",
+        ] {
+            assert!(parse_workflow_yaml(&workflow(bad)).is_err(), "{bad}");
+        }
+
+        // on_failure is judged by plain dependencies only
+        let yaml = workflow("[{task: b, on: failure}]") + "    run_condition: on_failure\n";
+        assert!(parse_workflow_yaml(&yaml).is_err());
+    }
+
+    #[test]
+    fn test_run_condition_parsing() {
+        let yaml = r#"
+name: test
+tasks:
+  - name: build
+    type: shell
+    config:
+      command: "/bin/true"
+  - name: cleanup
+    type: shell
+    depends_on: [build]
+    run_condition: on_failure
+    config:
+      command: "/bin/true"
+"#;
+        let config = parse_workflow_yaml(yaml).unwrap();
+        assert_eq!(config.tasks[0].run_condition, RunCondition::OnSuccess);
+        assert_eq!(config.tasks[1].run_condition, RunCondition::OnFailure);
+
+        // An on_failure task without dependencies could never run
+        let yaml = r#"
+name: test
+tasks:
+  - name: cleanup
+    type: shell
+    run_condition: on_failure
+    config:
+      command: "/bin/true"
+"#;
+        let err = parse_workflow_yaml(yaml).unwrap_err();
+        assert!(matches!(err, PicoFlowError::Validation(_)));
+        assert!(err.to_string().contains("cleanup"));
+    }
+
+    #[test]
+    fn test_enabled_parsing() {
+        let yaml = r#"
+name: test
+tasks:
+  - name: build
+    type: shell
+    config:
+      command: "/bin/true"
+  - name: migrate
+    type: shell
+    enabled: false
+    depends_on: [build]
+    config:
+      command: "/bin/true"
+"#;
+        let config = parse_workflow_yaml(yaml).unwrap();
+        assert_eq!(config.tasks[0].enabled, None);
+        assert!(config.tasks[0].is_enabled());
+        assert!(!config.tasks[1].is_enabled());
+    }
+
+    #[test]
+    fn test_requires_files_parsing() {
+        let yaml = r#"
+name: test
+tasks:
+  - name: ingest
+    type: shell
+    requires_files: [/data/input.csv]
+    missing_files_action: fail
+    config:
+      command: "/bin/true"
+  - name: report
+    type: shell
+    config:
+      command: "/bin/true"
+"#;
+        let config = parse_workflow_yaml(yaml).unwrap();
+        assert_eq!(config.tasks[0].requires_files, vec!["/data/input.csv"]);
+        assert_eq!(
+            config.tasks[0].missing_files_action,
+            MissingFilesAction::Fail
+        );
+        assert_eq!(
+            config.tasks[1].missing_files_action,
+            MissingFilesAction::Skip
+        );
+
+        let err = parse_workflow_yaml(&yaml.replace("/data/input.csv", "\"\"")).unwrap_err();
+        assert!(err.to_string().contains("empty requires_files"), "{}", err);
+    }
+
+    #[test]
+    fn test_cache_key_validation() {
+        let yaml = r#"
+name: test
+tasks:
+  - name: fetch
+    type: shell
+    cache_key: fetch-v1
+    cache_ttl_secs: 3600
+    config:
+      command: "/bin/true"
+"#;
+        let config = parse_workflow_yaml(yaml).unwrap();
+        assert_eq!(config.tasks[0].cache_key.as_deref(), Some("fetch-v1"));
+        assert_eq!(config.tasks[0].cache_ttl_secs, Some(3600));
+
+        let err = parse_workflow_yaml(&yaml.replace("3600", "0")).unwrap_err();
+        assert!(err.to_string().contains("greater than 0"), "{}", err);
+        let err = parse_workflow_yaml(&yaml.replace("fetch-v1", "\"\"")).unwrap_err();
+        assert!(err.to_string().contains("empty cache_key"), "{}", err);
+        let err = parse_workflow_yaml(&yaml.replace("    cache_key: fetch-v1\n", "")).unwrap_err();
+        assert!(err.to_string().contains("without a cache_key"), "{}", err);
+    }
+
+    #[test]
+    fn test_ssh_defaults_fill_unset_fields() {
+        let yaml = r#"
+name: test
+config:
+  ssh_defaults:
+    user: pi
+    key_path: ~/.ssh/id_ed25519
+    port: 2222
+tasks:
+  - name: inherit
+    type: ssh
+    config:
+      host: sensor-1.local
+      command: uptime
+  - name: override
+    type: ssh
+    config:
+      host: sensor-2.local
+      user: admin
+      command: uptime
+      key_path: /etc/picoflow/key
+      port: 22
+"#;
+        let config = parse_workflow_yaml(yaml).unwrap();
+        let ssh = |i: usize| match &config.tasks[i].config {
+            TaskExecutorConfig::Ssh(ssh) => ssh.clone(),
+            other => panic!("expected ssh config, got {:?}", other),
+        };
+
+        let inherit = ssh(0);
+        assert_eq!(inherit.user, "pi");
+        assert_eq!(inherit.port, Some(2222));
+        let home = std::env::var("HOME").unwrap();
+        assert_eq!(
+            inherit.key_path,
+            Some(format!("{}/.ssh/id_ed25519", home.trim_end_matches('/')))
+        );
+
+        let over = ssh(1);
+        assert_eq!(over.user, "admin");
+        assert_eq!(over.port, Some(22));
+        assert_eq!(over.key_path.as_deref(), Some("/etc/picoflow/key"));
+    }
+
+    #[test]
+    fn test_ssh_task_without_user_rejected() {
+        let yaml = r#"
+name: test
+tasks:
+  - name: remote
+    type: ssh
+    config:
+      host: sensor-1.local
+      command: uptime
+"#;
+        let err = parse_workflow_yaml(yaml).unwrap_err().to_string();
+        assert!(err.contains("no SSH user"), "{}", err);
+    }
+
+    #[test]
+    fn test_expand_home() {
+        let home = std::env::var("HOME").unwrap();
+        let home = home.trim_end_matches('/');
+        assert_eq!(
+            expand_home("~/.ssh/id_rsa"),
+            format!("{}/.ssh/id_rsa", home)
+        );
+        assert_eq!(expand_home("~"), home);
+        assert_eq!(expand_home("~other/.ssh/id_rsa"), "~other/.ssh/id_rsa");
+        assert_eq!(expand_home("/keys/~/id_rsa"), "/keys/~/id_rsa");
+    }
+
+    #[test]
+    fn test_max_depth_enforced() {
+        let yaml = |max_depth: usize| {
+            format!(
+                r#"
+name: test
+config:
+  max_depth: {max_depth}
+tasks:
+  - name: a
+    type: shell
+    config:
+      command: "/bin/true"
+  - name: b
+    type: shell
+    depends_on: [a]
+    config:
+      command: "/bin/true"
+  - name: c
+    type: shell
+    depends_on: [b]
+    config:
+      command: "/bin/true"
+"#
+            )
+        };
+
+        assert!(parse_workflow_yaml(&yaml(3)).is_ok());
+        let err = parse_workflow_yaml(&yaml(2)).unwrap_err();
+        assert!(
+            matches!(err, PicoFlowError::Validation(ref m) if m.contains("exceeds max_depth 2")),
+            "{}",
+            err
+        );
+        assert!(parse_workflow_yaml(&yaml(0)).is_err());
+    }
+
     #[test]
     fn test_apply_defaults() {
         let yaml = r#"
@@ -541,16 +1907,14 @@ tasks:
             command: "/bin/echo".to_string(),
             args: vec!["hello".to_string()],
             workdir: Some("/tmp".to_string()),
-            env: None,
+            ..Default::default()
         };
         assert!(validate_shell_config(&config).is_ok());
 
         // Invalid: relative path
         let config = ShellConfig {
             command: "echo".to_string(),
-            args: vec![],
-            workdir: None,
-            env: None,
+            ..Default::default()
         };
         assert!(matches!(
             validate_shell_config(&config),
@@ -560,14 +1924,35 @@ tasks:
         // Invalid: path traversal in workdir
         let config = ShellConfig {
             command: "/bin/echo".to_string(),
-            args: vec![],
             workdir: Some("/tmp/../etc".to_string()),
-            env: None,
+            ..Default::default()
         };
         assert!(matches!(
             validate_shell_config(&config),
             Err(PicoFlowError::PathTraversal(_))
         ));
+
+        // Invalid: fail_on_stderr_regex does not compile
+        let config = ShellConfig {
+            command: "/bin/echo".to_string(),
+            fail_on_stderr_regex: Some("FATAL(".to_string()),
+            ..Default::default()
+        };
+        let err = validate_shell_config(&config).unwrap_err();
+        assert!(matches!(err, PicoFlowError::Validation(_)));
+        assert!(err.to_string().contains("fail_on_stderr_regex"));
+
+        // With `shell`, the command is a script and the shell must be an absolute path
+        let mut config = ShellConfig {
+            command: "df -h | grep /data > /tmp/df.txt".to_string(),
+            shell: Some("/bin/sh".to_string()),
+            ..Default::default()
+        };
+        assert!(validate_shell_config(&config).is_ok());
+        config.shell = Some("bash".to_string());
+        assert!(validate_shell_config(&config).is_err());
+        config.shell = None;
+        assert!(validate_shell_config(&config).is_err());
     }
 
     #[test]
@@ -607,4 +1992,421 @@ tasks:
             Err(PicoFlowError::InvalidPath(_))
         ));
     }
+
+    #[test]
+    fn test_validate_docker_config() {
+        let valid = DockerConfig {
+            image: "alpine:3.19".to_string(),
+            command: None,
+            args: vec![],
+            env: None,
+            volumes: vec![
+                "/srv/data:/data".to_string(),
+                "/etc/app:/config:ro".to_string(),
+                "app-data:/var/lib/app".to_string(),
+                "cache_v1.2:/cache:rw".to_string(),
+            ],
+            network: Some("bridge".to_string()),
+        };
+        assert!(validate_docker_config(&valid).is_ok());
+
+        let empty_image = DockerConfig {
+            image: "  ".to_string(),
+            ..valid.clone()
+        };
+        assert!(matches!(
+            validate_docker_config(&empty_image),
+            Err(PicoFlowError::Validation(_))
+        ));
+
+        for bad in [
+            "/data",
+            "/a:/b:rx",
+            "/a:/b:ro:extra",
+            ":/data",
+            "/a:",
+            "./data:/data",
+            "-data:/data",
+            "data:relative",
+        ] {
+            let config = DockerConfig {
+                volumes: vec![bad.to_string()],
+                ..valid.clone()
+            };
+            assert!(
+                validate_docker_config(&config).is_err(),
+                "volume '{bad}' should be rejected"
+            );
+        }
+
+        let traversal = DockerConfig {
+            volumes: vec!["/srv/../etc:/etc".to_string()],
+            ..valid.clone()
+        };
+        assert!(matches!(
+            validate_docker_config(&traversal),
+            Err(PicoFlowError::PathTraversal(_))
+        ));
+    }
+
+    #[test]
+    fn test_parse_docker_task() {
+        let yaml = r#"
+name: docker-wf
+tasks:
+  - name: run
+    type: docker
+    config:
+      image: "alpine:3.19"
+      command: "/bin/echo"
+      args: ["hello"]
+      volumes: ["./relative:/data"]
+"#;
+        assert!(matches!(
+            parse_workflow_yaml(yaml),
+            Err(PicoFlowError::InvalidPath(_))
+        ));
+    }
+
+    fn lookup(name: &str) -> Option<String> {
+        match name {
+            "DB_HOST" => Some("db.local".to_string()),
+            "EMPTY" => Some(String::new()),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_expand_env_vars_defined() {
+        assert_eq!(
+            expand_env_vars_with("host: ${DB_HOST}:5432", lookup).unwrap(),
+            "host: db.local:5432"
+        );
+        assert_eq!(
+            expand_env_vars_with("v: \"${EMPTY}\"", lookup).unwrap(),
+            "v: \"\""
+        );
+    }
+
+    #[test]
+    fn test_expand_env_vars_undefined_errors() {
+        let err = expand_env_vars_with("a: 1\nb: ${MISSING}\n", lookup).unwrap_err();
+        assert!(matches!(err, PicoFlowError::Validation(_)));
+        let msg = err.to_string();
+        assert!(msg.contains("MISSING") && msg.contains("line 2"), "{msg}");
+    }
+
+    #[test]
+    fn test_expand_env_vars_default() {
+        assert_eq!(
+            expand_env_vars_with("port: ${DB_PORT:-5432}", lookup).unwrap(),
+            "port: 5432"
+        );
+        // Defined variables win over the default, even when empty
+        assert_eq!(
+            expand_env_vars_with("h: ${DB_HOST:-x} e: ${EMPTY:-x}", lookup).unwrap(),
+            "h: db.local e: "
+        );
+        assert_eq!(expand_env_vars_with("d: ${NOPE:-}", lookup).unwrap(), "d: ");
+    }
+
+    #[test]
+    fn test_expand_env_vars_escapes_and_passthrough() {
+        assert_eq!(
+            expand_env_vars_with("cost: $$5 and $${DB_HOST}", lookup).unwrap(),
+            "cost: $5 and ${DB_HOST}"
+        );
+        // Plain $VAR, malformed references and task-output expressions are untouched
+        let untouched = "echo $HOME ${1} ${ bad } ${{ tasks.a.stdout }} $";
+        assert_eq!(expand_env_vars_with(untouched, lookup).unwrap(), untouched);
+        // Full-line comments are not expanded
+        let commented = "  # needs ${MISSING}\nx: 1\n";
+        assert_eq!(expand_env_vars_with(commented, lookup).unwrap(), commented);
+    }
+
+    #[test]
+    fn test_parse_workflow_expands_env_vars() {
+        let yaml = r#"
+name: env-workflow
+tasks:
+  - name: t
+    type: shell
+    config:
+      command: "/bin/echo"
+      args: ["${PICOFLOW_TEST_UNSET_VAR_8f2c:-fallback}", "$${LITERAL}"]
+"#;
+        let config = parse_workflow_yaml(yaml).unwrap();
+        match &config.tasks[0].config {
+            TaskExecutorConfig::Shell(shell) => {
+                assert_eq!(shell.args, vec!["fallback", "${LITERAL}"]);
+            }
+            other => panic!("unexpected config: {other:?}"),
+        }
+
+        let undefined = yaml.replace(":-fallback", "");
+        assert!(matches!(
+            parse_workflow_yaml(&undefined),
+            Err(PicoFlowError::Validation(_))
+        ));
+    }
+
+    #[test]
+    fn test_parse_workflow_params() {
+        let yaml = r#"
+name: ping
+params:
+  host:
+    description: Device to probe
+  count:
+    default: "3"
+tasks:
+  - name: probe
+    type: shell
+    config:
+      command: "/bin/ping"
+      args: ["-c", "${params.count}", "${params.host}"]
+"#;
+        let args = |params: &[(&str, &str)]| {
+            let params = params
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect();
+            parse_workflow_yaml_with_params(yaml, &HashMap::new(), &params).map(|config| {
+                match &config.tasks[0].config {
+                    TaskExecutorConfig::Shell(shell) => shell.args.clone(),
+                    other => panic!("unexpected config: {other:?}"),
+                }
+            })
+        };
+
+        // Default used, required one supplied
+        assert_eq!(
+            args(&[("host", "10.0.0.5")]).unwrap(),
+            ["-c", "3", "10.0.0.5"]
+        );
+        // Supplied value overrides the default
+        assert_eq!(
+            args(&[("host", "10.0.0.5"), ("count", "1")]).unwrap(),
+            ["-c", "1", "10.0.0.5"]
+        );
+
+        let err = args(&[]).unwrap_err();
+        assert!(err.to_string().contains("Missing required param 'host'"));
+        let err = args(&[("host", "x"), ("port", "22")]).unwrap_err();
+        assert!(err.to_string().contains("Unknown param 'port'"));
+
+        // A reference to a param that isn't declared
+        let undeclared = yaml.replace("${params.count}", "${params.cnt}");
+        let err = parse_workflow_yaml_with_params(
+            &undeclared,
+            &HashMap::new(),
+            &HashMap::from([("host".to_string(), "x".to_string())]),
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("Param 'cnt'"));
+
+        // Values are substituted into the parsed strings, so quotes can't reshape the YAML
+        assert_eq!(
+            args(&[("host", "a\", \"b"), ("count", "say \"hi\"")]).unwrap(),
+            ["-c", "say \"hi\"", "a\", \"b"]
+        );
+        // `$$` escapes still work next to params, and values are taken literally
+        let escaped = yaml.replace("\"-c\"", "\"$${params.host}$$\"");
+        let config = parse_workflow_yaml_with_params(
+            &escaped,
+            &HashMap::new(),
+            &HashMap::from([("host".to_string(), "$${X}".to_string())]),
+        )
+        .unwrap();
+        match &config.tasks[0].config {
+            TaskExecutorConfig::Shell(shell) => {
+                assert_eq!(shell.args, ["${params.host}$", "3", "$${X}"])
+            }
+            other => panic!("unexpected config: {other:?}"),
+        }
+
+        // Loading without running leaves required params unset instead of failing
+        let config = parse_workflow_yaml(yaml).unwrap();
+        match &config.tasks[0].config {
+            TaskExecutorConfig::Shell(shell) => {
+                assert_eq!(shell.args, ["-c", "3", "${params.host}"])
+            }
+            other => panic!("unexpected config: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_parse_workflow_file_with_include() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(
+            dir.path().join("common.yaml"),
+            r#"
+config:
+  max_parallel: 2
+  retry_default: 1
+tasks:
+  - name: setup
+    type: shell
+    config:
+      command: "/bin/true"
+"#,
+        )
+        .unwrap();
+        let main = dir.path().join("main.yaml");
+        fs::write(
+            &main,
+            r#"
+name: with-include
+includes: [common.yaml]
+config:
+  max_parallel: 3
+tasks:
+  - name: build
+    type: shell
+    depends_on: [setup]
+    config:
+      command: "/bin/true"
+"#,
+        )
+        .unwrap();
+
+        let config = parse_workflow_file(&main).unwrap();
+        let names: Vec<&str> = config.tasks.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, vec!["setup", "build"]);
+        // The including file's config wins; keys it does not set come from the include
+        assert_eq!(config.config.max_parallel, 3);
+        assert_eq!(config.tasks[1].retry, Some(1));
+
+        // Includes have no base directory when parsing a string
+        let content = fs::read_to_string(&main).unwrap();
+        assert!(parse_workflow_yaml(&content).is_err());
+    }
+
+    #[test]
+    fn test_parse_workflow_source_ignores_file_contents() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("main.yaml");
+        fs::write(&path, "name: swapped\ntasks: []\n").unwrap();
+
+        let config = parse_workflow_source_with_params(
+            "name: checked\ntasks: []\n",
+            &path,
+            &HashMap::new(),
+            &HashMap::new(),
+        )
+        .unwrap();
+        assert_eq!(config.name, "checked");
+    }
+
+    #[test]
+    fn test_yaml_size_limit_after_expansion() {
+        let yaml = "name: test\ntasks:\n  - name: x\n    type: shell\n    config:\n      command: /bin/echo\n      args: [\"${BIG}\"]\n";
+        let env = HashMap::from([("BIG".to_string(), "x".repeat(MAX_YAML_SIZE))]);
+        assert!(matches!(
+            parse_workflow_yaml_with_env(yaml, &env),
+            Err(PicoFlowError::YamlSizeExceeded(_))
+        ));
+
+        let env = HashMap::from([("BIG".to_string(), "x".to_string())]);
+        assert!(parse_workflow_yaml_with_env(yaml, &env).is_ok());
+    }
+
+    #[test]
+    fn test_parse_workflow_file_size_limit() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("large.yaml");
+        let large_yaml = "name: test\ntasks:\n".to_string() + &"#".repeat(MAX_YAML_SIZE);
+        fs::write(&path, &large_yaml).unwrap();
+        match parse_workflow_file(&path) {
+            Err(PicoFlowError::YamlSizeExceeded(size)) => assert_eq!(size, large_yaml.len()),
+            other => panic!("expected size error, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_parse_workflow_file_include_errors() {
+        let dir = tempfile::tempdir().unwrap();
+        let looped = dir.path().join("loop.yaml");
+        fs::write(
+            &looped,
+            "name: loop\nincludes: [loop.yaml]\ntasks:\n  - name: t\n    type: shell\n    config:\n      command: /bin/true\n",
+        )
+        .unwrap();
+        match parse_workflow_file(&looped) {
+            Err(PicoFlowError::Validation(msg)) => assert!(msg.contains("cycle"), "{msg}"),
+            other => panic!("expected include cycle error, got {other:?}"),
+        }
+
+        let missing = dir.path().join("missing.yaml");
+        fs::write(
+            &missing,
+            "name: missing\nincludes: [nope.yaml]\ntasks: []\n",
+        )
+        .unwrap();
+        match parse_workflow_file(&missing) {
+            Err(PicoFlowError::Validation(msg)) => assert!(msg.contains("nope.yaml"), "{msg}"),
+            other => panic!("expected missing include error, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_download_task_parsing() {
+        let yaml = r#"
+name: fetch
+tasks:
+  - name: firmware
+    type: download
+    config:
+      url: "https://example.com/fw.bin"
+      dest_path: "/var/lib/fw.bin"
+  - name: notify
+    type: http
+    depends_on: [firmware]
+    config:
+      url: "https://example.com/hook"
+"#;
+        let config = parse_workflow_yaml(yaml).unwrap();
+        assert!(matches!(
+            config.tasks[0].config,
+            TaskExecutorConfig::Download(_)
+        ));
+        assert!(matches!(
+            config.tasks[1].config,
+            TaskExecutorConfig::Http(_)
+        ));
+
+        let relative = yaml.replace("/var/lib/fw.bin", "fw.bin");
+        assert!(parse_workflow_yaml(&relative).is_err());
+
+        let bad_checksum = yaml.replace(
+            "dest_path: \"/var/lib/fw.bin\"",
+            "dest_path: \"/var/lib/fw.bin\"\n      checksum: \"abc\"",
+        );
+        assert!(parse_workflow_yaml(&bad_checksum).is_err());
+    }
+
+    #[test]
+    fn test_noop_task_parsing_without_config() {
+        let yaml = r#"
+name: join
+tasks:
+  - name: a
+    type: shell
+    config:
+      command: "/bin/true"
+  - name: barrier
+    type: noop
+    depends_on: [a]
+"#;
+        let config = parse_workflow_yaml(yaml).unwrap();
+        assert_eq!(config.tasks[1].task_type, TaskType::Noop);
+        assert!(matches!(
+            config.tasks[1].config,
+            TaskExecutorConfig::Noop(_)
+        ));
+
+        // A shell task still needs its config
+        let missing = yaml.replace("    config:\n      command: \"/bin/true\"\n", "");
+        assert!(parse_workflow_yaml(&missing).is_err());
+    }
 }
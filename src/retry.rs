@@ -2,6 +2,8 @@
 //!
 //! This module provides configurable retry mechanisms for failed task executions.
 //! It implements exponential backoff to avoid overwhelming systems during transient failures.
+//! Optional full jitter spreads out retries from many devices hitting the same endpoint.
+//! [`BackoffStrategy`] also offers fixed delays and AWS-style decorrelated jitter.
 //!
 //! # Example
 //!
@@ -21,12 +23,28 @@
 //! let delay = state.calculate_delay(&config);
 //! ```
 
+use rand::Rng;
+use serde::{Deserialize, Serialize};
 use std::time::Duration;
 use tracing::{debug, warn};
 
 /// Maximum backoff delay in seconds (prevents unbounded exponential growth)
 pub const MAX_BACKOFF_SECONDS: u64 = 60;
 
+/// How the delay between retries grows
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum BackoffStrategy {
+    /// `base_delay * 2^retry_count`, capped at `max_delay`
+    #[default]
+    Exponential,
+    /// `min(max_delay, random(base_delay, previous_delay * 3))`, as described in the AWS
+    /// "Exponential Backoff And Jitter" article. Already randomized, so `jitter` is ignored
+    DecorrelatedJitter,
+    /// Always `base_delay` (capped at `max_delay`)
+    Fixed,
+}
+
 /// Retry configuration for task execution
 #[derive(Debug, Clone)]
 pub struct RetryConfig {
@@ -36,6 +54,10 @@ pub struct RetryConfig {
     pub base_delay: Duration,
     /// Maximum delay cap to prevent excessive waiting
     pub max_delay: Duration,
+    /// Apply full jitter: wait a random duration in `[0, computed_delay]`
+    pub jitter: bool,
+    /// How delays grow between retries
+    pub strategy: BackoffStrategy,
 }
 
 impl RetryConfig {
@@ -60,9 +82,45 @@ impl RetryConfig {
             max_retries,
             base_delay,
             max_delay,
+            jitter: false,
+            strategy: BackoffStrategy::Exponential,
         }
     }
 
+    /// Enable or disable full jitter on the computed backoff delay
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picoflow::retry::RetryConfig;
+    /// use std::time::Duration;
+    ///
+    /// let config = RetryConfig::new(3, Duration::from_secs(1), Duration::from_secs(60))
+    ///     .with_jitter(true);
+    /// assert!(config.jitter);
+    /// ```
+    pub fn with_jitter(mut self, jitter: bool) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    /// Set the backoff strategy
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picoflow::retry::{BackoffStrategy, RetryConfig, RetryState};
+    /// use std::time::Duration;
+    ///
+    /// let config = RetryConfig::new(3, Duration::from_secs(5), Duration::from_secs(60))
+    ///     .with_strategy(BackoffStrategy::Fixed);
+    /// assert_eq!(RetryState::new().calculate_delay(&config), Duration::from_secs(5));
+    /// ```
+    pub fn with_strategy(mut self, strategy: BackoffStrategy) -> Self {
+        self.strategy = strategy;
+        self
+    }
+
     /// Create default retry configuration (3 retries, 1s base, 60s max)
     pub fn default_config() -> Self {
         Self::new(3, Duration::from_secs(1), Duration::from_secs(60))
@@ -82,6 +140,9 @@ pub struct RetryState {
     pub attempt: u32,
     /// Number of retries performed (0 for first attempt)
     pub retry_count: u32,
+    /// Delay returned by the last [`next_delay`](Self::next_delay) call, which
+    /// decorrelated jitter grows from
+    pub prev_delay: Option<Duration>,
 }
 
 impl RetryState {
@@ -90,6 +151,7 @@ impl RetryState {
         Self {
             attempt: 1,
             retry_count: 0,
+            prev_delay: None,
         }
     }
 
@@ -100,21 +162,51 @@ impl RetryState {
         self.retry_count < config.max_retries
     }
 
-    /// Calculate exponential backoff delay for the next retry
+    /// Calculate the backoff delay for the next retry
+    ///
+    /// With the default [`BackoffStrategy::Exponential`]: delay = base_delay * 2^(retry_count)
+    /// Capped at max_delay to prevent excessive waiting. When `config.jitter` is set,
+    /// a random duration in `[0, delay]` is returned instead (full jitter).
     ///
-    /// Formula: delay = base_delay * 2^(retry_count)
-    /// Capped at max_delay to prevent excessive waiting.
+    /// Decorrelated jitter grows from `prev_delay`, so callers using it should go through
+    /// [`next_delay`](Self::next_delay), which records the result.
     ///
     /// # Returns
     ///
     /// Duration to wait before next retry attempt
     pub fn calculate_delay(&self, config: &RetryConfig) -> Duration {
-        let exponential_delay = config
-            .base_delay
-            .as_secs()
-            .saturating_mul(2u64.saturating_pow(self.retry_count));
+        self.calculate_delay_with_rng(config, &mut rand::thread_rng())
+    }
 
-        let capped_delay = exponential_delay.min(config.max_delay.as_secs());
+    /// Same as [`calculate_delay`](Self::calculate_delay), drawing jitter from `rng`
+    ///
+    /// Pass a seeded RNG (e.g. `StdRng::seed_from_u64`) for reproducible delays.
+    pub fn calculate_delay_with_rng<R: Rng + ?Sized>(
+        &self,
+        config: &RetryConfig,
+        rng: &mut R,
+    ) -> Duration {
+        let capped_delay = match config.strategy {
+            BackoffStrategy::Exponential => config
+                .base_delay
+                .as_secs()
+                .saturating_mul(2u64.saturating_pow(self.retry_count)),
+            BackoffStrategy::Fixed => config.base_delay.as_secs(),
+            BackoffStrategy::DecorrelatedJitter => {
+                return self.decorrelated_delay(config, rng);
+            }
+        }
+        .min(config.max_delay.as_secs());
+
+        if config.jitter {
+            let capped_ms = capped_delay.saturating_mul(1000);
+            let jittered = Duration::from_millis(rng.gen_range(0..=capped_ms));
+            debug!(
+                "Calculated backoff delay: {:?} (jittered from {}s, attempt {}, retry {})",
+                jittered, capped_delay, self.attempt, self.retry_count
+            );
+            return jittered;
+        }
 
         debug!(
             "Calculated backoff delay: {}s (attempt {}, retry {})",
@@ -124,6 +216,41 @@ impl RetryState {
         Duration::from_secs(capped_delay)
     }
 
+    /// Calculate the delay for the next retry and remember it as `prev_delay`
+    pub fn next_delay(&mut self, config: &RetryConfig) -> Duration {
+        self.next_delay_with_rng(config, &mut rand::thread_rng())
+    }
+
+    /// Same as [`next_delay`](Self::next_delay), drawing randomness from `rng`
+    pub fn next_delay_with_rng<R: Rng + ?Sized>(
+        &mut self,
+        config: &RetryConfig,
+        rng: &mut R,
+    ) -> Duration {
+        let delay = self.calculate_delay_with_rng(config, rng);
+        self.prev_delay = Some(delay);
+        delay
+    }
+
+    /// `min(cap, random(base, prev * 3))`, starting from `prev = base`
+    fn decorrelated_delay<R: Rng + ?Sized>(&self, config: &RetryConfig, rng: &mut R) -> Duration {
+        let base = config.base_delay;
+        let prev = self.prev_delay.unwrap_or(base).max(base);
+        let upper = prev.saturating_mul(3);
+        let delay = if upper > base {
+            Duration::from_millis(rng.gen_range(base.as_millis()..=upper.as_millis()) as u64)
+        } else {
+            base
+        }
+        .min(config.max_delay);
+
+        debug!(
+            "Calculated backoff delay: {:?} (decorrelated from {:?}, attempt {}, retry {})",
+            delay, prev, self.attempt, self.retry_count
+        );
+        delay
+    }
+
     /// Record a retry attempt, incrementing counters
     pub fn record_retry(&mut self) {
         self.retry_count += 1;
@@ -322,4 +449,115 @@ mod tests {
         let delay = state.calculate_delay(&config);
         assert_eq!(delay, Duration::from_secs(3600)); // Capped at max_delay
     }
+
+    #[test]
+    fn test_jitter_disabled_by_default() {
+        assert!(!RetryConfig::default().jitter);
+        assert!(!RetryConfig::new(1, Duration::from_secs(1), Duration::from_secs(2)).jitter);
+    }
+
+    #[test]
+    fn test_jittered_delay_never_exceeds_max_delay() {
+        use rand::rngs::StdRng;
+        use rand::SeedableRng;
+
+        let config =
+            RetryConfig::new(20, Duration::from_secs(1), Duration::from_secs(60)).with_jitter(true);
+        let mut rng = StdRng::seed_from_u64(42);
+
+        for retry_count in 0..20 {
+            let state = RetryState {
+                attempt: retry_count + 1,
+                retry_count,
+                prev_delay: None,
+            };
+            let unjittered = 2u64.saturating_pow(retry_count).min(60);
+            for _ in 0..50 {
+                let delay = state.calculate_delay_with_rng(&config, &mut rng);
+                assert!(delay <= config.max_delay, "{delay:?} exceeds max_delay");
+                assert!(delay <= Duration::from_secs(unjittered));
+            }
+        }
+    }
+
+    #[test]
+    fn test_jitter_is_deterministic_with_seeded_rng() {
+        use rand::rngs::StdRng;
+        use rand::SeedableRng;
+
+        let config =
+            RetryConfig::new(5, Duration::from_secs(1), Duration::from_secs(60)).with_jitter(true);
+        let state = RetryState {
+            attempt: 6,
+            retry_count: 5,
+            prev_delay: None,
+        };
+
+        let first: Vec<_> = {
+            let mut rng = StdRng::seed_from_u64(7);
+            (0..5)
+                .map(|_| state.calculate_delay_with_rng(&config, &mut rng))
+                .collect()
+        };
+        let second: Vec<_> = {
+            let mut rng = StdRng::seed_from_u64(7);
+            (0..5)
+                .map(|_| state.calculate_delay_with_rng(&config, &mut rng))
+                .collect()
+        };
+        assert_eq!(first, second);
+        // With 32s of spread, five draws should not all be identical
+        assert!(first.iter().any(|d| *d != first[0]));
+    }
+
+    #[test]
+    fn test_fixed_strategy_returns_constant_delay() {
+        let config = RetryConfig::new(10, Duration::from_secs(5), Duration::from_secs(60))
+            .with_strategy(BackoffStrategy::Fixed);
+        let mut state = RetryState::new();
+
+        for _ in 0..10 {
+            assert_eq!(state.next_delay(&config), Duration::from_secs(5));
+            state.record_retry();
+        }
+
+        // Still capped by max_delay
+        let config = RetryConfig::new(3, Duration::from_secs(90), Duration::from_secs(60))
+            .with_strategy(BackoffStrategy::Fixed);
+        assert_eq!(
+            RetryState::new().calculate_delay(&config),
+            Duration::from_secs(60)
+        );
+    }
+
+    #[test]
+    fn test_decorrelated_jitter_stays_within_base_and_cap() {
+        use rand::rngs::StdRng;
+        use rand::SeedableRng;
+
+        let base = Duration::from_secs(1);
+        let cap = Duration::from_secs(20);
+        let config =
+            RetryConfig::new(50, base, cap).with_strategy(BackoffStrategy::DecorrelatedJitter);
+        let mut rng = StdRng::seed_from_u64(42);
+
+        for _ in 0..20 {
+            let mut state = RetryState::new();
+            let mut prev = base;
+            for _ in 0..50 {
+                let delay = state.next_delay_with_rng(&config, &mut rng);
+                assert!(
+                    delay >= base && delay <= cap,
+                    "{delay:?} outside [base, cap]"
+                );
+                assert!(
+                    delay <= prev * 3,
+                    "{delay:?} grew more than 3x from {prev:?}"
+                );
+                assert_eq!(state.prev_delay, Some(delay));
+                prev = delay;
+                state.record_retry();
+            }
+        }
+    }
 }
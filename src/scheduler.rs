@@ -2,17 +2,227 @@
 
 use crate::dag::DagEngine;
 use crate::error::{PicoFlowError, Result};
+use crate::executors::docker::DockerExecutor;
+use crate::executors::download::DownloadExecutor;
 use crate::executors::http::HttpExecutor;
-use crate::executors::shell::ShellExecutor;
+use crate::executors::shell::{cancelled, ShellExecutor, TERMINATE_GRACE_PERIOD};
 use crate::executors::ssh::SshExecutor;
 use crate::executors::ExecutorTrait;
-use crate::models::{TaskConfig, TaskStatus, WorkflowConfig};
-use crate::retry::calculate_backoff_delay;
-use crate::state::StateManager;
+use crate::memory::process_memory_bytes;
+use crate::models::{
+    BodyType, DependencyCondition, ExecutionResult, FailureReason, HttpConfig, MissingFilesAction,
+    RunCondition, TaskCacheEntry, TaskConfig, TaskExecutorConfig, TaskStatus, TaskType,
+    WorkflowConfig, WorkflowGlobalConfig, MAX_OUTPUT_SIZE, MAX_RESPONSE_SIZE,
+};
+use crate::retry::{RetryConfig, RetryState, MAX_BACKOFF_SECONDS};
+use crate::state::{StateManager, HEARTBEAT_INTERVAL};
+use crate::substitution::resolve_task_config;
 use futures::stream::StreamExt;
 use std::collections::HashMap;
+use std::path::PathBuf;
 use std::sync::Arc;
-use tracing::{error, info, warn};
+use tokio::sync::{mpsc, watch};
+use tracing::{error, info, info_span, warn, Instrument};
+
+/// Outcome of running a task through the retry loop: whether it succeeded, and its
+/// captured stdout when the task has `outputs: true`
+type TaskOutcome = (bool, Option<String>);
+
+/// Backoff before the retry following `attempt` (1-based)
+///
+/// `state` carries the previous delay between calls, for decorrelated jitter. Matches
+/// `calculate_backoff_delay(attempt)` unless the config enables jitter or another strategy.
+fn backoff_delay(
+    config: &RetryConfig,
+    state: &mut RetryState,
+    attempt: u32,
+) -> std::time::Duration {
+    state.attempt = attempt;
+    state.retry_count = attempt.saturating_sub(1);
+    state.next_delay(config)
+}
+
+/// Decide whether `task` should run, given the final status of every task finished so far
+///
+/// `halted` is set once a task fails without `continue_on_failure`; after that only tasks
+/// with `run_condition: always` or `on_failure` may still run. A dependency that failed with
+/// `continue_on_failure` does not block `on_success` dependents, but still counts as a
+/// failure for `on_failure` ones.
+///
+/// `{task, on}` dependencies are checked on their own and must all be met; the
+/// `run_condition` only judges plain ones. A task with an `on: failure` or `on: complete`
+/// dependency handles failures, so like `on_failure` it may still run once halted.
+fn should_run(
+    task: &TaskConfig,
+    finished: &HashMap<String, TaskStatus>,
+    task_map: &HashMap<String, Arc<TaskConfig>>,
+    settings: &WorkflowGlobalConfig,
+    halted: bool,
+) -> bool {
+    let mut all_ok = true;
+    let mut any_failed = false;
+    let mut handles_failure = false;
+
+    for dep in &task.depends_on {
+        let dep_name = dep.task();
+        if let Some(condition) = dep.condition() {
+            if !finished
+                .get(dep_name)
+                .is_some_and(|status| condition.is_met_by(status))
+            {
+                return false;
+            }
+            handles_failure |= condition != DependencyCondition::Success;
+            continue;
+        }
+
+        match finished.get(dep_name) {
+            Some(TaskStatus::Success) => {}
+            Some(TaskStatus::Failed) | Some(TaskStatus::Timeout) => {
+                any_failed = true;
+                let tolerated = task_map
+                    .get(dep_name)
+                    .map(|d| d.continues_on_failure(settings))
+                    .unwrap_or(false);
+                if !tolerated {
+                    all_ok = false;
+                }
+            }
+            // Skipped (or never ran)
+            _ => all_ok = false,
+        }
+    }
+
+    match task.run_condition {
+        RunCondition::Always => true,
+        RunCondition::OnSuccess => (!halted || handles_failure) && all_ok,
+        RunCondition::OnFailure => any_failed,
+    }
+}
+
+/// The missing required file of a task that should be skipped because of it
+///
+/// Tasks with `missing_files_action: fail` are checked when they are launched instead, so
+/// the failure goes through the usual `continue_on_failure` handling.
+fn missing_file_to_skip(task: &TaskConfig) -> Option<&str> {
+    if task.missing_files_action == MissingFilesAction::Skip {
+        task.missing_required_file()
+    } else {
+        None
+    }
+}
+
+/// Hash identifying the cached result of a task with a `cache_key`
+///
+/// Covers the key, the task type and the config with `${{ tasks.* }}` references resolved,
+/// so a changed upstream output misses the cache. `None` for tasks without a key, or whose
+/// references cannot be resolved (they fail when run instead).
+fn task_cache_hash(task: &TaskConfig, outputs: &HashMap<String, String>) -> Option<String> {
+    let key = task.cache_key.as_ref()?;
+    let config = resolve_task_config(&task.config, outputs).ok()?;
+    // Through `Value`, whose maps are sorted, so `HashMap` fields (env, headers) hash stably
+    let value = serde_json::to_value((key, &task.task_type, &config)).ok()?;
+    let digest = openssl::sha::sha256(value.to_string().as_bytes());
+    Some(digest.iter().map(|b| format!("{:02x}", b)).collect())
+}
+
+/// Reorder each level so tasks with a higher `priority` launch first, ties in the order
+/// they are defined in the workflow
+fn order_levels_by_priority(levels: &mut [Vec<String>], tasks: &[TaskConfig]) {
+    let rank: HashMap<&str, (std::cmp::Reverse<i32>, usize)> = tasks
+        .iter()
+        .enumerate()
+        .map(|(index, task)| {
+            let priority = task.priority.unwrap_or(0);
+            (task.name.as_str(), (std::cmp::Reverse(priority), index))
+        })
+        .collect();
+    for level in levels {
+        level.sort_by_key(|name| rank.get(name.as_str()).copied());
+    }
+}
+
+/// Fail unless `previous` (task statuses of an earlier execution) covers exactly the
+/// tasks of `config`, so a resume never reuses results of a different workflow shape
+fn check_same_tasks(
+    config: &WorkflowConfig,
+    previous: &HashMap<String, TaskStatus>,
+    previous_execution_id: i64,
+) -> Result<()> {
+    let mut added: Vec<&str> = config
+        .tasks
+        .iter()
+        .map(|t| t.name.as_str())
+        .filter(|name| !previous.contains_key(*name))
+        .collect();
+    let mut removed: Vec<&str> = previous
+        .keys()
+        .map(String::as_str)
+        .filter(|name| !config.tasks.iter().any(|t| t.name == *name))
+        .collect();
+    if added.is_empty() && removed.is_empty() {
+        return Ok(());
+    }
+
+    added.sort_unstable();
+    removed.sort_unstable();
+    Err(PicoFlowError::Validation(format!(
+        "Task set changed since execution {} (added: [{}], removed: [{}]); run the workflow from scratch",
+        previous_execution_id,
+        added.join(", "),
+        removed.join(", ")
+    )))
+}
+
+/// How often memory usage is re-checked while task launches are held back
+const MEMORY_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_secs(1);
+
+/// Longest a task launch is held back for memory; it then starts regardless
+const MEMORY_MAX_WAIT: std::time::Duration = std::time::Duration::from_secs(60);
+
+/// Sleep for a retry backoff, returning `false` if shutdown was signalled first
+async fn backoff(delay: std::time::Duration, shutdown: &watch::Receiver<bool>) -> bool {
+    let mut cancel = shutdown.clone();
+    tokio::select! {
+        _ = tokio::time::sleep(delay) => true,
+        _ = cancelled(&mut cancel) => false,
+    }
+}
+
+/// Progress of a workflow run, sent to the channel set with
+/// [`TaskScheduler::set_event_sender`]
+///
+/// For each run, `WorkflowStarted` comes first and `WorkflowFinished` last. A task that
+/// runs sends `TaskStarted` and then `TaskFinished`; a task that is skipped (run
+/// condition, disabled, reused by a resume, or shutdown) only sends `TaskFinished` with
+/// `TaskStatus::Skipped`. Tasks in the same parallel level may interleave.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SchedulerEvent {
+    WorkflowStarted { workflow: String, execution_id: i64 },
+    TaskStarted { name: String },
+    TaskFinished { name: String, status: TaskStatus },
+    WorkflowFinished { status: TaskStatus },
+}
+
+/// Outcome of one workflow run, as persisted for its execution
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WorkflowRunResult {
+    /// Database ID of the execution
+    pub execution_id: i64,
+    /// Final status of the execution (`Success`, `Failed` or `Cancelled`)
+    pub status: TaskStatus,
+    /// Final status of every recorded task, in workflow declaration order
+    pub task_results: Vec<(String, TaskStatus)>,
+    /// Wall-clock time from creating the execution record to its final status
+    pub duration: std::time::Duration,
+}
+
+impl WorkflowRunResult {
+    /// Whether the workflow succeeded
+    pub fn succeeded(&self) -> bool {
+        self.status == TaskStatus::Success
+    }
+}
 
 /// Task scheduler supporting both sequential and parallel execution
 ///
@@ -23,6 +233,20 @@ pub struct TaskScheduler {
     shell_executor: Arc<ShellExecutor>,
     ssh_executor: Arc<SshExecutor>,
     http_executor: Arc<HttpExecutor>,
+    docker_executor: Arc<DockerExecutor>,
+    download_executor: Arc<DownloadExecutor>,
+    /// Re-check interval while waiting for memory under `memory_limit_mb`
+    memory_poll_interval: std::time::Duration,
+    /// Longest wait for memory under `memory_limit_mb` before launching anyway
+    memory_max_wait: std::time::Duration,
+    /// Receives progress events, if anyone subscribed
+    events: Option<mpsc::UnboundedSender<SchedulerEvent>>,
+    /// Shell output is streamed to `<dir>/<execution_id>/<task>.log` when set
+    task_log_dir: Option<PathBuf>,
+    /// Receives workflow and task measurements, when set. Behind a lock so it can be
+    /// attached to a scheduler that is already shared (see [`TaskScheduler::set_metrics`])
+    #[cfg(feature = "metrics")]
+    metrics: std::sync::RwLock<Option<Arc<crate::metrics::MetricsServer>>>,
 }
 
 impl TaskScheduler {
@@ -33,6 +257,146 @@ impl TaskScheduler {
             shell_executor: Arc::new(ShellExecutor::new()),
             ssh_executor: Arc::new(SshExecutor::new()),
             http_executor: Arc::new(HttpExecutor::new()),
+            docker_executor: Arc::new(DockerExecutor::new()),
+            download_executor: Arc::new(DownloadExecutor::new()),
+            memory_poll_interval: MEMORY_POLL_INTERVAL,
+            memory_max_wait: MEMORY_MAX_WAIT,
+            events: None,
+            task_log_dir: None,
+            #[cfg(feature = "metrics")]
+            metrics: std::sync::RwLock::new(None),
+        }
+    }
+
+    /// Send [`SchedulerEvent`]s for every run to `sender`, e.g. to drive a progress display
+    ///
+    /// The channel is unbounded so a slow consumer never holds up execution. Events are
+    /// dropped silently once the receiver is gone.
+    pub fn set_event_sender(&mut self, sender: mpsc::UnboundedSender<SchedulerEvent>) {
+        self.events = Some(sender);
+    }
+
+    /// Stream the output of shell tasks to `<dir>/<execution_id>/<task>.log` while they run
+    ///
+    /// The directory is created on demand. Each file is rotated once it reaches 10 MB; the
+    /// stored output in the database is unaffected.
+    pub fn set_task_log_dir(&mut self, dir: PathBuf) {
+        self.task_log_dir = Some(dir);
+    }
+
+    /// Remove the task log directories of executions no longer in the database
+    ///
+    /// Retention deletes old executions from the database only; this drops their
+    /// `<dir>/<execution_id>` directories as well. Entries not named by an execution ID
+    /// are left alone. Does nothing unless [`TaskScheduler::set_task_log_dir`] was called.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(usize)` - Number of directories removed
+    ///
+    /// # Errors
+    ///
+    /// * `PicoFlowError::Io` - If the log directory cannot be read or an entry removed
+    /// * `PicoFlowError::Database` - If looking up an execution fails
+    pub async fn prune_task_logs(&self) -> Result<usize> {
+        let Some(dir) = &self.task_log_dir else {
+            return Ok(0);
+        };
+        let entries = match std::fs::read_dir(dir) {
+            Ok(entries) => entries,
+            // Nothing has been logged yet
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(0),
+            Err(e) => return Err(e.into()),
+        };
+
+        let mut removed = 0;
+        for entry in entries {
+            let path = entry?.path();
+            let Some(execution_id) = path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .and_then(|name| name.parse::<i64>().ok())
+            else {
+                continue;
+            };
+            if path.is_dir()
+                && self
+                    .state_manager
+                    .get_execution(execution_id)
+                    .await?
+                    .is_none()
+            {
+                std::fs::remove_dir_all(&path)?;
+                removed += 1;
+            }
+        }
+        Ok(removed)
+    }
+
+    /// Record workflow and task executions, durations and output sizes in `metrics`
+    ///
+    /// Takes `&self` so metrics can be attached to a scheduler already shared through an
+    /// `Arc`; runs that start afterwards record into `metrics`.
+    #[cfg(feature = "metrics")]
+    pub fn set_metrics(&self, metrics: Arc<crate::metrics::MetricsServer>) {
+        *self
+            .metrics
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()) = Some(metrics);
+    }
+
+    /// The metrics set with [`TaskScheduler::set_metrics`], if any
+    #[cfg(feature = "metrics")]
+    fn metrics(&self) -> Option<Arc<crate::metrics::MetricsServer>> {
+        self.metrics
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .clone()
+    }
+
+    /// Record the captured stdout+stderr size of a finished attempt, if metrics are set
+    #[cfg_attr(not(feature = "metrics"), allow(unused_variables))]
+    fn record_output_size(&self, workflow: &str, task: &str, result: &ExecutionResult) {
+        #[cfg(feature = "metrics")]
+        if let Some(metrics) = self.metrics() {
+            let bytes = result.stdout.as_ref().map_or(0, String::len)
+                + result.stderr.as_ref().map_or(0, String::len);
+            metrics.record_task_output_size(workflow, task, bytes);
+        }
+    }
+
+    /// Count a finished task attempt with its final status, if metrics are set
+    #[cfg_attr(not(feature = "metrics"), allow(unused_variables))]
+    fn record_task_attempt(
+        &self,
+        workflow: &str,
+        task: &str,
+        status: &TaskStatus,
+        duration: std::time::Duration,
+    ) {
+        #[cfg(feature = "metrics")]
+        if let Some(metrics) = self.metrics() {
+            metrics.record_task_execution(
+                workflow,
+                task,
+                &status.to_string(),
+                duration.as_secs_f64(),
+            );
+        }
+    }
+
+    /// Count a finished workflow execution with its final status, if metrics are set
+    #[cfg_attr(not(feature = "metrics"), allow(unused_variables))]
+    fn record_workflow_run(&self, workflow: &str, status: &TaskStatus) {
+        #[cfg(feature = "metrics")]
+        if let Some(metrics) = self.metrics() {
+            metrics.record_workflow_execution(workflow, &status.to_string());
+        }
+    }
+
+    fn emit(&self, event: SchedulerEvent) {
+        if let Some(events) = &self.events {
+            let _ = events.send(event);
         }
     }
 
@@ -47,14 +411,125 @@ impl TaskScheduler {
     /// - All tasks at the same level run concurrently
     /// - `buffer_unordered` caps in-flight tasks at max_parallel across each level
     /// - Wait for all tasks at a level to complete before proceeding
-    /// - Stop on first failure unless continue_on_failure is set
+    /// - Stop on first failure unless continue_on_failure is set; only tasks whose
+    ///   `run_condition` is `always` or `on_failure` may still run afterwards
+    ///
+    /// Every task that does not run is recorded with `TaskStatus::Skipped`. Disabled tasks
+    /// (`enabled: false`) are skipped too, but count as succeeded for their dependents.
     ///
     /// # Performance
     ///
     /// Target: 10 parallel tasks <50MB memory (PRD PERF-006)
-    pub async fn execute_workflow(&self, config: &WorkflowConfig) -> Result<bool> {
-        info!("Starting workflow execution: {}", config.name);
+    pub async fn execute_workflow(&self, config: &WorkflowConfig) -> Result<WorkflowRunResult> {
+        // The sender is dropped right away, so this run can never be cancelled
+        let (_, shutdown) = watch::channel(false);
+        self.execute_workflow_with_shutdown(config, shutdown).await
+    }
+
+    /// Execute a workflow once, returning only whether it succeeded
+    ///
+    /// Shorthand for [`TaskScheduler::execute_workflow`] followed by
+    /// [`WorkflowRunResult::succeeded`].
+    pub async fn execute_workflow_ok(&self, config: &WorkflowConfig) -> Result<bool> {
+        Ok(self.execute_workflow(config).await?.succeeded())
+    }
+
+    /// Execute a workflow once, cancelling it when `shutdown` becomes `true`
+    ///
+    /// Behaves like [`TaskScheduler::execute_workflow`] until shutdown is signalled. After
+    /// that no further tasks are launched (they are recorded as skipped), running shell
+    /// commands receive SIGTERM, other running tasks are abandoned, and neither retries nor
+    /// backoff sleeps happen. Interrupted tasks and the execution itself are recorded with
+    /// `TaskStatus::Cancelled`.
+    ///
+    /// # Returns
+    ///
+    /// The run's result, with status `Failed` or `Cancelled` if it did not succeed
+    pub async fn execute_workflow_with_shutdown(
+        &self,
+        config: &WorkflowConfig,
+        shutdown: watch::Receiver<bool>,
+    ) -> Result<WorkflowRunResult> {
+        info!(workflow = %config.name, "Starting workflow execution: {}", config.name);
+
+        let (dag, workflow_id) = self.prepare_workflow(config).await?;
+        self.start_run(config, &dag, workflow_id, &HashMap::new(), &shutdown)
+            .await
+    }
+
+    /// Re-run a failed execution, reusing the results of tasks that already succeeded
+    ///
+    /// Tasks whose latest attempt in `previous_execution_id` was `Success` are not run
+    /// again: they are recorded as succeeded (with attempt 0) in the new execution, so a
+    /// later resume of it reuses them too, and their stored stdout feeds
+    /// `${{ tasks.<name>.stdout }}` references. Every other task runs as in [`TaskScheduler::execute_workflow`].
+    ///
+    /// # Errors
+    ///
+    /// * `PicoFlowError::Validation` - If the previous execution does not exist, belongs
+    ///   to another workflow, or ran a different set of tasks than `config` declares
+    pub async fn resume_execution(
+        &self,
+        config: &WorkflowConfig,
+        previous_execution_id: i64,
+    ) -> Result<WorkflowRunResult> {
+        info!(
+            workflow = %config.name,
+            "Resuming workflow '{}' from execution {}",
+            config.name,
+            previous_execution_id
+        );
+
+        let (dag, workflow_id) = self.prepare_workflow(config).await?;
+
+        let previous = self
+            .state_manager
+            .get_execution(previous_execution_id)
+            .await?
+            .filter(|execution| execution.workflow_id == workflow_id)
+            .ok_or_else(|| {
+                PicoFlowError::Validation(format!(
+                    "Execution {} is not an execution of workflow '{}'",
+                    previous_execution_id, config.name
+                ))
+            })?;
+
+        let statuses = self
+            .state_manager
+            .get_execution_task_statuses(previous.id)
+            .await?;
+        check_same_tasks(config, &statuses, previous.id)?;
+
+        let mut completed = HashMap::new();
+        for task in &config.tasks {
+            if statuses.get(&task.name) != Some(&TaskStatus::Success) {
+                continue;
+            }
+            let stdout = if task.outputs {
+                self.state_manager
+                    .get_task_output(previous.id, &task.name)
+                    .await?
+                    .map(|(stdout, _)| stdout.trim().to_string())
+                    .unwrap_or_default()
+            } else {
+                String::new()
+            };
+            completed.insert(task.name.clone(), stdout);
+        }
+        info!(
+            "Reusing {} successful task(s) from execution {}",
+            completed.len(),
+            previous.id
+        );
+
+        // The sender is dropped right away, so this run can never be cancelled
+        let (_, shutdown) = watch::channel(false);
+        self.start_run(config, &dag, workflow_id, &completed, &shutdown)
+            .await
+    }
 
+    /// Validate a workflow for execution and register it, returning its DAG and database ID
+    async fn prepare_workflow(&self, config: &WorkflowConfig) -> Result<(DagEngine, i64)> {
         // Validate that every task's executor config matches its declared type. The CLI
         // parse path already does this, but library callers can construct a WorkflowConfig
         // directly (or deserialize one) and bypass parser validation, so enforce it here
@@ -65,17 +540,152 @@ impl TaskScheduler {
 
         // Build DAG and validate
         let dag = DagEngine::build(&config.tasks)?;
+        if let Some(max_depth) = config.config.max_depth {
+            dag.check_max_depth(max_depth)?;
+        }
         info!("DAG validation successful");
 
-        // Create workflow execution record
         let workflow_id = self
             .state_manager
             .get_or_create_workflow(&config.name, config.schedule.as_deref())
             .await?;
+        self.state_manager
+            .set_workflow_labels(workflow_id, &config.labels)
+            .await?;
+
+        Ok((dag, workflow_id))
+    }
+
+    /// Create the execution record and run the workflow inside its span
+    ///
+    /// `completed` maps tasks reused from an earlier execution to their captured stdout.
+    async fn start_run(
+        &self,
+        config: &WorkflowConfig,
+        dag: &DagEngine,
+        workflow_id: i64,
+        completed: &HashMap<String, String>,
+        shutdown: &watch::Receiver<bool>,
+    ) -> Result<WorkflowRunResult> {
+        let start = std::time::Instant::now();
         let execution_id = self.state_manager.start_execution(workflow_id).await?;
+        self.state_manager
+            .record_execution_config(execution_id, config)
+            .await?;
+        self.state_manager
+            .record_execution_graph(execution_id, &config.tasks)
+            .await?;
+
+        info!(
+            workflow = %config.name,
+            execution_id,
+            "Created workflow execution record (id: {})",
+            execution_id
+        );
+        self.emit(SchedulerEvent::WorkflowStarted {
+            workflow: config.name.clone(),
+            execution_id,
+        });
+
+        // Everything logged from here on (including from tasks running concurrently under
+        // the daemon) carries the `workflow` and `execution_id` span fields
+        let span = info_span!("workflow", workflow = %config.name, execution_id);
+        let run = self
+            .run_execution(execution_id, dag, config, completed, shutdown)
+            .instrument(span);
+
+        let status = tokio::select! {
+            result = run => result?,
+            () = self.heartbeat(execution_id) => unreachable!("heartbeat loop never returns"),
+        };
+
+        let mut statuses = self
+            .state_manager
+            .get_execution_task_statuses(execution_id)
+            .await?;
+        let task_results = config
+            .tasks
+            .iter()
+            .filter_map(|task| Some((task.name.clone(), statuses.remove(&task.name)?)))
+            .collect();
+
+        let result = WorkflowRunResult {
+            execution_id,
+            status,
+            task_results,
+            duration: start.elapsed(),
+        };
+        if let Some(hook) = &config.on_complete {
+            self.notify_completion(&config.name, hook, &result).await;
+        }
+        Ok(result)
+    }
+
+    /// Send the `on_complete` request for a finished run
+    ///
+    /// Failures are logged and never change the run's result.
+    async fn notify_completion(
+        &self,
+        workflow: &str,
+        hook: &HttpConfig,
+        result: &WorkflowRunResult,
+    ) {
+        let payload = serde_json::json!({
+            "workflow": workflow,
+            "execution_id": result.execution_id,
+            "status": result.status.to_string(),
+            "duration": result.duration.as_secs_f64(),
+        });
+        let mut hook = hook.clone();
+        hook.body = match serde_yaml::to_value(&payload) {
+            Ok(body) => Some(body),
+            Err(e) => {
+                warn!(
+                    "Failed to encode on_complete payload for '{}': {}",
+                    workflow, e
+                );
+                return;
+            }
+        };
+        hook.body_type = BodyType::Json;
+
+        let config = TaskExecutorConfig::Http(hook);
+        match self.http_executor.execute(&config).await {
+            Ok(response) if response.status == TaskStatus::Success => {
+                info!(workflow = %workflow, "Sent on_complete notification");
+            }
+            Ok(response) => warn!(
+                workflow = %workflow,
+                "on_complete notification failed (HTTP status {:?})",
+                response.exit_code
+            ),
+            Err(e) => warn!(workflow = %workflow, "on_complete notification failed: {}", e),
+        }
+    }
 
-        info!("Created workflow execution record (id: {})", execution_id);
+    /// Refresh the execution's heartbeat every [`HEARTBEAT_INTERVAL`] until dropped, so
+    /// crash recovery in another process does not mistake this run for an abandoned one
+    async fn heartbeat(&self, execution_id: i64) {
+        loop {
+            tokio::time::sleep(HEARTBEAT_INTERVAL).await;
+            if let Err(e) = self.state_manager.record_heartbeat(execution_id).await {
+                warn!(
+                    "Failed to record heartbeat for execution {}: {}",
+                    execution_id, e
+                );
+            }
+        }
+    }
 
+    /// Run the tasks of a validated workflow and record the final execution status
+    async fn run_execution(
+        &self,
+        execution_id: i64,
+        dag: &DagEngine,
+        config: &WorkflowConfig,
+        completed: &HashMap<String, String>,
+        shutdown: &watch::Receiver<bool>,
+    ) -> Result<TaskStatus> {
         // Build task lookup map. Values are `Arc<TaskConfig>` so the parallel executor can
         // hand each spawned task a cheap refcount bump instead of a deep clone of its
         // command/args/env on every level.
@@ -85,17 +695,28 @@ impl TaskScheduler {
             task_map.insert(task.name.clone(), Arc::new(task.clone()));
         }
 
+        // Levels of independent tasks, each ordered by priority
+        let mut parallel_levels = dag.parallel_levels();
+        order_levels_by_priority(&mut parallel_levels, &config.tasks);
+
         // Execute workflow based on max_parallel setting
         let workflow_success = if config.config.max_parallel == 1 {
-            // Sequential execution (Phase 1 behavior)
+            // Sequential execution (Phase 1 behavior), one level after another
             info!("Executing workflow sequentially (max_parallel=1)");
-            let execution_order = dag.topological_sort()?;
+            let execution_order: Vec<String> = parallel_levels.into_iter().flatten().collect();
             info!("Execution order: {:?}", execution_order);
-            self.execute_sequential(execution_id, &execution_order, &task_map)
-                .await?
+            self.execute_sequential(
+                execution_id,
+                &config.name,
+                &execution_order,
+                &task_map,
+                &config.config,
+                completed,
+                shutdown,
+            )
+            .await?
         } else {
             // Parallel execution by DAG levels (Phase 3)
-            let parallel_levels = dag.parallel_levels();
             info!(
                 "Executing workflow in parallel (max_parallel={}, levels={})",
                 config.config.max_parallel,
@@ -103,9 +724,12 @@ impl TaskScheduler {
             );
             self.execute_parallel(
                 execution_id,
+                &config.name,
                 &parallel_levels,
                 &task_map,
-                config.config.max_parallel,
+                &config.config,
+                completed,
+                shutdown,
             )
             .await?
         };
@@ -113,6 +737,8 @@ impl TaskScheduler {
         // Update workflow execution status
         let final_status = if workflow_success {
             TaskStatus::Success
+        } else if *shutdown.borrow() {
+            TaskStatus::Cancelled
         } else {
             TaskStatus::Failed
         };
@@ -120,40 +746,102 @@ impl TaskScheduler {
         self.state_manager
             .update_execution_status(execution_id, final_status.clone())
             .await?;
+        self.record_workflow_run(&config.name, &final_status);
+        self.emit(SchedulerEvent::WorkflowFinished {
+            status: final_status.clone(),
+        });
+
+        info!(
+            status = %final_status,
+            "Workflow execution completed with status: {}",
+            final_status
+        );
 
-        info!("Workflow execution completed with status: {}", final_status);
-
-        Ok(workflow_success)
+        Ok(final_status)
     }
 
     /// Execute tasks sequentially in topological order
+    #[allow(clippy::too_many_arguments)]
     async fn execute_sequential(
         &self,
         execution_id: i64,
+        workflow: &str,
         execution_order: &[String],
         task_map: &HashMap<String, Arc<TaskConfig>>,
+        settings: &WorkflowGlobalConfig,
+        completed: &HashMap<String, String>,
+        shutdown: &watch::Receiver<bool>,
     ) -> Result<bool> {
         let mut workflow_success = true;
+        let mut halted = false;
+        // Final status of every task handled so far, for evaluating run conditions
+        let mut finished: HashMap<String, TaskStatus> = HashMap::new();
+        // Captured stdout of completed tasks with `outputs: true`, keyed by task name
+        let mut outputs: HashMap<String, String> = HashMap::new();
 
         for task_name in execution_order {
             let task = task_map.get(task_name).ok_or_else(|| {
                 PicoFlowError::Other(format!("internal error: unknown task '{task_name}'"))
             })?;
 
-            info!("Executing task: {}", task_name);
+            if *shutdown.borrow() {
+                self.skip_cancelled_task(execution_id, task).await?;
+                workflow_success = false;
+                finished.insert(task_name.clone(), TaskStatus::Skipped);
+                continue;
+            }
+
+            if let Some(stdout) = completed.get(task_name) {
+                self.reuse_completed_task(execution_id, task, stdout, settings)
+                    .await?;
+                if task.outputs {
+                    outputs.insert(task_name.clone(), stdout.clone());
+                }
+                finished.insert(task_name.clone(), TaskStatus::Success);
+                continue;
+            }
+
+            if !task.is_enabled() {
+                self.skip_disabled_task(execution_id, task).await?;
+                finished.insert(task_name.clone(), TaskStatus::Success);
+                continue;
+            }
+
+            if !should_run(task, &finished, task_map, settings, halted) {
+                self.skip_task(execution_id, task).await?;
+                finished.insert(task_name.clone(), TaskStatus::Skipped);
+                continue;
+            }
+
+            if let Some(path) = missing_file_to_skip(task) {
+                self.skip_missing_file_task(execution_id, task, path)
+                    .await?;
+                finished.insert(task_name.clone(), TaskStatus::Skipped);
+                continue;
+            }
+
+            info!(task = %task_name, "Executing task: {}", task_name);
 
             // Execute task with retry logic
-            let task_success = self.execute_task_with_retry(execution_id, task).await?;
+            let (task_success, captured) = self
+                .execute_task_with_retry(execution_id, workflow, task, &outputs, settings, shutdown)
+                .await?;
+            if let Some(stdout) = captured {
+                outputs.insert(task_name.clone(), stdout);
+            }
 
-            if !task_success {
+            if task_success {
+                finished.insert(task_name.clone(), TaskStatus::Success);
+            } else {
                 workflow_success = false;
+                finished.insert(task_name.clone(), TaskStatus::Failed);
 
-                if !task.continue_on_failure {
+                if !task.continues_on_failure(settings) {
                     error!(
                         "Task '{}' failed and continue_on_failure=false, stopping workflow",
                         task_name
                     );
-                    break;
+                    halted = true;
                 } else {
                     warn!(
                         "Task '{}' failed but continue_on_failure=true, continuing",
@@ -171,16 +859,25 @@ impl TaskScheduler {
     /// Each level runs with bounded concurrency (`buffer_unordered(max_parallel)`), so at
     /// most `max_parallel` task futures are in flight at once regardless of how wide the
     /// level is. All tasks at a level must complete before moving to the next level.
-    /// Tasks are skipped if their dependencies failed (unless those deps had continue_on_failure).
+    /// Tasks whose run condition is not met (see [`should_run`]) are skipped.
+    #[allow(clippy::too_many_arguments)]
     async fn execute_parallel(
         &self,
         execution_id: i64,
+        workflow: &str,
         parallel_levels: &[Vec<String>],
         task_map: &HashMap<String, Arc<TaskConfig>>,
-        max_parallel: usize,
+        settings: &WorkflowGlobalConfig,
+        completed: &HashMap<String, String>,
+        shutdown: &watch::Receiver<bool>,
     ) -> Result<bool> {
         let mut workflow_success = true;
-        let mut failed_tasks: std::collections::HashSet<String> = std::collections::HashSet::new();
+        let mut halted = false;
+        // Final status of every task handled so far, for evaluating run conditions
+        let mut finished: HashMap<String, TaskStatus> = HashMap::new();
+        // Captured stdout of completed tasks with `outputs: true`. Dependents always sit in a
+        // later level, so the map is only read while a level runs and extended afterwards.
+        let mut outputs: HashMap<String, String> = HashMap::new();
 
         for (level_num, level_tasks) in parallel_levels.iter().enumerate() {
             info!(
@@ -190,34 +887,36 @@ impl TaskScheduler {
                 level_tasks
             );
 
-            // Decide which tasks in this level are runnable (their dependencies did not
-            // fail — or failed but were marked continue_on_failure).
+            // Decide which tasks in this level are runnable. Dependencies always sit in an
+            // earlier level, so their final status is already known.
             let mut runnable: Vec<Arc<TaskConfig>> = Vec::new();
             for task_name in level_tasks {
                 let task = task_map.get(task_name).ok_or_else(|| {
                     PicoFlowError::Other(format!("internal error: unknown task '{task_name}'"))
                 })?;
 
-                let mut should_skip = false;
-                for dep_name in &task.depends_on {
-                    if failed_tasks.contains(dep_name) {
-                        let dep_skips = task_map
-                            .get(dep_name)
-                            .map(|d| !d.continue_on_failure)
-                            .unwrap_or(true);
-                        if dep_skips {
-                            warn!(
-                                "Skipping task '{}' because dependency '{}' failed",
-                                task_name, dep_name
-                            );
-                            should_skip = true;
-                            failed_tasks.insert(task_name.clone());
-                            break;
-                        }
+                if *shutdown.borrow() {
+                    self.skip_cancelled_task(execution_id, task).await?;
+                    workflow_success = false;
+                    finished.insert(task_name.clone(), TaskStatus::Skipped);
+                } else if let Some(stdout) = completed.get(task_name) {
+                    self.reuse_completed_task(execution_id, task, stdout, settings)
+                        .await?;
+                    if task.outputs {
+                        outputs.insert(task_name.clone(), stdout.clone());
                     }
-                }
-
-                if !should_skip {
+                    finished.insert(task_name.clone(), TaskStatus::Success);
+                } else if !task.is_enabled() {
+                    self.skip_disabled_task(execution_id, task).await?;
+                    finished.insert(task_name.clone(), TaskStatus::Success);
+                } else if !should_run(task, &finished, task_map, settings, halted) {
+                    self.skip_task(execution_id, task).await?;
+                    finished.insert(task_name.clone(), TaskStatus::Skipped);
+                } else if let Some(path) = missing_file_to_skip(task) {
+                    self.skip_missing_file_task(execution_id, task, path)
+                        .await?;
+                    finished.insert(task_name.clone(), TaskStatus::Skipped);
+                } else {
                     runnable.push(Arc::clone(task));
                 }
             }
@@ -226,31 +925,46 @@ impl TaskScheduler {
             // keeps at most `max_parallel` task futures in flight at once, so memory and
             // scheduling cost scale with the concurrency limit rather than the (possibly
             // very wide) level size.
-            let results: Vec<(String, bool, Result<bool>)> = futures::stream::iter(runnable)
+            let outputs_ref = &outputs;
+            let results: Vec<(String, bool, Result<TaskOutcome>)> = futures::stream::iter(runnable)
                 .map(|task| async move {
                     let name = task.name.clone();
-                    let continue_on_failure = task.continue_on_failure;
-                    let result = self.execute_task_with_retry(execution_id, &task).await;
+                    let continue_on_failure = task.continues_on_failure(settings);
+                    let result = self
+                        .execute_task_with_retry(
+                            execution_id,
+                            workflow,
+                            &task,
+                            outputs_ref,
+                            settings,
+                            shutdown,
+                        )
+                        .await;
                     (name, continue_on_failure, result)
                 })
-                .buffer_unordered(max_parallel)
+                .buffer_unordered(settings.max_parallel)
                 .collect()
                 .await;
 
             // Check results and track failed tasks
             for (task_name, continue_on_failure, result) in results {
                 match result {
-                    Ok(task_success) => {
-                        if !task_success {
+                    Ok((task_success, captured)) => {
+                        if let Some(stdout) = captured {
+                            outputs.insert(task_name.clone(), stdout);
+                        }
+                        if task_success {
+                            finished.insert(task_name, TaskStatus::Success);
+                        } else {
                             workflow_success = false;
-                            failed_tasks.insert(task_name.clone());
+                            finished.insert(task_name.clone(), TaskStatus::Failed);
 
                             if !continue_on_failure {
                                 error!(
                                     "Task '{}' failed and continue_on_failure=false, stopping workflow",
                                     task_name
                                 );
-                                return Ok(false);
+                                halted = true;
                             } else {
                                 warn!(
                                     "Task '{}' failed but continue_on_failure=true, continuing",
@@ -261,7 +975,6 @@ impl TaskScheduler {
                     }
                     Err(e) => {
                         error!("Task '{}' execution error: {}", task_name, e);
-                        failed_tasks.insert(task_name);
                         return Ok(false);
                     }
                 }
@@ -271,62 +984,443 @@ impl TaskScheduler {
         Ok(workflow_success)
     }
 
-    /// Execute a single task with retry logic
-    async fn execute_task_with_retry(&self, execution_id: i64, task: &TaskConfig) -> Result<bool> {
-        let max_retries = task.retry.unwrap_or(3);
-        let timeout = task.timeout.unwrap_or(300);
-        // Total attempts = initial try + retries. `saturating_add` guards against overflow
-        // for library callers that bypass parser validation (the parser caps retry at
-        // MAX_RETRY_COUNT); without it, retry == u32::MAX would wrap the range to empty and
-        // the task would silently never run.
-        let total_attempts = max_retries.saturating_add(1);
+    /// Record `task` as skipped and report it finished
+    async fn record_skipped(&self, execution_id: i64, task: &TaskConfig) -> Result<()> {
+        self.state_manager
+            .record_skipped_task(execution_id, &task.name)
+            .await?;
+        self.emit(SchedulerEvent::TaskFinished {
+            name: task.name.clone(),
+            status: TaskStatus::Skipped,
+        });
+        Ok(())
+    }
 
-        for attempt in 1..=total_attempts {
-            info!(
-                "Executing task '{}' (attempt {}/{})",
-                task.name, attempt, total_attempts
-            );
+    /// Record a task whose run condition was not met as skipped
+    async fn skip_task(&self, execution_id: i64, task: &TaskConfig) -> Result<()> {
+        info!(
+            "Skipping task '{}' (run_condition: {})",
+            task.name, task.run_condition
+        );
+        self.record_skipped(execution_id, task).await
+    }
 
-            // Start task execution record
-            let task_exec_id = self
-                .state_manager
-                .start_task(execution_id, &task.name, attempt as i32)
-                .await?;
+    /// Record a disabled task as skipped
+    ///
+    /// Callers treat it as succeeded when evaluating dependents, so disabling a task in the
+    /// middle of a chain does not block the tasks after it.
+    async fn skip_disabled_task(&self, execution_id: i64, task: &TaskConfig) -> Result<()> {
+        info!(task = %task.name, "Skipping task '{}': disabled", task.name);
+        self.record_skipped(execution_id, task).await
+    }
 
-            // Execute task
-            let result = self.execute_task(task, timeout).await;
+    /// Record a task whose success (and `stdout`, for `outputs: true`) is reused from the
+    /// resumed execution as succeeded
+    async fn reuse_completed_task(
+        &self,
+        execution_id: i64,
+        task: &TaskConfig,
+        stdout: &str,
+        settings: &WorkflowGlobalConfig,
+    ) -> Result<()> {
+        info!(
+            task = %task.name,
+            "Skipping task '{}': succeeded in the resumed execution",
+            task.name
+        );
+        let store = task.outputs && settings.store_output.stores(&TaskStatus::Success);
+        self.state_manager
+            .record_reused_task(execution_id, &task.name, Some(stdout).filter(|_| store))
+            .await?;
+        self.emit(SchedulerEvent::TaskFinished {
+            name: task.name.clone(),
+            status: TaskStatus::Success,
+        });
+        Ok(())
+    }
 
-            match result {
-                Ok(exec_result) => {
-                    // Update task status in database
-                    self.state_manager
-                        .update_task_status(
-                            task_exec_id,
-                            exec_result.status.clone(),
-                            exec_result.exit_code,
-                            exec_result.stdout.as_deref(),
-                            exec_result.stderr.as_deref(),
-                        )
-                        .await?;
+    /// Record a task skipped because one of its `requires_files` is missing
+    async fn skip_missing_file_task(
+        &self,
+        execution_id: i64,
+        task: &TaskConfig,
+        path: &str,
+    ) -> Result<()> {
+        info!(
+            task = %task.name,
+            "Skipping task '{}': required file '{}' is missing",
+            task.name,
+            path
+        );
+        self.record_skipped(execution_id, task).await
+    }
 
-                    if exec_result.status == TaskStatus::Success {
-                        info!("Task '{}' completed successfully", task.name);
-                        return Ok(true);
-                    } else {
-                        error!(
-                            "Task '{}' failed with exit code {:?}",
-                            task.name, exec_result.exit_code
-                        );
+    /// Record a failed attempt for a task whose required file `path` is missing
+    ///
+    /// Used with `missing_files_action: fail`; the task is not run or retried.
+    async fn fail_missing_file_task(
+        &self,
+        execution_id: i64,
+        task: &TaskConfig,
+        path: &str,
+        settings: &WorkflowGlobalConfig,
+    ) -> Result<TaskOutcome> {
+        error!(
+            task = %task.name,
+            "Task '{}' failed: required file '{}' is missing",
+            task.name,
+            path
+        );
+        let task_exec_id = self
+            .state_manager
+            .start_task(execution_id, &task.name, 1)
+            .await?;
+        let stderr = settings
+            .store_output
+            .stores(&TaskStatus::Failed)
+            .then(|| format!("Required file '{}' is missing or unreadable", path));
+        self.state_manager
+            .update_task_status(
+                task_exec_id,
+                TaskStatus::Failed,
+                None,
+                None,
+                stderr.as_deref(),
+            )
+            .await?;
+        self.state_manager
+            .set_failure_reason(task_exec_id, FailureReason::Failed)
+            .await?;
+        Ok((false, None))
+    }
 
-                        if attempt <= max_retries {
-                            let delay = calculate_backoff_delay(attempt);
-                            warn!(
-                                "Task '{}' will retry in {} seconds (attempt {}/{})",
-                                task.name,
-                                delay.as_secs(),
-                                attempt + 1,
-                                total_attempts
-                            );
+    /// Record a task that was not launched because shutdown was signalled as skipped
+    async fn skip_cancelled_task(&self, execution_id: i64, task: &TaskConfig) -> Result<()> {
+        info!(task = %task.name, "Skipping task '{}': shutting down", task.name);
+        self.record_skipped(execution_id, task).await
+    }
+
+    /// Execute a single task with retry logic
+    ///
+    /// Returns whether the task succeeded, plus its trimmed stdout when it succeeded and
+    /// has `outputs: true`. `outputs` holds the captured stdout of earlier tasks and is
+    /// used to resolve `${{ tasks.<name>.stdout }}` references before each attempt.
+    /// Backoff between attempts follows `settings` (e.g. `retry_jitter`), and the task's
+    /// retries are clamped to `settings.max_retries_cap`.
+    ///
+    /// A task with `delay_before` first waits that long; the wait is not part of any
+    /// attempt's timeout. If shutdown is signalled during the wait, the task is recorded
+    /// as skipped.
+    ///
+    /// All attempts run inside a `task` span nested in the caller's workflow span.
+    async fn execute_task_with_retry(
+        &self,
+        execution_id: i64,
+        workflow: &str,
+        task: &TaskConfig,
+        outputs: &HashMap<String, String>,
+        settings: &WorkflowGlobalConfig,
+        shutdown: &watch::Receiver<bool>,
+    ) -> Result<TaskOutcome> {
+        let span = info_span!("task", task = %task.name);
+        if let Some(delay) = task.delay_before.filter(|&secs| secs > 0) {
+            info!(task = %task.name, "Waiting {}s before starting task '{}'", delay, task.name);
+            if !backoff(std::time::Duration::from_secs(delay), shutdown).await {
+                self.skip_cancelled_task(execution_id, task).await?;
+                return Ok((false, None));
+            }
+        }
+        // A noop task spawns nothing, so there is no memory to wait for
+        let memory_limit_mb = settings
+            .memory_limit_mb
+            .filter(|_| task.task_type != TaskType::Noop);
+        if let Some(limit_mb) = memory_limit_mb {
+            self.wait_for_memory(&task.name, limit_mb, shutdown)
+                .instrument(span.clone())
+                .await;
+        }
+        self.emit(SchedulerEvent::TaskStarted {
+            name: task.name.clone(),
+        });
+        let outcome = match task.missing_required_file() {
+            Some(path) if task.missing_files_action == MissingFilesAction::Fail => {
+                self.fail_missing_file_task(execution_id, task, path, settings)
+                    .await?
+            }
+            _ if task.task_type == TaskType::Noop => {
+                self.complete_noop_task(execution_id, task).await?
+            }
+            _ => {
+                self.run_task_attempts(execution_id, workflow, task, outputs, settings, shutdown)
+                    .instrument(span)
+                    .await?
+            }
+        };
+        let status = if outcome.0 {
+            TaskStatus::Success
+        } else if *shutdown.borrow() {
+            TaskStatus::Cancelled
+        } else {
+            TaskStatus::Failed
+        };
+        self.emit(SchedulerEvent::TaskFinished {
+            name: task.name.clone(),
+            status,
+        });
+        Ok(outcome)
+    }
+
+    /// Hold back a task launch while process RSS is above `limit_mb`
+    ///
+    /// This is soft backpressure: memory is polled every `memory_poll_interval`, and the
+    /// task starts anyway after `memory_max_wait` or on shutdown, so a limit below the
+    /// process's baseline slows workflows down rather than stalling them.
+    async fn wait_for_memory(&self, task: &str, limit_mb: u64, shutdown: &watch::Receiver<bool>) {
+        let limit = limit_mb.saturating_mul(1024 * 1024);
+        let started = std::time::Instant::now();
+        let mut warned = false;
+
+        loop {
+            let rss = match process_memory_bytes() {
+                Ok(rss) if rss > limit => rss,
+                Ok(_) => break,
+                Err(e) => {
+                    warn!("memory_limit_mb ignored: {}", e);
+                    break;
+                }
+            };
+            if !warned {
+                warn!(
+                    task = %task,
+                    "Memory usage {} MiB exceeds memory_limit_mb={}, delaying task '{}'",
+                    rss / (1024 * 1024),
+                    limit_mb,
+                    task
+                );
+                warned = true;
+            }
+            let waited = started.elapsed();
+            if waited >= self.memory_max_wait {
+                warn!(
+                    task = %task,
+                    "Starting task '{}' after waiting {:?} for memory to drop",
+                    task,
+                    waited
+                );
+                break;
+            }
+            let delay = self.memory_poll_interval.min(self.memory_max_wait - waited);
+            if !backoff(delay, shutdown).await {
+                break;
+            }
+        }
+    }
+
+    /// Record a `noop` task as succeeded without running anything
+    async fn complete_noop_task(
+        &self,
+        execution_id: i64,
+        task: &TaskConfig,
+    ) -> Result<TaskOutcome> {
+        info!(task = %task.name, "Task '{}' is a noop, nothing to run", task.name);
+        let task_exec_id = self
+            .state_manager
+            .start_task(execution_id, &task.name, 1)
+            .await?;
+        self.state_manager
+            .update_task_status(task_exec_id, TaskStatus::Success, None, None, None)
+            .await?;
+        Ok((true, task.outputs.then(String::new)))
+    }
+
+    /// Record a task as succeeded with a result reused from the task cache
+    async fn use_cached_result(
+        &self,
+        execution_id: i64,
+        task: &TaskConfig,
+        entry: TaskCacheEntry,
+        settings: &WorkflowGlobalConfig,
+    ) -> Result<TaskOutcome> {
+        info!(
+            task = %task.name,
+            "Task '{}' served from cache (result from {})",
+            task.name,
+            entry.created_at.format("%Y-%m-%d %H:%M:%S")
+        );
+        let store = settings.store_output.stores(&TaskStatus::Success);
+        self.state_manager
+            .record_cached_task(
+                execution_id,
+                &task.name,
+                entry.exit_code,
+                entry.stdout.as_deref().filter(|_| store),
+                entry.stderr.as_deref().filter(|_| store),
+            )
+            .await?;
+        let captured = task
+            .outputs
+            .then(|| entry.stdout.as_deref().unwrap_or("").trim().to_string());
+        Ok((true, captured))
+    }
+
+    /// Retry loop behind [`Self::execute_task_with_retry`]
+    async fn run_task_attempts(
+        &self,
+        execution_id: i64,
+        workflow: &str,
+        task: &TaskConfig,
+        outputs: &HashMap<String, String>,
+        settings: &WorkflowGlobalConfig,
+        shutdown: &watch::Receiver<bool>,
+    ) -> Result<TaskOutcome> {
+        let cache_hash = task_cache_hash(task, outputs);
+        if let Some(hash) = &cache_hash {
+            let max_age = task.cache_ttl_secs.map(std::time::Duration::from_secs);
+            if let Some(entry) = self.state_manager.get_cached_result(hash, max_age).await? {
+                return self
+                    .use_cached_result(execution_id, task, entry, settings)
+                    .await;
+            }
+        }
+
+        let requested_retries = task.retry.unwrap_or(settings.retry_default);
+        let max_retries = task.effective_retry(settings);
+        if max_retries < requested_retries {
+            warn!(
+                task = %task.name,
+                "Task '{}' requests {} retries; capped at max_retries_cap={}",
+                task.name,
+                requested_retries,
+                max_retries
+            );
+        }
+        let retry_config = RetryConfig::new(
+            max_retries,
+            std::time::Duration::from_secs(1),
+            std::time::Duration::from_secs(MAX_BACKOFF_SECONDS),
+        )
+        .with_jitter(settings.retry_jitter)
+        .with_strategy(settings.backoff_strategy);
+        let mut retry_state = RetryState::new();
+        let timeout = task.effective_timeout(settings);
+        // Total attempts = initial try + retries. `saturating_add` guards against overflow
+        // for library callers that bypass parser validation (the parser caps retry at
+        // MAX_RETRY_COUNT); without it, retry == u32::MAX would wrap the range to empty and
+        // the task would silently never run.
+        let total_attempts = max_retries.saturating_add(1);
+
+        for attempt in 1..=total_attempts {
+            info!(
+                task = %task.name,
+                attempt,
+                "Executing task '{}' (attempt {}/{})",
+                task.name,
+                attempt,
+                total_attempts
+            );
+
+            // Start task execution record
+            let task_exec_id = self
+                .state_manager
+                .start_task(execution_id, &task.name, attempt as i32)
+                .await?;
+
+            // Execute task
+            let attempt_started = std::time::Instant::now();
+            let result = self
+                .execute_task(
+                    execution_id,
+                    task,
+                    timeout,
+                    outputs,
+                    settings.max_output_bytes,
+                    shutdown,
+                )
+                .await;
+
+            match result {
+                Ok(exec_result) => {
+                    self.record_output_size(workflow, &task.name, &exec_result);
+                    self.record_task_attempt(
+                        workflow,
+                        &task.name,
+                        &exec_result.status,
+                        attempt_started.elapsed(),
+                    );
+                    // Update task status in database
+                    let store = settings.store_output.stores(&exec_result.status);
+                    self.state_manager
+                        .update_task_status(
+                            task_exec_id,
+                            exec_result.status.clone(),
+                            exec_result.exit_code,
+                            exec_result.stdout.as_deref().filter(|_| store),
+                            exec_result.stderr.as_deref().filter(|_| store),
+                        )
+                        .await?;
+                    if store && exec_result.output_truncated {
+                        self.state_manager
+                            .mark_output_truncated(task_exec_id)
+                            .await?;
+                    }
+                    if !matches!(
+                        exec_result.status,
+                        TaskStatus::Success | TaskStatus::Cancelled
+                    ) {
+                        self.state_manager
+                            .set_failure_reason(task_exec_id, FailureReason::Failed)
+                            .await?;
+                    }
+
+                    if exec_result.status == TaskStatus::Cancelled {
+                        warn!(task = %task.name, "Task '{}' cancelled by shutdown", task.name);
+                        return Ok((false, None));
+                    } else if exec_result.status == TaskStatus::Success {
+                        info!(task = %task.name, "Task '{}' completed successfully", task.name);
+                        if let Some(hash) = &cache_hash {
+                            // Without stored output, keep only the stdout dependents read
+                            let store = settings.store_output.stores(&TaskStatus::Success);
+                            self.state_manager
+                                .store_cached_result(
+                                    hash,
+                                    &task.name,
+                                    exec_result.exit_code,
+                                    exec_result
+                                        .stdout
+                                        .as_deref()
+                                        .filter(|_| store || task.outputs),
+                                    exec_result.stderr.as_deref().filter(|_| store),
+                                    task.cache_ttl_secs.map(std::time::Duration::from_secs),
+                                )
+                                .await?;
+                        }
+                        let captured = task.outputs.then(|| {
+                            exec_result
+                                .stdout
+                                .as_deref()
+                                .unwrap_or("")
+                                .trim()
+                                .to_string()
+                        });
+                        return Ok((true, captured));
+                    } else {
+                        error!(
+                            task = %task.name,
+                            exit_code = ?exec_result.exit_code,
+                            "Task '{}' failed with exit code {:?}",
+                            task.name,
+                            exec_result.exit_code
+                        );
+
+                        if attempt <= max_retries {
+                            let delay = backoff_delay(&retry_config, &mut retry_state, attempt);
+                            warn!(
+                                "Task '{}' will retry in {:.1} seconds (attempt {}/{})",
+                                task.name,
+                                delay.as_secs_f64(),
+                                attempt + 1,
+                                total_attempts
+                            );
 
                             // Set retry information
                             let next_retry_at = chrono::Utc::now()
@@ -336,82 +1430,190 @@ impl TaskScheduler {
                                 .set_task_retry(task_exec_id, (attempt - 1) as i32, next_retry_at)
                                 .await?;
 
-                            tokio::time::sleep(delay).await;
+                            if !backoff(delay, shutdown).await {
+                                return Ok((false, None));
+                            }
                         } else {
                             error!(
+                                task = %task.name,
                                 "Task '{}' failed after {} attempts",
-                                task.name, total_attempts
+                                task.name,
+                                total_attempts
                             );
-                            return Ok(false);
+                            return Ok((false, None));
                         }
                     }
                 }
                 Err(e) => {
-                    // Classify timeouts by the typed error variant rather than by matching
+                    // Classify errors by their typed variant rather than by matching
                     // formatted text, which would break silently if wording changed (or
                     // misfire on an unrelated error whose message contains "timed out").
-                    let is_timeout = e
-                        .downcast_ref::<PicoFlowError>()
-                        .map(|pe| matches!(pe, PicoFlowError::TaskTimeout { .. }))
-                        .unwrap_or(false);
-                    let status = if is_timeout {
+                    let reason = classify_error(&e);
+                    let status = if reason == FailureReason::Timeout {
                         TaskStatus::Timeout
                     } else {
                         TaskStatus::Failed
                     };
+                    self.record_task_attempt(
+                        workflow,
+                        &task.name,
+                        &status,
+                        attempt_started.elapsed(),
+                    );
 
-                    error!("Task '{}' execution error ({}): {}", task.name, status, e);
+                    error!(
+                        task = %task.name,
+                        status = %status,
+                        "Task '{}' execution error ({}): {}",
+                        task.name,
+                        status,
+                        e
+                    );
 
                     // Update task status
+                    let stderr = settings
+                        .store_output
+                        .stores(&status)
+                        .then(|| format!("Execution error: {}", e));
                     self.state_manager
-                        .update_task_status(
-                            task_exec_id,
-                            status,
-                            None,
-                            None,
-                            Some(&format!("Execution error: {}", e)),
-                        )
+                        .update_task_status(task_exec_id, status, None, None, stderr.as_deref())
                         .await?;
+                    self.state_manager
+                        .set_failure_reason(task_exec_id, reason)
+                        .await?;
+
+                    // An unresolvable output reference fails identically on every attempt,
+                    // so don't burn retries (and backoff sleeps) on it.
+                    let is_validation = e
+                        .downcast_ref::<PicoFlowError>()
+                        .map(|pe| matches!(pe, PicoFlowError::Validation(_)))
+                        .unwrap_or(false);
 
-                    if attempt <= max_retries {
-                        let delay = calculate_backoff_delay(attempt);
+                    if attempt <= max_retries && !is_validation {
+                        let delay = backoff_delay(&retry_config, &mut retry_state, attempt);
                         warn!(
-                            "Task '{}' will retry in {} seconds after error",
+                            "Task '{}' will retry in {:.1} seconds after error",
                             task.name,
-                            delay.as_secs()
+                            delay.as_secs_f64()
                         );
-                        tokio::time::sleep(delay).await;
+                        if !backoff(delay, shutdown).await {
+                            return Ok((false, None));
+                        }
                     } else {
-                        return Ok(false);
+                        return Ok((false, None));
                     }
                 }
             }
         }
 
-        Ok(false)
+        Ok((false, None))
     }
 
     /// Execute a single task with timeout enforcement
+    ///
+    /// Task-output references in the config are resolved against `outputs` first; an
+    /// unresolvable reference fails with `PicoFlowError::Validation`.
+    ///
+    /// `max_output_bytes` overrides how much of each output stream executors keep
+    /// (`MAX_OUTPUT_SIZE`, or `MAX_RESPONSE_SIZE` for HTTP bodies).
+    ///
+    /// When `shutdown` fires, shell commands are terminated by the shell executor; any other
+    /// task is abandoned. Either way the result has `TaskStatus::Cancelled`.
     async fn execute_task(
         &self,
+        execution_id: i64,
         task: &TaskConfig,
         timeout_secs: u64,
-    ) -> anyhow::Result<crate::models::ExecutionResult> {
+        outputs: &HashMap<String, String>,
+        max_output_bytes: Option<usize>,
+        shutdown: &watch::Receiver<bool>,
+    ) -> anyhow::Result<ExecutionResult> {
         use tokio::time::{timeout, Duration};
 
+        let config = resolve_task_config(&task.config, outputs)?;
+        let start = std::time::Instant::now();
+        let mut cancel = shutdown.clone();
+        let output_limit = max_output_bytes.unwrap_or(MAX_OUTPUT_SIZE);
+
         // Wrap task execution with timeout
         let task_future = async {
-            match task.task_type {
-                crate::models::TaskType::Shell => self.shell_executor.execute(&task.config).await,
-                crate::models::TaskType::Ssh => self.ssh_executor.execute(&task.config).await,
-                crate::models::TaskType::Http => self.http_executor.execute(&task.config).await,
+            let execution: ExecutionFuture<'_> = match task.task_type {
+                TaskType::Shell => {
+                    let Some(log_dir) = &self.task_log_dir else {
+                        return self
+                            .shell_executor
+                            .execute_cancellable(
+                                &config,
+                                shutdown.clone(),
+                                timeout_secs,
+                                output_limit,
+                            )
+                            .await;
+                    };
+                    let log_path = log_dir
+                        .join(execution_id.to_string())
+                        .join(format!("{}.log", task.name));
+                    return self
+                        .shell_executor
+                        .execute_logged(
+                            &config,
+                            shutdown.clone(),
+                            timeout_secs,
+                            output_limit,
+                            &log_path,
+                        )
+                        .await;
+                }
+                TaskType::Ssh => Box::pin(self.ssh_executor.execute_with_timeout(
+                    &config,
+                    timeout_secs,
+                    output_limit,
+                )),
+                TaskType::Http => self.http_executor.execute_with_output_limit(
+                    &config,
+                    max_output_bytes.unwrap_or(MAX_RESPONSE_SIZE),
+                ),
+                TaskType::Docker => self
+                    .docker_executor
+                    .execute_with_output_limit(&config, output_limit),
+                TaskType::Download => self.download_executor.execute(&config),
+                // Handled before any attempt is made; see `complete_noop_task`
+                TaskType::Noop => {
+                    return Ok(ExecutionResult {
+                        status: TaskStatus::Success,
+                        stdout: None,
+                        stderr: None,
+                        exit_code: None,
+                        duration: start.elapsed(),
+                        output_truncated: false,
+                        output_binary: false,
+                    })
+                }
+            };
+            tokio::select! {
+                result = execution => result,
+                _ = cancelled(&mut cancel) => Ok(ExecutionResult {
+                    status: TaskStatus::Cancelled,
+                    stdout: None,
+                    stderr: Some("Cancelled by shutdown".to_string()),
+                    exit_code: None,
+                    duration: start.elapsed(),
+                    output_truncated: false,
+                    output_binary: false,
+                }),
             }
         };
 
         // Apply timeout. On elapse, return a *typed* timeout error so the caller can
-        // classify it as TaskStatus::Timeout without string matching.
-        match timeout(Duration::from_secs(timeout_secs), task_future).await {
-            Ok(result) => result,
+        // classify it as TaskStatus::Timeout without string matching. Shell commands time
+        // out by themselves so they can be terminated (SIGTERM, then SIGKILL); for them
+        // this is only a backstop.
+        let mut limit = Duration::from_secs(timeout_secs);
+        if task.task_type == TaskType::Shell {
+            limit += TERMINATE_GRACE_PERIOD + Duration::from_secs(2);
+        }
+        match timeout(limit, task_future).await {
+            Ok(result) => result.map_err(|e| attribute_to_task(e, &task.name)),
             Err(_) => Err(anyhow::Error::new(PicoFlowError::TaskTimeout {
                 task: task.name.clone(),
                 timeout: timeout_secs,
@@ -420,10 +1622,53 @@ impl TaskScheduler {
     }
 }
 
+/// Map an execution error onto the reason persisted with the task attempt
+fn classify_error(e: &anyhow::Error) -> FailureReason {
+    match e.downcast_ref::<PicoFlowError>() {
+        Some(PicoFlowError::TaskTimeout { .. }) => FailureReason::Timeout,
+        Some(PicoFlowError::TaskSpawn { .. }) => FailureReason::Spawn,
+        _ => FailureReason::Error,
+    }
+}
+
+/// A boxed executor call, as returned by `ExecutorTrait` methods
+type ExecutionFuture<'a> = std::pin::Pin<
+    Box<dyn std::future::Future<Output = anyhow::Result<ExecutionResult>> + Send + 'a>,
+>;
+
+/// Executors name the command or image in timeout and spawn errors; report the task
+/// name instead so the error identifies the workflow task that failed
+fn attribute_to_task(e: anyhow::Error, task: &str) -> anyhow::Error {
+    // Only unwrap the error we rewrite; anything else keeps its context chain intact
+    if !matches!(
+        e.downcast_ref::<PicoFlowError>(),
+        Some(PicoFlowError::TaskTimeout { .. } | PicoFlowError::TaskSpawn { .. })
+    ) {
+        return e;
+    }
+    match e.downcast::<PicoFlowError>() {
+        Ok(PicoFlowError::TaskTimeout { timeout, .. }) => {
+            anyhow::Error::new(PicoFlowError::TaskTimeout {
+                task: task.to_string(),
+                timeout,
+            })
+        }
+        Ok(PicoFlowError::TaskSpawn { source, .. }) => {
+            anyhow::Error::new(PicoFlowError::TaskSpawn {
+                task: task.to_string(),
+                source,
+            })
+        }
+        Ok(other) => anyhow::Error::new(other),
+        Err(e) => e,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::models::{ShellConfig, TaskExecutorConfig, TaskType};
+    use crate::models::{NoopConfig, ShellConfig, TaskExecutorConfig};
+    use crate::retry::calculate_backoff_delay;
 
     #[test]
     fn test_calculate_backoff_delay() {
@@ -457,6 +1702,39 @@ mod tests {
         ); // Capped
     }
 
+    #[test]
+    fn test_backoff_delay_matches_legacy_without_jitter() {
+        let config = RetryConfig::new(
+            10,
+            std::time::Duration::from_secs(1),
+            std::time::Duration::from_secs(MAX_BACKOFF_SECONDS),
+        );
+        for attempt in 1..=10 {
+            assert_eq!(
+                backoff_delay(&config, &mut RetryState::new(), attempt),
+                calculate_backoff_delay(attempt)
+            );
+        }
+    }
+
+    #[test]
+    fn test_backoff_delay_with_jitter_stays_within_legacy_delay() {
+        let config = RetryConfig::new(
+            10,
+            std::time::Duration::from_secs(1),
+            std::time::Duration::from_secs(MAX_BACKOFF_SECONDS),
+        )
+        .with_jitter(true);
+        for attempt in 1..=10 {
+            for _ in 0..20 {
+                assert!(
+                    backoff_delay(&config, &mut RetryState::new(), attempt)
+                        <= calculate_backoff_delay(attempt)
+                );
+            }
+        }
+    }
+
     #[tokio::test]
     async fn test_execute_simple_workflow() {
         let state_manager = Arc::new(StateManager::in_memory().await.unwrap());
@@ -466,6 +1744,11 @@ mod tests {
             name: "test-workflow".to_string(),
             description: Some("Test".to_string()),
             schedule: None,
+            timezone: None,
+            labels: HashMap::new(),
+            allow_overlap: false,
+            on_complete: None,
+            params: HashMap::new(),
             config: Default::default(),
             tasks: vec![TaskConfig {
                 name: "task1".to_string(),
@@ -474,16 +1757,25 @@ mod tests {
                 config: TaskExecutorConfig::Shell(ShellConfig {
                     command: "/bin/echo".to_string(),
                     args: vec!["hello".to_string()],
-                    workdir: None,
-                    env: None,
+                    ..Default::default()
                 }),
                 retry: Some(1),
                 timeout: Some(10),
-                continue_on_failure: false,
+                delay_before: None,
+                continue_on_failure: None,
+                outputs: false,
+                run_condition: Default::default(),
+                enabled: None,
+                labels: HashMap::new(),
+                requires_files: vec![],
+                missing_files_action: Default::default(),
+                cache_key: None,
+                cache_ttl_secs: None,
+                priority: None,
             }],
         };
 
-        let success = scheduler.execute_workflow(&config).await.unwrap();
+        let success = scheduler.execute_workflow_ok(&config).await.unwrap();
         assert!(success);
 
         // Verify execution was recorded
@@ -495,6 +1787,64 @@ mod tests {
         assert_eq!(history[0].status, TaskStatus::Success);
     }
 
+    #[tokio::test]
+    async fn test_execute_workflow_result_matches_persisted() {
+        let state_manager = Arc::new(StateManager::in_memory().await.unwrap());
+        let scheduler = TaskScheduler::new(state_manager.clone());
+
+        let mut failing = shell_task("fail", "/bin/sh", &["-c", "exit 1"], 10);
+        failing.depends_on = vec!["ok".into()];
+        let mut dependent = shell_task("after", "/bin/echo", &["never"], 10);
+        dependent.depends_on = vec!["fail".into()];
+        let config = WorkflowConfig {
+            name: "result-workflow".to_string(),
+            description: None,
+            schedule: None,
+            timezone: None,
+            labels: HashMap::new(),
+            allow_overlap: false,
+            on_complete: None,
+            params: HashMap::new(),
+            config: Default::default(),
+            tasks: vec![
+                shell_task("ok", "/bin/echo", &["hi"], 10),
+                failing,
+                dependent,
+            ],
+        };
+
+        let result = scheduler.execute_workflow(&config).await.unwrap();
+        assert!(!result.succeeded());
+        assert_eq!(result.status, TaskStatus::Failed);
+        assert_eq!(
+            result.task_results,
+            vec![
+                ("ok".to_string(), TaskStatus::Success),
+                ("fail".to_string(), TaskStatus::Failed),
+                ("after".to_string(), TaskStatus::Skipped),
+            ]
+        );
+
+        let history = state_manager
+            .get_execution_history("result-workflow", 1)
+            .await
+            .unwrap();
+        assert_eq!(result.execution_id, history[0].id);
+        assert_eq!(result.status, history[0].status);
+        let persisted = state_manager
+            .get_execution_task_statuses(result.execution_id)
+            .await
+            .unwrap();
+        assert_eq!(persisted.len(), result.task_results.len());
+        for (task, status) in &result.task_results {
+            assert_eq!(&persisted[task], status);
+        }
+        assert!(result.duration > std::time::Duration::ZERO);
+
+        // The bool wrapper agrees
+        assert!(!scheduler.execute_workflow_ok(&config).await.unwrap());
+    }
+
     #[tokio::test]
     async fn test_execute_failing_workflow() {
         let state_manager = Arc::new(StateManager::in_memory().await.unwrap());
@@ -504,6 +1854,11 @@ mod tests {
             name: "fail-workflow".to_string(),
             description: None,
             schedule: None,
+            timezone: None,
+            labels: HashMap::new(),
+            allow_overlap: false,
+            on_complete: None,
+            params: HashMap::new(),
             config: Default::default(),
             tasks: vec![TaskConfig {
                 name: "failing_task".to_string(),
@@ -512,16 +1867,25 @@ mod tests {
                 config: TaskExecutorConfig::Shell(ShellConfig {
                     command: "/bin/sh".to_string(),
                     args: vec!["-c".to_string(), "exit 1".to_string()],
-                    workdir: None,
-                    env: None,
+                    ..Default::default()
                 }),
                 retry: Some(1),
                 timeout: Some(10),
-                continue_on_failure: false,
+                delay_before: None,
+                continue_on_failure: None,
+                outputs: false,
+                run_condition: Default::default(),
+                enabled: None,
+                labels: HashMap::new(),
+                requires_files: vec![],
+                missing_files_action: Default::default(),
+                cache_key: None,
+                cache_ttl_secs: None,
+                priority: None,
             }],
         };
 
-        let success = scheduler.execute_workflow(&config).await.unwrap();
+        let success = scheduler.execute_workflow_ok(&config).await.unwrap();
         assert!(!success);
 
         // Verify execution was recorded as failed
@@ -542,6 +1906,11 @@ mod tests {
             name: "continue-workflow".to_string(),
             description: None,
             schedule: None,
+            timezone: None,
+            labels: HashMap::new(),
+            allow_overlap: false,
+            on_complete: None,
+            params: HashMap::new(),
             config: Default::default(),
             tasks: vec![
                 TaskConfig {
@@ -551,31 +1920,49 @@ mod tests {
                     config: TaskExecutorConfig::Shell(ShellConfig {
                         command: "/bin/sh".to_string(),
                         args: vec!["-c".to_string(), "exit 1".to_string()],
-                        workdir: None,
-                        env: None,
+                        ..Default::default()
                     }),
                     retry: Some(0),
                     timeout: Some(10),
-                    continue_on_failure: true, // Continue despite failure
+                    delay_before: None,
+                    continue_on_failure: Some(true), // Continue despite failure
+                    outputs: false,
+                    run_condition: Default::default(),
+                    enabled: None,
+                    labels: HashMap::new(),
+                    requires_files: vec![],
+                    missing_files_action: Default::default(),
+                    cache_key: None,
+                    cache_ttl_secs: None,
+                    priority: None,
                 },
                 TaskConfig {
                     name: "task2".to_string(),
                     task_type: TaskType::Shell,
-                    depends_on: vec!["task1".to_string()],
+                    depends_on: vec!["task1".into()],
                     config: TaskExecutorConfig::Shell(ShellConfig {
                         command: "/bin/echo".to_string(),
                         args: vec!["task2".to_string()],
-                        workdir: None,
-                        env: None,
+                        ..Default::default()
                     }),
                     retry: Some(0),
                     timeout: Some(10),
-                    continue_on_failure: false,
+                    delay_before: None,
+                    continue_on_failure: None,
+                    outputs: false,
+                    run_condition: Default::default(),
+                    enabled: None,
+                    labels: HashMap::new(),
+                    requires_files: vec![],
+                    missing_files_action: Default::default(),
+                    cache_key: None,
+                    cache_ttl_secs: None,
+                    priority: None,
                 },
             ],
         };
 
-        let success = scheduler.execute_workflow(&config).await.unwrap();
+        let success = scheduler.execute_workflow_ok(&config).await.unwrap();
         // Overall workflow fails because task1 failed, but task2 should have executed
         assert!(!success);
 
@@ -605,11 +1992,16 @@ mod tests {
             name: "empty".to_string(),
             description: None,
             schedule: None,
+            timezone: None,
+            labels: HashMap::new(),
+            allow_overlap: false,
+            on_complete: None,
+            params: HashMap::new(),
             config: Default::default(),
             tasks: vec![],
         };
 
-        let success = scheduler.execute_workflow(&config).await.unwrap();
+        let success = scheduler.execute_workflow_ok(&config).await.unwrap();
         assert!(success, "an empty workflow should succeed as a no-op");
 
         let history = state_manager
@@ -636,6 +2028,11 @@ mod tests {
             name: "retry-recover".to_string(),
             description: None,
             schedule: None,
+            timezone: None,
+            labels: HashMap::new(),
+            allow_overlap: false,
+            on_complete: None,
+            params: HashMap::new(),
             config: Default::default(),
             tasks: vec![TaskConfig {
                 name: "flaky".to_string(),
@@ -650,16 +2047,25 @@ mod tests {
                         "if [ -e \"$0\" ]; then exit 0; else : > \"$0\"; exit 1; fi".to_string(),
                         marker_str,
                     ],
-                    workdir: None,
-                    env: None,
+                    ..Default::default()
                 }),
                 retry: Some(1),
                 timeout: Some(10),
-                continue_on_failure: false,
+                delay_before: None,
+                continue_on_failure: None,
+                outputs: false,
+                run_condition: Default::default(),
+                enabled: None,
+                labels: HashMap::new(),
+                requires_files: vec![],
+                missing_files_action: Default::default(),
+                cache_key: None,
+                cache_ttl_secs: None,
+                priority: None,
             }],
         };
 
-        let success = scheduler.execute_workflow(&config).await.unwrap();
+        let success = scheduler.execute_workflow_ok(&config).await.unwrap();
         assert!(success, "task should succeed on the second attempt");
 
         // Two task-execution rows: the failed first attempt and the successful retry.
@@ -680,50 +2086,1614 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn test_max_parallel_bounds_concurrency() {
-        // Four independent tasks (all at DAG level 0) each sleep 0.4s. With max_parallel=2
-        // they must run in two waves, so wall-clock is ~0.8s. If the concurrency cap were
-        // not enforced, all four would run at once (~0.4s). We assert only a lower bound to
-        // stay robust on slow CI.
+    async fn test_task_attempts_are_ordered() {
         let state_manager = Arc::new(StateManager::in_memory().await.unwrap());
         let scheduler = TaskScheduler::new(state_manager.clone());
 
-        let make_task = |n: usize| TaskConfig {
-            name: format!("sleep{n}"),
+        let mut task = shell_task("b", "/bin/sh", &["-c", "exit 1"], 10);
+        task.retry = Some(2);
+        let config = WorkflowConfig {
+            name: "attempts".to_string(),
+            description: None,
+            schedule: None,
+            timezone: None,
+            labels: HashMap::new(),
+            allow_overlap: false,
+            on_complete: None,
+            params: HashMap::new(),
+            config: Default::default(),
+            tasks: vec![task],
+        };
+
+        assert!(!scheduler.execute_workflow_ok(&config).await.unwrap());
+
+        let history = state_manager
+            .get_execution_history("attempts", 1)
+            .await
+            .unwrap();
+        let attempts = state_manager
+            .get_task_attempts(history[0].id, "b")
+            .await
+            .unwrap();
+        assert_eq!(
+            attempts.iter().map(|a| a.attempt).collect::<Vec<_>>(),
+            vec![1, 2, 3]
+        );
+        // Attempts that were followed by a retry are left in the Retrying state
+        assert_eq!(
+            attempts
+                .iter()
+                .map(|a| a.status.clone())
+                .collect::<Vec<_>>(),
+            vec![
+                TaskStatus::Retrying,
+                TaskStatus::Retrying,
+                TaskStatus::Failed
+            ]
+        );
+        assert!(attempts.iter().all(|a| a.exit_code == Some(1)));
+        assert!(state_manager
+            .get_task_attempts(history[0].id, "missing")
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    fn shell_task(name: &str, command: &str, args: &[&str], timeout: u64) -> TaskConfig {
+        TaskConfig {
+            name: name.to_string(),
             task_type: TaskType::Shell,
             depends_on: vec![],
             config: TaskExecutorConfig::Shell(ShellConfig {
-                command: "/bin/sleep".to_string(),
-                args: vec!["0.4".to_string()],
-                workdir: None,
-                env: None,
+                command: command.to_string(),
+                args: args.iter().map(|a| a.to_string()).collect(),
+                ..Default::default()
             }),
             retry: Some(0),
-            timeout: Some(10),
-            continue_on_failure: false,
-        };
+            timeout: Some(timeout),
+            delay_before: None,
+            continue_on_failure: None,
+            outputs: false,
+            run_condition: Default::default(),
+            enabled: None,
+            labels: HashMap::new(),
+            requires_files: vec![],
+            missing_files_action: Default::default(),
+            cache_key: None,
+            cache_ttl_secs: None,
+            priority: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_execute_task_errors_are_typed() {
+        let state_manager = Arc::new(StateManager::in_memory().await.unwrap());
+        let scheduler = TaskScheduler::new(state_manager);
+        let (_tx, shutdown) = watch::channel(false);
+
+        let slow = shell_task("slow", "/bin/sleep", &["5"], 1);
+        let err = scheduler
+            .execute_task(1, &slow, 1, &HashMap::new(), None, &shutdown)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<PicoFlowError>(),
+            Some(PicoFlowError::TaskTimeout { task, timeout: 1 }) if task == "slow"
+        ));
+
+        // Errors raised inside an executor are attributed to the task as well
+        let err = attribute_to_task(
+            anyhow::Error::new(PicoFlowError::TaskTimeout {
+                task: "uptime".to_string(),
+                timeout: 5,
+            }),
+            "check-host",
+        );
+        assert!(matches!(
+            err.downcast_ref::<PicoFlowError>(),
+            Some(PicoFlowError::TaskTimeout { task, timeout: 5 }) if task == "check-host"
+        ));
+
+        let missing = shell_task("missing", "/nonexistent/bin/tool", &[], 10);
+        let err = scheduler
+            .execute_task(1, &missing, 10, &HashMap::new(), None, &shutdown)
+            .await
+            .unwrap_err();
+        match err.downcast_ref::<PicoFlowError>() {
+            Some(PicoFlowError::TaskSpawn { task, source }) => {
+                assert_eq!(task, "missing");
+                assert_eq!(source.kind(), std::io::ErrorKind::NotFound);
+            }
+            other => panic!("expected TaskSpawn, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_failure_reason_persisted() {
+        let state_manager = Arc::new(StateManager::in_memory().await.unwrap());
+        let scheduler = TaskScheduler::new(state_manager.clone());
 
         let config = WorkflowConfig {
-            name: "parallel-bound".to_string(),
+            name: "failure-reasons".to_string(),
             description: None,
             schedule: None,
-            config: crate::models::WorkflowGlobalConfig {
-                max_parallel: 2,
-                retry_default: 0,
-                timeout_default: 10,
-            },
-            tasks: (0..4).map(make_task).collect(),
+            timezone: None,
+            labels: HashMap::new(),
+            allow_overlap: false,
+            on_complete: None,
+            params: HashMap::new(),
+            config: Default::default(),
+            tasks: vec![
+                shell_task("ok", "/bin/true", &[], 10),
+                shell_task("exits", "/bin/false", &[], 10),
+                shell_task("slow", "/bin/sleep", &["5"], 1),
+                shell_task("missing", "/nonexistent/bin/tool", &[], 10),
+            ],
         };
 
-        let start = std::time::Instant::now();
-        let success = scheduler.execute_workflow(&config).await.unwrap();
-        let elapsed = start.elapsed();
+        assert!(!scheduler.execute_workflow_ok(&config).await.unwrap());
 
-        assert!(success);
-        assert!(
-            elapsed >= std::time::Duration::from_millis(650),
-            "4 tasks @0.4s with max_parallel=2 should take ~0.8s (two waves), took {elapsed:?} — \
-             concurrency cap not enforced?"
-        );
+        let history = state_manager
+            .get_execution_history("failure-reasons", 1)
+            .await
+            .unwrap();
+        let reasons: HashMap<_, _> = state_manager
+            .get_task_executions(history[0].id)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|t| (t.task_name, t.failure_reason))
+            .collect();
+
+        assert_eq!(reasons["ok"], None);
+        assert_eq!(reasons["exits"], Some(FailureReason::Failed));
+        assert_eq!(reasons["slow"], Some(FailureReason::Timeout));
+        assert_eq!(reasons["missing"], Some(FailureReason::Spawn));
+    }
+
+    #[tokio::test]
+    async fn test_resume_execution_skips_succeeded_tasks() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let runs = temp.path().join("runs");
+        let fixed = temp.path().join("fixed");
+        let runs_str = runs.to_string_lossy().to_string();
+        let fixed_str = fixed.to_string_lossy().to_string();
+
+        let state_manager = Arc::new(StateManager::in_memory().await.unwrap());
+        let scheduler = TaskScheduler::new(state_manager.clone());
+
+        let a = shell_task("a", "/bin/sh", &["-c", "echo run >> \"$0\"", &runs_str], 10);
+        let mut b = shell_task("b", "/bin/sh", &["-c", "[ -e \"$0\" ]", &fixed_str], 10);
+        b.depends_on = vec!["a".into()];
+        let mut c = shell_task("c", "/bin/true", &[], 10);
+        c.depends_on = vec!["b".into()];
+        let mut config = WorkflowConfig {
+            name: "resume".to_string(),
+            description: None,
+            schedule: None,
+            timezone: None,
+            labels: HashMap::new(),
+            allow_overlap: false,
+            on_complete: None,
+            params: HashMap::new(),
+            config: Default::default(),
+            tasks: vec![a, b, c],
+        };
+
+        assert!(!scheduler.execute_workflow_ok(&config).await.unwrap());
+        let statuses = state_manager
+            .get_last_execution_task_statuses("resume")
+            .await
+            .unwrap();
+        assert_eq!(statuses["a"], TaskStatus::Success);
+        assert_eq!(statuses["b"], TaskStatus::Failed);
+        assert_eq!(statuses["c"], TaskStatus::Skipped);
+        let failed_id = state_manager
+            .get_execution_history("resume", 1)
+            .await
+            .unwrap()[0]
+            .id;
+
+        // Fix B, then resume: only B and C run
+        std::fs::write(&fixed, "").unwrap();
+        assert!(scheduler
+            .resume_execution(&config, failed_id)
+            .await
+            .unwrap()
+            .succeeded());
+        assert_eq!(std::fs::read_to_string(&runs).unwrap(), "run\n");
+
+        let statuses = state_manager
+            .get_last_execution_task_statuses("resume")
+            .await
+            .unwrap();
+        assert_eq!(statuses["a"], TaskStatus::Success);
+        assert_eq!(statuses["b"], TaskStatus::Success);
+        assert_eq!(statuses["c"], TaskStatus::Success);
+
+        // Resuming the resumed execution reuses A again instead of re-running it
+        let resumed_id = state_manager
+            .get_execution_history("resume", 1)
+            .await
+            .unwrap()[0]
+            .id;
+        assert!(scheduler
+            .resume_execution(&config, resumed_id)
+            .await
+            .unwrap()
+            .succeeded());
+        assert_eq!(std::fs::read_to_string(&runs).unwrap(), "run\n");
+
+        // A changed task set cannot reuse the old results
+        config.tasks.push(shell_task("d", "/bin/true", &[], 10));
+        let err = scheduler
+            .resume_execution(&config, failed_id)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, PicoFlowError::Validation(ref m) if m.contains("added: [d]")));
+    }
+
+    #[tokio::test]
+    async fn test_max_output_bytes_truncates_stored_output() {
+        let state_manager = Arc::new(StateManager::in_memory().await.unwrap());
+        let scheduler = TaskScheduler::new(state_manager.clone());
+
+        let mut config = WorkflowConfig {
+            name: "small-output".to_string(),
+            description: None,
+            schedule: None,
+            timezone: None,
+            labels: HashMap::new(),
+            allow_overlap: false,
+            on_complete: None,
+            params: HashMap::new(),
+            config: Default::default(),
+            tasks: vec![shell_task("say", "/bin/echo", &["hello world"], 10)],
+        };
+
+        let stored = |config: WorkflowConfig| {
+            let scheduler = &scheduler;
+            let state_manager = &state_manager;
+            async move {
+                assert!(scheduler.execute_workflow_ok(&config).await.unwrap());
+                let history = state_manager
+                    .get_execution_history("small-output", 1)
+                    .await
+                    .unwrap();
+                let tasks = state_manager
+                    .get_task_executions(history[0].id)
+                    .await
+                    .unwrap();
+                (tasks[0].stdout.clone(), tasks[0].output_truncated)
+            }
+        };
+
+        assert_eq!(
+            stored(config.clone()).await,
+            (Some("hello world\n".to_string()), false)
+        );
+
+        config.config.max_output_bytes = Some(5);
+        assert_eq!(stored(config).await, (Some("hello".to_string()), true));
+    }
+
+    #[tokio::test]
+    async fn test_store_output_on_failure_only_drops_successful_output() {
+        let state_manager = Arc::new(StateManager::in_memory().await.unwrap());
+        let scheduler = TaskScheduler::new(state_manager.clone());
+
+        let mut failing = shell_task("fail", "/bin/sh", &["-c", "echo oops; exit 1"], 10);
+        failing.continue_on_failure = Some(true);
+        let mut config = WorkflowConfig {
+            name: "output-policy".to_string(),
+            description: None,
+            schedule: None,
+            timezone: None,
+            labels: HashMap::new(),
+            allow_overlap: false,
+            on_complete: None,
+            params: HashMap::new(),
+            config: Default::default(),
+            tasks: vec![shell_task("say", "/bin/echo", &["hello"], 10), failing],
+        };
+        config.config.store_output = crate::models::OutputPolicy::OnFailureOnly;
+
+        scheduler.execute_workflow_ok(&config).await.unwrap();
+        let history = state_manager
+            .get_execution_history("output-policy", 1)
+            .await
+            .unwrap();
+        let tasks = state_manager
+            .get_task_executions(history[0].id)
+            .await
+            .unwrap();
+        let stdout = |name: &str| {
+            let task = tasks.iter().find(|t| t.task_name == name).unwrap();
+            task.stdout.clone()
+        };
+        assert_eq!(stdout("say"), None);
+        assert_eq!(stdout("fail"), Some("oops\n".to_string()));
+
+        config.config.store_output = crate::models::OutputPolicy::Never;
+        scheduler.execute_workflow_ok(&config).await.unwrap();
+        let history = state_manager
+            .get_execution_history("output-policy", 1)
+            .await
+            .unwrap();
+        let tasks = state_manager
+            .get_task_executions(history[0].id)
+            .await
+            .unwrap();
+        assert!(tasks.iter().all(|t| t.stdout.is_none()));
+    }
+
+    #[tokio::test]
+    async fn test_cache_key_reuses_result_until_ttl_expires() {
+        let state_manager = Arc::new(StateManager::in_memory().await.unwrap());
+        let scheduler = TaskScheduler::new(state_manager.clone());
+        let dir = tempfile::tempdir().unwrap();
+        let counter = dir.path().join("runs");
+        let script = format!("echo run >> {}; echo fetched", counter.display());
+
+        let mut task = shell_task("fetch", "/bin/sh", &["-c", &script], 10);
+        task.cache_key = Some("fetch-v1".to_string());
+        task.cache_ttl_secs = Some(1);
+        let config = WorkflowConfig {
+            name: "cached".to_string(),
+            description: None,
+            schedule: None,
+            timezone: None,
+            labels: HashMap::new(),
+            allow_overlap: false,
+            on_complete: None,
+            params: HashMap::new(),
+            config: Default::default(),
+            tasks: vec![task],
+        };
+        let runs = || std::fs::read_to_string(&counter).unwrap().lines().count();
+        let last_task = || async {
+            let history = state_manager
+                .get_execution_history("cached", 1)
+                .await
+                .unwrap();
+            state_manager
+                .get_task_executions(history[0].id)
+                .await
+                .unwrap()
+                .remove(0)
+        };
+
+        assert!(scheduler.execute_workflow_ok(&config).await.unwrap());
+        assert_eq!(runs(), 1);
+        assert!(!last_task().await.cached);
+
+        // Served from the cache: recorded as a success without running the command
+        assert!(scheduler.execute_workflow_ok(&config).await.unwrap());
+        assert_eq!(runs(), 1);
+        let task = last_task().await;
+        assert!(task.cached);
+        assert_eq!(task.status, TaskStatus::Success);
+        assert_eq!(task.stdout.as_deref(), Some("fetched\n"));
+
+        tokio::time::sleep(std::time::Duration::from_millis(1100)).await;
+        assert!(scheduler.execute_workflow_ok(&config).await.unwrap());
+        assert_eq!(runs(), 2);
+        assert!(!last_task().await.cached);
+    }
+
+    #[tokio::test]
+    async fn test_cache_honours_store_output() {
+        let state_manager = Arc::new(StateManager::in_memory().await.unwrap());
+        let scheduler = TaskScheduler::new(state_manager.clone());
+
+        let mut quiet = shell_task("quiet", "/bin/sh", &["-c", "echo secret; echo e >&2"], 10);
+        quiet.cache_key = Some("v1".to_string());
+        let mut shared = quiet.clone();
+        shared.name = "shared".to_string();
+        shared.cache_key = Some("v2".to_string());
+        shared.outputs = true;
+        let mut config = WorkflowConfig {
+            name: "cache-policy".to_string(),
+            description: None,
+            schedule: None,
+            timezone: None,
+            labels: HashMap::new(),
+            allow_overlap: false,
+            on_complete: None,
+            params: HashMap::new(),
+            config: Default::default(),
+            tasks: vec![quiet.clone(), shared.clone()],
+        };
+        config.config.store_output = crate::models::OutputPolicy::Never;
+        assert!(scheduler.execute_workflow_ok(&config).await.unwrap());
+
+        let entry = |task: &TaskConfig| {
+            let hash = task_cache_hash(task, &HashMap::new()).unwrap();
+            let state_manager = state_manager.clone();
+            async move {
+                state_manager
+                    .get_cached_result(&hash, None)
+                    .await
+                    .unwrap()
+                    .unwrap()
+            }
+        };
+        let cached = entry(&quiet).await;
+        assert_eq!((cached.stdout, cached.stderr), (None, None));
+        // Dependents of a cached `outputs: true` task still need its stdout
+        let cached = entry(&shared).await;
+        assert_eq!(
+            (cached.stdout.as_deref(), cached.stderr),
+            (Some("secret\n"), None)
+        );
+    }
+
+    #[cfg(feature = "metrics")]
+    #[tokio::test]
+    async fn test_run_records_pushed_metrics() {
+        use wiremock::matchers::method;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(200))
+            .mount(&server)
+            .await;
+
+        let state_manager = Arc::new(StateManager::in_memory().await.unwrap());
+        let scheduler = TaskScheduler::new(state_manager);
+        let metrics = Arc::new(crate::metrics::MetricsServer::new());
+        scheduler.set_metrics(metrics.clone());
+
+        let mut config = WorkflowConfig {
+            name: "measured".to_string(),
+            description: None,
+            schedule: None,
+            timezone: None,
+            labels: HashMap::new(),
+            allow_overlap: false,
+            on_complete: None,
+            params: HashMap::new(),
+            config: Default::default(),
+            tasks: vec![shell_task("ok", "/bin/true", &[], 10)],
+        };
+        assert!(scheduler.execute_workflow_ok(&config).await.unwrap());
+        config.tasks = vec![shell_task("broken", "/bin/false", &[], 10)];
+        assert!(!scheduler.execute_workflow_ok(&config).await.unwrap());
+
+        metrics
+            .push_to_gateway(&server.uri(), "edge-1")
+            .await
+            .unwrap();
+        let requests = server.received_requests().await.unwrap();
+        let body = String::from_utf8_lossy(&requests[0].body);
+        for line in [
+            r#"picoflow_workflow_executions_total{status="success",workflow="measured"} 1"#,
+            r#"picoflow_workflow_executions_total{status="failed",workflow="measured"} 1"#,
+            r#"picoflow_workflow_success_ratio{workflow="measured"} 0.5"#,
+            r#"picoflow_task_executions_total{status="success",task="ok",workflow="measured"} 1"#,
+            r#"picoflow_task_executions_total{status="failed",task="broken",workflow="measured"} 1"#,
+        ] {
+            assert!(body.contains(line), "missing {line} in:\n{body}");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_task_log_dir_receives_shell_output() {
+        let state_manager = Arc::new(StateManager::in_memory().await.unwrap());
+        let mut scheduler = TaskScheduler::new(state_manager.clone());
+        let dir = tempfile::tempdir().unwrap();
+        scheduler.set_task_log_dir(dir.path().to_path_buf());
+
+        let config = WorkflowConfig {
+            name: "logged".to_string(),
+            description: None,
+            schedule: None,
+            timezone: None,
+            labels: HashMap::new(),
+            allow_overlap: false,
+            on_complete: None,
+            params: HashMap::new(),
+            config: Default::default(),
+            tasks: vec![shell_task(
+                "build",
+                "/bin/sh",
+                &["-c", "echo compiling; echo done"],
+                10,
+            )],
+        };
+        assert!(scheduler.execute_workflow_ok(&config).await.unwrap());
+
+        let history = state_manager
+            .get_execution_history("logged", 1)
+            .await
+            .unwrap();
+        let log_path = dir.path().join(history[0].id.to_string()).join("build.log");
+        assert_eq!(
+            std::fs::read_to_string(&log_path).unwrap(),
+            "compiling\ndone\n"
+        );
+
+        // Kept while the execution is, removed once retention has deleted it
+        std::fs::create_dir(dir.path().join("notes")).unwrap();
+        assert_eq!(scheduler.prune_task_logs().await.unwrap(), 0);
+        assert!(log_path.exists());
+        assert!(scheduler.execute_workflow_ok(&config).await.unwrap());
+        state_manager
+            .prune_executions("logged", 1, 0)
+            .await
+            .unwrap();
+        assert_eq!(scheduler.prune_task_logs().await.unwrap(), 1);
+        assert!(!dir.path().join(history[0].id.to_string()).exists());
+        assert!(dir.path().join("notes").exists());
+        let latest = state_manager
+            .get_execution_history("logged", 1)
+            .await
+            .unwrap();
+        assert!(dir.path().join(latest[0].id.to_string()).exists());
+    }
+
+    #[tokio::test]
+    async fn test_delay_before_waits_after_dependency() {
+        let state_manager = Arc::new(StateManager::in_memory().await.unwrap());
+        let scheduler = TaskScheduler::new(state_manager.clone());
+
+        // The delay must not eat into the 1s timeout
+        let mut second = shell_task("second", "/bin/true", &[], 1);
+        second.depends_on = vec!["first".into()];
+        second.delay_before = Some(2);
+        let config = WorkflowConfig {
+            name: "delayed".to_string(),
+            description: None,
+            schedule: None,
+            timezone: None,
+            labels: HashMap::new(),
+            allow_overlap: false,
+            on_complete: None,
+            params: HashMap::new(),
+            config: Default::default(),
+            tasks: vec![shell_task("first", "/bin/true", &[], 10), second],
+        };
+
+        assert!(scheduler.execute_workflow_ok(&config).await.unwrap());
+
+        let history = state_manager
+            .get_execution_history("delayed", 1)
+            .await
+            .unwrap();
+        let tasks = state_manager
+            .get_task_executions(history[0].id)
+            .await
+            .unwrap();
+        let first = tasks.iter().find(|t| t.task_name == "first").unwrap();
+        let second = tasks.iter().find(|t| t.task_name == "second").unwrap();
+        let gap = second.started_at - first.completed_at.unwrap();
+        assert!(
+            gap >= chrono::Duration::milliseconds(1900),
+            "second started {gap} after first completed"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_max_parallel_bounds_concurrency() {
+        // Four independent tasks (all at DAG level 0) each sleep 0.4s. With max_parallel=2
+        // they must run in two waves, so wall-clock is ~0.8s. If the concurrency cap were
+        // not enforced, all four would run at once (~0.4s). We assert only a lower bound to
+        // stay robust on slow CI.
+        let state_manager = Arc::new(StateManager::in_memory().await.unwrap());
+        let scheduler = TaskScheduler::new(state_manager.clone());
+
+        let make_task = |n: usize| TaskConfig {
+            name: format!("sleep{n}"),
+            task_type: TaskType::Shell,
+            depends_on: vec![],
+            config: TaskExecutorConfig::Shell(ShellConfig {
+                command: "/bin/sleep".to_string(),
+                args: vec!["0.4".to_string()],
+                ..Default::default()
+            }),
+            retry: Some(0),
+            timeout: Some(10),
+            delay_before: None,
+            continue_on_failure: None,
+            outputs: false,
+            run_condition: Default::default(),
+            enabled: None,
+            labels: HashMap::new(),
+            requires_files: vec![],
+            missing_files_action: Default::default(),
+            cache_key: None,
+            cache_ttl_secs: None,
+            priority: None,
+        };
+
+        let config = WorkflowConfig {
+            name: "parallel-bound".to_string(),
+            description: None,
+            schedule: None,
+            timezone: None,
+            labels: HashMap::new(),
+            allow_overlap: false,
+            on_complete: None,
+            params: HashMap::new(),
+            config: crate::models::WorkflowGlobalConfig {
+                max_parallel: 2,
+                retry_default: 0,
+                timeout_default: 10,
+                retry_jitter: false,
+                max_retries_cap: 10,
+                continue_on_failure_default: false,
+                memory_limit_mb: None,
+                max_output_bytes: None,
+                store_output: Default::default(),
+                backoff_strategy: Default::default(),
+                ssh_defaults: None,
+                max_depth: None,
+            },
+            tasks: (0..4).map(make_task).collect(),
+        };
+
+        let start = std::time::Instant::now();
+        let success = scheduler.execute_workflow_ok(&config).await.unwrap();
+        let elapsed = start.elapsed();
+
+        assert!(success);
+        assert!(
+            elapsed >= std::time::Duration::from_millis(650),
+            "4 tasks @0.4s with max_parallel=2 should take ~0.8s (two waves), took {elapsed:?} — \
+             concurrency cap not enforced?"
+        );
+    }
+
+    #[test]
+    fn test_order_levels_by_priority() {
+        let mut tasks: Vec<TaskConfig> = ["c", "a", "d", "b"]
+            .iter()
+            .map(|name| shell_task(name, "/bin/true", &[], 10))
+            .collect();
+        tasks[2].priority = Some(5);
+        tasks[3].priority = Some(-1);
+
+        // `parallel_levels` sorts alphabetically; ties go back to definition order
+        let mut levels = vec![vec![
+            "a".to_string(),
+            "b".to_string(),
+            "c".to_string(),
+            "d".to_string(),
+        ]];
+        order_levels_by_priority(&mut levels, &tasks);
+        assert_eq!(levels, vec![vec!["d", "c", "a", "b"]]);
+    }
+
+    #[tokio::test]
+    async fn test_priority_launches_first_within_level() {
+        let state_manager = Arc::new(StateManager::in_memory().await.unwrap());
+        let scheduler = TaskScheduler::new(state_manager.clone());
+
+        // Two slots for three independent tasks: `urgent` (defined last, and last
+        // alphabetically) must be in the first wave, leaving `normal` to wait
+        let mut urgent = shell_task("urgent", "/bin/sleep", &["0.3"], 10);
+        urgent.priority = Some(10);
+        let config = WorkflowConfig {
+            name: "priority".to_string(),
+            description: None,
+            schedule: None,
+            timezone: None,
+            labels: HashMap::new(),
+            allow_overlap: false,
+            on_complete: None,
+            params: HashMap::new(),
+            config: crate::models::WorkflowGlobalConfig {
+                max_parallel: 2,
+                ..Default::default()
+            },
+            tasks: vec![
+                shell_task("routine", "/bin/sleep", &["0.3"], 10),
+                shell_task("normal", "/bin/sleep", &["0.3"], 10),
+                urgent,
+            ],
+        };
+
+        assert!(scheduler.execute_workflow_ok(&config).await.unwrap());
+
+        let history = state_manager
+            .get_execution_history("priority", 1)
+            .await
+            .unwrap();
+        let mut tasks = state_manager
+            .get_task_executions(history[0].id)
+            .await
+            .unwrap();
+        tasks.sort_by_key(|t| t.started_at);
+        assert_eq!(tasks[2].task_name, "normal");
+        assert!(tasks[2].started_at >= tasks[0].completed_at.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_priority_orders_sequential_execution() {
+        let state_manager = Arc::new(StateManager::in_memory().await.unwrap());
+        let scheduler = TaskScheduler::new(state_manager.clone());
+
+        let mut low = shell_task("low", "/bin/true", &[], 10);
+        low.priority = Some(-1);
+        let mut urgent = shell_task("urgent", "/bin/true", &[], 10);
+        urgent.priority = Some(10);
+        let mut after = shell_task("after", "/bin/true", &[], 10);
+        after.priority = Some(100);
+        after.depends_on = vec!["low".into()];
+        let config = WorkflowConfig {
+            name: "sequential-priority".to_string(),
+            description: None,
+            schedule: None,
+            timezone: None,
+            labels: HashMap::new(),
+            allow_overlap: false,
+            on_complete: None,
+            params: HashMap::new(),
+            config: crate::models::WorkflowGlobalConfig {
+                max_parallel: 1,
+                ..Default::default()
+            },
+            tasks: vec![
+                low,
+                shell_task("normal", "/bin/true", &[], 10),
+                urgent,
+                after,
+            ],
+        };
+
+        assert!(scheduler.execute_workflow_ok(&config).await.unwrap());
+
+        let history = state_manager
+            .get_execution_history("sequential-priority", 1)
+            .await
+            .unwrap();
+        let mut tasks = state_manager
+            .get_task_executions(history[0].id)
+            .await
+            .unwrap();
+        tasks.sort_by_key(|t| t.id);
+        let order: Vec<&str> = tasks.iter().map(|t| t.task_name.as_str()).collect();
+        // Priority orders tasks within a level but never runs one before its dependency
+        assert_eq!(order, ["urgent", "normal", "low", "after"]);
+    }
+
+    fn output_passing_workflow(name: &str, max_parallel: usize, capture: bool) -> WorkflowConfig {
+        WorkflowConfig {
+            name: name.to_string(),
+            description: None,
+            schedule: None,
+            timezone: None,
+            labels: HashMap::new(),
+            allow_overlap: false,
+            on_complete: None,
+            params: HashMap::new(),
+            config: crate::models::WorkflowGlobalConfig {
+                max_parallel,
+                retry_default: 0,
+                timeout_default: 10,
+                retry_jitter: false,
+                max_retries_cap: 10,
+                continue_on_failure_default: false,
+                memory_limit_mb: None,
+                max_output_bytes: None,
+                store_output: Default::default(),
+                backoff_strategy: Default::default(),
+                ssh_defaults: None,
+                max_depth: None,
+            },
+            tasks: vec![
+                TaskConfig {
+                    name: "produce".to_string(),
+                    task_type: TaskType::Shell,
+                    depends_on: vec![],
+                    config: TaskExecutorConfig::Shell(ShellConfig {
+                        command: "/bin/echo".to_string(),
+                        args: vec!["  payload  ".to_string()],
+                        ..Default::default()
+                    }),
+                    retry: Some(0),
+                    timeout: Some(10),
+                    delay_before: None,
+                    continue_on_failure: None,
+                    outputs: capture,
+                    run_condition: Default::default(),
+                    enabled: None,
+                    labels: HashMap::new(),
+                    requires_files: vec![],
+                    missing_files_action: Default::default(),
+                    cache_key: None,
+                    cache_ttl_secs: None,
+                    priority: None,
+                },
+                TaskConfig {
+                    name: "consume".to_string(),
+                    task_type: TaskType::Shell,
+                    depends_on: vec!["produce".into()],
+                    config: TaskExecutorConfig::Shell(ShellConfig {
+                        // Succeeds only if the trimmed stdout was substituted into $0
+                        command: "/bin/sh".to_string(),
+                        args: vec![
+                            "-c".to_string(),
+                            "test \"$0\" = payload".to_string(),
+                            "${{ tasks.produce.stdout }}".to_string(),
+                        ],
+                        ..Default::default()
+                    }),
+                    retry: Some(2),
+                    timeout: Some(10),
+                    delay_before: None,
+                    continue_on_failure: None,
+                    outputs: false,
+                    run_condition: Default::default(),
+                    enabled: None,
+                    labels: HashMap::new(),
+                    requires_files: vec![],
+                    missing_files_action: Default::default(),
+                    cache_key: None,
+                    cache_ttl_secs: None,
+                    priority: None,
+                },
+            ],
+        }
+    }
+
+    #[tokio::test]
+    async fn test_task_output_passed_to_dependent() {
+        for (name, max_parallel) in [("outputs-seq", 1), ("outputs-par", 4)] {
+            let state_manager = Arc::new(StateManager::in_memory().await.unwrap());
+            let scheduler = TaskScheduler::new(state_manager.clone());
+
+            let config = output_passing_workflow(name, max_parallel, true);
+            let success = scheduler.execute_workflow_ok(&config).await.unwrap();
+            assert!(success, "{name}: dependent should see the captured stdout");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_missing_task_output_fails_without_retry() {
+        let state_manager = Arc::new(StateManager::in_memory().await.unwrap());
+        let scheduler = TaskScheduler::new(state_manager.clone());
+
+        // `produce` does not capture its output, so the reference cannot be resolved
+        let config = output_passing_workflow("outputs-missing", 1, false);
+        let success = scheduler.execute_workflow_ok(&config).await.unwrap();
+        assert!(!success);
+
+        let history = state_manager
+            .get_execution_history("outputs-missing", 1)
+            .await
+            .unwrap();
+        let tasks = state_manager
+            .get_task_executions(history[0].id)
+            .await
+            .unwrap();
+        let consume: Vec<_> = tasks.iter().filter(|t| t.task_name == "consume").collect();
+        assert_eq!(consume.len(), 1, "validation errors should not be retried");
+        assert_eq!(consume[0].status, TaskStatus::Failed);
+        assert!(consume[0]
+            .stderr
+            .as_deref()
+            .unwrap_or("")
+            .contains("produce"));
+    }
+
+    fn conditional_task(
+        name: &str,
+        depends_on: &[&str],
+        exit_code: i32,
+        run_condition: RunCondition,
+    ) -> TaskConfig {
+        TaskConfig {
+            name: name.to_string(),
+            task_type: TaskType::Shell,
+            depends_on: depends_on.iter().map(|&d| d.into()).collect(),
+            config: TaskExecutorConfig::Shell(ShellConfig {
+                command: "/bin/sh".to_string(),
+                args: vec!["-c".to_string(), format!("exit {exit_code}")],
+                ..Default::default()
+            }),
+            retry: Some(0),
+            timeout: Some(10),
+            delay_before: None,
+            continue_on_failure: None,
+            outputs: false,
+            run_condition,
+            enabled: None,
+            labels: HashMap::new(),
+            requires_files: vec![],
+            missing_files_action: Default::default(),
+            cache_key: None,
+            cache_ttl_secs: None,
+            priority: None,
+        }
+    }
+
+    /// Run a workflow and return the final status of each task by name
+    async fn run_and_collect_statuses(
+        name: &str,
+        max_parallel: usize,
+        tasks: Vec<TaskConfig>,
+    ) -> (bool, HashMap<String, TaskStatus>) {
+        let settings = WorkflowGlobalConfig {
+            max_parallel,
+            ..Default::default()
+        };
+        run_with_settings_and_collect_statuses(name, settings, tasks).await
+    }
+
+    /// Like [`run_and_collect_statuses`], with custom workflow-wide settings
+    async fn run_with_settings_and_collect_statuses(
+        name: &str,
+        settings: WorkflowGlobalConfig,
+        tasks: Vec<TaskConfig>,
+    ) -> (bool, HashMap<String, TaskStatus>) {
+        let state_manager = Arc::new(StateManager::in_memory().await.unwrap());
+        let scheduler = TaskScheduler::new(state_manager.clone());
+        let config = WorkflowConfig {
+            name: name.to_string(),
+            description: None,
+            schedule: None,
+            timezone: None,
+            labels: HashMap::new(),
+            allow_overlap: false,
+            on_complete: None,
+            params: HashMap::new(),
+            config: settings,
+            tasks,
+        };
+
+        let success = scheduler.execute_workflow_ok(&config).await.unwrap();
+        let history = state_manager.get_execution_history(name, 1).await.unwrap();
+        let statuses = state_manager
+            .get_task_executions(history[0].id)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|t| (t.task_name, t.status))
+            .collect();
+        (success, statuses)
+    }
+
+    #[tokio::test]
+    async fn test_events_follow_execution_order() {
+        let state_manager = Arc::new(StateManager::in_memory().await.unwrap());
+        let mut scheduler = TaskScheduler::new(state_manager);
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        scheduler.set_event_sender(tx);
+
+        let config = WorkflowConfig {
+            name: "events".to_string(),
+            description: None,
+            schedule: None,
+            timezone: None,
+            labels: HashMap::new(),
+            allow_overlap: false,
+            on_complete: None,
+            params: HashMap::new(),
+            config: Default::default(),
+            tasks: vec![
+                conditional_task("a", &[], 0, RunCondition::OnSuccess),
+                conditional_task("b", &["a"], 1, RunCondition::OnSuccess),
+                conditional_task("c", &["b"], 0, RunCondition::OnSuccess),
+            ],
+        };
+        assert!(!scheduler.execute_workflow_ok(&config).await.unwrap());
+        drop(scheduler);
+
+        let mut events = Vec::new();
+        while let Some(event) = rx.recv().await {
+            events.push(event);
+        }
+        let task = |name: &str| name.to_string();
+        assert_eq!(
+            events,
+            vec![
+                SchedulerEvent::WorkflowStarted {
+                    workflow: "events".to_string(),
+                    execution_id: 1,
+                },
+                SchedulerEvent::TaskStarted { name: task("a") },
+                SchedulerEvent::TaskFinished {
+                    name: task("a"),
+                    status: TaskStatus::Success,
+                },
+                SchedulerEvent::TaskStarted { name: task("b") },
+                SchedulerEvent::TaskFinished {
+                    name: task("b"),
+                    status: TaskStatus::Failed,
+                },
+                SchedulerEvent::TaskFinished {
+                    name: task("c"),
+                    status: TaskStatus::Skipped,
+                },
+                SchedulerEvent::WorkflowFinished {
+                    status: TaskStatus::Failed,
+                },
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_on_failure_cleanup_runs_after_upstream_failure() {
+        for (name, max_parallel) in [("cleanup-seq", 1), ("cleanup-par", 4)] {
+            let (success, statuses) = run_and_collect_statuses(
+                name,
+                max_parallel,
+                vec![
+                    conditional_task("build", &[], 1, RunCondition::OnSuccess),
+                    conditional_task("deploy", &["build"], 0, RunCondition::OnSuccess),
+                    conditional_task("cleanup", &["build"], 0, RunCondition::OnFailure),
+                    conditional_task("report", &["deploy"], 0, RunCondition::Always),
+                ],
+            )
+            .await;
+
+            assert!(
+                !success,
+                "{name}: upstream failure still fails the workflow"
+            );
+            assert_eq!(statuses["build"], TaskStatus::Failed, "{name}");
+            assert_eq!(statuses["deploy"], TaskStatus::Skipped, "{name}");
+            assert_eq!(statuses["cleanup"], TaskStatus::Success, "{name}");
+            assert_eq!(statuses["report"], TaskStatus::Success, "{name}");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_dependency_conditions_gate_per_edge() {
+        use crate::models::{Dependency, DependencyCondition};
+
+        let on = |task: &str, on: DependencyCondition| Dependency::Conditional {
+            task: task.to_string(),
+            on,
+        };
+        let with_deps = |name: &str, depends_on: Vec<Dependency>| {
+            let mut task = conditional_task(name, &[], 0, RunCondition::OnSuccess);
+            task.depends_on = depends_on;
+            task
+        };
+
+        for (name, max_parallel) in [("edges-seq", 1), ("edges-par", 4)] {
+            let (success, statuses) = run_and_collect_statuses(
+                name,
+                max_parallel,
+                vec![
+                    conditional_task("a", &[], 0, RunCondition::OnSuccess),
+                    // After `a`, so halting on its failure cannot skip `a`
+                    conditional_task("b", &["a"], 1, RunCondition::OnSuccess),
+                    with_deps(
+                        "a_ok_b_failed",
+                        vec![
+                            on("a", DependencyCondition::Success),
+                            on("b", DependencyCondition::Failure),
+                        ],
+                    ),
+                    with_deps("a_failed", vec![on("a", DependencyCondition::Failure)]),
+                    with_deps("b_done", vec![on("b", DependencyCondition::Complete)]),
+                    with_deps(
+                        "a_and_b_ok",
+                        vec!["a".into(), on("b", DependencyCondition::Success)],
+                    ),
+                ],
+            )
+            .await;
+
+            assert!(!success, "{name}");
+            assert_eq!(statuses["a_ok_b_failed"], TaskStatus::Success, "{name}");
+            assert_eq!(statuses["a_failed"], TaskStatus::Skipped, "{name}");
+            assert_eq!(statuses["b_done"], TaskStatus::Success, "{name}");
+            assert_eq!(statuses["a_and_b_ok"], TaskStatus::Skipped, "{name}");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_execution_graph_after_run() {
+        let state_manager = Arc::new(StateManager::in_memory().await.unwrap());
+        let scheduler = TaskScheduler::new(state_manager.clone());
+        let config = WorkflowConfig {
+            name: "graph".to_string(),
+            description: None,
+            schedule: None,
+            timezone: None,
+            labels: HashMap::new(),
+            allow_overlap: false,
+            on_complete: None,
+            params: HashMap::new(),
+            config: Default::default(),
+            tasks: vec![
+                conditional_task("extract", &[], 0, RunCondition::OnSuccess),
+                conditional_task("transform", &["extract"], 1, RunCondition::OnSuccess),
+                conditional_task("load", &["transform"], 0, RunCondition::OnSuccess),
+                conditional_task("report", &["extract", "load"], 0, RunCondition::Always),
+            ],
+        };
+        assert!(!scheduler.execute_workflow_ok(&config).await.unwrap());
+
+        let history = state_manager
+            .get_execution_history("graph", 1)
+            .await
+            .unwrap();
+        let graph = state_manager
+            .get_execution_graph(history[0].id)
+            .await
+            .unwrap();
+        assert_eq!(graph.status, TaskStatus::Failed);
+        let nodes: Vec<(&str, &TaskStatus)> = graph
+            .nodes
+            .iter()
+            .map(|n| (n.task.as_str(), &n.status))
+            .collect();
+        assert_eq!(
+            nodes,
+            vec![
+                ("extract", &TaskStatus::Success),
+                ("transform", &TaskStatus::Failed),
+                ("load", &TaskStatus::Skipped),
+                ("report", &TaskStatus::Success),
+            ]
+        );
+        let edges: Vec<(&str, &str)> = graph
+            .edges
+            .iter()
+            .map(|e| (e.from.as_str(), e.to.as_str()))
+            .collect();
+        assert_eq!(
+            edges,
+            vec![
+                ("extract", "transform"),
+                ("transform", "load"),
+                ("extract", "report"),
+                ("load", "report"),
+            ]
+        );
+
+        let json = serde_json::to_value(&graph).unwrap();
+        assert_eq!(json["nodes"][1]["status"], "failed");
+        assert_eq!(json["edges"][0]["from"], "extract");
+        assert!(json["edges"][0].get("on").is_none());
+
+        let snapshot = state_manager
+            .get_execution_config(history[0].id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(snapshot.tasks.len(), 4);
+    }
+
+    #[tokio::test]
+    async fn test_memory_limit_delays_task_launches() {
+        let state_manager = Arc::new(StateManager::in_memory().await.unwrap());
+        let mut scheduler = TaskScheduler::new(state_manager);
+        scheduler.memory_poll_interval = std::time::Duration::from_millis(20);
+        scheduler.memory_max_wait = std::time::Duration::from_millis(200);
+
+        // The test process is always above 1 MiB, so every launch waits the maximum
+        let config = WorkflowConfig {
+            name: "memory-limited".to_string(),
+            description: None,
+            schedule: None,
+            timezone: None,
+            labels: HashMap::new(),
+            allow_overlap: false,
+            on_complete: None,
+            params: HashMap::new(),
+            config: WorkflowGlobalConfig {
+                max_parallel: 1,
+                memory_limit_mb: Some(1),
+                ..Default::default()
+            },
+            tasks: vec![
+                conditional_task("first", &[], 0, RunCondition::OnSuccess),
+                conditional_task("second", &["first"], 0, RunCondition::OnSuccess),
+            ],
+        };
+
+        let start = std::time::Instant::now();
+        assert!(scheduler.execute_workflow_ok(&config).await.unwrap());
+        assert!(
+            start.elapsed() >= std::time::Duration::from_millis(400),
+            "both launches should be delayed, took {:?}",
+            start.elapsed()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_retries_clamped_to_max_retries_cap() {
+        let state_manager = Arc::new(StateManager::in_memory().await.unwrap());
+        let scheduler = TaskScheduler::new(state_manager.clone());
+        let mut task = conditional_task("flaky", &[], 1, RunCondition::OnSuccess);
+        task.retry = Some(100);
+        let config = WorkflowConfig {
+            name: "capped".to_string(),
+            description: None,
+            schedule: None,
+            timezone: None,
+            labels: HashMap::new(),
+            allow_overlap: false,
+            on_complete: None,
+            params: HashMap::new(),
+            config: crate::models::WorkflowGlobalConfig {
+                max_parallel: 1,
+                max_retries_cap: 3,
+                ..Default::default()
+            },
+            tasks: vec![task],
+        };
+
+        let success = scheduler.execute_workflow_ok(&config).await.unwrap();
+        assert!(!success);
+
+        let history = state_manager
+            .get_execution_history("capped", 1)
+            .await
+            .unwrap();
+        let attempts = state_manager
+            .get_task_executions(history[0].id)
+            .await
+            .unwrap();
+        assert_eq!(attempts.len(), 4, "initial try plus 3 capped retries");
+        let numbers: Vec<i32> = attempts.iter().map(|t| t.attempt).collect();
+        assert_eq!(numbers, [1, 2, 3, 4]);
+        assert_eq!(attempts[3].status, TaskStatus::Failed);
+    }
+
+    #[tokio::test]
+    async fn test_disabled_task_skipped_but_dependents_run() {
+        for (name, max_parallel) in [("disabled-seq", 1), ("disabled-par", 4)] {
+            // The disabled task would fail if it ran
+            let mut migrate = conditional_task("migrate", &["build"], 1, RunCondition::OnSuccess);
+            migrate.enabled = Some(false);
+
+            let (success, statuses) = run_and_collect_statuses(
+                name,
+                max_parallel,
+                vec![
+                    conditional_task("build", &[], 0, RunCondition::OnSuccess),
+                    migrate,
+                    conditional_task("deploy", &["migrate"], 0, RunCondition::OnSuccess),
+                    conditional_task("rollback", &["migrate"], 0, RunCondition::OnFailure),
+                ],
+            )
+            .await;
+
+            assert!(
+                success,
+                "{name}: a disabled task does not fail the workflow"
+            );
+            assert_eq!(statuses["build"], TaskStatus::Success, "{name}");
+            assert_eq!(statuses["migrate"], TaskStatus::Skipped, "{name}");
+            assert_eq!(statuses["deploy"], TaskStatus::Success, "{name}");
+            assert_eq!(statuses["rollback"], TaskStatus::Skipped, "{name}");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_noop_barrier_succeeds_and_unblocks_dependents() {
+        for (name, max_parallel) in [("noop-seq", 1), ("noop-par", 4)] {
+            let mut barrier =
+                conditional_task("barrier", &["fetch", "build"], 0, RunCondition::OnSuccess);
+            barrier.task_type = TaskType::Noop;
+            barrier.config = TaskExecutorConfig::Noop(NoopConfig::default());
+
+            let (success, statuses) = run_and_collect_statuses(
+                name,
+                max_parallel,
+                vec![
+                    conditional_task("fetch", &[], 0, RunCondition::OnSuccess),
+                    conditional_task("build", &[], 0, RunCondition::OnSuccess),
+                    barrier,
+                    conditional_task("deploy", &["barrier"], 0, RunCondition::OnSuccess),
+                ],
+            )
+            .await;
+
+            assert!(success, "{name}");
+            assert_eq!(statuses["barrier"], TaskStatus::Success, "{name}");
+            assert_eq!(statuses["deploy"], TaskStatus::Success, "{name}");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_requires_files_skip_or_fail_when_missing() {
+        let dir = tempfile::tempdir().unwrap();
+        let present = dir.path().join("input.csv");
+        std::fs::write(&present, "a,b\n").unwrap();
+        let missing = dir.path().join("absent.csv");
+
+        for (name, max_parallel) in [("requires-seq", 1), ("requires-par", 4)] {
+            let mut ingest = conditional_task("ingest", &[], 0, RunCondition::OnSuccess);
+            ingest.requires_files = vec![present.to_string_lossy().into_owned()];
+            let mut optional = conditional_task("optional", &[], 0, RunCondition::OnSuccess);
+            optional.requires_files = vec![
+                present.to_string_lossy().into_owned(),
+                missing.to_string_lossy().into_owned(),
+            ];
+
+            let (success, statuses) = run_and_collect_statuses(
+                name,
+                max_parallel,
+                vec![
+                    ingest,
+                    optional,
+                    conditional_task("report", &["optional"], 0, RunCondition::OnSuccess),
+                ],
+            )
+            .await;
+
+            assert!(success, "{name}: a skipped task does not fail the workflow");
+            assert_eq!(statuses["ingest"], TaskStatus::Success, "{name}");
+            assert_eq!(statuses["optional"], TaskStatus::Skipped, "{name}");
+            assert_eq!(statuses["report"], TaskStatus::Skipped, "{name}");
+
+            let mut strict = conditional_task("strict", &[], 0, RunCondition::OnSuccess);
+            strict.requires_files = vec![missing.to_string_lossy().into_owned()];
+            strict.missing_files_action = MissingFilesAction::Fail;
+            strict.retry = Some(2);
+
+            let (success, statuses) =
+                run_and_collect_statuses(&format!("{name}-fail"), max_parallel, vec![strict]).await;
+
+            assert!(!success, "{name}: a missing file fails the task");
+            assert_eq!(statuses["strict"], TaskStatus::Failed, "{name}");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_on_failure_task_skipped_when_dependencies_succeed() {
+        for (name, max_parallel) in [("no-cleanup-seq", 1), ("no-cleanup-par", 4)] {
+            let (success, statuses) = run_and_collect_statuses(
+                name,
+                max_parallel,
+                vec![
+                    conditional_task("build", &[], 0, RunCondition::OnSuccess),
+                    conditional_task("cleanup", &["build"], 0, RunCondition::OnFailure),
+                ],
+            )
+            .await;
+
+            assert!(success, "{name}: a skipped hook does not fail the workflow");
+            assert_eq!(statuses["build"], TaskStatus::Success, "{name}");
+            assert_eq!(statuses["cleanup"], TaskStatus::Skipped, "{name}");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_continue_on_failure_default() {
+        for (name, max_parallel) in [("best-effort-seq", 1), ("best-effort-par", 4)] {
+            let settings = WorkflowGlobalConfig {
+                max_parallel,
+                continue_on_failure_default: true,
+                ..Default::default()
+            };
+            let (success, statuses) = run_with_settings_and_collect_statuses(
+                name,
+                settings,
+                vec![
+                    conditional_task("rotate-logs", &[], 1, RunCondition::OnSuccess),
+                    conditional_task("vacuum-db", &[], 0, RunCondition::OnSuccess),
+                    conditional_task("prune-cache", &[], 0, RunCondition::OnSuccess),
+                    conditional_task("report", &["rotate-logs"], 0, RunCondition::OnSuccess),
+                ],
+            )
+            .await;
+
+            assert!(!success, "{name}: a failed task still fails the workflow");
+            assert_eq!(statuses["rotate-logs"], TaskStatus::Failed, "{name}");
+            assert_eq!(statuses["vacuum-db"], TaskStatus::Success, "{name}");
+            assert_eq!(statuses["prune-cache"], TaskStatus::Success, "{name}");
+            assert_eq!(statuses["report"], TaskStatus::Success, "{name}");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_task_continue_on_failure_overrides_default() {
+        let settings = WorkflowGlobalConfig {
+            max_parallel: 1,
+            continue_on_failure_default: true,
+            ..Default::default()
+        };
+        let mut critical = conditional_task("critical", &[], 1, RunCondition::OnSuccess);
+        critical.continue_on_failure = Some(false);
+
+        let (success, statuses) = run_with_settings_and_collect_statuses(
+            "override",
+            settings,
+            vec![
+                critical,
+                conditional_task("after", &["critical"], 0, RunCondition::OnSuccess),
+            ],
+        )
+        .await;
+
+        assert!(!success);
+        assert_eq!(statuses["critical"], TaskStatus::Failed);
+        assert_eq!(statuses["after"], TaskStatus::Skipped);
+    }
+
+    #[test]
+    fn test_should_run_tolerates_continue_on_failure_dependency() {
+        let mut flaky = conditional_task("flaky", &[], 1, RunCondition::OnSuccess);
+        flaky.continue_on_failure = Some(true);
+        let mut task_map = HashMap::new();
+        task_map.insert("flaky".to_string(), Arc::new(flaky));
+        let mut finished = HashMap::new();
+        finished.insert("flaky".to_string(), TaskStatus::Failed);
+
+        let on_success = conditional_task("next", &["flaky"], 0, RunCondition::OnSuccess);
+        let on_failure = conditional_task("alert", &["flaky"], 0, RunCondition::OnFailure);
+        let settings = WorkflowGlobalConfig::default();
+        assert!(should_run(
+            &on_success,
+            &finished,
+            &task_map,
+            &settings,
+            false
+        ));
+        assert!(should_run(
+            &on_failure,
+            &finished,
+            &task_map,
+            &settings,
+            false
+        ));
+        // Once the workflow halted, only always/on_failure tasks may run
+        assert!(!should_run(
+            &on_success,
+            &finished,
+            &task_map,
+            &settings,
+            true
+        ));
+        assert!(should_run(
+            &on_failure,
+            &finished,
+            &task_map,
+            &settings,
+            true
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_logs_carry_workflow_and_execution_id() {
+        use std::io::Write;
+        use std::sync::Mutex;
+
+        #[derive(Clone)]
+        struct Capture(Arc<Mutex<Vec<u8>>>);
+        impl Write for Capture {
+            fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+                self.0.lock().unwrap().write(buf)
+            }
+            fn flush(&mut self) -> std::io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let buffer = Arc::new(Mutex::new(Vec::new()));
+        let capture = Capture(buffer.clone());
+        let subscriber = tracing_subscriber::fmt()
+            .json()
+            .with_span_list(true)
+            .with_max_level(tracing::Level::INFO)
+            .with_writer(move || capture.clone())
+            .finish();
+        // Single-threaded test runtime, so the thread-local default sees every poll
+        let _guard = tracing::subscriber::set_default(subscriber);
+
+        let (success, _) = run_and_collect_statuses(
+            "traced",
+            2,
+            vec![
+                conditional_task("a", &[], 0, RunCondition::OnSuccess),
+                conditional_task("b", &[], 1, RunCondition::OnSuccess),
+            ],
+        )
+        .await;
+        assert!(!success);
+
+        let output = String::from_utf8(buffer.lock().unwrap().clone()).unwrap();
+        let events: Vec<serde_json::Value> = output
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        let message =
+            |e: &serde_json::Value| e["fields"]["message"].as_str().unwrap_or("").to_string();
+
+        let task_events: Vec<_> = events
+            .iter()
+            // Attempt-level logs from inside the retry loop
+            .filter(|e| {
+                let message = message(e);
+                message.starts_with("Executing task '")
+                    || message.contains("completed successfully")
+                    || message.contains("failed with exit code")
+            })
+            .collect();
+        assert!(task_events.len() >= 4, "{output}");
+        for event in task_events {
+            let spans = event["spans"].as_array().expect("event inside spans");
+            assert_eq!(spans[0]["name"], "workflow", "{event}");
+            assert_eq!(spans[0]["workflow"], "traced", "{event}");
+            assert!(spans[0]["execution_id"].is_i64(), "{event}");
+            assert_eq!(spans[1]["name"], "task", "{event}");
+            assert!(
+                spans[1]["task"] == "a" || spans[1]["task"] == "b",
+                "{event}"
+            );
+        }
+
+        let completed = events
+            .iter()
+            .find(|e| message(e).starts_with("Workflow execution completed"))
+            .expect("completion logged");
+        assert_eq!(completed["spans"][0]["workflow"], "traced");
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_cancels_running_task() {
+        let state_manager = Arc::new(StateManager::in_memory().await.unwrap());
+        let scheduler = Arc::new(TaskScheduler::new(state_manager.clone()));
+
+        let mut long = conditional_task("long", &[], 0, RunCondition::OnSuccess);
+        long.config = TaskExecutorConfig::Shell(ShellConfig {
+            command: "/bin/sleep".to_string(),
+            args: vec!["30".to_string()],
+            ..Default::default()
+        });
+        long.retry = Some(3);
+        let config = WorkflowConfig {
+            name: "cancel-me".to_string(),
+            description: None,
+            schedule: None,
+            timezone: None,
+            labels: HashMap::new(),
+            allow_overlap: false,
+            on_complete: None,
+            params: HashMap::new(),
+            config: Default::default(),
+            tasks: vec![
+                long,
+                // Would run even after a failure, but must not start once shutting down
+                conditional_task("cleanup", &["long"], 0, RunCondition::Always),
+            ],
+        };
+
+        let (shutdown_tx, shutdown_rx) = watch::channel(false);
+        let run = tokio::spawn({
+            let scheduler = scheduler.clone();
+            async move {
+                scheduler
+                    .execute_workflow_with_shutdown(&config, shutdown_rx)
+                    .await
+            }
+        });
+        tokio::time::sleep(std::time::Duration::from_millis(300)).await;
+        shutdown_tx.send(true).unwrap();
+
+        let result = tokio::time::timeout(std::time::Duration::from_secs(5), run)
+            .await
+            .expect("cancellation stops the sleeping task promptly")
+            .unwrap()
+            .unwrap();
+        assert_eq!(result.status, TaskStatus::Cancelled);
+
+        let history = state_manager
+            .get_execution_history("cancel-me", 1)
+            .await
+            .unwrap();
+        assert_eq!(history[0].status, TaskStatus::Cancelled);
+        let tasks = state_manager
+            .get_task_executions(history[0].id)
+            .await
+            .unwrap();
+        let statuses: HashMap<_, _> = tasks
+            .iter()
+            .map(|t| (t.task_name.as_str(), t.status.clone()))
+            .collect();
+        // One attempt only: a cancelled task is not retried
+        assert_eq!(tasks.len(), 2);
+        assert_eq!(statuses["long"], TaskStatus::Cancelled);
+        assert_eq!(statuses["cleanup"], TaskStatus::Skipped);
     }
 }
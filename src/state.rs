@@ -2,13 +2,43 @@
 
 use crate::error::{PicoFlowError, Result};
 use crate::models::{
-    TaskExecution, TaskStatus, WorkflowExecution, WorkflowStatistics, WorkflowSummary,
+    DependencyCondition, DurationStats, ExecutionGraph, ExecutionGraphEdge, ExecutionGraphNode,
+    ExportFormat, ExportedExecution, FailureReason, HttpAuth, HttpConfig, TaskCacheEntry,
+    TaskConfig, TaskExecution, TaskExecutorConfig, TaskStatus, WorkflowConfig, WorkflowExecution,
+    WorkflowStatistics, WorkflowSummary,
 };
 use chrono::{DateTime, Utc};
-use rusqlite::{params, Connection, OptionalExtension};
-use std::path::Path;
+use rusqlite::{
+    params, Connection, ErrorCode, OpenFlags, OptionalExtension, Transaction, TransactionBehavior,
+};
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
-use tracing::debug;
+use std::time::Duration;
+use tracing::{debug, warn};
+
+/// How long SQLite waits on a locked database before a statement fails with `SQLITE_BUSY`
+const BUSY_TIMEOUT: Duration = Duration::from_millis(5000);
+
+/// Attempts for a write that keeps failing with `SQLITE_BUSY`/`SQLITE_LOCKED`
+const WRITE_ATTEMPTS: u32 = 3;
+
+/// Pause before retrying a busy write; doubles on each retry
+const WRITE_RETRY_BACKOFF: Duration = Duration::from_millis(50);
+
+/// How often a running execution refreshes its `heartbeat_at`
+pub const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Age after which a running execution's heartbeat counts as stale, i.e. the process
+/// running it is gone
+pub const HEARTBEAT_STALE_AFTER: Duration = Duration::from_secs(3 * 30);
+
+/// Executions kept per workflow when it has no `retention_policy` row
+pub const DEFAULT_RETENTION_MAX_EXECUTIONS: usize = 100;
+
+/// Maximum execution age in days when a workflow has no `retention_policy` row
+pub const DEFAULT_RETENTION_MAX_AGE_DAYS: i64 = 30;
 
 /// State manager for workflow and task execution tracking using SQLite.
 ///
@@ -33,9 +63,14 @@ use tracing::debug;
 /// - Memory temp store
 /// - No memory mapping (safer for SD cards)
 /// - Foreign keys enabled
+/// - 5s busy timeout; writes that still hit a locked database are retried a few times
+///
+/// A manager opened with [`StateManager::new_readonly`] rejects every write with
+/// `PicoFlowError::ReadOnlyDatabase`.
 #[derive(Clone)]
 pub struct StateManager {
     conn: Arc<Mutex<Connection>>,
+    read_only: bool,
 }
 
 impl StateManager {
@@ -62,6 +97,49 @@ impl StateManager {
         .map_err(|e| PicoFlowError::Other(format!("state DB task failed to complete: {e}")))?
     }
 
+    /// Like [`StateManager::with_conn`], but retries the closure when the database is busy
+    /// or locked (e.g. a WAL checkpoint or an external reader holds the lock past the busy
+    /// timeout).
+    ///
+    /// The closure may run more than once, so it must be safe to repeat: a failed
+    /// statement leaves the database unchanged, and multi-statement writes must either be
+    /// idempotent or run in a transaction.
+    async fn with_write_conn<F, T>(&self, f: F) -> Result<T>
+    where
+        F: Fn(&Connection) -> Result<T> + Send + 'static,
+        T: Send + 'static,
+    {
+        if self.read_only {
+            return Err(PicoFlowError::ReadOnlyDatabase);
+        }
+        let conn = Arc::clone(&self.conn);
+        tokio::task::spawn_blocking(move || {
+            let mut backoff = WRITE_RETRY_BACKOFF;
+            let mut attempt = 1;
+            loop {
+                // Lock per attempt so other callers can get in while we back off
+                let result = {
+                    let guard = conn.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+                    f(&guard)
+                };
+                match result {
+                    Err(e) if attempt < WRITE_ATTEMPTS && is_busy(&e) => {
+                        warn!(
+                            "State DB busy (attempt {}/{}), retrying in {:?}: {}",
+                            attempt, WRITE_ATTEMPTS, backoff, e
+                        );
+                        std::thread::sleep(backoff);
+                        backoff *= 2;
+                        attempt += 1;
+                    }
+                    other => return other,
+                }
+            }
+        })
+        .await
+        .map_err(|e| PicoFlowError::Other(format!("state DB task failed to complete: {e}")))?
+    }
+
     /// Create a new state manager with file-based SQLite database.
     ///
     /// This initializes the database schema if needed and configures SQLite
@@ -130,6 +208,7 @@ impl StateManager {
             }
 
             let conn = Connection::open(&db_path)?;
+            conn.busy_timeout(BUSY_TIMEOUT)?;
 
             // Configure SQLite for edge devices
             conn.execute_batch(
@@ -147,6 +226,45 @@ impl StateManager {
 
             Ok(Self {
                 conn: Arc::new(Mutex::new(conn)),
+                read_only: false,
+            })
+        })
+        .await
+        .map_err(|e| PicoFlowError::Other(format!("state DB init failed to complete: {e}")))?
+    }
+
+    /// Open a database for queries only.
+    ///
+    /// Meant for commands like `picoflow history` that only read, so they never take a
+    /// write lock a running daemon is waiting on. The connection is opened with
+    /// `SQLITE_OPEN_READ_ONLY` and `PRAGMA query_only`; write methods return
+    /// `PicoFlowError::ReadOnlyDatabase`. A database that does not exist yet or predates
+    /// the current schema is first created or migrated through [`StateManager::new`],
+    /// so a fresh install reads as empty rather than failing.
+    ///
+    /// # Errors
+    ///
+    /// * `PicoFlowError::Database` - If the database cannot be created, migrated or opened
+    pub async fn new_readonly<P: AsRef<Path>>(db_path: P) -> Result<Self> {
+        let db_path = db_path.as_ref().to_path_buf();
+
+        if needs_initialising(db_path.clone()).await? {
+            drop(Self::new(&db_path).await?);
+        }
+
+        tokio::task::spawn_blocking(move || {
+            let conn = Connection::open_with_flags(
+                &db_path,
+                OpenFlags::SQLITE_OPEN_READ_ONLY
+                    | OpenFlags::SQLITE_OPEN_NO_MUTEX
+                    | OpenFlags::SQLITE_OPEN_URI,
+            )?;
+            conn.busy_timeout(BUSY_TIMEOUT)?;
+            conn.execute_batch("PRAGMA query_only = ON;")?;
+
+            Ok(Self {
+                conn: Arc::new(Mutex::new(conn)),
+                read_only: true,
             })
         })
         .await
@@ -158,10 +276,12 @@ impl StateManager {
     pub async fn in_memory() -> Result<Self> {
         tokio::task::spawn_blocking(|| {
             let conn = Connection::open_in_memory()?;
+            conn.busy_timeout(BUSY_TIMEOUT)?;
             conn.execute_batch("PRAGMA foreign_keys = ON;")?;
             init_schema(&conn)?;
             Ok(Self {
                 conn: Arc::new(Mutex::new(conn)),
+                read_only: false,
             })
         })
         .await
@@ -188,7 +308,7 @@ impl StateManager {
         let name = name.to_string();
         let schedule = schedule.map(|s| s.to_string());
 
-        self.with_conn(move |conn| {
+        self.with_write_conn(move |conn| {
             // Try to get existing workflow
             let existing: Option<i64> = conn
                 .query_row(
@@ -234,10 +354,12 @@ impl StateManager {
     ///
     /// * `PicoFlowError::Database` - If database operation fails
     pub async fn start_execution(&self, workflow_id: i64) -> Result<i64> {
-        self.with_conn(move |conn| {
+        self.with_write_conn(move |conn| {
+            let now = Utc::now();
             conn.execute(
-                "INSERT INTO executions (workflow_id, started_at, status) VALUES (?1, ?2, ?3)",
-                params![workflow_id, Utc::now(), TaskStatus::Running.to_string()],
+                "INSERT INTO executions (workflow_id, started_at, status, heartbeat_at)
+                 VALUES (?1, ?2, ?3, ?2)",
+                params![workflow_id, now, TaskStatus::Running.to_string()],
             )?;
 
             Ok(conn.last_insert_rowid())
@@ -245,6 +367,25 @@ impl StateManager {
         .await
     }
 
+    /// Mark a running execution as still alive.
+    ///
+    /// The scheduler calls this every [`HEARTBEAT_INTERVAL`] so that
+    /// [`StateManager::recover_from_crash`] can tell live executions from abandoned ones.
+    ///
+    /// # Errors
+    ///
+    /// * `PicoFlowError::Database` - If database operation fails
+    pub async fn record_heartbeat(&self, execution_id: i64) -> Result<()> {
+        self.with_write_conn(move |conn| {
+            conn.execute(
+                "UPDATE executions SET heartbeat_at = ?1 WHERE id = ?2",
+                params![Utc::now(), execution_id],
+            )?;
+            Ok(())
+        })
+        .await
+    }
+
     /// Update workflow execution status and set completion time if terminal.
     ///
     /// Sets `completed_at` timestamp for terminal states (Success, Failed, Timeout).
@@ -262,14 +403,17 @@ impl StateManager {
         execution_id: i64,
         status: TaskStatus,
     ) -> Result<()> {
-        self.with_conn(move |conn| {
+        self.with_write_conn(move |conn| {
             conn.execute(
                 "UPDATE executions SET status = ?1, completed_at = ?2 WHERE id = ?3",
                 params![
                     status.to_string(),
                     if matches!(
                         status,
-                        TaskStatus::Success | TaskStatus::Failed | TaskStatus::Timeout
+                        TaskStatus::Success
+                            | TaskStatus::Failed
+                            | TaskStatus::Timeout
+                            | TaskStatus::Cancelled
                     ) {
                         Some(Utc::now())
                     } else {
@@ -309,7 +453,7 @@ impl StateManager {
     ) -> Result<i64> {
         let task_name = task_name.to_string();
 
-        self.with_conn(move |conn| {
+        self.with_write_conn(move |conn| {
             conn.execute(
                 "INSERT INTO task_executions (execution_id, task_name, status, started_at, attempt) VALUES (?1, ?2, ?3, ?4, ?5)",
                 params![
@@ -326,6 +470,201 @@ impl StateManager {
         .await
     }
 
+    /// Record a task that was skipped instead of run.
+    ///
+    /// Inserts a `skipped` row with `started_at` set to the time of the decision and no
+    /// `completed_at`, exit code or output. The attempt number is 0 since the task never ran.
+    ///
+    /// # Arguments
+    ///
+    /// * `execution_id` - ID of the parent workflow execution
+    /// * `task_name` - Name of the skipped task
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(i64)` - Database ID of the task execution record
+    ///
+    /// # Errors
+    ///
+    /// * `PicoFlowError::Database` - If database operation fails
+    pub async fn record_skipped_task(&self, execution_id: i64, task_name: &str) -> Result<i64> {
+        let task_name = task_name.to_string();
+
+        self.with_write_conn(move |conn| {
+            conn.execute(
+                "INSERT INTO task_executions (execution_id, task_name, status, started_at, attempt) VALUES (?1, ?2, ?3, ?4, 0)",
+                params![
+                    execution_id,
+                    task_name,
+                    TaskStatus::Skipped.to_string(),
+                    Utc::now(),
+                ],
+            )?;
+
+            Ok(conn.last_insert_rowid())
+        })
+        .await
+    }
+
+    /// Record a task whose success is reused from an earlier execution when resuming.
+    ///
+    /// Inserts a completed `Success` row with attempt 0 (no attempt ran), so resuming
+    /// this execution in turn reuses the task again. `stdout` is the reused output, if the
+    /// caller stores it.
+    ///
+    /// # Errors
+    ///
+    /// * `PicoFlowError::Database` - If database operation fails
+    pub async fn record_reused_task(
+        &self,
+        execution_id: i64,
+        task_name: &str,
+        stdout: Option<&str>,
+    ) -> Result<i64> {
+        let task_name = task_name.to_string();
+        let stdout = stdout.map(|s| s.to_string());
+
+        self.with_write_conn(move |conn| {
+            let now = Utc::now();
+            conn.execute(
+                "INSERT INTO task_executions (execution_id, task_name, status, started_at, completed_at, stdout, attempt)
+                 VALUES (?1, ?2, ?3, ?4, ?4, ?5, 0)",
+                params![
+                    execution_id,
+                    task_name,
+                    TaskStatus::Success.to_string(),
+                    now,
+                    stdout,
+                ],
+            )?;
+
+            Ok(conn.last_insert_rowid())
+        })
+        .await
+    }
+
+    /// Record a task whose result was reused from the task cache.
+    ///
+    /// Inserts a completed `Success` attempt flagged `cached`, with the cached exit code
+    /// and whatever output the caller chooses to store.
+    ///
+    /// # Errors
+    ///
+    /// * `PicoFlowError::Database` - If database operation fails
+    pub async fn record_cached_task(
+        &self,
+        execution_id: i64,
+        task_name: &str,
+        exit_code: Option<i32>,
+        stdout: Option<&str>,
+        stderr: Option<&str>,
+    ) -> Result<i64> {
+        let task_name = task_name.to_string();
+        let stdout = stdout.map(|s| s.to_string());
+        let stderr = stderr.map(|s| s.to_string());
+
+        self.with_write_conn(move |conn| {
+            let now = Utc::now();
+            conn.execute(
+                "INSERT INTO task_executions (execution_id, task_name, status, started_at, completed_at, exit_code, stdout, stderr, attempt, cached)
+                 VALUES (?1, ?2, ?3, ?4, ?4, ?5, ?6, ?7, 1, 1)",
+                params![
+                    execution_id,
+                    task_name,
+                    TaskStatus::Success.to_string(),
+                    now,
+                    exit_code,
+                    stdout,
+                    stderr,
+                ],
+            )?;
+
+            Ok(conn.last_insert_rowid())
+        })
+        .await
+    }
+
+    /// Look up a cached task result.
+    ///
+    /// # Arguments
+    ///
+    /// * `cache_hash` - Hash of the task's cache key and config
+    /// * `max_age` - Ignore entries older than this (`None` accepts any age)
+    ///
+    /// # Errors
+    ///
+    /// * `PicoFlowError::Database` - If database operation fails
+    pub async fn get_cached_result(
+        &self,
+        cache_hash: &str,
+        max_age: Option<Duration>,
+    ) -> Result<Option<TaskCacheEntry>> {
+        let cache_hash = cache_hash.to_string();
+
+        self.with_conn(move |conn| {
+            let entry = conn
+                .query_row(
+                    "SELECT exit_code, stdout, stderr, created_at FROM task_cache WHERE cache_hash = ?1",
+                    params![cache_hash],
+                    |row| {
+                        Ok(TaskCacheEntry {
+                            exit_code: row.get(0)?,
+                            stdout: row.get(1)?,
+                            stderr: row.get(2)?,
+                            created_at: row.get(3)?,
+                        })
+                    },
+                )
+                .optional()?;
+
+            let fresh = |entry: &TaskCacheEntry| match max_age {
+                Some(max_age) => chrono::Duration::from_std(max_age)
+                    .map(|max_age| Utc::now() - entry.created_at <= max_age)
+                    .unwrap_or(true),
+                None => true,
+            };
+            Ok(entry.filter(fresh))
+        })
+        .await
+    }
+
+    /// Store (or replace) the cached result for `cache_hash`.
+    ///
+    /// `ttl` is the task's `cache_ttl_secs`; once it has passed the entry is deleted by
+    /// [`StateManager::apply_retention_policies`].
+    ///
+    /// # Errors
+    ///
+    /// * `PicoFlowError::Database` - If database operation fails
+    pub async fn store_cached_result(
+        &self,
+        cache_hash: &str,
+        task_name: &str,
+        exit_code: Option<i32>,
+        stdout: Option<&str>,
+        stderr: Option<&str>,
+        ttl: Option<Duration>,
+    ) -> Result<()> {
+        let cache_hash = cache_hash.to_string();
+        let task_name = task_name.to_string();
+        let stdout = stdout.map(|s| s.to_string());
+        let stderr = stderr.map(|s| s.to_string());
+
+        self.with_write_conn(move |conn| {
+            let now = Utc::now();
+            let expires_at = ttl
+                .and_then(|ttl| chrono::Duration::from_std(ttl).ok())
+                .and_then(|ttl| now.checked_add_signed(ttl));
+            conn.execute(
+                "INSERT OR REPLACE INTO task_cache (cache_hash, task_name, exit_code, stdout, stderr, created_at, expires_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                params![cache_hash, task_name, exit_code, stdout, stderr, now, expires_at],
+            )?;
+            Ok(())
+        })
+        .await
+    }
+
     /// Update task execution status with results.
     ///
     /// Records task completion status, exit code, and output (stdout/stderr).
@@ -353,12 +692,12 @@ impl StateManager {
         let stdout = stdout.map(|s| s.to_string());
         let stderr = stderr.map(|s| s.to_string());
 
-        self.with_conn(move |conn| {
+        self.with_write_conn(move |conn| {
             conn.execute(
                 "UPDATE task_executions SET status = ?1, completed_at = ?2, exit_code = ?3, stdout = ?4, stderr = ?5 WHERE id = ?6",
                 params![
                     status.to_string(),
-                    if matches!(status, TaskStatus::Success | TaskStatus::Failed | TaskStatus::Timeout) {
+                    if matches!(status, TaskStatus::Success | TaskStatus::Failed | TaskStatus::Timeout | TaskStatus::Cancelled) {
                         Some(Utc::now())
                     } else {
                         None
@@ -375,6 +714,52 @@ impl StateManager {
         .await
     }
 
+    /// Record that a task's stored output was truncated at the workflow's
+    /// `max_output_bytes` limit (`MAX_OUTPUT_SIZE` by default).
+    ///
+    /// # Arguments
+    ///
+    /// * `task_execution_id` - Database ID of the task execution
+    ///
+    /// # Errors
+    ///
+    /// * `PicoFlowError::Database` - If database operation fails
+    pub async fn mark_output_truncated(&self, task_execution_id: i64) -> Result<()> {
+        self.with_write_conn(move |conn| {
+            conn.execute(
+                "UPDATE task_executions SET output_truncated = 1 WHERE id = ?1",
+                params![task_execution_id],
+            )?;
+            Ok(())
+        })
+        .await
+    }
+
+    /// Record why a task attempt failed.
+    ///
+    /// # Arguments
+    ///
+    /// * `task_execution_id` - Database ID of the task execution
+    /// * `reason` - Classified cause of the failure
+    ///
+    /// # Errors
+    ///
+    /// * `PicoFlowError::Database` - If database operation fails
+    pub async fn set_failure_reason(
+        &self,
+        task_execution_id: i64,
+        reason: FailureReason,
+    ) -> Result<()> {
+        self.with_write_conn(move |conn| {
+            conn.execute(
+                "UPDATE task_executions SET failure_reason = ?1 WHERE id = ?2",
+                params![reason.to_string(), task_execution_id],
+            )?;
+            Ok(())
+        })
+        .await
+    }
+
     /// Set task retry information
     pub async fn set_task_retry(
         &self,
@@ -382,7 +767,7 @@ impl StateManager {
         retry_count: i32,
         next_retry_at: DateTime<Utc>,
     ) -> Result<()> {
-        self.with_conn(move |conn| {
+        self.with_write_conn(move |conn| {
             conn.execute(
                 "UPDATE task_executions SET status = ?1, retry_count = ?2, next_retry_at = ?3 WHERE id = ?4",
                 params![
@@ -422,45 +807,419 @@ impl StateManager {
         .await
     }
 
-    /// Get task executions for a workflow execution
-    pub async fn get_task_executions(&self, execution_id: i64) -> Result<Vec<TaskExecution>> {
-        self.with_conn(move |conn| {
-            let mut stmt = conn.prepare(
-                "SELECT id, execution_id, task_name, status, started_at, completed_at, exit_code, stdout, stderr, attempt, retry_count, next_retry_at
-                 FROM task_executions WHERE execution_id = ?1 ORDER BY started_at",
-            )?;
-
-            let rows = stmt.query_map(params![execution_id], |row| {
-                Ok(TaskExecution {
-                    id: row.get(0)?,
-                    execution_id: row.get(1)?,
-                    task_name: row.get(2)?,
-                    status: parse_task_status(&row.get::<_, String>(3)?),
-                    started_at: row.get(4)?,
-                    completed_at: row.get(5)?,
-                    exit_code: row.get(6)?,
-                    stdout: row.get(7)?,
-                    stderr: row.get(8)?,
-                    attempt: row.get(9)?,
-                    retry_count: row.get(10)?,
-                    next_retry_at: row.get(11)?,
-                })
-            })?;
+    /// Record the tasks of an execution and their `depends_on` edges, for
+    /// [`StateManager::get_execution_graph`]
+    ///
+    /// The scheduler calls this right after [`StateManager::start_execution`], so the graph
+    /// of a run is available while it is still in progress.
+    ///
+    /// # Errors
+    ///
+    /// * `PicoFlowError::Database` - If database operation fails
+    pub async fn record_execution_graph(
+        &self,
+        execution_id: i64,
+        tasks: &[TaskConfig],
+    ) -> Result<()> {
+        let tasks = tasks
+            .iter()
+            .map(|task| {
+                let deps: Vec<_> = task
+                    .depends_on
+                    .iter()
+                    .map(|dep| (dep.task().to_string(), dep.condition()))
+                    .collect();
+                (task.name.clone(), deps)
+            })
+            .collect::<Vec<_>>();
 
-            let mut executions = Vec::new();
-            for row in rows {
-                executions.push(row?);
+        self.with_write_conn(move |conn| {
+            let tx = conn.unchecked_transaction()?;
+            {
+                let mut insert_task = tx.prepare(
+                    "INSERT INTO execution_tasks (execution_id, position, task_name)
+                     VALUES (?1, ?2, ?3)",
+                )?;
+                let mut insert_edge = tx.prepare(
+                    "INSERT INTO execution_edges (execution_id, task_name, dependency, condition)
+                     VALUES (?1, ?2, ?3, ?4)",
+                )?;
+                for (position, (name, deps)) in tasks.iter().enumerate() {
+                    insert_task.execute(params![execution_id, position as i64, name])?;
+                    for (dep, condition) in deps {
+                        insert_edge.execute(params![
+                            execution_id,
+                            name,
+                            dep,
+                            condition.map(|c| c.to_string())
+                        ])?;
+                    }
+                }
+            }
+            tx.commit()?;
+            Ok(())
+        })
+        .await
+    }
+
+    /// Store the workflow configuration an execution runs with, for
+    /// [`StateManager::get_execution_config`]
+    ///
+    /// The snapshot is taken after `${VAR}` and param expansion, so history can be read
+    /// against the config that actually ran even after the YAML changes. Since expanded
+    /// values may be secrets, every shell and Docker `env` value, HTTP `auth` credentials,
+    /// sensitive headers (as in logs), HTTP bodies, and URL query values and passwords are
+    /// stored as `[REDACTED]`. Commands and args are kept, so pass secrets through `env`.
+    ///
+    /// # Errors
+    ///
+    /// * `PicoFlowError::Database` - If database operation fails
+    pub async fn record_execution_config(
+        &self,
+        execution_id: i64,
+        config: &WorkflowConfig,
+    ) -> Result<()> {
+        let snapshot = serde_json::to_string(&redacted_config(config)).map_err(|e| {
+            PicoFlowError::Other(format!("Failed to serialize workflow config: {}", e))
+        })?;
+
+        self.with_write_conn(move |conn| {
+            conn.execute(
+                "INSERT OR REPLACE INTO execution_configs (execution_id, config) VALUES (?1, ?2)",
+                params![execution_id, snapshot],
+            )?;
+            Ok(())
+        })
+        .await
+    }
+
+    /// Get the workflow configuration snapshot stored when an execution started
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(None)` - If the execution does not exist or predates config snapshots
+    ///
+    /// # Errors
+    ///
+    /// * `PicoFlowError::Database` - If database query fails
+    /// * `PicoFlowError::Other` - If the stored snapshot cannot be parsed
+    pub async fn get_execution_config(&self, execution_id: i64) -> Result<Option<WorkflowConfig>> {
+        self.with_conn(move |conn| {
+            let snapshot: Option<String> = conn
+                .query_row(
+                    "SELECT config FROM execution_configs WHERE execution_id = ?1",
+                    params![execution_id],
+                    |row| row.get(0),
+                )
+                .optional()?;
+
+            snapshot
+                .map(|json| {
+                    serde_json::from_str(&json).map_err(|e| {
+                        PicoFlowError::Other(format!(
+                            "Invalid config snapshot for execution {}: {}",
+                            execution_id, e
+                        ))
+                    })
+                })
+                .transpose()
+        })
+        .await
+    }
+
+    /// Get the dependency graph of an execution with the latest status of each task
+    ///
+    /// Tasks that have not started yet are reported as `pending`. Executions recorded
+    /// before the graph was persisted only list the tasks that ran, without edges.
+    ///
+    /// # Errors
+    ///
+    /// * `PicoFlowError::Other` - If the execution does not exist
+    /// * `PicoFlowError::Database` - If database query fails
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use picoflow::state::StateManager;
+    /// # async fn example() -> anyhow::Result<()> {
+    /// let manager = StateManager::new("/var/lib/picoflow/state.db").await?;
+    /// let graph = manager.get_execution_graph(42).await?;
+    /// println!("{}", serde_json::to_string(&graph)?);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn get_execution_graph(&self, execution_id: i64) -> Result<ExecutionGraph> {
+        self.with_conn(move |conn| {
+            let (workflow, status, started_at, completed_at) = conn
+                .query_row(
+                    "SELECT w.name, e.status, e.started_at, e.completed_at
+                     FROM executions e JOIN workflows w ON e.workflow_id = w.id
+                     WHERE e.id = ?1",
+                    params![execution_id],
+                    |row| {
+                        Ok((
+                            row.get::<_, String>(0)?,
+                            parse_task_status(&row.get::<_, String>(1)?),
+                            row.get(2)?,
+                            row.get(3)?,
+                        ))
+                    },
+                )
+                .optional()?
+                .ok_or_else(|| {
+                    PicoFlowError::Other(format!("Execution {} not found", execution_id))
+                })?;
+
+            // Latest attempt of every task that has a row, in the order tasks started
+            let mut stmt = conn.prepare(
+                "SELECT task_name, status, attempt, started_at, completed_at FROM task_executions
+                 WHERE id IN (
+                     SELECT MAX(id) FROM task_executions WHERE execution_id = ?1 GROUP BY task_name
+                 )
+                 ORDER BY id",
+            )?;
+            let rows = stmt.query_map(params![execution_id], |row| {
+                Ok(ExecutionGraphNode {
+                    task: row.get(0)?,
+                    status: parse_task_status(&row.get::<_, String>(1)?),
+                    attempt: row.get(2)?,
+                    started_at: row.get(3)?,
+                    completed_at: row.get(4)?,
+                })
+            })?;
+            let mut latest: Vec<ExecutionGraphNode> = rows.collect::<rusqlite::Result<_>>()?;
+
+            let mut stmt = conn.prepare(
+                "SELECT task_name FROM execution_tasks WHERE execution_id = ?1 ORDER BY position",
+            )?;
+            let names: Vec<String> = stmt
+                .query_map(params![execution_id], |row| row.get(0))?
+                .collect::<rusqlite::Result<_>>()?;
+            let nodes = if names.is_empty() {
+                latest
+            } else {
+                names
+                    .into_iter()
+                    .map(|task| match latest.iter().position(|n| n.task == task) {
+                        Some(index) => latest.swap_remove(index),
+                        None => ExecutionGraphNode {
+                            task,
+                            status: TaskStatus::Pending,
+                            attempt: 0,
+                            started_at: None,
+                            completed_at: None,
+                        },
+                    })
+                    .collect()
+            };
+
+            let mut stmt = conn.prepare(
+                "SELECT dependency, task_name, condition FROM execution_edges
+                 WHERE execution_id = ?1 ORDER BY rowid",
+            )?;
+            let edges = stmt
+                .query_map(params![execution_id], |row| {
+                    Ok(ExecutionGraphEdge {
+                        from: row.get(0)?,
+                        to: row.get(1)?,
+                        on: row
+                            .get::<_, Option<String>>(2)?
+                            .as_deref()
+                            .and_then(parse_dependency_condition),
+                    })
+                })?
+                .collect::<rusqlite::Result<_>>()?;
+
+            Ok(ExecutionGraph {
+                execution_id,
+                workflow,
+                status,
+                started_at,
+                completed_at,
+                nodes,
+                edges,
+            })
+        })
+        .await
+    }
+
+    /// Get task executions for a workflow execution
+    pub async fn get_task_executions(&self, execution_id: i64) -> Result<Vec<TaskExecution>> {
+        self.with_conn(move |conn| {
+            let mut stmt = conn.prepare(&format!(
+                "SELECT {} FROM task_executions WHERE execution_id = ?1 ORDER BY started_at",
+                TASK_EXECUTION_COLUMNS
+            ))?;
+
+            let rows = stmt.query_map(params![execution_id], task_execution_from_row)?;
+
+            let mut executions = Vec::new();
+            for row in rows {
+                executions.push(row?);
+            }
+
+            Ok(executions)
+        })
+        .await
+    }
+
+    /// Get every attempt of a task in an execution, ordered by attempt number
+    ///
+    /// Each retry is stored as its own row, so this returns the whole attempt chain (e.g.
+    /// two failed attempts followed by the successful third). A task that was skipped has
+    /// a single row with attempt 0.
+    ///
+    /// # Arguments
+    ///
+    /// * `execution_id` - ID of the workflow execution
+    /// * `task_name` - Name of the task
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Vec<TaskExecution>)` - Attempts in order (empty if the task never ran)
+    ///
+    /// # Errors
+    ///
+    /// * `PicoFlowError::Database` - If database query fails
+    pub async fn get_task_attempts(
+        &self,
+        execution_id: i64,
+        task_name: &str,
+    ) -> Result<Vec<TaskExecution>> {
+        let task_name = task_name.to_string();
+
+        self.with_conn(move |conn| {
+            let mut stmt = conn.prepare(&format!(
+                "SELECT {} FROM task_executions
+                 WHERE execution_id = ?1 AND task_name = ?2
+                 ORDER BY attempt, id",
+                TASK_EXECUTION_COLUMNS
+            ))?;
+
+            let rows = stmt.query_map(params![execution_id, task_name], task_execution_from_row)?;
+
+            let mut attempts = Vec::new();
+            for row in rows {
+                attempts.push(row?);
+            }
+
+            Ok(attempts)
+        })
+        .await
+    }
+
+    /// Get the stored stdout and stderr of a task in an execution.
+    ///
+    /// When the task was retried, the output of its latest attempt is returned. Output
+    /// that was never captured (e.g. a skipped task) is returned as an empty string.
+    ///
+    /// # Arguments
+    ///
+    /// * `execution_id` - ID of the workflow execution
+    /// * `task_name` - Name of the task
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Some((stdout, stderr)))` - Output of the task's latest attempt
+    /// * `Ok(None)` - If the execution has no record of the task
+    ///
+    /// # Errors
+    ///
+    /// * `PicoFlowError::Database` - If database query fails
+    pub async fn get_task_output(
+        &self,
+        execution_id: i64,
+        task_name: &str,
+    ) -> Result<Option<(String, String)>> {
+        let task_name = task_name.to_string();
+
+        self.with_conn(move |conn| {
+            let output = conn
+                .query_row(
+                    "SELECT stdout, stderr FROM task_executions
+                     WHERE execution_id = ?1 AND task_name = ?2
+                     ORDER BY attempt DESC, id DESC LIMIT 1",
+                    params![execution_id, task_name],
+                    |row| {
+                        let stdout: Option<String> = row.get(0)?;
+                        let stderr: Option<String> = row.get(1)?;
+                        Ok((stdout.unwrap_or_default(), stderr.unwrap_or_default()))
+                    },
+                )
+                .optional()?;
+            Ok(output)
+        })
+        .await
+    }
+
+    /// Get the final status of each task in an execution.
+    ///
+    /// When a task was retried, the status of its latest attempt is returned.
+    ///
+    /// # Arguments
+    ///
+    /// * `execution_id` - ID of the workflow execution
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(HashMap)` - Task name to status (empty if the execution has no task records)
+    ///
+    /// # Errors
+    ///
+    /// * `PicoFlowError::Database` - If database query fails
+    pub async fn get_execution_task_statuses(
+        &self,
+        execution_id: i64,
+    ) -> Result<HashMap<String, TaskStatus>> {
+        self.with_conn(move |conn| latest_task_statuses(conn, execution_id))
+            .await
+    }
+
+    /// Get the final status of each task in the most recent execution of a workflow.
+    ///
+    /// # Arguments
+    ///
+    /// * `workflow_name` - Name of the workflow
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(HashMap)` - Task name to status (empty if the workflow never ran)
+    ///
+    /// # Errors
+    ///
+    /// * `PicoFlowError::Database` - If database query fails
+    pub async fn get_last_execution_task_statuses(
+        &self,
+        workflow_name: &str,
+    ) -> Result<HashMap<String, TaskStatus>> {
+        let workflow_name = workflow_name.to_string();
+
+        self.with_conn(move |conn| {
+            let execution_id: Option<i64> = conn
+                .query_row(
+                    "SELECT e.id FROM executions e
+                     JOIN workflows w ON e.workflow_id = w.id
+                     WHERE w.name = ?1
+                     ORDER BY e.started_at DESC, e.id DESC LIMIT 1",
+                    params![workflow_name],
+                    |row| row.get(0),
+                )
+                .optional()?;
+
+            match execution_id {
+                Some(id) => latest_task_statuses(conn, id),
+                None => Ok(HashMap::new()),
             }
-
-            Ok(executions)
         })
         .await
     }
 
     /// Recover from process crash by marking incomplete executions as failed.
     ///
-    /// Finds all executions with status `Running` (indicating the process crashed
-    /// while they were executing) and marks them as `Failed` with current timestamp.
+    /// Finds executions with status `Running` whose heartbeat is older than
+    /// [`HEARTBEAT_STALE_AFTER`] (indicating the process running them crashed) and marks
+    /// them as `Failed` with current timestamp. Executions with a fresh heartbeat belong to
+    /// a live process, such as a daemon sharing the database, and are left alone.
     /// Any task rows belonging to those executions that were still `Running`/`Retrying`
     /// are also marked `Failed`, so no orphaned "perpetually running" task rows remain.
     /// This is called on daemon startup for crash recovery.
@@ -485,27 +1244,40 @@ impl StateManager {
     /// # }
     /// ```
     pub async fn recover_from_crash(&self) -> Result<Vec<i64>> {
-        self.with_conn(move |conn| {
-            // Find executions that were running when process crashed
+        self.with_write_conn(move |conn| {
+            // All or nothing, so a crash part-way never leaves tasks running under a
+            // failed execution
+            let tx = conn.unchecked_transaction()?;
+            let now = Utc::now();
+            let stale_before =
+                now - chrono::Duration::seconds(HEARTBEAT_STALE_AFTER.as_secs() as i64);
+
+            // Find executions that were running when process crashed. Rows from before the
+            // heartbeat column existed have no heartbeat and count as stale.
             let crashed_ids: Vec<i64> = {
-                let mut stmt = conn.prepare("SELECT id FROM executions WHERE status = ?1")?;
+                let mut stmt = tx.prepare(
+                    "SELECT id FROM executions
+                     WHERE status = ?1 AND (heartbeat_at IS NULL OR heartbeat_at < ?2)",
+                )?;
                 let ids = stmt
-                    .query_map(params![TaskStatus::Running.to_string()], |row| row.get(0))?
+                    .query_map(
+                        params![TaskStatus::Running.to_string(), stale_before],
+                        |row| row.get(0),
+                    )?
                     .collect::<std::result::Result<Vec<_>, _>>()?;
                 ids
             };
 
-            let now = Utc::now();
             for id in &crashed_ids {
                 // Mark the execution failed
-                conn.execute(
+                tx.execute(
                     "UPDATE executions SET status = ?1, completed_at = ?2 WHERE id = ?3",
                     params![TaskStatus::Failed.to_string(), now, id],
                 )?;
 
                 // Mark any still-in-flight task rows for this execution failed too,
                 // so they don't linger as "running"/"retrying" forever.
-                conn.execute(
+                tx.execute(
                     "UPDATE task_executions SET status = ?1, completed_at = ?2 \
                      WHERE execution_id = ?3 AND status IN (?4, ?5)",
                     params![
@@ -518,11 +1290,41 @@ impl StateManager {
                 )?;
             }
 
+            tx.commit()?;
             Ok(crashed_ids)
         })
         .await
     }
 
+    /// Whether the workflow has an execution that is still running
+    ///
+    /// Only executions with a fresh heartbeat count, so a row left `running` by a crashed
+    /// process does not block the workflow until [`StateManager::recover_from_crash`]
+    /// cleans it up.
+    ///
+    /// # Errors
+    ///
+    /// * `PicoFlowError::Database` - If database query fails
+    pub async fn has_running_execution(&self, workflow_name: &str) -> Result<bool> {
+        let workflow_name = workflow_name.to_string();
+
+        self.with_conn(move |conn| {
+            let stale_before =
+                Utc::now() - chrono::Duration::seconds(HEARTBEAT_STALE_AFTER.as_secs() as i64);
+            let running = conn.query_row(
+                "SELECT EXISTS(
+                     SELECT 1 FROM executions e
+                     JOIN workflows w ON e.workflow_id = w.id
+                     WHERE w.name = ?1 AND e.status = ?2 AND e.heartbeat_at >= ?3
+                 )",
+                params![workflow_name, TaskStatus::Running.to_string(), stale_before],
+                |row| row.get(0),
+            )?;
+            Ok(running)
+        })
+        .await
+    }
+
     /// Get execution history for a workflow
     pub async fn get_execution_history(
         &self,
@@ -635,6 +1437,136 @@ impl StateManager {
         .await
     }
 
+    /// Write every stored execution to `writer` as CSV or NDJSON
+    ///
+    /// Executions are read and written one row at a time in ID order, so exporting a
+    /// large history does not hold it all in memory. With `include_tasks`, NDJSON records
+    /// carry the execution's task attempts in a `tasks` array, and CSV gets one row per
+    /// attempt (an execution without tasks still gets a row, with empty task columns).
+    ///
+    /// The connection stays locked for the whole export, so other state writes wait
+    /// until it finishes.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(W)` - The writer, flushed, once every execution has been written
+    ///
+    /// # Errors
+    ///
+    /// * `PicoFlowError::Database` - If database query fails
+    /// * `PicoFlowError::Io` - If writing to `writer` fails
+    pub async fn export_executions<W>(
+        &self,
+        writer: W,
+        format: ExportFormat,
+        include_tasks: bool,
+    ) -> Result<W>
+    where
+        W: Write + Send + 'static,
+    {
+        self.with_conn(move |conn| {
+            let mut writer = writer;
+            let mut executions = conn.prepare(
+                "SELECT w.name, e.id, e.workflow_id, e.started_at, e.completed_at, e.status
+                 FROM executions e
+                 JOIN workflows w ON e.workflow_id = w.id
+                 ORDER BY e.id",
+            )?;
+            let mut tasks = conn.prepare(&format!(
+                "SELECT {} FROM task_executions WHERE execution_id = ?1 ORDER BY started_at, id",
+                TASK_EXECUTION_COLUMNS
+            ))?;
+
+            if format == ExportFormat::Csv {
+                let header = if include_tasks {
+                    [CSV_EXECUTION_HEADER, CSV_TASK_HEADER].join(",")
+                } else {
+                    CSV_EXECUTION_HEADER.to_string()
+                };
+                writeln!(writer, "{}", header)?;
+            }
+
+            let mut rows = executions.query([])?;
+            while let Some(row) = rows.next()? {
+                let workflow: String = row.get(0)?;
+                let execution = WorkflowExecution {
+                    id: row.get(1)?,
+                    workflow_id: row.get(2)?,
+                    started_at: row.get(3)?,
+                    completed_at: row.get(4)?,
+                    status: parse_task_status(&row.get::<_, String>(5)?),
+                };
+                let task_executions = if include_tasks {
+                    tasks
+                        .query_map(params![execution.id], task_execution_from_row)?
+                        .collect::<rusqlite::Result<Vec<_>>>()?
+                } else {
+                    Vec::new()
+                };
+
+                match format {
+                    ExportFormat::Ndjson => {
+                        let record = ExportedExecution {
+                            workflow,
+                            execution,
+                            tasks: task_executions,
+                        };
+                        serde_json::to_writer(&mut writer, &record)
+                            .map_err(std::io::Error::from)?;
+                        writeln!(writer)?;
+                    }
+                    ExportFormat::Csv => {
+                        let mut fields = vec![
+                            execution.id.to_string(),
+                            workflow,
+                            execution.started_at.to_rfc3339(),
+                            execution
+                                .completed_at
+                                .map(|t| t.to_rfc3339())
+                                .unwrap_or_default(),
+                            execution.status.to_string(),
+                        ];
+                        if !include_tasks {
+                            write_csv_row(&mut writer, &fields)?;
+                        } else if task_executions.is_empty() {
+                            fields.resize(
+                                fields.len() + CSV_TASK_HEADER.split(',').count(),
+                                String::new(),
+                            );
+                            write_csv_row(&mut writer, &fields)?;
+                        }
+                        for task in &task_executions {
+                            let mut task_fields = fields.clone();
+                            task_fields.extend([
+                                task.id.to_string(),
+                                task.task_name.clone(),
+                                task.status.to_string(),
+                                task.attempt.to_string(),
+                                task.started_at.to_rfc3339(),
+                                task.completed_at
+                                    .map(|t| t.to_rfc3339())
+                                    .unwrap_or_default(),
+                                task.exit_code.map(|c| c.to_string()).unwrap_or_default(),
+                                task.failure_reason
+                                    .map(|r| r.to_string())
+                                    .unwrap_or_default(),
+                                task.cached.to_string(),
+                                task.output_truncated.to_string(),
+                                task.stdout.clone().unwrap_or_default(),
+                                task.stderr.clone().unwrap_or_default(),
+                            ]);
+                            write_csv_row(&mut writer, &task_fields)?;
+                        }
+                    }
+                }
+            }
+
+            writer.flush()?;
+            Ok(writer)
+        })
+        .await
+    }
+
     /// Get workflow execution statistics
     ///
     /// Returns aggregate statistics including:
@@ -713,26 +1645,77 @@ impl StateManager {
         .await
     }
 
-    /// Delete executions older than retention period
+    /// Get run-time statistics for one task of a workflow
     ///
-    /// This method removes old execution records and associated task executions
-    /// to keep the database size manageable. Foreign key cascades handle
-    /// deletion of task_executions automatically.
+    /// Durations are `completed_at - started_at` of every task attempt that finished
+    /// (any final status), across all retained executions. Running and skipped rows have
+    /// no `completed_at` and are ignored.
     ///
     /// # Arguments
     ///
-    /// * `retention_days` - Number of days to retain execution history
+    /// * `workflow` - Name of the workflow
+    /// * `task` - Name of the task within the workflow
     ///
     /// # Returns
     ///
-    /// * `Ok(usize)` - Number of executions deleted
-    pub async fn cleanup_old_executions(&self, retention_days: u32) -> Result<usize> {
-        self.with_conn(move |conn| {
-            let cutoff = Utc::now() - chrono::Duration::days(retention_days as i64);
+    /// * `Ok(DurationStats)` - Min/max/avg/p95 in seconds (`sample_count` 0 if no runs)
+    ///
+    /// # Errors
+    ///
+    /// * `PicoFlowError::Database` - If database operation fails
+    pub async fn get_task_duration_stats(
+        &self,
+        workflow: &str,
+        task: &str,
+    ) -> Result<DurationStats> {
+        let workflow = workflow.to_string();
+        let task = task.to_string();
 
-            let deleted = conn.execute(
-                "DELETE FROM executions WHERE completed_at < ?1",
-                params![cutoff],
+        self.with_conn(move |conn| {
+            let mut stmt = conn.prepare(
+                "SELECT t.started_at, t.completed_at
+                 FROM task_executions t
+                 JOIN executions e ON t.execution_id = e.id
+                 JOIN workflows w ON e.workflow_id = w.id
+                 WHERE w.name = ?1 AND t.task_name = ?2 AND t.completed_at IS NOT NULL",
+            )?;
+
+            let rows = stmt.query_map(params![workflow, task], |row| {
+                let started: DateTime<Utc> = row.get(0)?;
+                let completed: DateTime<Utc> = row.get(1)?;
+                Ok((completed - started).num_milliseconds() as f64 / 1000.0)
+            })?;
+
+            let mut durations = Vec::new();
+            for row in rows {
+                durations.push(row?);
+            }
+
+            Ok(DurationStats::from_durations(durations))
+        })
+        .await
+    }
+
+    /// Delete executions older than retention period
+    ///
+    /// This method removes old execution records and associated task executions
+    /// to keep the database size manageable. Foreign key cascades handle
+    /// deletion of task_executions automatically.
+    ///
+    /// # Arguments
+    ///
+    /// * `retention_days` - Number of days to retain execution history
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(usize)` - Number of executions deleted
+    pub async fn cleanup_old_executions(&self, retention_days: u32) -> Result<usize> {
+        self.with_write_conn(move |conn| {
+            let cutoff = Utc::now() - chrono::Duration::days(retention_days as i64);
+
+            let deleted = conn.execute(
+                "DELETE FROM executions WHERE completed_at < ?1",
+                params![cutoff],
             )?;
 
             Ok(deleted)
@@ -740,6 +1723,155 @@ impl StateManager {
         .await
     }
 
+    /// Prune a workflow's execution history by count and age
+    ///
+    /// Keeps the newest `max_executions` executions of the workflow and deletes the
+    /// rest, along with any execution that started more than `max_age_days` ago.
+    /// Running executions are never deleted. Foreign key cascades remove the
+    /// associated task_executions.
+    ///
+    /// # Arguments
+    ///
+    /// * `workflow_name` - Workflow whose history should be pruned
+    /// * `max_executions` - Number of most recent executions to keep (0 = no count limit)
+    /// * `max_age_days` - Maximum age of executions in days (<= 0 = no age limit)
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(usize)` - Number of executions deleted
+    ///
+    /// # Errors
+    ///
+    /// * `PicoFlowError::Database` - If the delete fails
+    pub async fn prune_executions(
+        &self,
+        workflow_name: &str,
+        max_executions: usize,
+        max_age_days: i64,
+    ) -> Result<usize> {
+        let workflow_name = workflow_name.to_string();
+        self.with_write_conn(move |conn| {
+            prune_workflow_executions(conn, &workflow_name, max_executions, max_age_days)
+        })
+        .await
+    }
+
+    /// Set the retention policy for a workflow
+    ///
+    /// The policy is applied by [`StateManager::apply_retention_policies`].
+    ///
+    /// # Arguments
+    ///
+    /// * `workflow_name` - Workflow the policy applies to
+    /// * `max_executions` - Number of most recent executions to keep (0 = no count limit)
+    /// * `max_age_days` - Maximum age of executions in days (<= 0 = no age limit)
+    pub async fn set_retention_policy(
+        &self,
+        workflow_name: &str,
+        max_executions: usize,
+        max_age_days: i64,
+    ) -> Result<()> {
+        let workflow_name = workflow_name.to_string();
+        self.with_write_conn(move |conn| {
+            conn.execute(
+                "INSERT INTO retention_policy (workflow_name, max_executions, max_age_days)
+                 VALUES (?1, ?2, ?3)
+                 ON CONFLICT(workflow_name) DO UPDATE SET
+                    max_executions = excluded.max_executions,
+                    max_age_days = excluded.max_age_days",
+                params![workflow_name, max_executions as i64, max_age_days],
+            )?;
+            Ok(())
+        })
+        .await
+    }
+
+    /// Prune the execution history of every workflow, and the task cache
+    ///
+    /// Workflows with a row in the `retention_policy` table are pruned with that policy;
+    /// all others use the table defaults ([`DEFAULT_RETENTION_MAX_EXECUTIONS`] executions,
+    /// [`DEFAULT_RETENTION_MAX_AGE_DAYS`] days). Cached task results are deleted once
+    /// their TTL has passed or they are older than [`DEFAULT_RETENTION_MAX_AGE_DAYS`]
+    /// days, since a changed cache key or config leaves the old entry unused. Everything
+    /// is deleted in one transaction. The daemon calls this periodically.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(usize)` - Total number of executions deleted
+    ///
+    /// # Errors
+    ///
+    /// * `PicoFlowError::Database` - If reading policies or deleting executions fails
+    pub async fn apply_retention_policies(&self) -> Result<usize> {
+        self.with_write_conn(move |conn| {
+            let tx = conn.unchecked_transaction()?;
+            let policies: Vec<(String, Option<i64>, Option<i64>)> = {
+                let mut stmt = tx.prepare(
+                    "SELECT w.name, r.max_executions, r.max_age_days
+                     FROM workflows w
+                     LEFT JOIN retention_policy r ON r.workflow_name = w.name",
+                )?;
+                let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?;
+                rows.collect::<std::result::Result<_, _>>()?
+            };
+
+            let mut total = 0;
+            for (name, max_executions, max_age_days) in policies {
+                let max_executions = max_executions
+                    .unwrap_or(DEFAULT_RETENTION_MAX_EXECUTIONS as i64)
+                    .max(0) as usize;
+                let max_age_days = max_age_days.unwrap_or(DEFAULT_RETENTION_MAX_AGE_DAYS);
+                let deleted = prune_workflow_executions(&tx, &name, max_executions, max_age_days)?;
+                if deleted > 0 {
+                    debug!("Pruned {} executions of workflow '{}'", deleted, name);
+                }
+                total += deleted;
+            }
+
+            let now = Utc::now();
+            let cutoff = now - chrono::Duration::days(DEFAULT_RETENTION_MAX_AGE_DAYS);
+            let pruned = tx.execute(
+                "DELETE FROM task_cache WHERE expires_at <= ?1 OR created_at < ?2",
+                params![now, cutoff],
+            )?;
+            tx.commit()?;
+            if pruned > 0 {
+                debug!("Pruned {} cached task results", pruned);
+            }
+
+            Ok(total)
+        })
+        .await
+    }
+
+    /// Reclaim disk space freed by deleted executions.
+    ///
+    /// SQLite keeps freed pages inside the database file, so pruning history does not
+    /// shrink it. This checkpoints and truncates the WAL, rebuilds the database with
+    /// `VACUUM`, and truncates the WAL again (in WAL mode `VACUUM` writes the rebuilt pages
+    /// through the log).
+    ///
+    /// `VACUUM` rewrites the whole database and blocks writes while it runs, so schedule
+    /// it when no workflows are executing; on an SD card expect a few seconds per
+    /// hundred megabytes.
+    ///
+    /// # Errors
+    ///
+    /// * `PicoFlowError::Database` - If the checkpoint or `VACUUM` fails
+    pub async fn compact(&self) -> Result<()> {
+        self.with_write_conn(move |conn| {
+            conn.execute_batch(
+                "
+                PRAGMA wal_checkpoint(TRUNCATE);
+                VACUUM;
+                PRAGMA wal_checkpoint(TRUNCATE);
+                ",
+            )?;
+            Ok(())
+        })
+        .await
+    }
+
     /// List all workflows with their execution statistics.
     ///
     /// Returns workflow information including name, total executions, and last execution time.
@@ -766,41 +1898,125 @@ impl StateManager {
     /// # }
     /// ```
     pub async fn list_workflows(&self) -> Result<Vec<WorkflowSummary>> {
-        self.with_conn(move |conn| {
-            let mut stmt = conn.prepare(
-                "SELECT
-                    w.name,
-                    w.schedule,
-                    COUNT(e.id) as execution_count,
-                    SUM(CASE WHEN e.status = 'success' THEN 1 ELSE 0 END) as success_count,
-                    SUM(CASE WHEN e.status IN ('failed', 'timeout') THEN 1 ELSE 0 END) as failed_count,
-                    MAX(e.started_at) as last_execution
-                 FROM workflows w
-                 LEFT JOIN executions e ON w.id = e.workflow_id
-                 GROUP BY w.id, w.name, w.schedule
-                 ORDER BY last_execution DESC NULLS LAST",
-            )?;
+        self.with_conn(move |conn| query_workflow_summaries(conn, None))
+            .await
+    }
 
-            let rows = stmt.query_map([], |row| {
-                Ok(WorkflowSummary {
-                    name: row.get(0)?,
-                    schedule: row.get(1)?,
-                    execution_count: row.get(2)?,
-                    success_count: row.get(3)?,
-                    failed_count: row.get(4)?,
-                    last_execution: row.get(5)?,
-                })
-            })?;
+    /// Replace the labels stored for a workflow.
+    ///
+    /// Labels not present in `labels` are removed, so the stored set always matches the
+    /// workflow's current YAML.
+    ///
+    /// # Arguments
+    ///
+    /// * `workflow_id` - Database ID of the workflow (from `get_or_create_workflow`)
+    /// * `labels` - Label key/value pairs
+    ///
+    /// # Errors
+    ///
+    /// * `PicoFlowError::Database` - If database operation fails
+    pub async fn set_workflow_labels(
+        &self,
+        workflow_id: i64,
+        labels: &HashMap<String, String>,
+    ) -> Result<()> {
+        let labels = labels.clone();
 
-            let mut workflows = Vec::new();
-            for row in rows {
-                workflows.push(row?);
+        self.with_write_conn(move |conn| {
+            let tx = conn.unchecked_transaction()?;
+            tx.execute(
+                "DELETE FROM workflow_labels WHERE workflow_id = ?1",
+                params![workflow_id],
+            )?;
+            for (key, value) in &labels {
+                tx.execute(
+                    "INSERT INTO workflow_labels (workflow_id, key, value) VALUES (?1, ?2, ?3)",
+                    params![workflow_id, key, value],
+                )?;
             }
-
-            Ok(workflows)
+            tx.commit()?;
+            Ok(())
         })
         .await
     }
+
+    /// List workflows carrying the label `key: value`, with their execution statistics.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - Label key
+    /// * `value` - Label value (matched exactly)
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Vec<WorkflowSummary>)` - Matching workflows, ordered like [`StateManager::list_workflows`]
+    ///
+    /// # Errors
+    ///
+    /// * `PicoFlowError::Database` - If database query fails
+    pub async fn list_workflows_by_label(
+        &self,
+        key: &str,
+        value: &str,
+    ) -> Result<Vec<WorkflowSummary>> {
+        let label = (key.to_string(), value.to_string());
+
+        self.with_conn(move |conn| query_workflow_summaries(conn, Some(&label)))
+            .await
+    }
+}
+
+/// Workflow summaries, optionally restricted to workflows carrying the label `(key, value)`
+fn query_workflow_summaries(
+    conn: &Connection,
+    label: Option<&(String, String)>,
+) -> Result<Vec<WorkflowSummary>> {
+    let mut stmt = conn.prepare(
+        "SELECT
+            w.name,
+            w.schedule,
+            COUNT(e.id) as execution_count,
+            SUM(CASE WHEN e.status = 'success' THEN 1 ELSE 0 END) as success_count,
+            SUM(CASE WHEN e.status IN ('failed', 'timeout') THEN 1 ELSE 0 END) as failed_count,
+            MAX(e.started_at) as last_execution
+         FROM workflows w
+         LEFT JOIN executions e ON w.id = e.workflow_id
+         WHERE ?1 IS NULL OR EXISTS (
+            SELECT 1 FROM workflow_labels l
+            WHERE l.workflow_id = w.id AND l.key = ?1 AND l.value = ?2
+         )
+         GROUP BY w.id, w.name, w.schedule
+         ORDER BY last_execution DESC NULLS LAST",
+    )?;
+
+    let (key, value) = match label {
+        Some((key, value)) => (Some(key), Some(value)),
+        None => (None, None),
+    };
+    let now = Utc::now();
+    let rows = stmt.query_map(params![key, value], |row| {
+        let schedule: Option<String> = row.get(1)?;
+        // A stored schedule that no longer parses simply has no next run
+        let next_run = schedule
+            .as_deref()
+            .and_then(|s| crate::cron_scheduler::next_run_time(s, now).ok());
+        Ok(WorkflowSummary {
+            name: row.get(0)?,
+            schedule,
+            execution_count: row.get(2)?,
+            success_count: row.get(3)?,
+            failed_count: row.get(4)?,
+            last_execution: row.get(5)?,
+            next_run,
+        })
+    })?;
+
+    let mut workflows = Vec::new();
+    for row in rows {
+        workflows.push(row?);
+    }
+
+    Ok(workflows)
 }
 
 /// Initialize database schema (synchronous; run on the connection at construction time).
@@ -820,6 +2036,7 @@ fn init_schema(conn: &Connection) -> Result<()> {
             started_at TIMESTAMP NOT NULL,
             completed_at TIMESTAMP,
             status TEXT NOT NULL,
+            heartbeat_at TIMESTAMP,
             FOREIGN KEY (workflow_id) REFERENCES workflows(id) ON DELETE CASCADE
         );
 
@@ -836,41 +2053,450 @@ fn init_schema(conn: &Connection) -> Result<()> {
             attempt INTEGER DEFAULT 1,
             retry_count INTEGER DEFAULT 0,
             next_retry_at TIMESTAMP,
+            output_truncated INTEGER NOT NULL DEFAULT 0,
+            failure_reason TEXT,
             FOREIGN KEY (execution_id) REFERENCES executions(id) ON DELETE CASCADE
         );
 
+        CREATE TABLE IF NOT EXISTS workflow_labels (
+            workflow_id INTEGER NOT NULL,
+            key TEXT NOT NULL,
+            value TEXT NOT NULL,
+            PRIMARY KEY (workflow_id, key),
+            FOREIGN KEY (workflow_id) REFERENCES workflows(id) ON DELETE CASCADE
+        );
+
+        CREATE TABLE IF NOT EXISTS task_cache (
+            cache_hash TEXT PRIMARY KEY,
+            task_name TEXT NOT NULL,
+            exit_code INTEGER,
+            stdout TEXT,
+            stderr TEXT,
+            created_at TIMESTAMP NOT NULL,
+            expires_at TIMESTAMP
+        );
+
         CREATE TABLE IF NOT EXISTS retention_policy (
             workflow_name TEXT PRIMARY KEY,
             max_executions INTEGER DEFAULT 100,
             max_age_days INTEGER DEFAULT 30
         );
 
+        CREATE TABLE IF NOT EXISTS schema_migrations (
+            version INTEGER PRIMARY KEY,
+            description TEXT NOT NULL,
+            applied_at TIMESTAMP NOT NULL
+        );
+
         CREATE UNIQUE INDEX IF NOT EXISTS idx_workflows_name ON workflows(name);
         CREATE INDEX IF NOT EXISTS idx_executions_workflow_started ON executions(workflow_id, started_at DESC);
         CREATE INDEX IF NOT EXISTS idx_task_executions_status ON task_executions(status);
         CREATE INDEX IF NOT EXISTS idx_task_executions_execution ON task_executions(execution_id);
         CREATE INDEX IF NOT EXISTS idx_task_executions_started ON task_executions(started_at);
+        CREATE INDEX IF NOT EXISTS idx_workflow_labels_key_value ON workflow_labels(key, value);
         ",
     )?;
 
-    // Migration: Add schedule column if it doesn't exist (for existing databases)
-    let has_schedule_column: bool = conn
-        .query_row(
-            "SELECT COUNT(*) FROM pragma_table_info('workflows') WHERE name='schedule'",
+    apply_migrations(conn, MIGRATIONS)
+}
+
+/// A schema change applied once to databases created before it existed
+struct Migration {
+    /// Position in [`MIGRATIONS`]; recorded in `schema_migrations` once applied
+    version: i64,
+    description: &'static str,
+    /// Must tolerate a database that already has the change, since tables created by
+    /// [`init_schema`] and databases predating `schema_migrations` may include it
+    apply: fn(&Connection) -> Result<()>,
+}
+
+/// Schema changes made after the initial release, in version order.
+///
+/// Append new steps here rather than editing earlier ones; a step's version must be
+/// one higher than the last.
+const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        description: "add workflows.schedule",
+        apply: |conn| add_column(conn, "workflows", "schedule", "TEXT"),
+    },
+    Migration {
+        version: 2,
+        description: "add executions.heartbeat_at",
+        apply: |conn| add_column(conn, "executions", "heartbeat_at", "TIMESTAMP"),
+    },
+    Migration {
+        version: 3,
+        description: "add task_executions.output_truncated",
+        apply: |conn| {
+            add_column(
+                conn,
+                "task_executions",
+                "output_truncated",
+                "INTEGER NOT NULL DEFAULT 0",
+            )
+        },
+    },
+    Migration {
+        version: 4,
+        description: "add task_executions.failure_reason",
+        apply: |conn| add_column(conn, "task_executions", "failure_reason", "TEXT"),
+    },
+    Migration {
+        version: 5,
+        description: "add task_executions.cached",
+        apply: |conn| {
+            add_column(
+                conn,
+                "task_executions",
+                "cached",
+                "INTEGER NOT NULL DEFAULT 0",
+            )
+        },
+    },
+    Migration {
+        version: 6,
+        description: "add execution_tasks and execution_edges",
+        apply: |conn| {
+            conn.execute_batch(
+                "
+                CREATE TABLE IF NOT EXISTS execution_tasks (
+                    execution_id INTEGER NOT NULL,
+                    position INTEGER NOT NULL,
+                    task_name TEXT NOT NULL,
+                    PRIMARY KEY (execution_id, task_name),
+                    FOREIGN KEY (execution_id) REFERENCES executions(id) ON DELETE CASCADE
+                );
+
+                CREATE TABLE IF NOT EXISTS execution_edges (
+                    execution_id INTEGER NOT NULL,
+                    task_name TEXT NOT NULL,
+                    dependency TEXT NOT NULL,
+                    condition TEXT,
+                    FOREIGN KEY (execution_id) REFERENCES executions(id) ON DELETE CASCADE
+                );
+
+                CREATE INDEX IF NOT EXISTS idx_execution_edges_execution ON execution_edges(execution_id);
+                ",
+            )?;
+            Ok(())
+        },
+    },
+    Migration {
+        version: 7,
+        description: "add execution_configs",
+        apply: |conn| {
+            conn.execute_batch(
+                "
+                CREATE TABLE IF NOT EXISTS execution_configs (
+                    execution_id INTEGER PRIMARY KEY,
+                    config TEXT NOT NULL,
+                    FOREIGN KEY (execution_id) REFERENCES executions(id) ON DELETE CASCADE
+                );
+                ",
+            )?;
+            Ok(())
+        },
+    },
+    Migration {
+        version: 8,
+        description: "add task_cache.expires_at",
+        apply: |conn| add_column(conn, "task_cache", "expires_at", "TIMESTAMP"),
+    },
+];
+
+/// Whether the database at `db_path` is missing or behind the current schema.
+///
+/// The check opens the file read-only, so it never blocks on a writer.
+async fn needs_initialising(db_path: PathBuf) -> Result<bool> {
+    tokio::task::spawn_blocking(move || {
+        if !db_path.exists() {
+            return Ok(true);
+        }
+        let conn = Connection::open_with_flags(
+            &db_path,
+            OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+        )?;
+        conn.busy_timeout(BUSY_TIMEOUT)?;
+        let has_migrations: bool = conn.query_row(
+            "SELECT COUNT(*) > 0 FROM sqlite_master WHERE type = 'table' AND name = 'schema_migrations'",
             [],
             |row| row.get(0),
-        )
-        .unwrap_or(0)
-        > 0;
+        )?;
+        Ok(!has_migrations || schema_version(&conn)? < MIGRATIONS.len() as i64)
+    })
+    .await
+    .map_err(|e| PicoFlowError::Other(format!("state DB init failed to complete: {e}")))?
+}
+
+/// Highest migration version recorded in `schema_migrations`, or 0 if none
+fn schema_version(conn: &Connection) -> Result<i64> {
+    Ok(conn.query_row(
+        "SELECT COALESCE(MAX(version), 0) FROM schema_migrations",
+        [],
+        |row| row.get(0),
+    )?)
+}
 
-    if !has_schedule_column {
-        conn.execute("ALTER TABLE workflows ADD COLUMN schedule TEXT", [])?;
-        debug!("Added schedule column to workflows table");
+/// Apply every migration newer than the database's schema version.
+///
+/// Each step and its `schema_migrations` row are committed together, so an
+/// interrupted upgrade resumes at the failed step on the next open. Steps run in
+/// `BEGIN IMMEDIATE` transactions that re-read the version, so when two processes
+/// upgrade the same database at once, each step is applied by only one of them.
+fn apply_migrations(conn: &Connection, migrations: &[Migration]) -> Result<()> {
+    let current = schema_version(conn)?;
+    for migration in migrations.iter().filter(|m| m.version > current) {
+        let tx = Transaction::new_unchecked(conn, TransactionBehavior::Immediate)?;
+        if schema_version(&tx)? >= migration.version {
+            // Applied by another connection since the version was first read
+            continue;
+        }
+        (migration.apply)(&tx)?;
+        tx.execute(
+            "INSERT INTO schema_migrations (version, description, applied_at) VALUES (?1, ?2, ?3)",
+            params![migration.version, migration.description, Utc::now()],
+        )?;
+        tx.commit()?;
+        debug!(
+            "Applied schema migration {}: {}",
+            migration.version, migration.description
+        );
     }
+    Ok(())
+}
 
+/// `ALTER TABLE ... ADD COLUMN` unless `table` already has `column`
+fn add_column(conn: &Connection, table: &str, column: &str, definition: &str) -> Result<()> {
+    if !has_column(conn, table, column) {
+        conn.execute(
+            &format!("ALTER TABLE {table} ADD COLUMN {column} {definition}"),
+            [],
+        )?;
+    }
     Ok(())
 }
 
+/// CSV columns describing an execution in [`StateManager::export_executions`]
+const CSV_EXECUTION_HEADER: &str = "id,workflow,started_at,completed_at,status";
+
+/// CSV columns appended per task attempt when tasks are exported
+const CSV_TASK_HEADER: &str = "task_id,task_name,task_status,attempt,task_started_at,task_completed_at,exit_code,failure_reason,cached,output_truncated,stdout,stderr";
+
+/// Write one CSV record, quoting fields that contain a delimiter, quote or line break
+fn write_csv_row(writer: &mut impl Write, fields: &[String]) -> std::io::Result<()> {
+    let row: Vec<String> = fields
+        .iter()
+        .map(|field| {
+            if field.contains([',', '"', '\n', '\r']) {
+                format!("\"{}\"", field.replace('"', "\"\""))
+            } else {
+                field.clone()
+            }
+        })
+        .collect();
+    writeln!(writer, "{}", row.join(","))
+}
+
+/// Columns read by [`task_execution_from_row`], in order
+const TASK_EXECUTION_COLUMNS: &str = "id, execution_id, task_name, status, started_at, completed_at, exit_code, stdout, stderr, attempt, retry_count, next_retry_at, output_truncated, failure_reason, cached";
+
+/// Build a [`TaskExecution`] from a row selecting [`TASK_EXECUTION_COLUMNS`]
+fn task_execution_from_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<TaskExecution> {
+    Ok(TaskExecution {
+        id: row.get(0)?,
+        execution_id: row.get(1)?,
+        task_name: row.get(2)?,
+        status: parse_task_status(&row.get::<_, String>(3)?),
+        started_at: row.get(4)?,
+        completed_at: row.get(5)?,
+        exit_code: row.get(6)?,
+        stdout: row.get(7)?,
+        stderr: row.get(8)?,
+        attempt: row.get(9)?,
+        retry_count: row.get(10)?,
+        next_retry_at: row.get(11)?,
+        output_truncated: row.get(12)?,
+        failure_reason: row
+            .get::<_, Option<String>>(13)?
+            .as_deref()
+            .and_then(parse_failure_reason),
+        cached: row.get(14)?,
+    })
+}
+
+/// Status of the latest attempt of every task in an execution
+fn latest_task_statuses(
+    conn: &Connection,
+    execution_id: i64,
+) -> Result<HashMap<String, TaskStatus>> {
+    let mut stmt = conn.prepare(
+        "SELECT task_name, status FROM task_executions
+         WHERE id IN (
+             SELECT MAX(id) FROM task_executions WHERE execution_id = ?1 GROUP BY task_name
+         )",
+    )?;
+    let rows = stmt.query_map(params![execution_id], |row| {
+        Ok((
+            row.get::<_, String>(0)?,
+            parse_task_status(&row.get::<_, String>(1)?),
+        ))
+    })?;
+
+    let mut statuses = HashMap::new();
+    for row in rows {
+        let (name, status) = row?;
+        statuses.insert(name, status);
+    }
+    Ok(statuses)
+}
+
+/// Whether `table` already has a column named `column`
+fn has_column(conn: &Connection, table: &str, column: &str) -> bool {
+    conn.query_row(
+        "SELECT COUNT(*) FROM pragma_table_info(?1) WHERE name = ?2",
+        params![table, column],
+        |row| row.get::<_, i64>(0),
+    )
+    .unwrap_or(0)
+        > 0
+}
+
+/// Delete a workflow's executions beyond `max_executions` or older than `max_age_days`.
+///
+/// Shared by [`StateManager::prune_executions`] and
+/// [`StateManager::apply_retention_policies`]; running executions are always kept.
+fn prune_workflow_executions(
+    conn: &Connection,
+    workflow_name: &str,
+    max_executions: usize,
+    max_age_days: i64,
+) -> Result<usize> {
+    // SQLite treats a negative LIMIT as unbounded and `x < NULL` as false, so these
+    // values disable the corresponding limit in the query below
+    let keep: i64 = if max_executions > 0 {
+        max_executions as i64
+    } else {
+        -1
+    };
+    let cutoff: Option<DateTime<Utc>> =
+        (max_age_days > 0).then(|| Utc::now() - chrono::Duration::days(max_age_days));
+
+    let deleted = conn.execute(
+        "DELETE FROM executions
+         WHERE id IN (
+             SELECT e.id FROM executions e
+             JOIN workflows w ON w.id = e.workflow_id
+             WHERE w.name = ?1
+               AND e.status != 'running'
+               AND (
+                   e.id NOT IN (
+                       SELECT e2.id FROM executions e2
+                       WHERE e2.workflow_id = e.workflow_id
+                       ORDER BY e2.started_at DESC, e2.id DESC
+                       LIMIT ?2
+                   )
+                   OR e.started_at < ?3
+               )
+         )",
+        params![workflow_name, keep, cutoff],
+    )?;
+
+    Ok(deleted)
+}
+
+/// Whether an error means the database was busy or locked, so retrying may succeed
+fn is_busy(error: &PicoFlowError) -> bool {
+    matches!(
+        error,
+        PicoFlowError::Database(rusqlite::Error::SqliteFailure(e, _))
+            if matches!(e.code, ErrorCode::DatabaseBusy | ErrorCode::DatabaseLocked)
+    )
+}
+
+fn parse_failure_reason(s: &str) -> Option<FailureReason> {
+    match s {
+        "failed" => Some(FailureReason::Failed),
+        "timeout" => Some(FailureReason::Timeout),
+        "spawn" => Some(FailureReason::Spawn),
+        "error" => Some(FailureReason::Error),
+        _ => {
+            tracing::warn!("Unknown failure reason '{}', ignoring", s);
+            None
+        }
+    }
+}
+
+/// `config` with values that may hold secrets replaced by `[REDACTED]` (see
+/// [`StateManager::record_execution_config`])
+fn redacted_config(config: &WorkflowConfig) -> WorkflowConfig {
+    const REDACTED: &str = "[REDACTED]";
+
+    fn redact_env(env: &mut Option<HashMap<String, String>>) {
+        for value in env.iter_mut().flat_map(|env| env.values_mut()) {
+            *value = REDACTED.to_string();
+        }
+    }
+
+    fn redact_url(url: &mut String) {
+        let Ok(mut parsed) = url::Url::parse(url) else {
+            return;
+        };
+        if parsed.password().is_some() {
+            let _ = parsed.set_password(Some(REDACTED));
+        }
+        if parsed.query().is_some() {
+            let keys: Vec<String> = parsed.query_pairs().map(|(k, _)| k.into_owned()).collect();
+            parsed
+                .query_pairs_mut()
+                .clear()
+                .extend_pairs(keys.iter().map(|k| (k, REDACTED)));
+        }
+        *url = parsed.to_string();
+    }
+
+    fn redact_http(http: &mut HttpConfig) {
+        redact_url(&mut http.url);
+        http.headers = crate::executors::redact_headers(&http.headers);
+        if http.body.is_some() {
+            http.body = Some(serde_yaml::Value::from(REDACTED));
+        }
+        match &mut http.auth {
+            Some(HttpAuth::Basic { password, .. }) => *password = REDACTED.to_string(),
+            Some(HttpAuth::Bearer { token }) => *token = REDACTED.to_string(),
+            None => {}
+        }
+    }
+
+    let mut config = config.clone();
+    if let Some(http) = &mut config.on_complete {
+        redact_http(http);
+    }
+    for task in &mut config.tasks {
+        match &mut task.config {
+            TaskExecutorConfig::Http(http) => redact_http(http),
+            TaskExecutorConfig::Shell(shell) => redact_env(&mut shell.env),
+            TaskExecutorConfig::Docker(docker) => redact_env(&mut docker.env),
+            TaskExecutorConfig::Download(download) => redact_url(&mut download.url),
+            TaskExecutorConfig::Ssh(_) | TaskExecutorConfig::Noop(_) => {}
+        }
+    }
+    config
+}
+
+/// Parse a stored `execution_edges.condition`
+fn parse_dependency_condition(s: &str) -> Option<DependencyCondition> {
+    match s {
+        "success" => Some(DependencyCondition::Success),
+        "failure" => Some(DependencyCondition::Failure),
+        "complete" => Some(DependencyCondition::Complete),
+        _ => {
+            warn!("Unknown dependency condition '{}', ignoring it", s);
+            None
+        }
+    }
+}
+
 fn parse_task_status(s: &str) -> TaskStatus {
     match s {
         "pending" => TaskStatus::Pending,
@@ -879,6 +2505,8 @@ fn parse_task_status(s: &str) -> TaskStatus {
         "failed" => TaskStatus::Failed,
         "retrying" => TaskStatus::Retrying,
         "timeout" => TaskStatus::Timeout,
+        "skipped" => TaskStatus::Skipped,
+        "cancelled" => TaskStatus::Cancelled,
         _ => {
             tracing::warn!("Unknown task status '{}', defaulting to Failed", s);
             TaskStatus::Failed
@@ -973,6 +2601,219 @@ mod tests {
         assert_eq!(tasks[0].stdout, Some("output".to_string()));
     }
 
+    #[tokio::test]
+    async fn test_execution_graph_in_progress() {
+        use crate::models::{Dependency, DependencyCondition};
+
+        let manager = StateManager::in_memory().await.unwrap();
+        let workflow_id = manager.get_or_create_workflow("graph", None).await.unwrap();
+        let execution_id = manager.start_execution(workflow_id).await.unwrap();
+
+        let tasks: Vec<TaskConfig> = serde_yaml::from_str(
+            "
+            - {name: fetch, type: noop}
+            - {name: notify, type: noop, depends_on: [{task: fetch, on: failure}]}
+            - {name: load, type: noop, depends_on: [fetch]}
+            ",
+        )
+        .unwrap();
+        assert_eq!(
+            tasks[1].depends_on,
+            vec![Dependency::Conditional {
+                task: "fetch".to_string(),
+                on: DependencyCondition::Failure
+            }]
+        );
+        manager
+            .record_execution_graph(execution_id, &tasks)
+            .await
+            .unwrap();
+        manager.start_task(execution_id, "fetch", 1).await.unwrap();
+
+        let graph = manager.get_execution_graph(execution_id).await.unwrap();
+        assert_eq!(graph.workflow, "graph");
+        assert_eq!(graph.status, TaskStatus::Running);
+        let nodes: Vec<(&str, &TaskStatus)> = graph
+            .nodes
+            .iter()
+            .map(|n| (n.task.as_str(), &n.status))
+            .collect();
+        assert_eq!(
+            nodes,
+            vec![
+                ("fetch", &TaskStatus::Running),
+                ("notify", &TaskStatus::Pending),
+                ("load", &TaskStatus::Pending),
+            ]
+        );
+        assert_eq!(graph.edges.len(), 2);
+        assert_eq!(graph.edges[0].on, Some(DependencyCondition::Failure));
+        assert_eq!(graph.edges[1].on, None);
+
+        let err = manager.get_execution_graph(999).await.unwrap_err();
+        assert!(err.to_string().contains("not found"), "{err}");
+    }
+
+    #[tokio::test]
+    async fn test_execution_config_snapshot() {
+        let manager = StateManager::in_memory().await.unwrap();
+        let workflow_id = manager.get_or_create_workflow("snap", None).await.unwrap();
+        let execution_id = manager.start_execution(workflow_id).await.unwrap();
+        assert!(manager
+            .get_execution_config(execution_id)
+            .await
+            .unwrap()
+            .is_none());
+
+        let config = crate::parser::parse_workflow_yaml(
+            r#"
+name: snap
+config:
+  max_parallel: 2
+tasks:
+  - name: fetch
+    type: http
+    config:
+      url: "https://api.example.com/data?page=2&api_key=s3cret"
+      method: POST
+      body: {token: s3cret}
+      headers: {Authorization: "Bearer abc", Accept: "application/json"}
+      auth: {type: basic, username: admin, password: hunter2}
+  - name: store
+    type: shell
+    depends_on: [{task: fetch, on: success}]
+    config:
+      command: "/bin/echo"
+      args: [stored]
+      env: {DB_PASSWORD: s3cret}
+  - name: done
+    type: noop
+    depends_on: [store]
+"#,
+        )
+        .unwrap();
+        manager
+            .record_execution_config(execution_id, &config)
+            .await
+            .unwrap();
+
+        let snapshot = manager
+            .get_execution_config(execution_id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(snapshot.name, "snap");
+        assert_eq!(snapshot.config.max_parallel, 2);
+        assert_eq!(snapshot.tasks.len(), 3);
+        assert_eq!(snapshot.tasks[1].depends_on, config.tasks[1].depends_on);
+        assert!(matches!(
+            &snapshot.tasks[1].config,
+            TaskExecutorConfig::Shell(shell) if shell.args == ["stored"]
+        ));
+        assert!(matches!(
+            snapshot.tasks[2].config,
+            TaskExecutorConfig::Noop(_)
+        ));
+        let TaskExecutorConfig::Http(http) = &snapshot.tasks[0].config else {
+            panic!("expected http config, got {:?}", snapshot.tasks[0].config);
+        };
+        assert_eq!(http.headers["Authorization"], "[REDACTED]");
+        assert_eq!(http.headers["Accept"], "application/json");
+        assert_eq!(
+            http.url,
+            "https://api.example.com/data?page=%5BREDACTED%5D&api_key=%5BREDACTED%5D"
+        );
+        assert_eq!(http.body, Some(serde_yaml::Value::from("[REDACTED]")));
+        let TaskExecutorConfig::Shell(shell) = &snapshot.tasks[1].config else {
+            panic!("expected shell config, got {:?}", snapshot.tasks[1].config);
+        };
+        assert_eq!(shell.env.as_ref().unwrap()["DB_PASSWORD"], "[REDACTED]");
+        assert_eq!(
+            http.auth,
+            Some(HttpAuth::Basic {
+                username: "admin".to_string(),
+                password: "[REDACTED]".to_string()
+            })
+        );
+
+        // Snapshots go with their execution
+        manager
+            .with_conn(|conn| Ok(conn.execute("DELETE FROM executions", [])?))
+            .await
+            .unwrap();
+        assert!(manager
+            .get_execution_config(execution_id)
+            .await
+            .unwrap()
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn test_get_task_output() {
+        let manager = StateManager::in_memory().await.unwrap();
+        let workflow_id = manager.get_or_create_workflow("test", None).await.unwrap();
+        let execution_id = manager.start_execution(workflow_id).await.unwrap();
+
+        // Two attempts; the latest one's output is returned
+        for (attempt, stdout) in [(1, "first try"), (2, "second try")] {
+            let id = manager
+                .start_task(execution_id, "fetch", attempt)
+                .await
+                .unwrap();
+            manager
+                .update_task_status(id, TaskStatus::Success, Some(0), Some(stdout), Some("warn"))
+                .await
+                .unwrap();
+        }
+        let big = manager.start_task(execution_id, "dump", 1).await.unwrap();
+        manager
+            .update_task_status(big, TaskStatus::Success, Some(0), Some("…"), None)
+            .await
+            .unwrap();
+        manager.mark_output_truncated(big).await.unwrap();
+        manager
+            .record_skipped_task(execution_id, "notify")
+            .await
+            .unwrap();
+
+        assert_eq!(
+            manager
+                .get_task_output(execution_id, "fetch")
+                .await
+                .unwrap(),
+            Some(("second try".to_string(), "warn".to_string()))
+        );
+        assert_eq!(
+            manager
+                .get_task_output(execution_id, "notify")
+                .await
+                .unwrap(),
+            Some((String::new(), String::new()))
+        );
+        assert_eq!(
+            manager
+                .get_task_output(execution_id, "missing")
+                .await
+                .unwrap(),
+            None
+        );
+        assert_eq!(
+            manager
+                .get_task_output(execution_id + 1, "fetch")
+                .await
+                .unwrap(),
+            None
+        );
+
+        let tasks = manager.get_task_executions(execution_id).await.unwrap();
+        let truncated: Vec<&str> = tasks
+            .iter()
+            .filter(|t| t.output_truncated)
+            .map(|t| t.task_name.as_str())
+            .collect();
+        assert_eq!(truncated, ["dump"]);
+    }
+
     #[tokio::test]
     async fn test_task_retry() {
         let manager = StateManager::in_memory().await.unwrap();
@@ -1008,7 +2849,8 @@ mod tests {
             .await
             .unwrap();
 
-        // Simulate crash (exec2 still running)
+        // Simulate crash (exec2 still running, but no longer heartbeating)
+        age_heartbeat(&manager, exec2).await;
         let crashed = manager.recover_from_crash().await.unwrap();
 
         assert_eq!(crashed.len(), 1);
@@ -1019,6 +2861,47 @@ mod tests {
         assert_eq!(execution.status, TaskStatus::Failed);
     }
 
+    /// Backdate an execution's heartbeat past `HEARTBEAT_STALE_AFTER`
+    async fn age_heartbeat(manager: &StateManager, execution_id: i64) {
+        manager
+            .with_write_conn(move |conn| {
+                conn.execute(
+                    "UPDATE executions SET heartbeat_at = ?1 WHERE id = ?2",
+                    params![
+                        Utc::now()
+                            - chrono::Duration::seconds(HEARTBEAT_STALE_AFTER.as_secs() as i64 + 1),
+                        execution_id
+                    ],
+                )?;
+                Ok(())
+            })
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_crash_recovery_skips_live_executions() {
+        let manager = StateManager::in_memory().await.unwrap();
+        let workflow_id = manager.get_or_create_workflow("test", None).await.unwrap();
+
+        // Another process is still running `live`; `stale` was abandoned
+        let live = manager.start_execution(workflow_id).await.unwrap();
+        let stale = manager.start_execution(workflow_id).await.unwrap();
+        age_heartbeat(&manager, live).await;
+        age_heartbeat(&manager, stale).await;
+        manager.record_heartbeat(live).await.unwrap();
+
+        let crashed = manager.recover_from_crash().await.unwrap();
+        assert_eq!(crashed, vec![stale]);
+
+        let status = |id| {
+            let manager = manager.clone();
+            async move { manager.get_execution(id).await.unwrap().unwrap().status }
+        };
+        assert_eq!(status(live).await, TaskStatus::Running);
+        assert_eq!(status(stale).await, TaskStatus::Failed);
+    }
+
     #[tokio::test]
     async fn test_crash_recovery_cleans_orphaned_task_rows() {
         let manager = StateManager::in_memory().await.unwrap();
@@ -1041,6 +2924,7 @@ mod tests {
             .await
             .unwrap();
 
+        age_heartbeat(&manager, exec).await;
         let crashed = manager.recover_from_crash().await.unwrap();
         assert_eq!(crashed, vec![exec]);
 
@@ -1082,6 +2966,126 @@ mod tests {
         assert_eq!(history.len(), 3);
     }
 
+    #[tokio::test]
+    async fn test_has_running_execution() {
+        let manager = StateManager::in_memory().await.unwrap();
+        let workflow_id = manager.get_or_create_workflow("sync", None).await.unwrap();
+        assert!(!manager.has_running_execution("sync").await.unwrap());
+
+        let exec_id = manager.start_execution(workflow_id).await.unwrap();
+        assert!(manager.has_running_execution("sync").await.unwrap());
+        assert!(!manager.has_running_execution("other").await.unwrap());
+
+        // A running row whose heartbeat went stale belongs to a dead process
+        age_heartbeat(&manager, exec_id).await;
+        assert!(!manager.has_running_execution("sync").await.unwrap());
+
+        let exec_id = manager.start_execution(workflow_id).await.unwrap();
+        manager
+            .update_execution_status(exec_id, TaskStatus::Success)
+            .await
+            .unwrap();
+        assert!(!manager.has_running_execution("sync").await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_export_executions_ndjson_round_trip() {
+        let manager = StateManager::in_memory().await.unwrap();
+        for (workflow, status) in [
+            ("backup", TaskStatus::Success),
+            ("sync", TaskStatus::Failed),
+            ("backup", TaskStatus::Success),
+        ] {
+            let workflow_id = manager
+                .get_or_create_workflow(workflow, None)
+                .await
+                .unwrap();
+            let exec_id = manager.start_execution(workflow_id).await.unwrap();
+            let task_id = manager.start_task(exec_id, "step", 1).await.unwrap();
+            manager
+                .update_task_status(task_id, status.clone(), Some(0), Some("line1\nline2"), None)
+                .await
+                .unwrap();
+            manager
+                .update_execution_status(exec_id, status)
+                .await
+                .unwrap();
+        }
+
+        let buf = manager
+            .export_executions(Vec::new(), ExportFormat::Ndjson, true)
+            .await
+            .unwrap();
+        let exported: Vec<ExportedExecution> = String::from_utf8(buf)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+
+        assert_eq!(exported.len(), 3);
+        let workflows: Vec<_> = exported.iter().map(|e| e.workflow.as_str()).collect();
+        assert_eq!(workflows, ["backup", "sync", "backup"]);
+        for record in &exported {
+            let stored = manager
+                .get_execution(record.execution.id)
+                .await
+                .unwrap()
+                .unwrap();
+            assert_eq!(record.execution.started_at, stored.started_at);
+            assert_eq!(record.execution.completed_at, stored.completed_at);
+            assert_eq!(record.execution.status, stored.status);
+            assert_eq!(record.tasks.len(), 1);
+            assert_eq!(record.tasks[0].task_name, "step");
+            assert_eq!(record.tasks[0].stdout.as_deref(), Some("line1\nline2"));
+        }
+
+        // Without tasks, records omit the array
+        let buf = manager
+            .export_executions(Vec::new(), ExportFormat::Ndjson, false)
+            .await
+            .unwrap();
+        let text = String::from_utf8(buf).unwrap();
+        assert_eq!(text.lines().count(), 3);
+        assert!(!text.contains("\"tasks\""));
+    }
+
+    #[tokio::test]
+    async fn test_export_executions_csv() {
+        let manager = StateManager::in_memory().await.unwrap();
+        let workflow_id = manager.get_or_create_workflow("etl", None).await.unwrap();
+        let exec_id = manager.start_execution(workflow_id).await.unwrap();
+        let task_id = manager.start_task(exec_id, "load", 1).await.unwrap();
+        manager
+            .update_task_status(task_id, TaskStatus::Success, Some(0), Some("a,\"b\""), None)
+            .await
+            .unwrap();
+        // An execution without tasks still gets a row when tasks are exported
+        manager.start_execution(workflow_id).await.unwrap();
+
+        let buf = manager
+            .export_executions(Vec::new(), ExportFormat::Csv, false)
+            .await
+            .unwrap();
+        let text = String::from_utf8(buf).unwrap();
+        let lines: Vec<_> = text.lines().collect();
+        assert_eq!(lines[0], CSV_EXECUTION_HEADER);
+        assert_eq!(lines.len(), 3);
+        assert!(lines[1].starts_with(&format!("{},etl,", exec_id)));
+        assert!(lines[2].ends_with(",running"));
+
+        let buf = manager
+            .export_executions(Vec::new(), ExportFormat::Csv, true)
+            .await
+            .unwrap();
+        let text = String::from_utf8(buf).unwrap();
+        let lines: Vec<_> = text.lines().collect();
+        assert_eq!(lines.len(), 3);
+        let columns = lines[0].split(',').count();
+        assert!(lines[1].contains(",load,success,1,"));
+        assert!(lines[1].ends_with(",false,false,\"a,\"\"b\"\"\","));
+        assert_eq!(lines[2].split(',').count(), columns);
+    }
+
     #[tokio::test]
     #[cfg(unix)]
     async fn test_database_file_permissions() {
@@ -1118,7 +3122,7 @@ mod tests {
 
         // Create multiple workflows with different execution counts
         let wf1_id = manager
-            .get_or_create_workflow("workflow-alpha", None)
+            .get_or_create_workflow("workflow-alpha", Some("0 */5 * * * *"))
             .await
             .unwrap();
         let wf2_id = manager
@@ -1178,6 +3182,9 @@ mod tests {
         assert_eq!(alpha.success_count, 3);
         assert_eq!(alpha.failed_count, 0);
         assert!(alpha.last_execution.is_some());
+        let next_run = alpha.next_run.expect("scheduled workflow has a next run");
+        assert_eq!(next_run.timestamp() % 300, 0);
+        assert!(next_run <= Utc::now() + chrono::Duration::minutes(5));
 
         // Verify workflow-beta stats
         assert_eq!(beta.execution_count, 3);
@@ -1190,5 +3197,554 @@ mod tests {
         assert_eq!(gamma.success_count, 0);
         assert_eq!(gamma.failed_count, 0);
         assert!(gamma.last_execution.is_none());
+        assert!(gamma.next_run.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_list_workflows_by_label() {
+        let manager = StateManager::in_memory().await.unwrap();
+        let labels = |pairs: &[(&str, &str)]| -> HashMap<String, String> {
+            pairs
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect()
+        };
+
+        let backup = manager
+            .get_or_create_workflow("backup", None)
+            .await
+            .unwrap();
+        let sync = manager.get_or_create_workflow("sync", None).await.unwrap();
+        manager
+            .get_or_create_workflow("unlabelled", None)
+            .await
+            .unwrap();
+        manager
+            .set_workflow_labels(backup, &labels(&[("team", "ops"), ("tier", "critical")]))
+            .await
+            .unwrap();
+        manager
+            .set_workflow_labels(sync, &labels(&[("team", "ops")]))
+            .await
+            .unwrap();
+        let exec = manager.start_execution(backup).await.unwrap();
+        manager
+            .update_execution_status(exec, TaskStatus::Success)
+            .await
+            .unwrap();
+
+        let mut ops: Vec<String> = manager
+            .list_workflows_by_label("team", "ops")
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|w| w.name)
+            .collect();
+        ops.sort();
+        assert_eq!(ops, ["backup", "sync"]);
+
+        let critical = manager
+            .list_workflows_by_label("tier", "critical")
+            .await
+            .unwrap();
+        assert_eq!(critical.len(), 1);
+        assert_eq!(critical[0].name, "backup");
+        assert_eq!(critical[0].success_count, 1);
+
+        // Values match exactly
+        assert!(manager
+            .list_workflows_by_label("team", "OPS")
+            .await
+            .unwrap()
+            .is_empty());
+
+        // Setting labels replaces the previous set
+        manager
+            .set_workflow_labels(backup, &labels(&[("team", "data")]))
+            .await
+            .unwrap();
+        assert!(manager
+            .list_workflows_by_label("tier", "critical")
+            .await
+            .unwrap()
+            .is_empty());
+        let ops = manager
+            .list_workflows_by_label("team", "ops")
+            .await
+            .unwrap();
+        assert_eq!(ops.len(), 1);
+        assert_eq!(ops[0].name, "sync");
+        assert_eq!(manager.list_workflows().await.unwrap().len(), 3);
+    }
+
+    /// Insert a finished execution that started `days_ago` days in the past
+    async fn insert_finished_execution(
+        manager: &StateManager,
+        workflow_id: i64,
+        days_ago: i64,
+    ) -> i64 {
+        let exec = manager.start_execution(workflow_id).await.unwrap();
+        manager
+            .update_execution_status(exec, TaskStatus::Success)
+            .await
+            .unwrap();
+        let started = Utc::now() - chrono::Duration::days(days_ago);
+        manager
+            .conn
+            .lock()
+            .unwrap()
+            .execute(
+                "UPDATE executions SET started_at = ?1 WHERE id = ?2",
+                params![started, exec],
+            )
+            .unwrap();
+        exec
+    }
+
+    #[tokio::test]
+    async fn test_prune_executions_by_count() {
+        let manager = StateManager::in_memory().await.unwrap();
+        let workflow_id = manager.get_or_create_workflow("test", None).await.unwrap();
+
+        let mut execs = Vec::new();
+        for days_ago in (1..=5).rev() {
+            execs.push(insert_finished_execution(&manager, workflow_id, days_ago).await);
+        }
+        manager.start_task(execs[0], "task1", 1).await.unwrap();
+
+        // A running execution counts towards the limit but is never deleted
+        let running = manager.start_execution(workflow_id).await.unwrap();
+
+        let deleted = manager.prune_executions("test", 3, 0).await.unwrap();
+        assert_eq!(deleted, 3);
+
+        assert!(manager.get_execution(running).await.unwrap().is_some());
+        for exec in &execs[..3] {
+            assert!(manager.get_execution(*exec).await.unwrap().is_none());
+        }
+        for exec in &execs[3..] {
+            assert!(manager.get_execution(*exec).await.unwrap().is_some());
+        }
+        // task_executions cascade with their execution
+        assert!(manager
+            .get_task_executions(execs[0])
+            .await
+            .unwrap()
+            .is_empty());
+
+        // Already within the limit
+        assert_eq!(manager.prune_executions("test", 3, 0).await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_prune_executions_by_age() {
+        let manager = StateManager::in_memory().await.unwrap();
+        let workflow_id = manager.get_or_create_workflow("test", None).await.unwrap();
+        let other_id = manager.get_or_create_workflow("other", None).await.unwrap();
+
+        let old = insert_finished_execution(&manager, workflow_id, 40).await;
+        let recent = insert_finished_execution(&manager, workflow_id, 2).await;
+        let other_old = insert_finished_execution(&manager, other_id, 40).await;
+
+        let deleted = manager.prune_executions("test", 0, 30).await.unwrap();
+        assert_eq!(deleted, 1);
+        assert!(manager.get_execution(old).await.unwrap().is_none());
+        assert!(manager.get_execution(recent).await.unwrap().is_some());
+        // Other workflows are untouched
+        assert!(manager.get_execution(other_old).await.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_apply_retention_policies() {
+        let manager = StateManager::in_memory().await.unwrap();
+        let limited = manager
+            .get_or_create_workflow("limited", None)
+            .await
+            .unwrap();
+        let defaults = manager
+            .get_or_create_workflow("defaults", None)
+            .await
+            .unwrap();
+
+        for days_ago in 1..=3 {
+            insert_finished_execution(&manager, limited, days_ago).await;
+        }
+        let expired = insert_finished_execution(&manager, defaults, 45).await;
+        let kept = insert_finished_execution(&manager, defaults, 10).await;
+
+        manager.set_retention_policy("limited", 1, 0).await.unwrap();
+
+        let deleted = manager.apply_retention_policies().await.unwrap();
+        assert_eq!(deleted, 3);
+        assert_eq!(
+            manager
+                .get_execution_history("limited", 10)
+                .await
+                .unwrap()
+                .len(),
+            1
+        );
+        assert!(manager.get_execution(expired).await.unwrap().is_none());
+        assert!(manager.get_execution(kept).await.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_retention_prunes_task_cache() {
+        let manager = StateManager::in_memory().await.unwrap();
+        for (hash, ttl) in [
+            ("fresh", None),
+            ("expired", Some(Duration::ZERO)),
+            ("stale", None),
+        ] {
+            manager
+                .store_cached_result(hash, "t", Some(0), Some("out"), None, ttl)
+                .await
+                .unwrap();
+        }
+        manager
+            .conn
+            .lock()
+            .unwrap()
+            .execute(
+                "UPDATE task_cache SET created_at = ?1 WHERE cache_hash = 'stale'",
+                params![Utc::now() - chrono::Duration::days(DEFAULT_RETENTION_MAX_AGE_DAYS + 1)],
+            )
+            .unwrap();
+
+        manager.apply_retention_policies().await.unwrap();
+        let cached = |hash: &'static str| {
+            let manager = &manager;
+            async move { manager.get_cached_result(hash, None).await.unwrap() }
+        };
+        assert!(cached("fresh").await.is_some());
+        assert!(cached("expired").await.is_none());
+        assert!(cached("stale").await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_get_task_duration_stats() {
+        let manager = StateManager::in_memory().await.unwrap();
+        let workflow_id = manager.get_or_create_workflow("etl", None).await.unwrap();
+        let other_id = manager.get_or_create_workflow("other", None).await.unwrap();
+        let base = Utc::now() - chrono::Duration::days(1);
+
+        // Seed one `load` run per duration, plus noise that must be ignored
+        let mut seeds: Vec<(i64, &str, Option<i64>)> = (1..=20)
+            .map(|secs| (workflow_id, "load", Some(secs)))
+            .collect();
+        seeds.push((workflow_id, "extract", Some(500))); // Different task
+        seeds.push((other_id, "load", Some(900))); // Different workflow
+        seeds.push((workflow_id, "load", None)); // Still running
+
+        for (wf, task, secs) in seeds {
+            let exec = manager.start_execution(wf).await.unwrap();
+            let task_id = manager.start_task(exec, task, 1).await.unwrap();
+            if let Some(secs) = secs {
+                manager
+                    .conn
+                    .lock()
+                    .unwrap()
+                    .execute(
+                        "UPDATE task_executions SET status = 'success', started_at = ?1, completed_at = ?2 WHERE id = ?3",
+                        params![base, base + chrono::Duration::seconds(secs), task_id],
+                    )
+                    .unwrap();
+            }
+        }
+
+        let stats = manager
+            .get_task_duration_stats("etl", "load")
+            .await
+            .unwrap();
+        assert_eq!(stats.sample_count, 20);
+        assert_eq!(stats.min_seconds, 1.0);
+        assert_eq!(stats.max_seconds, 20.0);
+        assert_eq!(stats.avg_seconds, 10.5);
+        assert_eq!(stats.p95_seconds, 19.0);
+
+        let empty = manager
+            .get_task_duration_stats("etl", "missing")
+            .await
+            .unwrap();
+        assert_eq!(empty, DurationStats::default());
+    }
+
+    #[tokio::test]
+    async fn test_migrates_old_schema_database() {
+        use tempfile::TempDir;
+
+        let temp_dir = TempDir::new().unwrap();
+        let db_path = temp_dir.path().join("old.db");
+
+        // Schema as shipped before any migrations, with some history in it
+        {
+            let conn = Connection::open(&db_path).unwrap();
+            conn.execute_batch(
+                "
+                CREATE TABLE workflows (
+                    id INTEGER PRIMARY KEY,
+                    name TEXT NOT NULL UNIQUE,
+                    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+                );
+                CREATE TABLE executions (
+                    id INTEGER PRIMARY KEY,
+                    workflow_id INTEGER NOT NULL,
+                    started_at TIMESTAMP NOT NULL,
+                    completed_at TIMESTAMP,
+                    status TEXT NOT NULL
+                );
+                CREATE TABLE task_executions (
+                    id INTEGER PRIMARY KEY,
+                    execution_id INTEGER NOT NULL,
+                    task_name TEXT NOT NULL,
+                    status TEXT NOT NULL,
+                    started_at TIMESTAMP NOT NULL,
+                    completed_at TIMESTAMP,
+                    exit_code INTEGER,
+                    stdout TEXT,
+                    stderr TEXT,
+                    attempt INTEGER DEFAULT 1,
+                    retry_count INTEGER DEFAULT 0,
+                    next_retry_at TIMESTAMP
+                );
+                INSERT INTO workflows (id, name) VALUES (1, 'legacy');
+                INSERT INTO executions (id, workflow_id, started_at, completed_at, status)
+                    VALUES (1, 1, '2024-01-01T00:00:00Z', '2024-01-01T00:01:00Z', 'success');
+                INSERT INTO task_executions (execution_id, task_name, status, started_at, exit_code, stdout)
+                    VALUES (1, 'step', 'success', '2024-01-01T00:00:00Z', 0, 'kept');
+                ",
+            )
+            .unwrap();
+        }
+
+        let manager = StateManager::new(&db_path).await.unwrap();
+        {
+            let conn = manager.conn.lock().unwrap();
+            assert_eq!(schema_version(&conn).unwrap(), MIGRATIONS.len() as i64);
+            for (table, column) in [
+                ("workflows", "schedule"),
+                ("executions", "heartbeat_at"),
+                ("task_executions", "output_truncated"),
+                ("task_executions", "failure_reason"),
+                ("task_executions", "cached"),
+            ] {
+                assert!(has_column(&conn, table, column), "{table}.{column} missing");
+            }
+        }
+
+        let history = manager.get_execution_history("legacy", 10).await.unwrap();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].status, TaskStatus::Success);
+        let tasks = manager.get_task_executions(1).await.unwrap();
+        assert_eq!(tasks.len(), 1);
+        assert_eq!(tasks[0].stdout.as_deref(), Some("kept"));
+        assert!(!tasks[0].output_truncated);
+        assert!(!tasks[0].cached);
+        // No recorded graph: the tasks that ran, without edges
+        let graph = manager.get_execution_graph(1).await.unwrap();
+        assert_eq!(graph.nodes.len(), 1);
+        assert_eq!(graph.nodes[0].task, "step");
+        assert!(graph.edges.is_empty());
+        drop(manager);
+
+        // Reopening is a no-op
+        let manager = StateManager::new(&db_path).await.unwrap();
+        let conn = manager.conn.lock().unwrap();
+        let rows: i64 = conn
+            .query_row("SELECT COUNT(*) FROM schema_migrations", [], |row| {
+                row.get(0)
+            })
+            .unwrap();
+        assert_eq!(rows, MIGRATIONS.len() as i64);
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_opens_migrate_once() {
+        use tempfile::TempDir;
+
+        let temp_dir = TempDir::new().unwrap();
+        let db_path = temp_dir.path().join("state.db");
+        drop(StateManager::new(&db_path).await.unwrap());
+        Connection::open(&db_path)
+            .unwrap()
+            .execute("DELETE FROM schema_migrations WHERE version > 1", [])
+            .unwrap();
+
+        let opens = (0..4).map(|_| StateManager::new(&db_path));
+        for manager in futures::future::join_all(opens).await {
+            manager.unwrap();
+        }
+
+        let conn = Connection::open(&db_path).unwrap();
+        assert_eq!(schema_version(&conn).unwrap(), MIGRATIONS.len() as i64);
+    }
+
+    #[tokio::test]
+    async fn test_readonly_manager_rejects_writes() {
+        use tempfile::TempDir;
+
+        let temp_dir = TempDir::new().unwrap();
+        let db_path = temp_dir.path().join("state.db");
+        // A fresh install reads as empty instead of failing
+        let fresh = StateManager::new_readonly(&db_path).await.unwrap();
+        assert!(fresh
+            .get_execution_history("etl", 10)
+            .await
+            .unwrap()
+            .is_empty());
+        drop(fresh);
+
+        let writer = StateManager::new(&db_path).await.unwrap();
+        let workflow_id = writer.get_or_create_workflow("etl", None).await.unwrap();
+        let execution_id = writer.start_execution(workflow_id).await.unwrap();
+
+        let reader = StateManager::new_readonly(&db_path).await.unwrap();
+        let history = reader.get_execution_history("etl", 10).await.unwrap();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].id, execution_id);
+
+        assert!(matches!(
+            reader.start_execution(workflow_id).await,
+            Err(PicoFlowError::ReadOnlyDatabase)
+        ));
+        // The connection itself refuses writes too
+        let raw = reader
+            .with_conn(|conn| Ok(conn.execute("DELETE FROM executions", [])?))
+            .await;
+        assert!(raw.is_err());
+        assert_eq!(
+            writer.get_execution_history("etl", 10).await.unwrap().len(),
+            1
+        );
+    }
+
+    #[tokio::test]
+    async fn test_readonly_manager_upgrades_old_schema() {
+        use tempfile::TempDir;
+
+        let temp_dir = TempDir::new().unwrap();
+        let db_path = temp_dir.path().join("old.db");
+        drop(StateManager::new(&db_path).await.unwrap());
+        Connection::open(&db_path)
+            .unwrap()
+            .execute("DELETE FROM schema_migrations WHERE version > 1", [])
+            .unwrap();
+
+        let reader = StateManager::new_readonly(&db_path).await.unwrap();
+        let version = reader.with_conn(schema_version).await.unwrap();
+        assert_eq!(version, MIGRATIONS.len() as i64);
+    }
+
+    #[test]
+    fn test_failed_migration_is_rolled_back() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_schema(&conn).unwrap();
+
+        let next = MIGRATIONS.len() as i64 + 1;
+        let steps = [
+            Migration {
+                version: next,
+                description: "add workflows.owner",
+                apply: |conn| add_column(conn, "workflows", "owner", "TEXT"),
+            },
+            Migration {
+                version: next + 1,
+                description: "broken",
+                apply: |conn| {
+                    add_column(conn, "workflows", "region", "TEXT")?;
+                    conn.execute_batch("SELECT * FROM no_such_table")?;
+                    Ok(())
+                },
+            },
+        ];
+        assert!(apply_migrations(&conn, &steps).is_err());
+
+        // The good step stuck, the broken one left no trace
+        assert_eq!(schema_version(&conn).unwrap(), next);
+        assert!(has_column(&conn, "workflows", "owner"));
+        assert!(!has_column(&conn, "workflows", "region"));
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_write_waits_out_external_lock() {
+        use tempfile::TempDir;
+
+        let temp_dir = TempDir::new().unwrap();
+        let db_path = temp_dir.path().join("busy.db");
+        let manager = StateManager::new(&db_path).await.unwrap();
+        let workflow_id = manager.get_or_create_workflow("busy", None).await.unwrap();
+
+        // Another process holds the write lock for a while
+        let external = Connection::open(&db_path).unwrap();
+        external.execute_batch("BEGIN EXCLUSIVE").unwrap();
+        let holder = std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(300));
+            external.execute_batch("COMMIT").unwrap();
+        });
+
+        let start = std::time::Instant::now();
+        let execution_id = manager.start_execution(workflow_id).await.unwrap();
+        assert!(start.elapsed() >= Duration::from_millis(250));
+        holder.join().unwrap();
+
+        let execution = manager.get_execution(execution_id).await.unwrap().unwrap();
+        assert_eq!(execution.status, TaskStatus::Running);
+    }
+
+    #[test]
+    fn test_is_busy_matches_only_lock_errors() {
+        let sqlite_error = |code| {
+            PicoFlowError::Database(rusqlite::Error::SqliteFailure(
+                rusqlite::ffi::Error::new(code),
+                None,
+            ))
+        };
+
+        assert!(is_busy(&sqlite_error(rusqlite::ffi::SQLITE_BUSY)));
+        assert!(is_busy(&sqlite_error(rusqlite::ffi::SQLITE_LOCKED)));
+        assert!(!is_busy(&sqlite_error(rusqlite::ffi::SQLITE_CONSTRAINT)));
+        assert!(!is_busy(&PicoFlowError::Other("busy".to_string())));
+    }
+
+    #[tokio::test]
+    async fn test_compact_shrinks_database_file() {
+        use tempfile::TempDir;
+
+        let temp_dir = TempDir::new().unwrap();
+        let db_path = temp_dir.path().join("compact.db");
+        let on_disk = || {
+            let wal = temp_dir.path().join("compact.db-wal");
+            std::fs::metadata(&db_path).unwrap().len()
+                + std::fs::metadata(wal).map(|m| m.len()).unwrap_or(0)
+        };
+
+        let manager = StateManager::new(&db_path).await.unwrap();
+        let workflow_id = manager.get_or_create_workflow("bulky", None).await.unwrap();
+        let output = "x".repeat(16 * 1024);
+        for _ in 0..50 {
+            let execution_id = manager.start_execution(workflow_id).await.unwrap();
+            let task_id = manager.start_task(execution_id, "dump", 1).await.unwrap();
+            manager
+                .update_task_status(task_id, TaskStatus::Success, Some(0), Some(&output), None)
+                .await
+                .unwrap();
+            manager
+                .update_execution_status(execution_id, TaskStatus::Success)
+                .await
+                .unwrap();
+        }
+        assert_eq!(manager.prune_executions("bulky", 1, 0).await.unwrap(), 49);
+        let before = on_disk();
+
+        manager.compact().await.unwrap();
+
+        let after = on_disk();
+        assert!(
+            after < before / 4,
+            "before {before} bytes, after {after} bytes"
+        );
+        // The remaining execution survives compaction
+        let history = manager.get_execution_history("bulky", 10).await.unwrap();
+        assert_eq!(history.len(), 1);
     }
 }
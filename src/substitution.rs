@@ -0,0 +1,278 @@
+//! Runtime substitution of task outputs into dependent task configs
+//!
+//! A task with `outputs: true` has its trimmed stdout captured by the scheduler once it
+//! succeeds. Dependent tasks can reference it with `${{ tasks.<name>.stdout }}` in a shell
+//! `command`/`args` or an HTTP `url`/`body`; references are resolved immediately before
+//! the task runs.
+
+use crate::error::{PicoFlowError, Result};
+use crate::models::TaskExecutorConfig;
+use std::collections::HashMap;
+
+const EXPR_OPEN: &str = "${{";
+const EXPR_CLOSE: &str = "}}";
+
+/// Replace every `${{ tasks.<name>.stdout }}` expression in `input` with the captured
+/// output of task `<name>`.
+///
+/// # Errors
+///
+/// Returns `PicoFlowError::Validation` if an expression is unterminated, is not of the
+/// form `tasks.<name>.stdout`, or references a task with no captured output.
+///
+/// # Example
+///
+/// ```
+/// use picoflow::substitution::substitute_task_outputs;
+/// use std::collections::HashMap;
+///
+/// let mut outputs = HashMap::new();
+/// outputs.insert("fetch".to_string(), "42".to_string());
+///
+/// let resolved = substitute_task_outputs("count=${{ tasks.fetch.stdout }}", &outputs).unwrap();
+/// assert_eq!(resolved, "count=42");
+/// ```
+pub fn substitute_task_outputs(input: &str, outputs: &HashMap<String, String>) -> Result<String> {
+    // Fast path: nothing to substitute, avoid allocating a new string per field.
+    if !input.contains(EXPR_OPEN) {
+        return Ok(input.to_string());
+    }
+
+    let mut result = String::with_capacity(input.len());
+    let mut rest = input;
+
+    while let Some(start) = rest.find(EXPR_OPEN) {
+        result.push_str(&rest[..start]);
+        let after_open = &rest[start + EXPR_OPEN.len()..];
+        let end = after_open.find(EXPR_CLOSE).ok_or_else(|| {
+            PicoFlowError::Validation(format!(
+                "Unterminated '{}' expression in '{}'",
+                EXPR_OPEN, input
+            ))
+        })?;
+
+        let expr = after_open[..end].trim();
+        let task_name = parse_stdout_reference(expr)?;
+        let value = outputs.get(task_name).ok_or_else(|| {
+            PicoFlowError::Validation(format!(
+                "No captured output for task '{}' (referenced as '{}'); the task must set \
+                 `outputs: true` and complete before this task runs",
+                task_name, expr
+            ))
+        })?;
+        result.push_str(value);
+
+        rest = &after_open[end + EXPR_CLOSE.len()..];
+    }
+
+    result.push_str(rest);
+    Ok(result)
+}
+
+/// Parse `tasks.<name>.stdout` and return `<name>`.
+fn parse_stdout_reference(expr: &str) -> Result<&str> {
+    expr.strip_prefix("tasks.")
+        .and_then(|s| s.strip_suffix(".stdout"))
+        .filter(|name| !name.is_empty())
+        .ok_or_else(|| {
+            PicoFlowError::Validation(format!(
+                "Unsupported expression '{}': expected 'tasks.<name>.stdout'",
+                expr
+            ))
+        })
+}
+
+/// Return a copy of `config` with task-output references resolved.
+///
+/// Substitution applies to shell `command` and `args`, to HTTP `url` and every string
+/// inside `body`, and to download `url`. SSH, Docker and noop configs are returned
+/// unchanged.
+///
+/// # Errors
+///
+/// Propagates any `PicoFlowError::Validation` from [`substitute_task_outputs`].
+pub fn resolve_task_config(
+    config: &TaskExecutorConfig,
+    outputs: &HashMap<String, String>,
+) -> Result<TaskExecutorConfig> {
+    match config {
+        TaskExecutorConfig::Shell(shell) => {
+            let mut shell = shell.clone();
+            shell.command = substitute_task_outputs(&shell.command, outputs)?;
+            for arg in &mut shell.args {
+                *arg = substitute_task_outputs(arg, outputs)?;
+            }
+            Ok(TaskExecutorConfig::Shell(shell))
+        }
+        TaskExecutorConfig::Http(http) => {
+            let mut http = http.clone();
+            http.url = substitute_task_outputs(&http.url, outputs)?;
+            if let Some(body) = http.body.as_mut() {
+                substitute_yaml_value(body, outputs)?;
+            }
+            Ok(TaskExecutorConfig::Http(http))
+        }
+        TaskExecutorConfig::Download(download) => {
+            let mut download = download.clone();
+            download.url = substitute_task_outputs(&download.url, outputs)?;
+            Ok(TaskExecutorConfig::Download(download))
+        }
+        TaskExecutorConfig::Ssh(_)
+        | TaskExecutorConfig::Docker(_)
+        | TaskExecutorConfig::Noop(_) => Ok(config.clone()),
+    }
+}
+
+/// Recursively substitute every string scalar in a YAML value (keys are left as-is).
+fn substitute_yaml_value(
+    value: &mut serde_yaml::Value,
+    outputs: &HashMap<String, String>,
+) -> Result<()> {
+    match value {
+        serde_yaml::Value::String(s) => *s = substitute_task_outputs(s, outputs)?,
+        serde_yaml::Value::Sequence(seq) => {
+            for item in seq {
+                substitute_yaml_value(item, outputs)?;
+            }
+        }
+        serde_yaml::Value::Mapping(map) => {
+            for (_, item) in map.iter_mut() {
+                substitute_yaml_value(item, outputs)?;
+            }
+        }
+        serde_yaml::Value::Tagged(tagged) => substitute_yaml_value(&mut tagged.value, outputs)?,
+        _ => {}
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{HttpConfig, HttpMethod, ShellConfig};
+
+    fn outputs() -> HashMap<String, String> {
+        let mut map = HashMap::new();
+        map.insert("task_a".to_string(), "hello".to_string());
+        map.insert("task-b".to_string(), "/tmp/out".to_string());
+        map
+    }
+
+    #[test]
+    fn test_substitute_no_expressions() {
+        assert_eq!(
+            substitute_task_outputs("plain text", &outputs()).unwrap(),
+            "plain text"
+        );
+    }
+
+    #[test]
+    fn test_substitute_single_and_multiple() {
+        let out = outputs();
+        assert_eq!(
+            substitute_task_outputs("${{ tasks.task_a.stdout }}", &out).unwrap(),
+            "hello"
+        );
+        assert_eq!(
+            substitute_task_outputs(
+                "${{tasks.task_a.stdout}} > ${{ tasks.task-b.stdout }}!",
+                &out
+            )
+            .unwrap(),
+            "hello > /tmp/out!"
+        );
+    }
+
+    #[test]
+    fn test_substitute_missing_key_is_validation_error() {
+        let err = substitute_task_outputs("${{ tasks.nope.stdout }}", &outputs()).unwrap_err();
+        assert!(matches!(err, PicoFlowError::Validation(_)));
+        assert!(err.to_string().contains("nope"));
+    }
+
+    #[test]
+    fn test_substitute_unsupported_expression() {
+        for input in [
+            "${{ tasks.task_a.stderr }}",
+            "${{ params.x }}",
+            "${{ tasks..stdout }}",
+        ] {
+            let err = substitute_task_outputs(input, &outputs()).unwrap_err();
+            assert!(matches!(err, PicoFlowError::Validation(_)), "{input}");
+        }
+    }
+
+    #[test]
+    fn test_substitute_unterminated() {
+        let err = substitute_task_outputs("${{ tasks.task_a.stdout", &outputs()).unwrap_err();
+        assert!(matches!(err, PicoFlowError::Validation(_)));
+    }
+
+    #[test]
+    fn test_substitute_leaves_plain_env_syntax_alone() {
+        assert_eq!(
+            substitute_task_outputs("${HOME} $PATH", &outputs()).unwrap(),
+            "${HOME} $PATH"
+        );
+    }
+
+    #[test]
+    fn test_resolve_shell_config() {
+        let config = TaskExecutorConfig::Shell(ShellConfig {
+            command: "/bin/echo".to_string(),
+            args: vec!["${{ tasks.task_a.stdout }}".to_string(), "x".to_string()],
+            ..Default::default()
+        });
+        match resolve_task_config(&config, &outputs()).unwrap() {
+            TaskExecutorConfig::Shell(shell) => assert_eq!(shell.args, vec!["hello", "x"]),
+            other => panic!("unexpected config: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_resolve_http_config() {
+        let body: serde_yaml::Value = serde_yaml::from_str(
+            "path: \"${{ tasks.task-b.stdout }}\"\nitems: [\"${{ tasks.task_a.stdout }}\", 1]",
+        )
+        .unwrap();
+        let config = TaskExecutorConfig::Http(HttpConfig {
+            url: "https://example.com/${{ tasks.task_a.stdout }}".to_string(),
+            method: HttpMethod::Post,
+            body: Some(body),
+            body_type: Default::default(),
+            headers: HashMap::new(),
+            timeout: 30,
+            allow_private_ips: false,
+            auth: None,
+            retry_on_status: None,
+            rate_limit_per_sec: None,
+            expect_json_path: None,
+            allowed_hosts: None,
+            client_cert_path: None,
+            client_key_path: None,
+        });
+        match resolve_task_config(&config, &outputs()).unwrap() {
+            TaskExecutorConfig::Http(http) => {
+                assert_eq!(http.url, "https://example.com/hello");
+                let body = http.body.unwrap();
+                assert_eq!(body["path"].as_str(), Some("/tmp/out"));
+                assert_eq!(body["items"][0].as_str(), Some("hello"));
+                assert_eq!(body["items"][1].as_i64(), Some(1));
+            }
+            other => panic!("unexpected config: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_resolve_missing_key_errors() {
+        let config = TaskExecutorConfig::Shell(ShellConfig {
+            command: "/bin/echo".to_string(),
+            args: vec!["${{ tasks.missing.stdout }}".to_string()],
+            ..Default::default()
+        });
+        assert!(matches!(
+            resolve_task_config(&config, &outputs()),
+            Err(PicoFlowError::Validation(_))
+        ));
+    }
+}
@@ -0,0 +1,283 @@
+//! Re-run a workflow whenever its YAML file changes
+//!
+//! Backs `picoflow run --watch`. The workflow runs once immediately and again after
+//! each modification of its file. Bursts of filesystem events (editors often write a
+//! file in several steps) are debounced into a single run. A file that fails to parse
+//! is reported and watching continues, so a half-finished edit never ends the session.
+//!
+//! The file's parent directory is watched rather than the file itself, because editors
+//! that save by writing a new file and renaming it over the old one would otherwise
+//! leave the watch attached to the deleted inode.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use picoflow::scheduler::TaskScheduler;
+//! use picoflow::state::StateManager;
+//! use picoflow::watch::watch_workflow;
+//! use std::collections::HashMap;
+//! use std::path::Path;
+//! use std::sync::Arc;
+//! use tokio::sync::watch;
+//!
+//! # async fn example() -> anyhow::Result<()> {
+//! let state_manager = Arc::new(StateManager::new("picoflow.db").await?);
+//! let scheduler = TaskScheduler::new(state_manager);
+//! let (_stop_tx, stop_rx) = watch::channel(false);
+//!
+//! watch_workflow(
+//!     Path::new("workflow.yaml"),
+//!     &HashMap::new(),
+//!     &HashMap::new(),
+//!     None,
+//!     &scheduler,
+//!     stop_rx,
+//!     |run| println!("{}", run),
+//! )
+//! .await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::error::{PicoFlowError, Result};
+use crate::executors::shell::cancelled;
+use crate::parser::parse_workflow_file_with_params;
+use crate::scheduler::TaskScheduler;
+use notify::{Event, RecommendedWatcher, RecursiveMode, Watcher};
+use std::collections::HashMap;
+use std::path::Path;
+use std::time::{Duration, Instant};
+use tokio::sync::{mpsc, watch};
+use tracing::{debug, info, warn};
+
+/// Quiet period after the last filesystem event before the workflow is re-run
+pub const DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// Outcome of one watch iteration
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WatchRun {
+    /// The workflow was parsed and executed
+    Executed {
+        workflow: String,
+        success: bool,
+        duration: Duration,
+    },
+    /// The workflow file could not be loaded, or its execution could not start
+    Error(String),
+}
+
+impl std::fmt::Display for WatchRun {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WatchRun::Executed {
+                workflow,
+                success,
+                duration,
+            } => write!(
+                f,
+                "Workflow '{}' {} in {:.1}s",
+                workflow,
+                if *success { "succeeded" } else { "failed" },
+                duration.as_secs_f64()
+            ),
+            WatchRun::Error(message) => write!(f, "Workflow not run: {}", message),
+        }
+    }
+}
+
+/// Run `path` now and again after every change to it, until `stop` becomes `true`
+///
+/// The file is loaded with `env` and `params` as in
+/// [`crate::parser::parse_workflow_file_with_params`]. `max_parallel`, when set, replaces the workflow's `config.max_parallel` on every run.
+/// `report` is called after each run. Stopping also cancels a run in progress (see
+/// [`TaskScheduler::execute_workflow_with_shutdown`]).
+///
+/// # Errors
+///
+/// * `PicoFlowError::Other` - If the file watcher cannot be set up
+pub async fn watch_workflow<F>(
+    path: &Path,
+    env: &HashMap<String, String>,
+    params: &HashMap<String, String>,
+    max_parallel: Option<usize>,
+    scheduler: &TaskScheduler,
+    mut stop: watch::Receiver<bool>,
+    mut report: F,
+) -> Result<()>
+where
+    F: FnMut(&WatchRun),
+{
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| PicoFlowError::Other(format!("Not a file: {}", path.display())))?
+        .to_os_string();
+    let dir = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+        _ => std::path::PathBuf::from("."),
+    };
+
+    // notify calls back on its own thread; forward relevant events into the runtime
+    let (events_tx, mut events_rx) = mpsc::unbounded_channel();
+    let mut watcher = RecommendedWatcher::new(
+        move |event: notify::Result<Event>| match event {
+            Ok(event) => {
+                let touches_file = event
+                    .paths
+                    .iter()
+                    .any(|p| p.file_name() == Some(file_name.as_os_str()));
+                if touches_file && !event.kind.is_access() {
+                    let _ = events_tx.send(());
+                }
+            }
+            Err(e) => warn!("File watch error: {}", e),
+        },
+        notify::Config::default(),
+    )
+    .map_err(|e| PicoFlowError::Other(format!("Failed to create file watcher: {}", e)))?;
+    watcher
+        .watch(&dir, RecursiveMode::NonRecursive)
+        .map_err(|e| PicoFlowError::Other(format!("Failed to watch {}: {}", dir.display(), e)))?;
+
+    info!("Watching {} for changes", path.display());
+
+    loop {
+        let run = run_once(path, env, params, max_parallel, scheduler, stop.clone()).await;
+        report(&run);
+
+        // Wait for the next change, then for the burst of events to settle
+        tokio::select! {
+            event = events_rx.recv() => {
+                if event.is_none() {
+                    return Ok(());
+                }
+            }
+            _ = cancelled(&mut stop) => return Ok(()),
+        }
+        loop {
+            tokio::select! {
+                event = tokio::time::timeout(DEBOUNCE, events_rx.recv()) => match event {
+                    Ok(Some(())) => continue,
+                    Ok(None) => return Ok(()),
+                    Err(_) => break,
+                },
+                _ = cancelled(&mut stop) => return Ok(()),
+            }
+        }
+        debug!("{} changed, re-running", path.display());
+    }
+}
+
+/// Load and execute the workflow once
+async fn run_once(
+    path: &Path,
+    env: &HashMap<String, String>,
+    params: &HashMap<String, String>,
+    max_parallel: Option<usize>,
+    scheduler: &TaskScheduler,
+    stop: watch::Receiver<bool>,
+) -> WatchRun {
+    let mut config = match parse_workflow_file_with_params(path, env, params) {
+        Ok(config) => config,
+        Err(e) => return WatchRun::Error(e.to_string()),
+    };
+    if let Some(max_parallel) = max_parallel {
+        config.config.max_parallel = max_parallel;
+    }
+
+    let start = Instant::now();
+    match scheduler
+        .execute_workflow_with_shutdown(&config, stop)
+        .await
+    {
+        Ok(result) => WatchRun::Executed {
+            workflow: config.name,
+            success: result.succeeded(),
+            duration: start.elapsed(),
+        },
+        Err(e) => WatchRun::Error(e.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::StateManager;
+    use std::sync::{Arc, Mutex};
+
+    fn workflow_yaml(message: &str) -> String {
+        format!(
+            r#"
+name: watched
+tasks:
+  - name: say
+    type: shell
+    config:
+      command: /bin/echo
+      args: ["{message}"]
+"#
+        )
+    }
+
+    /// Poll until `runs` holds `count` entries
+    async fn wait_for_runs(runs: &Mutex<Vec<WatchRun>>, count: usize) {
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while runs.lock().unwrap().len() < count {
+            assert!(
+                Instant::now() < deadline,
+                "timed out waiting for run {count}"
+            );
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_edit_triggers_rerun() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("workflow.yaml");
+        std::fs::write(&path, workflow_yaml("first")).unwrap();
+
+        let state_manager = Arc::new(StateManager::in_memory().await.unwrap());
+        let scheduler = Arc::new(TaskScheduler::new(state_manager.clone()));
+        let runs = Arc::new(Mutex::new(Vec::new()));
+        let (stop_tx, stop_rx) = watch::channel(false);
+
+        let watcher = tokio::spawn({
+            let (path, scheduler, runs) = (path.clone(), scheduler.clone(), runs.clone());
+            async move {
+                watch_workflow(
+                    &path,
+                    &HashMap::new(),
+                    &HashMap::new(),
+                    None,
+                    &scheduler,
+                    stop_rx,
+                    |run| runs.lock().unwrap().push(run.clone()),
+                )
+                .await
+            }
+        });
+
+        wait_for_runs(&runs, 1).await;
+
+        // A broken edit is reported without ending the watch
+        std::fs::write(&path, "name: [unclosed").unwrap();
+        wait_for_runs(&runs, 2).await;
+
+        std::fs::write(&path, workflow_yaml("second")).unwrap();
+        wait_for_runs(&runs, 3).await;
+
+        stop_tx.send(true).unwrap();
+        watcher.await.unwrap().unwrap();
+
+        let runs = runs.lock().unwrap().clone();
+        assert!(matches!(&runs[0], WatchRun::Executed { success: true, .. }));
+        assert!(matches!(&runs[1], WatchRun::Error(_)));
+        assert!(matches!(&runs[2], WatchRun::Executed { success: true, .. }));
+
+        let history = state_manager
+            .get_execution_history("watched", 10)
+            .await
+            .unwrap();
+        assert_eq!(history.len(), 2);
+    }
+}
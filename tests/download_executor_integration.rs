@@ -0,0 +1,99 @@
+//! Integration tests for the download executor with a mock HTTP server
+
+use picoflow::executors::download::DownloadExecutor;
+use picoflow::executors::ExecutorTrait;
+use picoflow::models::{DownloadConfig, ExecutionResult, TaskExecutorConfig, TaskStatus};
+use std::path::Path;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+const BODY: &[u8] = b"firmware-image-v2";
+// sha256("firmware-image-v2")
+const BODY_SHA256: &str = "29b51743ea09bb404564ccb4c457a4ed03f0db58c506a70e3019762d08caf6b1";
+
+async fn download(
+    mock_server: &MockServer,
+    dest: &Path,
+    checksum: Option<&str>,
+) -> ExecutionResult {
+    let config = TaskExecutorConfig::Download(DownloadConfig {
+        url: format!("{}/firmware.bin", mock_server.uri()),
+        dest_path: dest.to_str().unwrap().to_string(),
+        checksum: checksum.map(str::to_string),
+        timeout: 5,
+        allow_private_ips: true, // Allow localhost for testing
+    });
+
+    DownloadExecutor::new().execute(&config).await.unwrap()
+}
+
+async fn firmware_server() -> MockServer {
+    let mock_server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/firmware.bin"))
+        .respond_with(ResponseTemplate::new(200).set_body_bytes(BODY))
+        .mount(&mock_server)
+        .await;
+    mock_server
+}
+
+#[tokio::test]
+async fn test_download_with_matching_checksum() {
+    let mock_server = firmware_server().await;
+    let dir = tempfile::tempdir().unwrap();
+    let dest = dir.path().join("firmware.bin");
+
+    // Checksums are compared case-insensitively
+    let result = download(&mock_server, &dest, Some(&BODY_SHA256.to_uppercase())).await;
+
+    assert_eq!(result.status, TaskStatus::Success);
+    assert_eq!(result.exit_code, Some(200));
+    assert_eq!(result.stdout, Some(BODY.len().to_string()));
+    assert_eq!(std::fs::read(&dest).unwrap(), BODY);
+    assert!(!dir.path().join("firmware.bin.part").exists());
+}
+
+#[tokio::test]
+async fn test_download_checksum_mismatch_leaves_no_file() {
+    let mock_server = firmware_server().await;
+    let dir = tempfile::tempdir().unwrap();
+    let dest = dir.path().join("firmware.bin");
+
+    let result = download(&mock_server, &dest, Some(&"0".repeat(64))).await;
+
+    assert_eq!(result.status, TaskStatus::Failed);
+    assert!(result.stderr.unwrap().contains("Checksum mismatch"));
+    assert!(!dest.exists());
+    assert!(!dir.path().join("firmware.bin.part").exists());
+}
+
+#[tokio::test]
+async fn test_download_http_error() {
+    let mock_server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(404))
+        .mount(&mock_server)
+        .await;
+    let dir = tempfile::tempdir().unwrap();
+    let dest = dir.path().join("firmware.bin");
+
+    let result = download(&mock_server, &dest, None).await;
+
+    assert_eq!(result.status, TaskStatus::Failed);
+    assert_eq!(result.exit_code, Some(404));
+    assert!(!dest.exists());
+}
+
+#[tokio::test]
+async fn test_download_blocks_private_ips_by_default() {
+    let mock_server = firmware_server().await;
+    let config = TaskExecutorConfig::Download(DownloadConfig {
+        url: format!("{}/firmware.bin", mock_server.uri()),
+        dest_path: "/tmp/picoflow-ssrf-test.bin".to_string(),
+        checksum: None,
+        timeout: 5,
+        allow_private_ips: false,
+    });
+
+    assert!(DownloadExecutor::new().execute(&config).await.is_err());
+}
@@ -2,11 +2,33 @@
 
 use picoflow::executors::http::HttpExecutor;
 use picoflow::executors::ExecutorTrait;
-use picoflow::models::{HttpConfig, HttpMethod, TaskExecutorConfig, TaskStatus};
+use picoflow::models::{
+    BodyType, HttpAuth, HttpConfig, HttpMethod, TaskExecutorConfig, TaskStatus,
+};
 use std::collections::HashMap;
-use wiremock::matchers::{body_json, header, method, path};
+use wiremock::matchers::{body_json, body_string, header, method, path};
 use wiremock::{Mock, MockServer, ResponseTemplate};
 
+/// A GET request to `url` that may reach the mock server on localhost
+fn http_config(url: impl Into<String>) -> HttpConfig {
+    HttpConfig {
+        url: url.into(),
+        method: HttpMethod::Get,
+        body: None,
+        body_type: Default::default(),
+        headers: HashMap::new(),
+        timeout: 5,
+        allow_private_ips: true,
+        auth: None,
+        retry_on_status: None,
+        rate_limit_per_sec: None,
+        expect_json_path: None,
+        allowed_hosts: None,
+        client_cert_path: None,
+        client_key_path: None,
+    }
+}
+
 #[tokio::test]
 async fn test_http_get_success() {
     // Start mock server
@@ -21,14 +43,7 @@ async fn test_http_get_success() {
 
     // Create executor and config
     let executor = HttpExecutor::new();
-    let config = TaskExecutorConfig::Http(HttpConfig {
-        url: format!("{}/api/health", mock_server.uri()),
-        method: HttpMethod::Get,
-        body: None,
-        headers: HashMap::new(),
-        timeout: 5,
-        allow_private_ips: true, // Allow localhost for testing
-    });
+    let config = TaskExecutorConfig::Http(http_config(format!("{}/api/health", mock_server.uri())));
 
     // Execute request
     let result = executor.execute(&config).await.unwrap();
@@ -68,12 +83,9 @@ email: test@example.com
     .unwrap();
 
     let config = TaskExecutorConfig::Http(HttpConfig {
-        url: format!("{}/api/users", mock_server.uri()),
         method: HttpMethod::Post,
         body: Some(body_yaml),
-        headers: HashMap::new(),
-        timeout: 5,
-        allow_private_ips: true, // Allow localhost for testing
+        ..http_config(format!("{}/api/users", mock_server.uri()))
     });
 
     let result = executor.execute(&config).await.unwrap();
@@ -83,6 +95,78 @@ email: test@example.com
     assert!(result.stdout.unwrap().contains("123"));
 }
 
+/// POST `body` encoded as `body_type`, expecting the server to see `expected_body` with
+/// `expected_content_type`.
+async fn post_with_body_type(
+    body: serde_yaml::Value,
+    body_type: BodyType,
+    headers: HashMap<String, String>,
+    expected_body: &str,
+    expected_content_type: &str,
+) {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(path("/api/submit"))
+        .and(header("Content-Type", expected_content_type))
+        .and(body_string(expected_body))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let config = TaskExecutorConfig::Http(HttpConfig {
+        method: HttpMethod::Post,
+        body: Some(body),
+        body_type,
+        headers,
+        ..http_config(format!("{}/api/submit", mock_server.uri()))
+    });
+
+    let result = HttpExecutor::new().execute(&config).await.unwrap();
+    assert_eq!(result.status, TaskStatus::Success);
+}
+
+#[tokio::test]
+async fn test_http_json_body_type() {
+    let body = serde_yaml::from_str("count: 2").unwrap();
+    post_with_body_type(
+        body,
+        BodyType::Json,
+        HashMap::new(),
+        r#"{"count":2}"#,
+        "application/json",
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_http_form_body_type() {
+    let body = serde_yaml::from_str("name: pico flow\nretries: 3\nactive: true").unwrap();
+    post_with_body_type(
+        body,
+        BodyType::Form,
+        HashMap::new(),
+        "name=pico+flow&retries=3&active=true",
+        "application/x-www-form-urlencoded",
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_http_raw_body_type_keeps_user_content_type() {
+    let mut headers = HashMap::new();
+    headers.insert("content-type".to_string(), "application/xml".to_string());
+    post_with_body_type(
+        serde_yaml::Value::String("<ping/>".to_string()),
+        BodyType::Raw,
+        headers,
+        "<ping/>",
+        "application/xml",
+    )
+    .await;
+}
+
 #[tokio::test]
 async fn test_http_put_request() {
     let mock_server = MockServer::start().await;
@@ -103,12 +187,9 @@ name: updated_name
     .unwrap();
 
     let config = TaskExecutorConfig::Http(HttpConfig {
-        url: format!("{}/api/users/123", mock_server.uri()),
         method: HttpMethod::Put,
         body: Some(body_yaml),
-        headers: HashMap::new(),
-        timeout: 5,
-        allow_private_ips: true, // Allow localhost for testing
+        ..http_config(format!("{}/api/users/123", mock_server.uri()))
     });
 
     let result = executor.execute(&config).await.unwrap();
@@ -129,12 +210,8 @@ async fn test_http_delete_request() {
 
     let executor = HttpExecutor::new();
     let config = TaskExecutorConfig::Http(HttpConfig {
-        url: format!("{}/api/users/123", mock_server.uri()),
         method: HttpMethod::Delete,
-        body: None,
-        headers: HashMap::new(),
-        timeout: 5,
-        allow_private_ips: true, // Allow localhost for testing
+        ..http_config(format!("{}/api/users/123", mock_server.uri()))
     });
 
     let result = executor.execute(&config).await.unwrap();
@@ -161,12 +238,8 @@ async fn test_http_custom_headers() {
     headers.insert("X-Custom-Header".to_string(), "custom_value".to_string());
 
     let config = TaskExecutorConfig::Http(HttpConfig {
-        url: format!("{}/api/protected", mock_server.uri()),
-        method: HttpMethod::Get,
-        body: None,
         headers,
-        timeout: 5,
-        allow_private_ips: true, // Allow localhost for testing
+        ..http_config(format!("{}/api/protected", mock_server.uri()))
     });
 
     let result = executor.execute(&config).await.unwrap();
@@ -176,6 +249,117 @@ async fn test_http_custom_headers() {
     assert_eq!(result.stdout, Some("Authorized".to_string()));
 }
 
+/// Run a GET against `/api/auth` with the given auth/headers, expecting the mock to
+/// require `expected_authorization`.
+async fn run_with_auth(
+    auth: HttpAuth,
+    headers: HashMap<String, String>,
+    expected_authorization: &str,
+) -> TaskStatus {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/api/auth"))
+        .and(header("Authorization", expected_authorization))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let config = TaskExecutorConfig::Http(HttpConfig {
+        headers,
+        auth: Some(auth),
+        ..http_config(format!("{}/api/auth", mock_server.uri()))
+    });
+
+    HttpExecutor::new().execute(&config).await.unwrap().status
+}
+
+#[tokio::test]
+async fn test_http_basic_auth() {
+    let auth = HttpAuth::Basic {
+        username: "user".to_string(),
+        password: "pass".to_string(),
+    };
+    // base64("user:pass")
+    let status = run_with_auth(auth, HashMap::new(), "Basic dXNlcjpwYXNz").await;
+    assert_eq!(status, TaskStatus::Success);
+}
+
+#[tokio::test]
+async fn test_http_bearer_auth() {
+    let auth = HttpAuth::Bearer {
+        token: "secret-token".to_string(),
+    };
+    let status = run_with_auth(auth, HashMap::new(), "Bearer secret-token").await;
+    assert_eq!(status, TaskStatus::Success);
+}
+
+#[tokio::test]
+async fn test_http_explicit_authorization_header_overrides_auth() {
+    let auth = HttpAuth::Bearer {
+        token: "from-auth".to_string(),
+    };
+    let mut headers = HashMap::new();
+    headers.insert("authorization".to_string(), "Token from-header".to_string());
+
+    let status = run_with_auth(auth, headers, "Token from-header").await;
+    assert_eq!(status, TaskStatus::Success);
+}
+
+fn retrying_config(uri: &str, method: HttpMethod, retry_on_status: Vec<u16>) -> TaskExecutorConfig {
+    TaskExecutorConfig::Http(HttpConfig {
+        method,
+        retry_on_status: Some(retry_on_status),
+        ..http_config(format!("{}/api/flaky", uri))
+    })
+}
+
+#[tokio::test]
+async fn test_http_retry_on_status_then_success() {
+    let mock_server = MockServer::start().await;
+
+    // Mocks are matched in mount order; the 503 stops matching after one use
+    Mock::given(method("POST"))
+        .and(path("/api/flaky"))
+        .respond_with(ResponseTemplate::new(503).insert_header("Retry-After", "0"))
+        .up_to_n_times(1)
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/api/flaky"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("done"))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let config = retrying_config(&mock_server.uri(), HttpMethod::Post, vec![429, 503]);
+    let result = HttpExecutor::new().execute(&config).await.unwrap();
+
+    assert_eq!(result.status, TaskStatus::Success);
+    assert_eq!(result.exit_code, Some(200));
+    assert_eq!(result.stdout, Some("done".to_string()));
+}
+
+#[tokio::test]
+async fn test_http_unlisted_status_not_retried() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/api/flaky"))
+        .respond_with(ResponseTemplate::new(500))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let config = retrying_config(&mock_server.uri(), HttpMethod::Get, vec![503]);
+    let result = HttpExecutor::new().execute(&config).await.unwrap();
+
+    assert_eq!(result.status, TaskStatus::Failed);
+    assert_eq!(result.exit_code, Some(500));
+}
+
 #[tokio::test]
 async fn test_http_4xx_error() {
     let mock_server = MockServer::start().await;
@@ -187,14 +371,8 @@ async fn test_http_4xx_error() {
         .await;
 
     let executor = HttpExecutor::new();
-    let config = TaskExecutorConfig::Http(HttpConfig {
-        url: format!("{}/api/not-found", mock_server.uri()),
-        method: HttpMethod::Get,
-        body: None,
-        headers: HashMap::new(),
-        timeout: 5,
-        allow_private_ips: true, // Allow localhost for testing
-    });
+    let config =
+        TaskExecutorConfig::Http(http_config(format!("{}/api/not-found", mock_server.uri())));
 
     let result = executor.execute(&config).await.unwrap();
 
@@ -214,14 +392,7 @@ async fn test_http_5xx_error() {
         .await;
 
     let executor = HttpExecutor::new();
-    let config = TaskExecutorConfig::Http(HttpConfig {
-        url: format!("{}/api/error", mock_server.uri()),
-        method: HttpMethod::Get,
-        body: None,
-        headers: HashMap::new(),
-        timeout: 5,
-        allow_private_ips: true, // Allow localhost for testing
-    });
+    let config = TaskExecutorConfig::Http(http_config(format!("{}/api/error", mock_server.uri())));
 
     let result = executor.execute(&config).await.unwrap();
 
@@ -243,12 +414,8 @@ async fn test_http_timeout() {
 
     let executor = HttpExecutor::new();
     let config = TaskExecutorConfig::Http(HttpConfig {
-        url: format!("{}/api/slow", mock_server.uri()),
-        method: HttpMethod::Get,
-        body: None,
-        headers: HashMap::new(),
-        timeout: 1,              // 1 second timeout
-        allow_private_ips: true, // Allow localhost for testing
+        timeout: 1, // 1 second timeout
+        ..http_config(format!("{}/api/slow", mock_server.uri()))
     });
 
     let result = executor.execute(&config).await.unwrap();
@@ -274,12 +441,8 @@ async fn test_http_large_response_truncation() {
 
     let executor = HttpExecutor::new();
     let config = TaskExecutorConfig::Http(HttpConfig {
-        url: format!("{}/api/large", mock_server.uri()),
-        method: HttpMethod::Get,
-        body: None,
-        headers: HashMap::new(),
         timeout: 30,
-        allow_private_ips: true, // Allow localhost for testing
+        ..http_config(format!("{}/api/large", mock_server.uri()))
     });
 
     let result = executor.execute(&config).await.unwrap();
@@ -294,19 +457,201 @@ async fn test_http_large_response_truncation() {
 }
 
 #[tokio::test]
-async fn test_http_connection_error() {
+async fn test_http_output_limit_truncates_body() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/api/status"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("{\"status\":\"ok\"}"))
+        .mount(&mock_server)
+        .await;
+
     let executor = HttpExecutor::new();
+    let config = TaskExecutorConfig::Http(http_config(format!("{}/api/status", mock_server.uri())));
 
-    // Use invalid host that will fail to connect
+    // The default limit keeps the whole body
+    let result = executor.execute(&config).await.unwrap();
+    assert!(!result.output_truncated);
+    assert_eq!(result.stdout.as_deref(), Some("{\"status\":\"ok\"}"));
+
+    let result = executor
+        .execute_with_output_limit(&config, 8)
+        .await
+        .unwrap();
+    assert_eq!(result.status, TaskStatus::Success);
+    assert!(result.output_truncated);
+    assert_eq!(result.stdout.as_deref(), Some("{\"status"));
+}
+
+#[tokio::test]
+async fn test_http_rate_limit_throttles_concurrent_requests() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/api/items"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("OK"))
+        .expect(5)
+        .mount(&mock_server)
+        .await;
+
+    let executor = HttpExecutor::new();
     let config = TaskExecutorConfig::Http(HttpConfig {
-        url: "http://invalid-host-that-does-not-exist-12345.com".to_string(),
-        method: HttpMethod::Get,
-        body: None,
-        headers: HashMap::new(),
-        timeout: 5,
-        allow_private_ips: true, // Allow localhost for testing
+        rate_limit_per_sec: Some(2),
+        ..http_config(format!("{}/api/items", mock_server.uri()))
+    });
+
+    // Two requests use the initial burst; the other three are spaced 500ms apart
+    let start = std::time::Instant::now();
+    let results = futures::future::join_all((0..5).map(|_| executor.execute(&config))).await;
+    let elapsed = start.elapsed();
+
+    for result in results {
+        assert_eq!(result.unwrap().status, TaskStatus::Success);
+    }
+    assert!(
+        elapsed >= std::time::Duration::from_millis(1400),
+        "5 requests at 2/s finished in {:?}",
+        elapsed
+    );
+}
+
+#[tokio::test]
+async fn test_http_binary_response_flagged() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/image.png"))
+        .respond_with(ResponseTemplate::new(200).set_body_bytes(vec![0x89, b'P', 0xff, 0x00]))
+        .mount(&mock_server)
+        .await;
+
+    let executor = HttpExecutor::new();
+    let config = TaskExecutorConfig::Http(http_config(format!("{}/image.png", mock_server.uri())));
+
+    let result = executor.execute(&config).await.unwrap();
+
+    assert_eq!(result.status, TaskStatus::Success);
+    assert!(result.output_binary);
+    assert_eq!(result.stdout, None);
+    assert_eq!(
+        result.stderr.as_deref(),
+        Some("[response body is not valid UTF-8: 4 bytes of binary output not stored]")
+    );
+}
+
+/// GET `/status` returning `body` with a 200, asserting `status == ok`
+async fn run_with_json_expectation(body: &str) -> picoflow::models::ExecutionResult {
+    let mock_server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/status"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(body))
+        .mount(&mock_server)
+        .await;
+
+    let executor = HttpExecutor::new();
+    let config = TaskExecutorConfig::Http(HttpConfig {
+        expect_json_path: Some(("$.result.status".to_string(), "ok".to_string())),
+        ..http_config(format!("{}/status", mock_server.uri()))
+    });
+
+    executor.execute(&config).await.unwrap()
+}
+
+#[tokio::test]
+async fn test_http_expect_json_path_matches() {
+    let body = r#"{"result": {"status": "ok", "count": 3}}"#;
+    let result = run_with_json_expectation(body).await;
+
+    assert_eq!(result.status, TaskStatus::Success);
+    assert_eq!(result.exit_code, Some(200));
+    assert_eq!(result.stdout.as_deref(), Some(body));
+    assert_eq!(result.stderr, None);
+}
+
+#[tokio::test]
+async fn test_http_expect_json_path_mismatch_fails_despite_2xx() {
+    let result = run_with_json_expectation(r#"{"result": {"status": "error"}}"#).await;
+    assert_eq!(result.status, TaskStatus::Failed);
+    assert_eq!(result.exit_code, Some(200));
+    let stderr = result.stderr.unwrap();
+    assert!(stderr.contains("expected 'ok'"), "{stderr}");
+    assert!(stderr.contains("got 'error'"), "{stderr}");
+
+    let result = run_with_json_expectation(r#"{"status": "ok"}"#).await;
+    assert_eq!(result.status, TaskStatus::Failed);
+    assert!(result.stderr.unwrap().contains("not found"));
+
+    let result = run_with_json_expectation("ok").await;
+    assert_eq!(result.status, TaskStatus::Failed);
+    assert!(result.stderr.unwrap().contains("not JSON"));
+}
+
+#[tokio::test]
+async fn test_http_allowed_hosts() {
+    let mock_server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/internal"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("OK"))
+        .mount(&mock_server)
+        .await;
+
+    let executor = HttpExecutor::new();
+    let mut http = HttpConfig {
+        allow_private_ips: false,
+        allowed_hosts: Some(vec!["127.0.0.1".to_string()]),
+        ..http_config(format!("{}/internal", mock_server.uri()))
+    };
+
+    // The listed loopback host is reachable without allow_private_ips
+    let result = executor
+        .execute(&TaskExecutorConfig::Http(http.clone()))
+        .await
+        .unwrap();
+    assert_eq!(result.status, TaskStatus::Success);
+    assert_eq!(result.stdout, Some("OK".to_string()));
+
+    // A host that is not listed is rejected before any request, even with allow_private_ips
+    http.allowed_hosts = Some(vec!["api.internal".to_string()]);
+    http.allow_private_ips = true;
+    let err = executor
+        .execute(&TaskExecutorConfig::Http(http))
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("not in allowed_hosts"), "{err}");
+    assert_eq!(mock_server.received_requests().await.unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn test_http_missing_client_certificate() {
+    let mock_server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&mock_server)
+        .await;
+
+    let dir = tempfile::tempdir().unwrap();
+    let executor = HttpExecutor::new();
+    let config = TaskExecutorConfig::Http(HttpConfig {
+        client_cert_path: Some(dir.path().join("client.crt").display().to_string()),
+        client_key_path: Some(dir.path().join("client.key").display().to_string()),
+        ..http_config(mock_server.uri())
     });
 
+    // The request is never sent without its client certificate
+    let err = executor.execute(&config).await.unwrap_err();
+    assert!(err.to_string().contains("client.crt"), "{err}");
+    assert!(mock_server.received_requests().await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_http_connection_error() {
+    let executor = HttpExecutor::new();
+
+    // Use invalid host that will fail to connect
+    let config = TaskExecutorConfig::Http(http_config(
+        "http://invalid-host-that-does-not-exist-12345.com",
+    ));
+
     let result = executor.execute(&config).await.unwrap();
 
     // Connection failure should result in failed status
@@ -323,9 +668,7 @@ async fn test_invalid_config_type() {
     // Create wrong config type
     let config = TaskExecutorConfig::Shell(ShellConfig {
         command: "/bin/echo".to_string(),
-        args: vec![],
-        workdir: None,
-        env: None,
+        ..Default::default()
     });
 
     let result = executor.execute(&config).await;
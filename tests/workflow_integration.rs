@@ -68,7 +68,7 @@ tasks:
 
     // Execute workflow
     let scheduler = TaskScheduler::new(state_manager.clone());
-    let success = scheduler.execute_workflow(&config).await.unwrap();
+    let success = scheduler.execute_workflow_ok(&config).await.unwrap();
     assert!(success, "Workflow execution should succeed");
 
     // Verify state persistence
@@ -151,7 +151,7 @@ tasks:
     let scheduler = TaskScheduler::new(state_manager.clone());
 
     // Execute workflow - should fail
-    let success = scheduler.execute_workflow(&config).await.unwrap();
+    let success = scheduler.execute_workflow_ok(&config).await.unwrap();
     assert!(!success, "Workflow should fail when a task fails");
 
     // Verify execution history shows failure
@@ -169,7 +169,7 @@ tasks:
         .await
         .unwrap();
 
-    // Should have task_a and task_b results (task_c should not run)
+    // Should have task_a and task_b results (task_c is recorded as skipped, not run)
     // Note: task_b might have multiple attempts due to retries
     let task_a_executions: Vec<_> = tasks.iter().filter(|t| t.task_name == "task_a").collect();
     let task_b_executions: Vec<_> = tasks.iter().filter(|t| t.task_name == "task_b").collect();
@@ -178,8 +178,8 @@ tasks:
     assert!(!task_a_executions.is_empty(), "task_a should have executed");
     assert!(!task_b_executions.is_empty(), "task_b should have executed");
     assert!(
-        task_c_executions.is_empty(),
-        "task_c should not execute when task_b fails"
+        task_c_executions.len() == 1 && task_c_executions[0].status == TaskStatus::Skipped,
+        "task_c should be skipped, not executed, when task_b fails"
     );
 
     // Find the final task_a execution - should succeed
@@ -234,7 +234,7 @@ tasks:
     let scheduler = TaskScheduler::new(state_manager.clone());
 
     // Execute workflow with parallel execution
-    let success = scheduler.execute_workflow(&config).await.unwrap();
+    let success = scheduler.execute_workflow_ok(&config).await.unwrap();
     assert!(success, "Parallel workflow should succeed");
 
     // Verify all tasks completed successfully
@@ -312,7 +312,7 @@ tasks:
     let config = parse_workflow_yaml(yaml).unwrap();
     let scheduler = TaskScheduler::new(state_manager.clone());
 
-    let success = scheduler.execute_workflow(&config).await.unwrap();
+    let success = scheduler.execute_workflow_ok(&config).await.unwrap();
     assert!(success, "All tasks should complete successfully");
 
     // Verify all tasks completed
@@ -381,7 +381,7 @@ tasks:
     let config = parse_workflow_yaml(yaml).unwrap();
     let scheduler = TaskScheduler::new(state_manager.clone());
 
-    let success = scheduler.execute_workflow(&config).await.unwrap();
+    let success = scheduler.execute_workflow_ok(&config).await.unwrap();
     assert!(!success, "Workflow should fail");
 
     let history = state_manager
@@ -407,14 +407,95 @@ tasks:
 
     // task_b should NOT have run (because task_a failed)
     assert!(
-        task_b_executions.is_empty(),
-        "task_b should not execute when task_a fails"
+        task_b_executions.len() == 1 && task_b_executions[0].status == TaskStatus::Skipped,
+        "task_b should be skipped, not executed, when task_a fails"
     );
 
     // task_c is independent and may or may not run depending on parallel execution timing
     // We don't assert on task_c behavior
 }
 
+#[tokio::test]
+async fn test_skipped_tasks_recorded_in_history() {
+    let (_temp_dir, state_manager) = setup_temp_state().await;
+
+    // extract fails, so transform and load are bypassed; notify is an on_failure hook
+    // whose dependencies all succeed in the second workflow below
+    let failing = r#"
+name: skipped-rows
+tasks:
+  - name: extract
+    type: shell
+    retry: 0
+    config:
+      command: "/bin/sh"
+      args: ["-c", "exit 1"]
+
+  - name: transform
+    type: shell
+    depends_on: [extract]
+    config:
+      command: "/bin/true"
+
+  - name: load
+    type: shell
+    depends_on: [transform]
+    config:
+      command: "/bin/true"
+"#;
+    let passing = r#"
+name: skipped-hook
+tasks:
+  - name: extract
+    type: shell
+    config:
+      command: "/bin/true"
+
+  - name: notify
+    type: shell
+    depends_on: [extract]
+    run_condition: on_failure
+    config:
+      command: "/bin/true"
+"#;
+
+    let scheduler = TaskScheduler::new(state_manager.clone());
+    let success = scheduler
+        .execute_workflow_ok(&parse_workflow_yaml(failing).unwrap())
+        .await
+        .unwrap();
+    assert!(!success);
+    let success = scheduler
+        .execute_workflow_ok(&parse_workflow_yaml(passing).unwrap())
+        .await
+        .unwrap();
+    assert!(success, "a skipped hook must not fail the workflow");
+
+    let history = state_manager
+        .get_execution_history("skipped-rows", 1)
+        .await
+        .unwrap();
+    let tasks = state_manager
+        .get_task_executions(history[0].id)
+        .await
+        .unwrap();
+    assert_eq!(tasks.len(), 3, "every task should have a row: {tasks:?}");
+    for name in ["transform", "load"] {
+        let task = tasks.iter().find(|t| t.task_name == name).unwrap();
+        assert_eq!(task.status, TaskStatus::Skipped, "{name}");
+        assert!(task.completed_at.is_none(), "{name}");
+        assert_eq!(task.exit_code, None, "{name}");
+        assert_eq!(task.attempt, 0, "{name}");
+    }
+
+    // Skipped tasks do not turn a successful execution into a failed one
+    let workflows = state_manager.list_workflows().await.unwrap();
+    let hook = workflows.iter().find(|w| w.name == "skipped-hook").unwrap();
+    assert_eq!((hook.success_count, hook.failed_count), (1, 0));
+    let rows = workflows.iter().find(|w| w.name == "skipped-rows").unwrap();
+    assert_eq!((rows.success_count, rows.failed_count), (0, 1));
+}
+
 #[tokio::test]
 async fn test_parallel_continue_on_failure() {
     let (_temp_dir, state_manager) = setup_temp_state().await;
@@ -450,7 +531,7 @@ tasks:
     let config = parse_workflow_yaml(yaml).unwrap();
     let scheduler = TaskScheduler::new(state_manager.clone());
 
-    let success = scheduler.execute_workflow(&config).await.unwrap();
+    let success = scheduler.execute_workflow_ok(&config).await.unwrap();
     // Workflow should fail overall (because task_a failed), but task_b should run
     assert!(!success, "Workflow should fail overall");
 
@@ -483,3 +564,234 @@ tasks:
         "task_b should run when task_a has continue_on_failure=true"
     );
 }
+
+#[tokio::test]
+async fn test_mixed_shell_and_http_workflow() {
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let mock_server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/notify"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("ack"))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let (_temp_dir, state_manager) = setup_temp_state().await;
+
+    let yaml = format!(
+        r#"
+name: mixed-workflow
+config:
+  max_parallel: 2
+  retry_default: 0
+  timeout_default: 30
+
+tasks:
+  - name: prepare
+    type: shell
+    config:
+      command: "/bin/echo"
+      args: ["prepared"]
+
+  - name: notify
+    type: http
+    depends_on: [prepare]
+    config:
+      url: "{}/notify"
+      method: POST
+      body:
+        status: "done"
+      allow_private_ips: true
+"#,
+        mock_server.uri()
+    );
+
+    let config = parse_workflow_yaml(&yaml).unwrap();
+    let scheduler = TaskScheduler::new(state_manager.clone());
+    let success = scheduler.execute_workflow_ok(&config).await.unwrap();
+    assert!(success, "Mixed shell+http workflow should succeed");
+
+    let history = state_manager
+        .get_execution_history("mixed-workflow", 1)
+        .await
+        .unwrap();
+    assert_eq!(history[0].status, TaskStatus::Success);
+
+    let tasks = state_manager
+        .get_task_executions(history[0].id)
+        .await
+        .unwrap();
+    assert_eq!(tasks.len(), 2);
+
+    let prepare = tasks.iter().find(|t| t.task_name == "prepare").unwrap();
+    assert_eq!(prepare.status, TaskStatus::Success);
+    assert_eq!(prepare.exit_code, Some(0));
+
+    let notify = tasks.iter().find(|t| t.task_name == "notify").unwrap();
+    assert_eq!(notify.status, TaskStatus::Success);
+    assert_eq!(notify.exit_code, Some(200));
+    assert_eq!(notify.stdout.as_deref(), Some("ack"));
+}
+
+#[tokio::test]
+async fn test_on_complete_webhook() {
+    use wiremock::matchers::{body_partial_json, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let mock_server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/hook"))
+        .and(body_partial_json(serde_json::json!({
+            "workflow": "notified",
+            "status": "failed",
+        })))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let (_temp_dir, state_manager) = setup_temp_state().await;
+
+    let yaml = format!(
+        r#"
+name: notified
+config:
+  retry_default: 0
+on_complete:
+  url: "{}/hook"
+  method: POST
+  allow_private_ips: true
+tasks:
+  - name: broken
+    type: shell
+    config:
+      command: "/bin/false"
+"#,
+        mock_server.uri()
+    );
+
+    let config = parse_workflow_yaml(&yaml).unwrap();
+    let scheduler = TaskScheduler::new(state_manager.clone());
+    let result = scheduler.execute_workflow(&config).await.unwrap();
+    assert_eq!(result.status, TaskStatus::Failed);
+
+    let requests = mock_server.received_requests().await.unwrap();
+    let body: serde_json::Value = serde_json::from_slice(&requests[0].body).unwrap();
+    assert_eq!(body["execution_id"], result.execution_id);
+    assert!(body["duration"].is_f64());
+}
+
+#[tokio::test]
+async fn test_on_complete_failure_keeps_result() {
+    use wiremock::matchers::method;
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let mock_server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(500))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let (_temp_dir, state_manager) = setup_temp_state().await;
+
+    let yaml = format!(
+        r#"
+name: notified-ok
+on_complete:
+  url: "{}/hook"
+  method: POST
+  allow_private_ips: true
+tasks:
+  - name: step
+    type: shell
+    config:
+      command: "/bin/true"
+"#,
+        mock_server.uri()
+    );
+
+    let config = parse_workflow_yaml(&yaml).unwrap();
+    let scheduler = TaskScheduler::new(state_manager.clone());
+    assert!(scheduler.execute_workflow_ok(&config).await.unwrap());
+}
+
+#[tokio::test]
+async fn test_workflow_labels_persisted() {
+    let (_temp_dir, state_manager) = setup_temp_state().await;
+
+    let yaml = r#"
+name: labelled
+labels:
+  team: ops
+  site: warehouse-3
+tasks:
+  - name: step
+    type: shell
+    labels:
+      kind: probe
+    config:
+      command: "/bin/true"
+"#;
+    let config = parse_workflow_yaml(yaml).unwrap();
+    assert_eq!(config.labels["site"], "warehouse-3");
+    assert_eq!(config.tasks[0].labels["kind"], "probe");
+
+    let scheduler = TaskScheduler::new(state_manager.clone());
+    assert!(scheduler.execute_workflow_ok(&config).await.unwrap());
+
+    let workflows = state_manager
+        .list_workflows_by_label("site", "warehouse-3")
+        .await
+        .unwrap();
+    assert_eq!(workflows.len(), 1);
+    assert_eq!(workflows[0].name, "labelled");
+    assert_eq!(workflows[0].execution_count, 1);
+}
+
+#[test]
+fn test_run_reads_workflow_from_stdin() {
+    use std::io::Write;
+    use std::process::{Command, Stdio};
+
+    let temp_dir = TempDir::new().unwrap();
+    let marker = temp_dir.path().join("ran");
+    let yaml = format!(
+        r#"
+name: piped-workflow
+tasks:
+  - name: touch
+    type: shell
+    config:
+      command: "/usr/bin/touch"
+      args: ["{}"]
+"#,
+        marker.display()
+    );
+
+    let mut child = Command::new(env!("CARGO_BIN_EXE_picoflow"))
+        .arg("--db-path")
+        .arg(temp_dir.path().join("state.db"))
+        .args(["run", "-"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(yaml.as_bytes())
+        .unwrap();
+    let output = child.wait_with_output().unwrap();
+
+    assert!(
+        output.status.success(),
+        "picoflow run - failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(marker.exists(), "workflow from stdin did not run");
+}